@@ -1,5 +1,6 @@
 use core::ops::ControlFlow;
 use std::borrow::Cow;
+use std::fmt::Write;
 
 use rustc_ast::visit::Visitor;
 use rustc_ast::*;
@@ -7,7 +8,7 @@
 use rustc_hir as hir;
 use rustc_session::config::FmtDebug;
 use rustc_span::symbol::{kw, Ident};
-use rustc_span::{sym, Span, Symbol};
+use rustc_span::{sym, ExpnKind, MacroKind, Span, Symbol};
 
 use super::LoweringContext;
 
@@ -21,7 +22,9 @@ pub(crate) fn lower_format_args(&mut self, sp: Span, fmt: &FormatArgs) -> hir::E
             fmt = flatten_format_args(fmt);
             fmt = self.inline_literals(fmt);
         }
-        expand_format_args(self, sp, &fmt, allow_const)
+        let defer =
+            self.tcx.sess.opts.unstable_opts.deferred_strings && is_panic_or_log_message(self, sp);
+        expand_format_args(self, sp, &fmt, allow_const, defer)
     }
 
     /// Try to convert a literal into an interned string
@@ -236,6 +239,7 @@ fn make_argument<'hir>(
     sp: Span,
     arg: &'hir hir::Expr<'hir>,
     ty: ArgumentType,
+    deferred: bool,
 ) -> hir::Expr<'hir> {
     use ArgumentType::*;
     use FormatTrait::*;
@@ -243,6 +247,15 @@ fn make_argument<'hir>(
         sp,
         hir::LangItem::FormatArgument,
         match ty {
+            Format(Display) if deferred => sym::new_deferred_display,
+            Format(Debug) if deferred => sym::new_deferred_debug,
+            Format(LowerExp) if deferred => sym::new_deferred_lower_exp,
+            Format(UpperExp) if deferred => sym::new_deferred_upper_exp,
+            Format(Octal) if deferred => sym::new_deferred_octal,
+            Format(Pointer) if deferred => sym::new_deferred_pointer,
+            Format(Binary) if deferred => sym::new_deferred_binary,
+            Format(LowerHex) if deferred => sym::new_deferred_lower_hex,
+            Format(UpperHex) if deferred => sym::new_deferred_upper_hex,
             Format(Display) => sym::new_display,
             Format(Debug) => match ctx.tcx.sess.opts.unstable_opts.fmt_debug {
                 FmtDebug::Full | FmtDebug::Shallow => sym::new_debug,
@@ -392,49 +405,138 @@ fn make_format_spec<'hir>(
     ctx.expr_call_mut(sp, format_placeholder_new, args)
 }
 
+/// Whether the `format_args!` at `sp` builds the message of a panic or of a log macro of the `log`
+/// crate. Only those are deferred with `-Z deferred-strings`: the output of macros such as
+/// `format!` and `write!` is used by the program itself.
+fn is_panic_or_log_message(ctx: &LoweringContext<'_, '_>, sp: Span) -> bool {
+    // The macro that invoked `format_args!`.
+    let caller = sp.ctxt().outer_expn_data().call_site.ctxt().outer_expn_data();
+    let (ExpnKind::Macro(MacroKind::Bang, name), Some(def_id)) = (caller.kind, caller.macro_def_id)
+    else {
+        return false;
+    };
+    match ctx.tcx.crate_name(def_id.krate) {
+        sym::core | sym::std => matches!(
+            name,
+            sym::panic_2015
+                | sym::panic_2021
+                | sym::unreachable_2015
+                | sym::unreachable_2021
+                | sym::assert_eq
+                | sym::assert_ne
+                | sym::assert_matches
+                | sym::todo
+                | sym::unimplemented
+        ),
+        krate => krate == sym::log,
+    }
+}
+
+/// With `-Z deferred-strings`, replaces a literal piece by a reference into the table of deferred
+/// strings, which is decoded on the host when the formatted output is read.
+fn defer_lit_piece(ctx: &LoweringContext<'_, '_>, s: Symbol, defer: bool) -> Symbol {
+    if defer { ctx.tcx.sess.defer_string(s.as_str()).unwrap_or(s) } else { s }
+}
+
+/// With `-Z deferred-strings`, replaces the whole format string of a panic or log message by a
+/// reference into the table of deferred strings. The arguments are written after the reference and
+/// formatted on the host, with the format string from the table.
+///
+/// In the format string stored in the table, the placeholders refer to the arguments in the order
+/// they are written, that is the order of `argmap`. Returns `None` if the format string can't be
+/// deferred as a whole, because it has no arguments or has dynamic widths or precisions, in which
+/// case only its literal pieces are deferred.
+fn defer_format_string(
+    ctx: &LoweringContext<'_, '_>,
+    fmt: &FormatArgs,
+    argmap: &FxIndexMap<(usize, ArgumentType), Option<Span>>,
+) -> Option<Symbol> {
+    if argmap.is_empty() {
+        return None;
+    }
+    let mut template = String::new();
+    for piece in &fmt.template {
+        let placeholder = match piece {
+            FormatArgsPiece::Literal(s) => {
+                template.push_str(&s.as_str().replace('{', "{{").replace('}', "}}"));
+                continue;
+            }
+            FormatArgsPiece::Placeholder(placeholder) => placeholder,
+        };
+        let ty = ArgumentType::Format(placeholder.format_trait);
+        let index = argmap.get_index_of(&(placeholder.argument.index.ok()?, ty))?;
+        if ty == ArgumentType::Format(FormatTrait::Debug)
+            && ctx.tcx.sess.opts.unstable_opts.fmt_debug == FmtDebug::None
+        {
+            return None;
+        }
+
+        let options = &placeholder.format_options;
+        let mut spec = String::new();
+        if let Some(alignment) = options.alignment {
+            spec.extend(options.fill);
+            spec.push(match alignment {
+                FormatAlignment::Left => '<',
+                FormatAlignment::Right => '>',
+                FormatAlignment::Center => '^',
+            });
+        }
+        match options.sign {
+            Some(FormatSign::Plus) => spec.push('+'),
+            Some(FormatSign::Minus) => spec.push('-'),
+            None => {}
+        }
+        if options.alternate {
+            spec.push('#');
+        }
+        if options.zero_pad {
+            spec.push('0');
+        }
+        match options.width {
+            Some(FormatCount::Literal(width)) => write!(spec, "{width}").unwrap(),
+            Some(FormatCount::Argument(_)) => return None,
+            None => {}
+        }
+        match options.precision {
+            Some(FormatCount::Literal(precision)) => write!(spec, ".{precision}").unwrap(),
+            Some(FormatCount::Argument(_)) => return None,
+            None => {}
+        }
+        spec.push_str(match (placeholder.format_trait, options.debug_hex) {
+            (FormatTrait::Display, _) => "",
+            (FormatTrait::Debug, None) => "?",
+            (FormatTrait::Debug, Some(FormatDebugHex::Lower)) => "x?",
+            (FormatTrait::Debug, Some(FormatDebugHex::Upper)) => "X?",
+            (FormatTrait::LowerExp, _) => "e",
+            (FormatTrait::UpperExp, _) => "E",
+            (FormatTrait::Octal, _) => "o",
+            (FormatTrait::Pointer, _) => "p",
+            (FormatTrait::Binary, _) => "b",
+            (FormatTrait::LowerHex, _) => "x",
+            (FormatTrait::UpperHex, _) => "X",
+        });
+        if spec.is_empty() {
+            write!(template, "{{{index}}}").unwrap();
+        } else {
+            write!(template, "{{{index}:{spec}}}").unwrap();
+        }
+    }
+    Some(ctx.tcx.sess.defer_format_string(&template))
+}
+
 fn expand_format_args<'hir>(
     ctx: &mut LoweringContext<'_, 'hir>,
     macsp: Span,
     fmt: &FormatArgs,
     allow_const: bool,
+    defer: bool,
 ) -> hir::ExprKind<'hir> {
-    let mut incomplete_lit = String::new();
-    let lit_pieces =
-        ctx.arena.alloc_from_iter(fmt.template.iter().enumerate().filter_map(|(i, piece)| {
-            match piece {
-                &FormatArgsPiece::Literal(s) => {
-                    // Coalesce adjacent literal pieces.
-                    if let Some(FormatArgsPiece::Literal(_)) = fmt.template.get(i + 1) {
-                        incomplete_lit.push_str(s.as_str());
-                        None
-                    } else if !incomplete_lit.is_empty() {
-                        incomplete_lit.push_str(s.as_str());
-                        let s = Symbol::intern(&incomplete_lit);
-                        incomplete_lit.clear();
-                        Some(ctx.expr_str(fmt.span, s))
-                    } else {
-                        Some(ctx.expr_str(fmt.span, s))
-                    }
-                }
-                &FormatArgsPiece::Placeholder(_) => {
-                    // Inject empty string before placeholders when not already preceded by a literal piece.
-                    if i == 0 || matches!(fmt.template[i - 1], FormatArgsPiece::Placeholder(_)) {
-                        Some(ctx.expr_str(fmt.span, kw::Empty))
-                    } else {
-                        None
-                    }
-                }
-            }
-        }));
-    let lit_pieces = ctx.expr_array_ref(fmt.span, lit_pieces);
-
-    // Whether we'll use the `Arguments::new_v1_formatted` form (true),
-    // or the `Arguments::new_v1` form (false).
-    let mut use_format_options = false;
-
     // Create a list of all _unique_ (argument, format trait) combinations.
     // E.g. "{0} {0:x} {0} {1}" -> [(0, Display), (0, LowerHex), (1, Display)]
     let mut argmap = FxIndexMap::default();
+    // Whether we'll use the `Arguments::new_v1_formatted` form (true),
+    // or the `Arguments::new_v1` form (false).
+    let mut use_format_options = false;
     for piece in &fmt.template {
         let FormatArgsPiece::Placeholder(placeholder) = piece else { continue };
         if placeholder.format_options != Default::default() {
@@ -453,6 +555,54 @@ fn expand_format_args<'hir>(
         }
     }
 
+    let deferred = if defer { defer_format_string(ctx, fmt, &argmap) } else { None };
+
+    let lit_pieces = if let Some(reference) = deferred {
+        // Generate:
+        //     &["\x1c<hash>", "", "", …, "\x1d"]
+        //
+        // so that each argument is written once, in order, after the reference.
+        use_format_options = false;
+        let lit_pieces = ctx.arena.alloc_from_iter(
+            [ctx.expr_str(fmt.span, reference)]
+                .into_iter()
+                .chain((1..argmap.len()).map(|_| ctx.expr_str(fmt.span, kw::Empty)))
+                .chain([ctx.expr_str(fmt.span, Symbol::intern("\x1d"))]),
+        );
+        ctx.expr_array_ref(fmt.span, lit_pieces)
+    } else {
+        let mut incomplete_lit = String::new();
+        let lit_pieces =
+            ctx.arena.alloc_from_iter(fmt.template.iter().enumerate().filter_map(|(i, piece)| {
+                match piece {
+                    &FormatArgsPiece::Literal(s) => {
+                        // Coalesce adjacent literal pieces.
+                        if let Some(FormatArgsPiece::Literal(_)) = fmt.template.get(i + 1) {
+                            incomplete_lit.push_str(s.as_str());
+                            None
+                        } else if !incomplete_lit.is_empty() {
+                            incomplete_lit.push_str(s.as_str());
+                            let s = Symbol::intern(&incomplete_lit);
+                            incomplete_lit.clear();
+                            Some(ctx.expr_str(fmt.span, defer_lit_piece(ctx, s, defer)))
+                        } else {
+                            Some(ctx.expr_str(fmt.span, defer_lit_piece(ctx, s, defer)))
+                        }
+                    }
+                    &FormatArgsPiece::Placeholder(_) => {
+                        // Inject empty string before placeholders when not already preceded by a literal piece.
+                        if i == 0 || matches!(fmt.template[i - 1], FormatArgsPiece::Placeholder(_))
+                        {
+                            Some(ctx.expr_str(fmt.span, kw::Empty))
+                        } else {
+                            None
+                        }
+                    }
+                }
+            }));
+        ctx.expr_array_ref(fmt.span, lit_pieces)
+    };
+
     let format_options = use_format_options.then(|| {
         // Generate:
         //     &[format_spec_0, format_spec_1, format_spec_2]
@@ -532,7 +682,7 @@ fn expand_format_args<'hir>(
                     arg_span,
                     hir::ExprKind::AddrOf(hir::BorrowKind::Ref, hir::Mutability::Not, arg),
                 ));
-                make_argument(ctx, placeholder_span, ref_arg, ty)
+                make_argument(ctx, placeholder_span, ref_arg, ty, deferred.is_some())
             },
         ));
         ctx.expr_array_ref(macsp, elements)
@@ -565,7 +715,7 @@ fn expand_format_args<'hir>(
                         Ident::new(sym::integer(arg_index), macsp),
                     ),
                 ));
-                make_argument(ctx, placeholder_span, arg, ty)
+                make_argument(ctx, placeholder_span, arg, ty, deferred.is_some())
             },
         ));
         let elements = ctx.arena.alloc_from_iter(arguments.iter().map(|arg| {
@@ -53,7 +53,7 @@ pub(crate) fn conv_to_call_conv(sess: &Session, c: Conv, default_call_conv: Call
             default_call_conv
         }
 
-        Conv::X86Intr | Conv::RiscvInterrupt { .. } => {
+        Conv::X86Intr | Conv::RiscvInterrupt { .. } | Conv::XtensaInterrupt { .. } => {
             sess.dcx().fatal(format!("interrupt call conv {c:?} not yet implemented"))
         }
 
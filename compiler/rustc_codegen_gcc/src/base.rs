@@ -4,7 +4,7 @@
 use std::time::Instant;
 
 use gccjit::{CType, FunctionType, GlobalKind};
-use rustc_codegen_ssa::base::maybe_create_entry_wrapper;
+use rustc_codegen_ssa::base::{codegen_deferred_strings, maybe_create_entry_wrapper};
 use rustc_codegen_ssa::mono_item::MonoItemExt;
 use rustc_codegen_ssa::traits::DebugInfoMethods;
 use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
@@ -214,6 +214,9 @@ fn module_codegen(
             // wrapper here
             maybe_create_entry_wrapper::<Builder<'_, '_, '_>>(&cx);
 
+            // Emit the strings deferred by this and the previous codegen units.
+            codegen_deferred_strings(&cx);
+
             // Finalize debuginfo
             if cx.sess().opts.debuginfo != DebugInfo::None {
                 cx.debuginfo_finalize();
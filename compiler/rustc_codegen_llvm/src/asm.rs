@@ -160,7 +160,7 @@ fn codegen_inline_asm(
                     }
                 }
                 InlineAsmOperandRef::SymFn { instance } => {
-                    inputs.push(self.cx.get_fn(instance));
+                    inputs.push(self.cx.get_fn_addr(instance));
                     op_idx.insert(idx, constraints.len());
                     constraints.push("s".to_string());
                 }
@@ -388,7 +388,7 @@ fn codegen_global_asm(
                             template_str.push_str(string);
                         }
                         GlobalAsmOperandRef::SymFn { instance } => {
-                            let llval = self.get_fn(instance);
+                            let llval = self.get_fn_addr(instance);
                             self.add_compiler_used_global(llval);
                             let symbol = llvm::build_string(|s| unsafe {
                                 llvm::LLVMRustGetMangledName(llval, s);
@@ -705,6 +705,10 @@ fn reg_to_llvm(reg: InlineAsmRegOrRegClass, layout: Option<&TyAndLayout<'_>>) ->
             InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::reg) => "r",
             InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::freg) => "f",
             InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::breg) => "b",
+            InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::qreg) => "q",
+            InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::accx) => {
+                unreachable!("clobber-only")
+            }
             InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::reg) => "r",
             InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::wreg) => "w",
             InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg) => "r",
@@ -888,6 +892,10 @@ fn dummy_output_type<'ll>(cx: &CodegenCx<'ll, '_>, reg: InlineAsmRegClass) -> &'
         InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::reg) => cx.type_i32(),
         InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::freg) => cx.type_f32(),
         InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::breg) => cx.type_i1(),
+        InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::qreg) => cx.type_vector(cx.type_i8(), 16),
+        InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::accx) => {
+            unreachable!("clobber-only")
+        }
         InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::reg) => cx.type_i64(),
         InlineAsmRegClass::Bpf(BpfInlineAsmRegClass::wreg) => cx.type_i32(),
         InlineAsmRegClass::Avr(AvrInlineAsmRegClass::reg) => cx.type_i8(),
@@ -1341,6 +1349,10 @@ fn llvm_fixup_output_type<'ll, 'tcx>(
         {
             cx.type_f32()
         }
+        // `bool` is stored as a byte, but is a single bit in the boolean registers.
+        (InlineAsmRegClass::Xtensa(XtensaInlineAsmRegClass::breg), Abi::Scalar(_)) => {
+            cx.type_i1()
+        }
         _ => layout.llvm_type(cx),
     }
 }
@@ -21,7 +21,8 @@
 use rustc_fs_util::{link_or_copy, path_to_c_string};
 use rustc_middle::ty::TyCtxt;
 use rustc_session::config::{
-    self, Lto, OutputType, Passes, RemapPathScopeComponents, SplitDwarfKind, SwitchWithOptPath,
+    self, Lto, OutputType, Passes, PrintKind, RemapPathScopeComponents, SplitDwarfKind,
+    SwitchWithOptPath,
 };
 use rustc_session::Session;
 use rustc_span::symbol::sym;
@@ -209,7 +210,11 @@ pub(crate) fn target_machine_factory(
     let abi = SmallCStr::new(&sess.target.llvm_abiname);
     let trap_unreachable =
         sess.opts.unstable_opts.trap_unreachable.unwrap_or(sess.target.trap_unreachable);
-    let emit_stack_size_section = sess.opts.unstable_opts.emit_stack_sizes;
+    // `--print stack-usage` and `-Z max-stack-frame` read the frame sizes from the `.stack_sizes`
+    // sections.
+    let emit_stack_size_section = sess.opts.unstable_opts.emit_stack_sizes
+        || sess.opts.unstable_opts.max_stack_frame.is_some()
+        || sess.opts.prints.iter().any(|print| print.kind == PrintKind::StackUsage);
 
     let verbose_asm = sess.opts.unstable_opts.verbose_asm;
     let relax_elf_relocations =
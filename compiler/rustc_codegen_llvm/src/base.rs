@@ -11,9 +11,10 @@
 //! [`Ty`]: rustc_middle::ty::Ty
 //! [`val_ty`]: crate::common::val_ty
 
+use std::str;
 use std::time::Instant;
 
-use rustc_codegen_ssa::base::maybe_create_entry_wrapper;
+use rustc_codegen_ssa::base::{codegen_deferred_strings, maybe_create_entry_wrapper};
 use rustc_codegen_ssa::mono_item::MonoItemExt;
 use rustc_codegen_ssa::traits::*;
 use rustc_codegen_ssa::{ModuleCodegen, ModuleKind};
@@ -101,6 +102,9 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
                 attributes::apply_to_llfn(entry, llvm::AttributePlace::Function, &attrs);
             }
 
+            // Emit the strings deferred by this and the previous codegen units.
+            codegen_deferred_strings(&cx);
+
             // Finalize code coverage by injecting the coverage map. Note, the coverage map will
             // also be added to the `llvm.compiler.used` variable, created next.
             if cx.sess().instrument_coverage() {
@@ -143,10 +147,21 @@ fn module_codegen(tcx: TyCtxt<'_>, cgu_name: Symbol) -> ModuleCodegen<ModuleLlvm
     (module, cost)
 }
 
-pub(crate) fn set_link_section(llval: &Value, attrs: &CodegenFnAttrs) {
-    let Some(sect) = attrs.link_section else { return };
+/// Returns the section `#[link_section]` or `#[link_region]` place a function (`is_fn`) or
+/// static in, if any.
+pub(crate) fn link_section(llval: &Value, attrs: &CodegenFnAttrs, is_fn: bool) -> Option<String> {
+    if let Some(sect) = attrs.link_section {
+        return Some(sect.to_string());
+    }
+    let region = attrs.link_region?;
+    let symbol_name = str::from_utf8(llvm::get_value_name(llval)).unwrap();
+    region.section_name(symbol_name, is_fn)
+}
+
+pub(crate) fn set_link_section(llval: &Value, attrs: &CodegenFnAttrs, is_fn: bool) {
+    let Some(sect) = link_section(llval, attrs, is_fn) else { return };
     unsafe {
-        let buf = SmallCStr::new(sect.as_str());
+        let buf = SmallCStr::new(&sect);
         llvm::LLVMSetSection(llval, buf.as_ptr());
     }
 }
@@ -530,7 +530,7 @@ fn codegen_static_item(&self, def_id: DefId) {
                     );
                 }
             } else {
-                base::set_link_section(g, attrs);
+                base::set_link_section(g, attrs, false);
             }
 
             if attrs.flags.contains(CodegenFnAttrFlags::USED) {
@@ -14,7 +14,7 @@
 use rustc_middle::middle::codegen_fn_attrs::PatchableFunctionEntry;
 use rustc_middle::mir::mono::CodegenUnit;
 use rustc_middle::ty::layout::{
-    FnAbiError, FnAbiOfHelpers, FnAbiRequest, HasParamEnv, LayoutError, LayoutOfHelpers,
+    FnAbiError, FnAbiOf, FnAbiOfHelpers, FnAbiRequest, HasParamEnv, LayoutError, LayoutOfHelpers,
     TyAndLayout,
 };
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
@@ -25,7 +25,7 @@
 use rustc_session::Session;
 use rustc_span::source_map::Spanned;
 use rustc_span::{Span, DUMMY_SP};
-use rustc_target::abi::call::FnAbi;
+use rustc_target::abi::call::{Conv, FnAbi};
 use rustc_target::abi::{HasDataLayout, TargetDataLayout, VariantIdx};
 use rustc_target::spec::{HasTargetSpec, RelocModel, Target, TlsModel};
 use smallvec::SmallVec;
@@ -602,6 +602,17 @@ fn get_fn(&self, instance: Instance<'tcx>) -> &'ll Value {
     }
 
     fn get_fn_addr(&self, instance: Instance<'tcx>) -> &'ll Value {
+        if self.tcx.sess.target.arch == "xtensa" {
+            // The symbol of an Xtensa interrupt handler belongs to its entry point in assembly,
+            // see `define_xtensa_interrupt_entry`, while `get_fn` returns the handler's body.
+            let fn_abi = self.fn_abi_of_instance(instance, ty::List::empty());
+            if let Conv::XtensaInterrupt { .. } = fn_abi.conv {
+                let sym = self.tcx.symbol_name(instance).name;
+                return self
+                    .get_declared_value(sym)
+                    .unwrap_or_else(|| self.declare_fn(sym, fn_abi, None));
+            }
+        }
         get_fn(self, instance)
     }
 
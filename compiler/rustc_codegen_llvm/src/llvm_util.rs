@@ -574,6 +574,12 @@ pub(crate) fn global_llvm_features(
         features.push("+exception-handling".into());
     }
 
+    // LLVM has no separate switch for the Xtensa call0 ABI, it is simply the absence of
+    // register windows.
+    if sess.target.arch == "xtensa" && sess.target.llvm_abiname == "call0" {
+        features.push("-windowed".into());
+    }
+
     // -Ctarget-features
     if !only_base_features {
         let supported_features = sess.target.supported_target_features();
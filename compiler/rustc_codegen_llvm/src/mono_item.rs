@@ -6,12 +6,14 @@
 use rustc_middle::ty::layout::{FnAbiOf, LayoutOf};
 use rustc_middle::ty::{self, Instance, TypeVisitableExt};
 use rustc_session::config::CrateType;
+use rustc_target::abi::call::Conv;
 use rustc_target::spec::RelocModel;
 use tracing::debug;
 
 use crate::context::CodegenCx;
 use crate::errors::SymbolAlreadyDefined;
 use crate::type_of::LayoutLlvmExt;
+use crate::value::Value;
 use crate::{base, llvm};
 
 impl<'tcx> PreDefineMethods<'tcx> for CodegenCx<'_, 'tcx> {
@@ -63,7 +65,7 @@ fn predefine_fn(
         let lldecl = self.declare_fn(symbol_name, fn_abi, Some(instance));
         unsafe { llvm::LLVMRustSetLinkage(lldecl, base::linkage_to_llvm(linkage)) };
         let attrs = self.tcx.codegen_fn_attrs(instance.def_id());
-        base::set_link_section(lldecl, attrs);
+        base::set_link_section(lldecl, attrs, true);
         if linkage == Linkage::LinkOnceODR || linkage == Linkage::WeakODR {
             llvm::SetUniqueComdat(self.llmod, lldecl);
         }
@@ -93,10 +95,132 @@ fn predefine_fn(
             }
         }
 
+        if let Conv::XtensaInterrupt { level } = fn_abi.conv {
+            let section = base::link_section(lldecl, attrs, true);
+            self.define_xtensa_interrupt_entry(lldecl, symbol_name, level, section);
+        }
+
         self.instances.borrow_mut().insert(instance, lldecl);
     }
 }
 
+impl<'ll> CodegenCx<'ll, '_> {
+    /// LLVM has no notion of Xtensa interrupt handlers, so a handler is compiled as a plain
+    /// function under a local name, and the handler's symbol is given to an entry point in
+    /// module-level assembly instead. The entry point saves the registers of the interrupted
+    /// code on its stack, calls the handler with exceptions enabled and interrupts masked up to
+    /// the handler's level, restores everything, and returns with `RFE` or `RFI <level>`.
+    /// Code that refers to the handler gets the entry point, see `get_fn_addr`.
+    fn define_xtensa_interrupt_entry(
+        &self,
+        llfn: &'ll Value,
+        symbol_name: &str,
+        level: u8,
+        link_section: Option<String>,
+    ) {
+        let body = format!("{symbol_name}.body");
+        let (linkage, visibility) =
+            unsafe { (llvm::LLVMRustGetLinkage(llfn), llvm::LLVMRustGetVisibility(llfn)) };
+        llvm::set_value_name(llfn, body.as_bytes());
+        unsafe {
+            llvm::LLVMRustSetLinkage(llfn, llvm::Linkage::InternalLinkage);
+            llvm::LLVMRustSetVisibility(llfn, llvm::Visibility::Default);
+        }
+        // Only the assembly refers to the body, which LLVM can't see.
+        self.add_compiler_used_global(llfn);
+
+        // `PS` fields.
+        const PS_EXCM: u32 = 1 << 4;
+        const PS_UM: u32 = 1 << 5;
+        const PS_WOE_SHIFT: u32 = 18;
+
+        let level = u32::from(level);
+        let windowed = self.tcx.sess.target.llvm_abiname != "call0";
+        let section = match link_section {
+            Some(section) => section,
+            None => format!(".text.{symbol_name}"),
+        };
+
+        let mut asm = String::new();
+        let mut line = |text: String| {
+            asm.push_str(&text);
+            asm.push('\n');
+        };
+        line(format!(".pushsection {section},\"ax\",@progbits"));
+        line(".p2align 2".to_string());
+        if !matches!(linkage, llvm::Linkage::InternalLinkage | llvm::Linkage::PrivateLinkage) {
+            line(format!(".globl {symbol_name}"));
+        }
+        if visibility == llvm::Visibility::Hidden {
+            line(format!(".hidden {symbol_name}"));
+        }
+        line(format!(".type {symbol_name},@function"));
+        line(format!("{symbol_name}:"));
+
+        // The 16 bytes at either end of the frame are left alone: the ones above hold the
+        // register spill area of the interrupted code, the ones below that of the entry point.
+        line("addi a1, a1, -112".to_string());
+        line("s32i a0, a1, 16".to_string());
+        for reg in 2..16 {
+            line(format!("s32i a{reg}, a1, {}", 16 + 4 * (reg - 1)));
+        }
+        // The return address and the interrupted `PS`, which level 1 interrupts leave in `PS`
+        // itself. High-priority interrupts can be taken in the middle of a level 1 exception
+        // handler, whose `EPC1` and `EXCSAVE1` the window exceptions of the handler overwrite.
+        let mut saved = vec!["sar".to_string()];
+        if level == 1 {
+            saved.extend(["epc1".to_string(), "ps".to_string()]);
+        } else {
+            saved.extend([format!("epc{level}"), format!("eps{level}")]);
+            saved.extend(["epc1".to_string(), "excsave1".to_string()]);
+        }
+        for (i, sr) in saved.iter().enumerate() {
+            line(format!("rsr a0, {sr}"));
+            line(format!("s32i a0, a1, {}", 76 + 4 * i));
+        }
+
+        // Leave exception mode so that the handler can take window exceptions.
+        if windowed {
+            line("movi a0, 1".to_string());
+            line(format!("slli a0, a0, {PS_WOE_SHIFT}"));
+            line(format!("addi a0, a0, {}", PS_UM | level));
+        } else {
+            line(format!("movi a0, {}", PS_UM | level));
+        }
+        line("wsr a0, ps".to_string());
+        line("rsync".to_string());
+        if windowed {
+            line(format!("call4 {body}"));
+        } else {
+            line(format!("call0 {body}"));
+        }
+
+        // Mask all interrupts up to the handler's level again before restoring anything.
+        line(format!("movi a0, {}", PS_EXCM | level));
+        line("wsr a0, ps".to_string());
+        line("rsync".to_string());
+        for (i, sr) in saved.iter().enumerate().rev() {
+            line(format!("l32i a0, a1, {}", 76 + 4 * i));
+            line(format!("wsr a0, {sr}"));
+        }
+        line("rsync".to_string());
+        for reg in 2..16 {
+            line(format!("l32i a{reg}, a1, {}", 16 + 4 * (reg - 1)));
+        }
+        line("l32i a0, a1, 16".to_string());
+        line("addi a1, a1, 112".to_string());
+        if level == 1 {
+            line("rfe".to_string());
+        } else {
+            line(format!("rfi {level}"));
+        }
+        line(format!(".size {symbol_name}, .-{symbol_name}"));
+        line(".popsection".to_string());
+
+        unsafe { llvm::LLVMAppendModuleInlineAsm(self.llmod, asm.as_ptr().cast(), asm.len()) };
+    }
+}
+
 impl CodegenCx<'_, '_> {
     /// Whether a definition or declaration can be assumed to be local to a group of
     /// libraries that form a single DSO or executable.
@@ -229,6 +229,11 @@ codegen_ssa_shuffle_indices_evaluation = could not evaluate shuffle_indices at c
 
 codegen_ssa_specify_libraries_to_link = use the `-l` flag to specify native libraries to link
 
+codegen_ssa_stack_frame_too_large =
+    the stack frame of `{$function}` is {$size} bytes, which is larger than the limit of {$limit} bytes
+    .label = this function
+    .help = the limit is set by `-Z max-stack-frame`, and can be changed for a function with `#[max_stack_frame = "..."]`
+
 codegen_ssa_static_library_native_artifacts = Link against the following native artifacts when linking against this static library. The order and any duplication can be significant on some platforms.
 
 codegen_ssa_static_library_native_artifacts_to_file = Native artifacts to link against have been written to {$path}. The order and any duplication can be significant on some platforms.
@@ -346,3 +351,6 @@ codegen_ssa_use_cargo_directive = use the `cargo:rustc-link-lib` directive to sp
 codegen_ssa_version_script_write_failure = failed to write version script: {$error}
 
 codegen_ssa_visual_studio_not_installed = you may need to install Visual Studio build tools with the "C++ build tools" workload
+
+codegen_ssa_xtensa_windowed_per_function = the target feature `windowed` changes the calling convention and cannot be enabled per function
+    .help = select the call0 or windowed ABI for the whole crate graph with `-C target-feature`
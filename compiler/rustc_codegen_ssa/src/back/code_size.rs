@@ -0,0 +1,191 @@
+//! `--print code-size`: the size of the generated code, attributed to crates, generic definitions
+//! and the functions instantiating them.
+//!
+//! Unlike `-Z dump-mono-stats`, which only has estimates from before codegen, the sizes here are
+//! the sizes of the symbols in the object files. They are joined with what partitioning knows about
+//! the mono items, which is recorded during codegen and carried to the linker in the `CrateInfo`.
+
+use std::cmp;
+use std::fmt::Write;
+use std::fs;
+
+use object::{Object, ObjectSymbol, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_macros::{Decodable, Encodable};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_monomorphize::call_graph::{self, Callee};
+use rustc_session::config::DumpMonoStatsFormat;
+use serde_json::json;
+
+use crate::CodegenResults;
+
+/// The mono items of the crate, keyed by symbol name.
+#[derive(Debug, Encodable, Decodable)]
+pub struct MonoItems {
+    items: FxIndexMap<String, Item>,
+}
+
+#[derive(Debug, Encodable, Decodable)]
+struct Item {
+    /// The instance, with its generic arguments.
+    name: String,
+    /// The generic definition this is an instance of.
+    definition: String,
+    /// The crate of the definition.
+    krate: String,
+    /// The codegen unit the item was placed in.
+    cgu: String,
+    /// The functions of this crate calling the item, which caused it to be instantiated.
+    callers: Vec<String>,
+}
+
+impl MonoItems {
+    pub fn new(tcx: TyCtxt<'_>) -> MonoItems {
+        let mut items: FxIndexMap<String, Item> = FxIndexMap::default();
+        let mut callers: FxHashMap<String, Vec<String>> = FxHashMap::default();
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            for item in cgu.items().keys() {
+                let symbol = item.symbol_name(tcx).name;
+                if items.contains_key(symbol) {
+                    continue;
+                }
+                let def_id = item.def_id();
+                let name = with_no_trimmed_paths!(item.to_string());
+
+                if let MonoItem::Fn(instance) = *item {
+                    for callee in call_graph::callees(tcx, instance) {
+                        if let Callee::Direct(callee) = callee {
+                            let callee = tcx.symbol_name(callee).name.to_string();
+                            callers.entry(callee).or_default().push(name.clone());
+                        }
+                    }
+                }
+
+                items.insert(
+                    symbol.to_string(),
+                    Item {
+                        name,
+                        definition: with_no_trimmed_paths!(tcx.def_path_str(def_id)),
+                        krate: tcx.crate_name(def_id.krate).to_string(),
+                        cgu: cgu.name().to_string(),
+                        callers: Vec::new(),
+                    },
+                );
+            }
+        }
+
+        for (symbol, mut item_callers) in callers {
+            if let Some(item) = items.get_mut(&symbol) {
+                item_callers.sort_unstable();
+                item_callers.dedup();
+                item.callers = item_callers;
+            }
+        }
+        MonoItems { items }
+    }
+}
+
+/// Reads the sizes of the defined symbols from the object files. Copies of an item in several
+/// codegen units all end up in the binary, so their sizes are added up.
+fn symbol_sizes(codegen_results: &CodegenResults) -> FxHashMap<String, u64> {
+    let mut sizes = FxHashMap::default();
+    let modules = codegen_results.modules.iter().chain(&codegen_results.allocator_module);
+    for path in modules.filter_map(|module| module.object.as_ref()) {
+        let Ok(data) = fs::read(path) else { continue };
+        let Ok(file) = object::File::parse(&*data) else { continue };
+        for symbol in file.symbols() {
+            if symbol.is_undefined() || !matches!(symbol.kind(), SymbolKind::Text | SymbolKind::Data)
+            {
+                continue;
+            }
+            if let Ok(name) = symbol.name() {
+                *sizes.entry(name.to_string()).or_default() += symbol.size();
+            }
+        }
+    }
+    sizes
+}
+
+/// Produces the `--print code-size` report.
+pub fn report(codegen_results: &CodegenResults, format: DumpMonoStatsFormat) -> String {
+    let Some(mono_items) = &codegen_results.crate_info.code_size else {
+        return String::new();
+    };
+    let sizes = symbol_sizes(codegen_results);
+
+    let mut instantiations: Vec<(&Item, u64)> = mono_items
+        .items
+        .iter()
+        .filter_map(|(symbol, item)| Some((item, *sizes.get(symbol)?)))
+        .filter(|&(_, size)| size > 0)
+        .collect();
+    instantiations.sort_by_key(|&(item, size)| (cmp::Reverse(size), &item.name));
+
+    let mut crates: FxIndexMap<&str, u64> = FxIndexMap::default();
+    let mut definitions: FxIndexMap<&str, (&str, usize, u64)> = FxIndexMap::default();
+    for &(item, size) in &instantiations {
+        *crates.entry(&item.krate).or_default() += size;
+        let definition = definitions.entry(&item.definition).or_insert((&item.krate, 0, 0));
+        definition.1 += 1;
+        definition.2 += size;
+    }
+    crates.sort_by(|a_name, a_size, b_name, b_size| (b_size, a_name).cmp(&(a_size, b_name)));
+    definitions.sort_by(|a_name, a, b_name, b| (b.2, a_name).cmp(&(a.2, b_name)));
+
+    match format {
+        DumpMonoStatsFormat::Json => {
+            let report = json!({
+                "crates": crates
+                    .iter()
+                    .map(|(name, size)| json!({ "name": name, "size": size }))
+                    .collect::<Vec<_>>(),
+                "definitions": definitions
+                    .iter()
+                    .map(|(name, (krate, instantiations, size))| json!({
+                        "name": name,
+                        "crate": krate,
+                        "instantiations": instantiations,
+                        "size": size,
+                    }))
+                    .collect::<Vec<_>>(),
+                "instantiations": instantiations
+                    .iter()
+                    .map(|(item, size)| json!({
+                        "name": item.name,
+                        "definition": item.definition,
+                        "crate": item.krate,
+                        "cgu": item.cgu,
+                        "size": size,
+                        "callers": item.callers,
+                    }))
+                    .collect::<Vec<_>>(),
+            });
+            format!("{report}\n")
+        }
+        DumpMonoStatsFormat::Markdown => {
+            let mut out = String::new();
+            writeln!(out, "| Crate | Size |").unwrap();
+            writeln!(out, "| --- | ---: |").unwrap();
+            for (name, size) in &crates {
+                writeln!(out, "| `{name}` | {size} |").unwrap();
+            }
+
+            writeln!(out, "\n| Definition | Crate | Instantiations | Size |").unwrap();
+            writeln!(out, "| --- | --- | ---: | ---: |").unwrap();
+            for (name, (krate, instantiations, size)) in &definitions {
+                writeln!(out, "| `{name}` | `{krate}` | {instantiations} | {size} |").unwrap();
+            }
+
+            writeln!(out, "\n| Instantiation | Size | Instantiated by |").unwrap();
+            writeln!(out, "| --- | ---: | --- |").unwrap();
+            for (item, size) in &instantiations {
+                let callers = item.callers.iter().map(|caller| format!("`{caller}`"));
+                let callers = callers.collect::<Vec<_>>().join(", ");
+                writeln!(out, "| `{}` | {size} | {callers} |", item.name).unwrap();
+            }
+            out
+        }
+    }
+}
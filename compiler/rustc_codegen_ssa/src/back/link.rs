@@ -46,10 +46,12 @@
 use tracing::{debug, info, warn};
 
 use super::archive::{ArchiveBuilder, ArchiveBuilderBuilder};
+use super::code_size;
 use super::command::Command;
 use super::linker::{self, Linker};
 use super::metadata::{create_wrapper_file, MetadataPosition};
 use super::rpath::{self, RPathConfig};
+use super::stack_usage;
 use crate::{
     common, errors, looks_like_rust_object_file, CodegenResults, CompiledModule, CrateInfo,
     NativeLib,
@@ -73,6 +75,20 @@ pub fn link_binary(
 ) -> Result<(), ErrorGuaranteed> {
     let _timer = sess.timer("link_binary");
     let output_metadata = sess.opts.output_types.contains_key(&OutputType::Metadata);
+
+    for print in &sess.opts.prints {
+        match print.kind {
+            PrintKind::StackUsage => {
+                print.out.overwrite(&stack_usage::report(codegen_results), sess);
+            }
+            PrintKind::CodeSize => {
+                let format = sess.opts.unstable_opts.dump_mono_stats_format;
+                print.out.overwrite(&code_size::report(codegen_results, format), sess);
+            }
+            _ => {}
+        }
+    }
+
     let mut tempfiles_for_stdout_output: Vec<PathBuf> = Vec::new();
     for &crate_type in &codegen_results.crate_info.crate_types {
         // Ignore executable crates if we have -Z no-codegen, as they will error.
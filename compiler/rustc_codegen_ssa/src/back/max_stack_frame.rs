@@ -0,0 +1,81 @@
+//! `-Z max-stack-frame`: an error for every function whose stack frame is larger than a limit.
+//!
+//! Unlike estimates from the MIR locals, the frame sizes checked here are the ones computed by the
+//! backend after optimizations and inlining, read from the `.stack_sizes` sections of the object
+//! files like for `--print stack-usage`. The limit of each function, which `#[max_stack_frame]`
+//! can override, is recorded during codegen and carried to the end of codegen in the `CrateInfo`.
+
+use rustc_data_structures::fx::FxIndexMap;
+use rustc_macros::{Decodable, Encodable};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, InstanceKind, TyCtxt};
+use rustc_session::Session;
+use rustc_span::Span;
+
+use super::stack_usage::frame_sizes;
+use crate::errors::StackFrameTooLarge;
+use crate::CompiledModule;
+
+/// The limits of the functions of the crate, keyed by symbol name.
+#[derive(Debug, Encodable, Decodable)]
+pub struct FrameLimits {
+    functions: FxIndexMap<String, Function>,
+}
+
+#[derive(Debug, Encodable, Decodable)]
+struct Function {
+    /// The name of the function for the error, as symbol names are mangled.
+    name: String,
+    span: Span,
+    limit: u64,
+}
+
+impl FrameLimits {
+    pub fn new(tcx: TyCtxt<'_>, default: u64) -> FrameLimits {
+        let mut functions = FxIndexMap::default();
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            for item in cgu.items().keys() {
+                // Shims are left out, they have no span to point at and no attributes.
+                let MonoItem::Fn(instance @ Instance { def: InstanceKind::Item(def_id), .. }) =
+                    *item
+                else {
+                    continue;
+                };
+                let symbol = tcx.symbol_name(instance).name;
+                if functions.contains_key(symbol) {
+                    continue;
+                }
+                let limit = tcx.codegen_fn_attrs(def_id).max_stack_frame.unwrap_or(default);
+                let name = with_no_trimmed_paths!(instance.to_string());
+                let span = tcx.def_span(def_id);
+                functions.insert(symbol.to_string(), Function { name, span, limit });
+            }
+        }
+        FrameLimits { functions }
+    }
+}
+
+/// Reports the functions of `modules` whose frame is larger than their limit, in source order.
+/// Functions that were inlined everywhere have no frame, and are not reported.
+pub fn check(sess: &Session, limits: &FrameLimits, modules: &[CompiledModule]) {
+    let frames = frame_sizes(modules);
+    let mut too_large: Vec<_> = limits
+        .functions
+        .iter()
+        .filter_map(|(symbol, function)| {
+            let size = *frames.get(symbol)?;
+            (size > function.limit).then_some((function, size))
+        })
+        .collect();
+    too_large.sort_by(|(a, _), (b, _)| a.span.cmp(&b.span).then_with(|| a.name.cmp(&b.name)));
+
+    for (function, size) in too_large {
+        sess.dcx().emit_err(StackFrameTooLarge {
+            span: function.span,
+            function: function.name.clone(),
+            size,
+            limit: function.limit,
+        });
+    }
+}
@@ -1,9 +1,12 @@
 pub mod archive;
+pub mod code_size;
 pub mod command;
 pub mod link;
 pub mod linker;
 pub mod lto;
+pub mod max_stack_frame;
 pub mod metadata;
 pub mod rpath;
+pub mod stack_usage;
 pub mod symbol_export;
 pub mod write;
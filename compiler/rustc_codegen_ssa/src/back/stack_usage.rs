@@ -0,0 +1,305 @@
+//! `--print stack-usage`: the worst-case stack depth of every entry point of the program.
+//!
+//! LLVM records the frame size of each function in `.stack_sizes` sections of the object files.
+//! Those are combined here with the call graph of the mono items, which is computed during
+//! codegen (while the `TyCtxt` is still around) and carried to the linker in the `CrateInfo`.
+//!
+//! Functions that LLVM inlined everywhere have no frame of their own, their callees are counted
+//! as callees of the caller instead. Recursion and indirect calls make the depth unbounded, and
+//! calls to functions outside of the call graph (other crates, C code) make the result a lower
+//! bound.
+
+use std::fmt::Write;
+use std::fs;
+
+use object::{Object, ObjectSection, ObjectSymbol, RelocationTarget, SectionIndex, SymbolKind};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap};
+use rustc_data_structures::stack::ensure_sufficient_stack;
+use rustc_macros::{Decodable, Encodable};
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::TyCtxt;
+use rustc_monomorphize::call_graph::{self, Callee};
+
+use crate::{CodegenResults, CompiledModule};
+
+/// The call graph of the mono items of the crate, keyed by symbol name.
+#[derive(Debug, Encodable, Decodable)]
+pub struct CallGraph {
+    functions: FxIndexMap<String, Function>,
+}
+
+#[derive(Debug, Encodable, Decodable)]
+struct Function {
+    /// The name of the function for the report, as symbol names are mangled.
+    name: String,
+    /// The symbol names of the functions called directly.
+    callees: Vec<String>,
+    /// Whether the function also calls through function pointers or vtables.
+    indirect_calls: bool,
+}
+
+impl CallGraph {
+    pub fn new(tcx: TyCtxt<'_>) -> CallGraph {
+        let mut functions = FxIndexMap::default();
+        for cgu in tcx.collect_and_partition_mono_items(()).1 {
+            for item in cgu.items().keys() {
+                let MonoItem::Fn(instance) = *item else { continue };
+                let symbol = tcx.symbol_name(instance).name;
+                if functions.contains_key(symbol) {
+                    continue;
+                }
+
+                let mut callees = Vec::new();
+                let mut indirect_calls = false;
+                for callee in call_graph::callees(tcx, instance) {
+                    match callee {
+                        Callee::Direct(callee) => {
+                            callees.push(tcx.symbol_name(callee).name.to_string())
+                        }
+                        Callee::Indirect => indirect_calls = true,
+                    }
+                }
+                let name = with_no_trimmed_paths!(instance.to_string());
+                functions.insert(symbol.to_string(), Function { name, callees, indirect_calls });
+            }
+        }
+        CallGraph { functions }
+    }
+}
+
+/// Why the stack depth below a function can't be bounded.
+#[derive(Clone, Copy)]
+enum Unbounded<'a> {
+    /// The function is part of a cycle in the call graph.
+    Recursion(&'a str),
+    /// The function calls through a function pointer or vtable.
+    IndirectCall(&'a str),
+}
+
+#[derive(Clone, Copy, Default)]
+struct Usage<'a> {
+    /// The deepest stack usage found, including the frame of the function itself.
+    bytes: u64,
+    /// The callee on the deepest path.
+    deepest: Option<&'a str>,
+    unbounded: Option<Unbounded<'a>>,
+    /// A function reached from here whose stack usage is unknown.
+    unknown: Option<&'a str>,
+}
+
+struct Analysis<'a> {
+    graph: &'a CallGraph,
+    frames: &'a FxHashMap<String, u64>,
+    usages: FxHashMap<&'a str, Usage<'a>>,
+    /// The functions currently being visited, to detect recursion.
+    visiting: Vec<&'a str>,
+}
+
+impl<'a> Analysis<'a> {
+    fn usage(&mut self, symbol: &'a str) -> Usage<'a> {
+        if let Some(&usage) = self.usages.get(symbol) {
+            return usage;
+        }
+        if self.visiting.contains(&symbol) {
+            return Usage { unbounded: Some(Unbounded::Recursion(symbol)), ..Usage::default() };
+        }
+
+        let frame = self.frames.get(symbol).copied();
+        let Some(function) = self.graph.functions.get(symbol) else {
+            // Either a function from another crate or from C, or compiler-generated code like the
+            // `main` shim. Only the latter has a known frame size, and no known callees.
+            let unknown = if frame.is_none() { Some(symbol) } else { None };
+            let usage = Usage { bytes: frame.unwrap_or(0), unknown, ..Usage::default() };
+            self.usages.insert(symbol, usage);
+            return usage;
+        };
+
+        self.visiting.push(symbol);
+        let mut usage = Usage::default();
+        for callee in &function.callees {
+            let callee_usage = ensure_sufficient_stack(|| self.usage(callee));
+            if callee_usage.bytes > usage.bytes {
+                usage.bytes = callee_usage.bytes;
+                usage.deepest = Some(callee);
+            }
+            usage.unbounded = usage.unbounded.or(callee_usage.unbounded);
+            usage.unknown = usage.unknown.or(callee_usage.unknown);
+        }
+        self.visiting.pop();
+
+        if function.indirect_calls {
+            usage.unbounded = usage.unbounded.or(Some(Unbounded::IndirectCall(symbol)));
+        }
+        // Without a frame the function was inlined into all of its callers.
+        usage.bytes += frame.unwrap_or(0);
+        self.usages.insert(symbol, usage);
+        usage
+    }
+
+    fn name(&self, symbol: &'a str) -> &'a str {
+        self.graph.functions.get(symbol).map_or(symbol, |function| &function.name)
+    }
+}
+
+/// Reads the frame sizes recorded by LLVM from the `.stack_sizes` sections of the object files.
+pub(crate) fn frame_sizes<'a>(
+    modules: impl IntoIterator<Item = &'a CompiledModule>,
+) -> FxHashMap<String, u64> {
+    let mut frames = FxHashMap::default();
+    for path in modules.into_iter().filter_map(|module| module.object.as_ref()) {
+        let Ok(data) = fs::read(path) else { continue };
+        let Ok(file) = object::File::parse(&*data) else { continue };
+        read_stack_sizes(&file, &mut frames);
+    }
+    frames
+}
+
+fn read_stack_sizes<'data>(file: &object::File<'data>, frames: &mut FxHashMap<String, u64>) {
+    // Relocations against local functions may refer to the section of the function instead.
+    let functions: FxHashMap<(SectionIndex, u64), &str> = file
+        .symbols()
+        .filter(|symbol| symbol.kind() == SymbolKind::Text)
+        .filter_map(|symbol| {
+            Some(((symbol.section_index()?, symbol.address()), symbol.name().ok()?))
+        })
+        .collect();
+    let address_size = if file.is_64() { 8 } else { 4 };
+
+    for section in file.sections().filter(|section| section.name() == Ok(".stack_sizes")) {
+        let Ok(data) = section.data() else { continue };
+        let relocations: FxHashMap<u64, object::Relocation> = section.relocations().collect();
+
+        // Each entry is the address of a function followed by its frame size as ULEB128.
+        let mut offset = 0;
+        while offset + address_size <= data.len() {
+            let function = relocations.get(&(offset as u64)).and_then(|relocation| {
+                let RelocationTarget::Symbol(index) = relocation.target() else { return None };
+                let symbol = file.symbol_by_index(index).ok()?;
+                if symbol.kind() == SymbolKind::Section {
+                    let addend = if relocation.has_implicit_addend() {
+                        let bytes = &data[offset..offset + address_size];
+                        let read = |addend, &byte| addend << 8 | u64::from(byte);
+                        if file.is_little_endian() {
+                            bytes.iter().rev().fold(0, read)
+                        } else {
+                            bytes.iter().fold(0, read)
+                        }
+                    } else {
+                        relocation.addend() as u64
+                    };
+                    functions.get(&(symbol.section_index()?, addend)).copied()
+                } else {
+                    symbol.name().ok()
+                }
+            });
+            offset += address_size;
+
+            let mut size = 0u64;
+            let mut shift = 0;
+            while let Some(&byte) = data.get(offset) {
+                offset += 1;
+                size |= u64::from(byte & 0x7f) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+            }
+
+            if let Some(function) = function {
+                let frame = frames.entry(function.to_string()).or_default();
+                *frame = (*frame).max(size);
+            }
+        }
+    }
+}
+
+/// Produces the `--print stack-usage` report.
+///
+/// The roots are the functions that are not called directly by any other function of the crate:
+/// `main`, exported functions such as interrupt handlers, and the functions that are only called
+/// indirectly, such as the closures passed to `thread::spawn`. A function calling itself is still
+/// a root, and so is the first function, by name, of a cycle that is not called from outside.
+pub fn report(codegen_results: &CodegenResults) -> String {
+    let Some(graph) = &codegen_results.crate_info.stack_usage else {
+        return String::new();
+    };
+    let frames =
+        frame_sizes(codegen_results.modules.iter().chain(&codegen_results.allocator_module));
+    let mut analysis =
+        Analysis { graph, frames: &frames, usages: Default::default(), visiting: Vec::new() };
+
+    let mut called: Vec<&str> = graph
+        .functions
+        .iter()
+        .flat_map(|(symbol, function)| {
+            function.callees.iter().filter(move |&callee| callee != symbol)
+        })
+        .map(|callee| &**callee)
+        .collect();
+    called.sort_unstable();
+    let mut roots: Vec<&str> = graph
+        .functions
+        .keys()
+        .map(|symbol| &**symbol)
+        .filter(|symbol| called.binary_search(symbol).is_err())
+        .collect();
+    for &root in &roots {
+        analysis.usage(root);
+    }
+    // Whatever the roots do not reach is part of, or called from, a cycle that no other function
+    // calls. Visiting a function of such a cycle reaches the rest of it.
+    let mut unreached: Vec<&str> = graph
+        .functions
+        .keys()
+        .map(|symbol| &**symbol)
+        .filter(|symbol| !analysis.usages.contains_key(symbol))
+        .collect();
+    unreached.sort_by_key(|&symbol| analysis.name(symbol));
+    for symbol in unreached {
+        if !analysis.usages.contains_key(symbol) {
+            analysis.usage(symbol);
+            roots.push(symbol);
+        }
+    }
+    roots.sort_by_key(|&symbol| analysis.name(symbol));
+
+    let mut out = String::new();
+    for root in roots {
+        let usage = analysis.usage(root);
+        let name = analysis.name(root);
+        match usage.unbounded {
+            Some(Unbounded::Recursion(function)) => {
+                let function = analysis.name(function);
+                writeln!(out, "{name}: unbounded, `{function}` is recursive").unwrap();
+            }
+            Some(Unbounded::IndirectCall(function)) => {
+                let function = analysis.name(function);
+                writeln!(
+                    out,
+                    "{name}: unbounded, `{function}` calls through a function pointer or vtable",
+                )
+                .unwrap();
+            }
+            None if let Some(function) = usage.unknown => {
+                let function = analysis.name(function);
+                writeln!(
+                    out,
+                    "{name}: at least {} bytes, the stack usage of `{function}` is unknown",
+                    usage.bytes,
+                )
+                .unwrap();
+            }
+            None => writeln!(out, "{name}: {} bytes", usage.bytes).unwrap(),
+        }
+
+        // The deepest path, with the frame size of every function on it.
+        let mut function = Some(root);
+        while let Some(symbol) = function {
+            let frame = frames.get(symbol).copied().unwrap_or(0);
+            writeln!(out, "    {frame:>6}  {}", analysis.name(symbol)).unwrap();
+            function = analysis.usages.get(symbol).and_then(|usage| usage.deepest);
+        }
+    }
+    out
+}
@@ -41,6 +41,7 @@
 
 use super::link::{self, ensure_removed};
 use super::lto::{self, SerializedModule};
+use super::max_stack_frame;
 use super::symbol_export::symbol_name_for_instance_in_crate;
 use crate::errors::ErrorCreatingRemarkDir;
 use crate::traits::*;
@@ -2060,6 +2061,13 @@ pub fn join(self, sess: &Session) -> (CodegenResults, FxIndexMap<WorkProductId,
 
         sess.dcx().abort_if_errors();
 
+        // Before the object files are removed by `produce_final_output_artifacts`.
+        if let Some(limits) = &self.crate_info.max_stack_frame {
+            sess.time("check_max_stack_frame", || {
+                max_stack_frame::check(sess, limits, &compiled_modules.modules)
+            });
+        }
+
         let work_products =
             copy_all_cgu_workproducts_to_incr_comp_cache_dir(sess, &compiled_modules);
         produce_final_output_artifacts(sess, &compiled_modules, &self.output_filenames);
@@ -1,9 +1,12 @@
+use std::borrow::Cow;
 use std::cmp;
 use std::collections::BTreeSet;
+use std::fmt::Write;
 use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use rustc_ast::expand::allocator::{global_fn_name, AllocatorKind, ALLOCATOR_METHODS};
+use rustc_ast::{InlineAsmOptions, InlineAsmTemplatePiece};
 use rustc_attr as attr;
 use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
 use rustc_data_structures::profiling::{get_resident_set_size, print_time_passes_entry};
@@ -22,7 +25,7 @@
 use rustc_middle::query::Providers;
 use rustc_middle::ty::layout::{HasTyCtxt, LayoutOf, TyAndLayout};
 use rustc_middle::ty::{self, Instance, Ty, TyCtxt};
-use rustc_session::config::{self, CrateType, EntryFnType, OptLevel, OutputType};
+use rustc_session::config::{self, CrateType, EntryFnType, OptLevel, OutputType, PrintKind};
 use rustc_session::Session;
 use rustc_span::symbol::sym;
 use rustc_span::{Symbol, DUMMY_SP};
@@ -30,8 +33,11 @@
 use tracing::{debug, info};
 
 use crate::assert_module_sources::CguReuse;
+use crate::back::code_size::MonoItems;
 use crate::back::link::are_upstream_rust_objects_already_included;
+use crate::back::max_stack_frame::FrameLimits;
 use crate::back::metadata::create_compressed_metadata_file;
+use crate::back::stack_usage::CallGraph;
 use crate::back::write::{
     compute_per_cgu_lto_type, start_async_codegen, submit_codegened_module_to_llvm,
     submit_post_lto_module_to_llvm, submit_pre_lto_module_to_llvm, ComputedLtoType, OngoingCodegen,
@@ -385,6 +391,44 @@ pub fn codegen_instance<'a, 'tcx: 'a, Bx: BuilderMethods<'a, 'tcx>>(
     mir::codegen_mir::<Bx>(cx, instance);
 }
 
+/// With `-Z deferred-strings`, emits the table of the strings replaced by references so far.
+///
+/// Each string is placed in its own `.rust_deferred_strings` section, in a COMDAT group named after
+/// its hash so that the copies from every codegen unit and crate are merged by the linker. The
+/// section is not allocated, so it is kept in the ELF file but not loaded on the target. An entry
+/// is the 64-bit hash in little endian, the length of the string as ULEB128, and the string.
+pub fn codegen_deferred_strings<'tcx, Cx: CodegenMethods<'tcx>>(cx: &Cx) {
+    let sess = cx.tcx().sess;
+    if !sess.opts.unstable_opts.deferred_strings {
+        return;
+    }
+    let strings = sess.deferred_strings.lock();
+    if strings.is_empty() {
+        return;
+    }
+
+    let mut asm = String::new();
+    for (hash, text) in strings.iter() {
+        let text = text.as_str();
+        let section = ".rust_deferred_strings,\"G\",%progbits";
+        writeln!(asm, ".pushsection {section},__rust_deferred_string_{hash:016x},comdat").unwrap();
+        let bytes = hash.to_le_bytes().into_iter().chain(text.bytes());
+        let bytes = bytes.map(|byte| byte.to_string()).collect::<Vec<_>>();
+        writeln!(asm, ".byte {}", bytes[..8].join(",")).unwrap();
+        writeln!(asm, ".uleb128 {}", text.len()).unwrap();
+        for chunk in bytes[8..].chunks(32) {
+            writeln!(asm, ".byte {}", chunk.join(",")).unwrap();
+        }
+        writeln!(asm, ".popsection").unwrap();
+    }
+    cx.codegen_global_asm(
+        &[InlineAsmTemplatePiece::String(Cow::Owned(asm))],
+        &[],
+        InlineAsmOptions::ATT_SYNTAX,
+        &[],
+    );
+}
+
 /// Creates the `main` function which will initialize the rust runtime and call
 /// users main function.
 pub fn maybe_create_entry_wrapper<'a, 'tcx, Bx: BuilderMethods<'a, 'tcx>>(
@@ -895,6 +939,26 @@ pub fn new(tcx: TyCtxt<'_>, target_cpu: String) -> CrateInfo {
             dependency_formats: tcx.dependency_formats(()).clone(),
             windows_subsystem,
             natvis_debugger_visualizers: Default::default(),
+            code_size: tcx
+                .sess
+                .opts
+                .prints
+                .iter()
+                .any(|print| print.kind == PrintKind::CodeSize)
+                .then(|| MonoItems::new(tcx)),
+            stack_usage: tcx
+                .sess
+                .opts
+                .prints
+                .iter()
+                .any(|print| print.kind == PrintKind::StackUsage)
+                .then(|| CallGraph::new(tcx)),
+            max_stack_frame: tcx
+                .sess
+                .opts
+                .unstable_opts
+                .max_stack_frame
+                .map(|limit| FrameLimits::new(tcx, limit)),
         };
 
         info.native_libraries.reserve(n_crates);
@@ -1,3 +1,5 @@
+use std::slice;
+
 use rustc_ast::{ast, attr, MetaItemKind, NestedMetaItem};
 use rustc_attr::{list_contains_name, InlineAttr, InstructionSetAttr, OptimizeAttr};
 use rustc_data_structures::fx::FxHashSet;
@@ -9,8 +11,9 @@
 use rustc_hir::weak_lang_items::WEAK_LANG_ITEMS;
 use rustc_hir::{lang_items, LangItem};
 use rustc_middle::middle::codegen_fn_attrs::{
-    CodegenFnAttrFlags, CodegenFnAttrs, PatchableFunctionEntry, TargetFeature,
+    CodegenFnAttrFlags, CodegenFnAttrs, LinkRegion, PatchableFunctionEntry, TargetFeature,
 };
+use rustc_middle::middle::limits::get_limit_size;
 use rustc_middle::mir::mono::Linkage;
 use rustc_middle::query::Providers;
 use rustc_middle::ty::{self as ty, TyCtxt};
@@ -118,6 +121,11 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, did: LocalDefId) -> CodegenFnAttrs {
                 codegen_fn_attrs.flags |= CodegenFnAttrFlags::ALLOCATOR_ZEROED
             }
             sym::naked => codegen_fn_attrs.flags |= CodegenFnAttrFlags::NAKED,
+            sym::no_panic => codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_PANIC,
+            sym::interrupt_context => {
+                codegen_fn_attrs.flags |= CodegenFnAttrFlags::INTERRUPT_CONTEXT
+            }
+            sym::may_block => codegen_fn_attrs.flags |= CodegenFnAttrFlags::MAY_BLOCK,
             sym::no_mangle => {
                 if tcx.opt_item_name(did.to_def_id()).is_some() {
                     codegen_fn_attrs.flags |= CodegenFnAttrFlags::NO_MANGLE
@@ -343,7 +351,21 @@ fn codegen_fn_attrs(tcx: TyCtxt<'_>, did: LocalDefId) -> CodegenFnAttrs {
                     }
                 }
             }
+            sym::link_region => {
+                // Misuse is reported by `rustc_passes::check_attr`.
+                codegen_fn_attrs.link_region = attr.meta_item_list().and_then(|l| match &l[..] {
+                    [NestedMetaItem::MetaItem(region)] => {
+                        LinkRegion::from_symbol(region.name_or_empty())
+                    }
+                    _ => None,
+                });
+            }
             sym::link_name => codegen_fn_attrs.link_name = attr.value_str(),
+            sym::max_stack_frame => {
+                codegen_fn_attrs.max_stack_frame =
+                    get_limit_size(slice::from_ref(attr), tcx.sess, sym::max_stack_frame)
+                        .map(|limit| limit as u64);
+            }
             sym::link_ordinal => {
                 link_ordinal_span = Some(attr.span);
                 if let ordinal @ Some(_) = check_link_ordinal(tcx, attr) {
@@ -741,6 +763,18 @@ fn emit_error_with_label(
         codegen_fn_attrs.inline = InlineAttr::Never;
     }
 
+    // Closures defined in code placed in RAM are most likely called from it, so they are placed
+    // in the same region unless they are going to be inlined anyway.
+    if codegen_fn_attrs.link_region.is_none()
+        && tcx.is_closure_like(did.to_def_id())
+        && codegen_fn_attrs.inline != InlineAttr::Always
+    {
+        let owner_id = tcx.parent(did.to_def_id());
+        if tcx.def_kind(owner_id).has_codegen_attrs() {
+            codegen_fn_attrs.link_region = tcx.codegen_fn_attrs(owner_id).link_region;
+        }
+    }
+
     // Weak lang items have the same semantics as "std internal" symbols in the
     // sense that they're preserved through all our LTO passes and only
     // strippable by the linker.
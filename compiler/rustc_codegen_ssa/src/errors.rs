@@ -462,6 +462,18 @@ pub struct UnableToRun<'a> {
 #[diag(codegen_ssa_linker_file_stem)]
 pub struct LinkerFileStem;
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_stack_frame_too_large)]
+#[help]
+pub struct StackFrameTooLarge {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub function: String,
+    pub size: u64,
+    pub limit: u64,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_static_library_native_artifacts)]
 pub struct StaticLibraryNativeArtifacts;
@@ -1017,6 +1029,14 @@ pub struct TargetFeatureSafeTrait {
     pub def: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_xtensa_windowed_per_function)]
+#[help]
+pub struct XtensaWindowedPerFunction {
+    #[primary_span]
+    pub span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(codegen_ssa_failed_to_get_layout)]
 pub struct FailedToGetLayout<'tcx> {
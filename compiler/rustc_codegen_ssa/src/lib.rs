@@ -198,6 +198,12 @@ pub struct CrateInfo {
     pub dependency_formats: Lrc<Dependencies>,
     pub windows_subsystem: Option<String>,
     pub natvis_debugger_visualizers: BTreeSet<DebuggerVisualizerFile>,
+    /// Only computed for `--print code-size`.
+    pub code_size: Option<back::code_size::MonoItems>,
+    /// Only computed for `--print stack-usage`.
+    pub stack_usage: Option<back::stack_usage::CallGraph>,
+    /// Only computed for `-Z max-stack-frame`.
+    pub max_stack_frame: Option<back::max_stack_frame::FrameLimits>,
 }
 
 #[derive(Encodable, Decodable)]
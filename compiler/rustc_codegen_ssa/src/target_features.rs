@@ -84,6 +84,7 @@ pub fn from_target_feature(
                 Some(sym::x86_amx_intrinsics) => rust_features.x86_amx_intrinsics,
                 Some(sym::xop_target_feature) => rust_features.xop_target_feature,
                 Some(sym::s390x_target_feature) => rust_features.s390x_target_feature,
+                Some(sym::xtensa_target_feature) => rust_features.xtensa_target_feature,
                 Some(name) => bug!("unknown target feature gate {}", name),
                 None => true,
             };
@@ -96,6 +97,12 @@ pub fn from_target_feature(
                 )
                 .emit();
             }
+            // Register windows decide the calling convention, so toggling them for a single
+            // function would make it uncallable from the rest of the crate graph.
+            if feature == "windowed" && tcx.sess.target.arch == "xtensa" {
+                tcx.dcx().emit_err(errors::XtensaWindowedPerFunction { span: item.span() });
+                return None;
+            }
             Some(Symbol::intern(feature))
         }));
     }
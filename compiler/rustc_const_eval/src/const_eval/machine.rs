@@ -241,6 +241,8 @@ fn hook_special_const_fn(
             }
 
             let msg = Symbol::intern(self.read_str(&msg_place)?);
+            // With `-Z deferred-strings`, the message may have been replaced by a reference.
+            let msg = self.tcx.sess.resolve_deferred_string(msg);
             let span = self.find_closest_untracked_caller_location();
             let (file, line, col) = self.location_triple_for_span(span);
             return Err(ConstEvalErrKind::Panic { msg, file, line, col }.into());
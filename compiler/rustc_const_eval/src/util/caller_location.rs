@@ -20,6 +20,9 @@ fn alloc_caller_location<'tcx>(
     // This can fail if rustc runs out of memory right here. Trying to emit an error would be
     // pointless, since that would require allocating more memory than these short strings.
     let file = if loc_details.file {
+        // With `-Z deferred-strings`, the file name is replaced by a reference into the table of
+        // deferred strings.
+        let filename = ecx.tcx.sess.defer_string(filename.as_str()).unwrap_or(filename);
         ecx.allocate_str(filename.as_str(), MemoryKind::CallerLocation, Mutability::Not).unwrap()
     } else {
         // FIXME: This creates a new allocation each time. It might be preferable to
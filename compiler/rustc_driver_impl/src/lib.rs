@@ -704,9 +704,11 @@ fn print_crate_info(
     #[allow(unused_imports)]
     use {do_not_use_safe_print as safe_print, do_not_use_safe_print as safe_println};
 
-    // NativeStaticLibs and LinkArgs are special - printed during linking
+    // NativeStaticLibs, LinkArgs, CodeSize and StackUsage are special - printed during linking
     // (empty iterator returns true)
-    if sess.opts.prints.iter().all(|p| p.kind == NativeStaticLibs || p.kind == LinkArgs) {
+    if sess.opts.prints.iter().all(|p| {
+        p.kind == NativeStaticLibs || p.kind == LinkArgs || p.kind == CodeSize || p.kind == StackUsage
+    }) {
         return Compilation::Continue;
     }
 
@@ -858,6 +860,8 @@ fn print_crate_info(
             // Any output here interferes with Cargo's parsing of other printed output
             NativeStaticLibs => {}
             LinkArgs => {}
+            CodeSize => {}
+            StackUsage => {}
             SplitDebuginfo => {
                 use rustc_target::spec::SplitDebuginfo::{Off, Packed, Unpacked};
 
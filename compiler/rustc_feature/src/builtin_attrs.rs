@@ -555,6 +555,26 @@ pub struct BuiltinAttribute {
         cmse_nonsecure_entry, Normal, template!(Word), WarnFollowing,
         EncodeCrossCrate::No, experimental!(cmse_nonsecure_entry)
     ),
+    gated!(
+        link_region, Normal, template!(List: "iram|dram|rtc_fast|rtc_slow"), ErrorPreceding,
+        EncodeCrossCrate::No, experimental!(link_region)
+    ),
+    gated!(
+        no_panic, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::No,
+        experimental!(no_panic)
+    ),
+    gated!(
+        interrupt_context, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::No,
+        experimental!(interrupt_context)
+    ),
+    gated!(
+        may_block, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::No,
+        interrupt_context, experimental!(may_block)
+    ),
+    gated!(
+        max_stack_frame, Normal, template!(NameValueStr: "N"), ErrorFollowing,
+        EncodeCrossCrate::No, experimental!(max_stack_frame)
+    ),
     // RFC 2632
     gated!(
         const_trait, Normal, template!(Word), WarnFollowing, EncodeCrossCrate::No, const_trait_impl,
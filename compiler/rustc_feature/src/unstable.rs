@@ -321,6 +321,7 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, sse4a_target_feature, "1.27.0", Some(44839)),
     (unstable, tbm_target_feature, "1.27.0", Some(44839)),
     (unstable, wasm_target_feature, "1.30.0", Some(44839)),
+    (unstable, xtensa_target_feature, "CURRENT_RUSTC_VERSION", Some(44839)),
     // !!!!    !!!!    !!!!    !!!!   !!!!    !!!!    !!!!    !!!!    !!!!    !!!!    !!!!
     // Features are listed in alphabetical order. Tidy will fail if you don't keep it this way.
     // !!!!    !!!!    !!!!    !!!!   !!!!    !!!!    !!!!    !!!!    !!!!    !!!!    !!!!
@@ -345,6 +346,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, abi_riscv_interrupt, "1.73.0", Some(111889)),
     /// Allows `extern "x86-interrupt" fn()`.
     (unstable, abi_x86_interrupt, "1.17.0", Some(40180)),
+    /// Allows `extern "xtensa-interrupt" fn()` and the `xtensa-interrupt-<level>` variants.
+    (unstable, abi_xtensa_interrupt, "CURRENT_RUSTC_VERSION", None),
     /// Allows additional const parameter types, such as `[u8; 10]` or user defined types
     (unstable, adt_const_params, "1.56.0", Some(95174)),
     /// Allows defining an `#[alloc_error_handler]`.
@@ -503,6 +506,9 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (incomplete, inherent_associated_types, "1.52.0", Some(8995)),
     /// Allow anonymous constants from an inline `const` block in pattern position
     (unstable, inline_const_pat, "1.58.0", Some(76001)),
+    /// Allows checking that interrupt handlers can't block with `#[interrupt_context]` and
+    /// `#[may_block]`.
+    (unstable, interrupt_context, "CURRENT_RUSTC_VERSION", None),
     /// Allows using `pointer` and `reference` in intra-doc links
     (unstable, intra_doc_pointers, "1.51.0", Some(80896)),
     // Allows setting the threshold for the `large_assignments` lint.
@@ -514,12 +520,16 @@ pub fn internal(&self, feature: Symbol) -> bool {
     /// Allows using `#[link(kind = "link-arg", name = "...")]`
     /// to pass custom arguments to the linker.
     (unstable, link_arg_attribute, "1.76.0", Some(99427)),
+    /// Allows placing functions and statics into a target memory region with `#[link_region]`.
+    (unstable, link_region, "CURRENT_RUSTC_VERSION", None),
     /// Give access to additional metadata about declarative macro meta-variables.
     (unstable, macro_metavar_expr, "1.61.0", Some(83527)),
     /// Provides a way to concatenate identifiers using metavariable expressions.
     (unstable, macro_metavar_expr_concat, "1.81.0", Some(124225)),
     /// Allows `#[marker]` on certain traits allowing overlapping implementations.
     (unstable, marker_trait_attr, "1.30.0", Some(29864)),
+    /// Allows overriding the limit of `-Z max-stack-frame` with `#[max_stack_frame = "N"]`.
+    (unstable, max_stack_frame, "CURRENT_RUSTC_VERSION", None),
     /// A minimal, sound subset of specialization intended to be used by the
     /// standard library until the soundness issues with specialization
     /// are fixed.
@@ -544,6 +554,8 @@ pub fn internal(&self, feature: Symbol) -> bool {
     (unstable, never_type_fallback, "1.41.0", Some(65992)),
     /// Allows `#![no_core]`.
     (unstable, no_core, "1.3.0", Some(29639)),
+    /// Allows checking that a function can't panic with `#[no_panic]`.
+    (unstable, no_panic, "CURRENT_RUSTC_VERSION", None),
     /// Allows the use of `no_sanitize` attribute.
     (unstable, no_sanitize, "1.42.0", Some(39699)),
     /// Allows using the `non_exhaustive_omitted_patterns` lint.
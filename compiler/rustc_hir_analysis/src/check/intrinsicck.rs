@@ -10,7 +10,8 @@
 use rustc_span::Symbol;
 use rustc_target::abi::FieldIdx;
 use rustc_target::asm::{
-    InlineAsmReg, InlineAsmRegClass, InlineAsmRegOrRegClass, InlineAsmType, ModifierInfo,
+    InlineAsmArch, InlineAsmReg, InlineAsmRegClass, InlineAsmRegOrRegClass, InlineAsmType,
+    ModifierInfo,
 };
 
 pub struct InlineAsmCtxt<'a, 'tcx> {
@@ -58,6 +59,10 @@ fn get_asm_ty(&self, ty: Ty<'tcx>) -> Option<InlineAsmType> {
         };
 
         match *ty.kind() {
+            // The boolean registers of Xtensa hold a single bit.
+            ty::Bool if self.tcx.sess.asm_arch == Some(InlineAsmArch::Xtensa) => {
+                Some(InlineAsmType::I1)
+            }
             ty::Int(IntTy::I8) | ty::Uint(UintTy::U8) => Some(InlineAsmType::I8),
             ty::Int(IntTy::I16) | ty::Uint(UintTy::U16) => Some(InlineAsmType::I16),
             ty::Int(IntTy::I32) | ty::Uint(UintTy::U32) => Some(InlineAsmType::I32),
@@ -1650,6 +1650,24 @@ fn check_fn_or_method<'tcx>(
             );
         }
     }
+
+    // Xtensa interrupt handlers are entered directly from the interrupt vector, so
+    // there is nobody to pass arguments or receive a return value.
+    if let Abi::XtensaInterrupt { .. } = sig.abi {
+        if let Some(input) = hir_decl.inputs.first() {
+            tcx.dcx().span_err(
+                input.span,
+                format!("functions with the {} ABI cannot have arguments", sig.abi),
+            );
+        }
+        let output = sig.output();
+        if !output.is_unit() && !output.is_never() {
+            tcx.dcx().span_err(
+                hir_decl.output.span(),
+                format!("functions with the {} ABI cannot have a return type", sig.abi),
+            );
+        }
+    }
 }
 
 #[instrument(level = "debug", skip(wfcx))]
@@ -82,6 +82,7 @@
         PROC_MACRO_DERIVE_RESOLUTION_FALLBACK,
         PTR_CAST_ADD_AUTO_TO_OBJECT,
         PUB_USE_OF_PRIVATE_EXTERN_CRATE,
+        RAM_CODE_CALLS_FLASH,
         REDUNDANT_IMPORTS,
         REDUNDANT_LIFETIMES,
         REFINING_IMPL_TRAIT_INTERNAL,
@@ -3207,6 +3208,53 @@
     "detects large moves or copies",
 }
 
+declare_lint! {
+    /// The `ram_code_calls_flash` lint detects calls from functions placed in
+    /// IRAM or RTC fast memory with `#[link_region]` to functions that are
+    /// neither placed in one of those regions nor `#[inline(always)]`.
+    ///
+    /// ### Example
+    ///
+    /// ```rust,ignore (needs an ESP32 target)
+    /// #![feature(link_region)]
+    ///
+    /// fn helper() {}
+    ///
+    /// #[link_region(iram)]
+    /// extern "C" fn isr() {
+    ///     helper();
+    /// }
+    /// ```
+    ///
+    /// This will produce:
+    ///
+    /// ```text
+    /// warning: function in `iram` calls `helper`, which is not in RAM
+    ///  --> src/lib.rs:7:5
+    ///   |
+    /// 7 |     helper();
+    ///   |     ^^^^^^^^ `helper` is placed in flash
+    ///   |
+    ///   = note: code in `iram` can run while the flash cache is disabled, and calling into flash then crashes
+    /// ```
+    ///
+    /// ### Explanation
+    ///
+    /// Interrupt handlers and code running during flash operations execute
+    /// while the flash cache is disabled. Any call that ends up in flash at
+    /// that point crashes the chip. The callee may or may not be inlined, so
+    /// unless it is `#[inline(always)]` it has to be placed in RAM as well.
+    ///
+    /// The check is done during monomorphization, so calls to generic
+    /// functions are checked for the concrete callee. Calls through function
+    /// pointers or trait objects, and calls to foreign functions (which are
+    /// often in ROM), are not checked.
+    pub RAM_CODE_CALLS_FLASH,
+    Warn,
+    "detects calls from code placed in RAM to functions placed in flash",
+    @feature_gate = link_region;
+}
+
 declare_lint! {
     /// The `deprecated_cfg_attr_crate_type_name` lint detects uses of the
     /// `#![cfg_attr(..., crate_type = "...")]` and
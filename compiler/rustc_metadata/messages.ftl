@@ -116,6 +116,9 @@ metadata_incompatible_rustc =
 metadata_incompatible_wasm_link =
     `wasm_import_module` is incompatible with other arguments in `#[link]` attributes
 
+metadata_incompatible_xtensa_abi =
+    the crate `{$crate_name}` is compiled for the `{$found_abi}` Xtensa ABI which is incompatible with this crate's `{$desired_abi}` ABI
+
 metadata_install_missing_components =
     maybe you need to install the missing components with: `rustup component add rust-src rustc-dev llvm-tools-preview`
 
@@ -64,9 +64,9 @@
 
 use crate::creader::CStore;
 use crate::errors::{
-    BadPanicStrategy, CrateDepMultiple, IncompatiblePanicInDropStrategy, LibRequired,
-    NonStaticCrateDep, RequiredPanicStrategy, RlibRequired, RustcDriverHelp, RustcLibRequired,
-    TwoPanicRuntimes,
+    BadPanicStrategy, CrateDepMultiple, IncompatiblePanicInDropStrategy, IncompatibleXtensaAbi,
+    LibRequired, NonStaticCrateDep, RequiredPanicStrategy, RlibRequired, RustcDriverHelp,
+    RustcLibRequired, TwoPanicRuntimes,
 };
 
 pub(crate) fn calculate(tcx: TyCtxt<'_>) -> Dependencies {
@@ -397,6 +397,19 @@ fn verify_ok(tcx: TyCtxt<'_>, list: &[Linkage]) {
         }
         let cnum = CrateNum::new(i + 1);
 
+        // Windowed and call0 code cannot call each other, so the whole crate graph has to agree
+        // on the Xtensa ABI.
+        if let Some(desired_abi) = sess.xtensa_abi()
+            && let Some(found_abi) = tcx.xtensa_abi(cnum)
+            && found_abi != desired_abi
+        {
+            sess.dcx().emit_err(IncompatibleXtensaAbi {
+                crate_name: tcx.crate_name(cnum),
+                found_abi: found_abi.desc(),
+                desired_abi: desired_abi.desc(),
+            });
+        }
+
         if tcx.is_panic_runtime(cnum) {
             if let Some((prev, _)) = panic_runtime {
                 let prev_name = tcx.crate_name(prev);
@@ -82,6 +82,14 @@ pub struct IncompatiblePanicInDropStrategy {
     pub desired_strategy: PanicStrategy,
 }
 
+#[derive(Diagnostic)]
+#[diag(metadata_incompatible_xtensa_abi)]
+pub struct IncompatibleXtensaAbi {
+    pub crate_name: Symbol,
+    pub found_abi: &'static str,
+    pub desired_abi: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(metadata_multiple_names_in_link)]
 pub struct MultipleNamesInLink {
@@ -263,6 +263,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     mir_for_ctfe => { table }
     closure_saved_names_of_captured_variables => { table }
     mir_coroutine_witnesses => { table }
+    blocking_call => { table }
     promoted_mir => { table }
     def_span => { table }
     def_ident_span => { table }
@@ -350,6 +351,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     is_profiler_runtime => { cdata.root.profiler_runtime }
     required_panic_strategy => { cdata.root.required_panic_strategy }
     panic_in_drop_strategy => { cdata.root.panic_in_drop_strategy }
+    xtensa_abi => { cdata.root.xtensa_abi }
     extern_crate => { cdata.extern_crate.map(|c| &*tcx.arena.alloc(c)) }
     is_no_builtins => { cdata.root.no_builtins }
     symbol_mangling_version => { cdata.root.symbol_mangling_version }
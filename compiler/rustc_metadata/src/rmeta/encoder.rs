@@ -638,6 +638,8 @@ macro_rules! stat {
 
         _ = stat!("mir", || self.encode_mir());
 
+        _ = stat!("blocking-calls", || self.encode_blocking_calls());
+
         _ = stat!("def-ids", || self.encode_def_ids());
 
         let interpret_alloc_index = stat!("interpret-alloc-index", || {
@@ -705,6 +707,7 @@ macro_rules! stat {
                 stable_crate_id: tcx.def_path_hash(LOCAL_CRATE.as_def_id()).stable_crate_id(),
                 required_panic_strategy: tcx.required_panic_strategy(LOCAL_CRATE),
                 panic_in_drop_strategy: tcx.sess.opts.unstable_opts.panic_in_drop,
+                xtensa_abi: tcx.sess.xtensa_abi(),
                 edition: tcx.sess.edition(),
                 has_global_allocator: tcx.has_global_allocator(LOCAL_CRATE),
                 has_alloc_error_handler: tcx.has_alloc_error_handler(LOCAL_CRATE),
@@ -1668,6 +1671,21 @@ fn encode_precise_capturing_args(&mut self, def_id: DefId) {
         record_array!(self.tables.rendered_precise_capturing_args[def_id] <- precise_capturing_args);
     }
 
+    fn encode_blocking_calls(&mut self) {
+        // This walks the calls of every function of the crate, so it is only done when asked for.
+        // It also needs the optimized MIR, which is only built by crates doing codegen.
+        if self.is_proc_macro
+            || !self.tcx.sess.opts.unstable_opts.interrupt_context_summaries
+            || !self.tcx.sess.opts.output_types.should_codegen()
+        {
+            return;
+        }
+
+        for (&def_id, call) in self.tcx.blocking_calls(()) {
+            record!(self.tables.blocking_call[def_id.to_def_id()] <- call);
+        }
+    }
+
     fn encode_mir(&mut self) {
         if self.is_proc_macro {
             return;
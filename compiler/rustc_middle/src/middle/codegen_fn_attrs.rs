@@ -1,6 +1,6 @@
 use rustc_attr::{InlineAttr, InstructionSetAttr, OptimizeAttr};
 use rustc_macros::{HashStable, TyDecodable, TyEncodable};
-use rustc_span::symbol::Symbol;
+use rustc_span::symbol::{sym, Symbol};
 use rustc_target::abi::Align;
 use rustc_target::spec::SanitizerSet;
 
@@ -49,6 +49,12 @@ pub struct CodegenFnAttrs {
     /// The `#[patchable_function_entry(...)]` attribute. Indicates how many nops should be around
     /// the function entry.
     pub patchable_function_entry: Option<PatchableFunctionEntry>,
+    /// The `#[link_region(...)]` attribute. Indicates the memory region of the target this item
+    /// is placed in, unless `link_section` is given as well. See `LinkRegion::section_name`.
+    pub link_region: Option<LinkRegion>,
+    /// The `#[max_stack_frame = "..."]` attribute. Overrides the limit of `-Z max-stack-frame`
+    /// for the size of the stack frame of this function.
+    pub max_stack_frame: Option<u64>,
 }
 
 #[derive(Copy, Clone, Debug, TyEncodable, TyDecodable, HashStable)]
@@ -83,6 +89,76 @@ pub fn entry(&self) -> u8 {
     }
 }
 
+/// A memory region of ESP32-style chips that an item can be placed in with `#[link_region]`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, TyEncodable, TyDecodable, HashStable)]
+pub enum LinkRegion {
+    /// Internal instruction RAM, for code that must run while the flash cache is disabled.
+    Iram,
+    /// Internal data RAM, for data accessed while the flash cache is disabled.
+    Dram,
+    /// RTC fast memory, retained in deep sleep and executable by the main cores.
+    RtcFast,
+    /// RTC slow memory, retained in deep sleep and shared with the ULP coprocessor.
+    RtcSlow,
+}
+
+impl LinkRegion {
+    pub fn from_symbol(name: Symbol) -> Option<Self> {
+        match name {
+            sym::iram => Some(LinkRegion::Iram),
+            sym::dram => Some(LinkRegion::Dram),
+            sym::rtc_fast => Some(LinkRegion::RtcFast),
+            sym::rtc_slow => Some(LinkRegion::RtcSlow),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            LinkRegion::Iram => "iram",
+            LinkRegion::Dram => "dram",
+            LinkRegion::RtcFast => "rtc_fast",
+            LinkRegion::RtcSlow => "rtc_slow",
+        }
+    }
+
+    /// Returns the prefix of the output section for a function (`is_fn`) or static placed in
+    /// this region, or `None` if the region can't hold that kind of item. The names are the
+    /// ones used by the ESP-IDF linker scripts.
+    pub fn section_prefix(self, is_fn: bool) -> Option<&'static str> {
+        match (self, is_fn) {
+            (LinkRegion::Iram, true) => Some(".iram1"),
+            (LinkRegion::RtcFast, true) => Some(".rtc.text"),
+            (LinkRegion::Dram, false) => Some(".dram1"),
+            (LinkRegion::RtcFast, false) => Some(".rtc.force_fast"),
+            (LinkRegion::RtcSlow, false) => Some(".rtc.force_slow"),
+            (LinkRegion::Dram | LinkRegion::RtcSlow, true) | (LinkRegion::Iram, false) => None,
+        }
+    }
+
+    /// Returns the section of the function (`is_fn`) or static with the given symbol name in
+    /// this region. Every item gets its own section, so that `--gc-sections` can still drop
+    /// unused ones, and mangled symbol names keep the instances of a generic function apart
+    /// while only containing characters that linker scripts can match.
+    pub fn section_name(self, symbol_name: &str, is_fn: bool) -> Option<String> {
+        self.section_prefix(is_fn).map(|prefix| format!("{prefix}.{symbol_name}"))
+    }
+
+    /// Whether code in this region keeps running while the flash cache is disabled, and so
+    /// must not call into code in flash.
+    pub fn is_executable(self) -> bool {
+        matches!(self, LinkRegion::Iram | LinkRegion::RtcFast)
+    }
+
+    /// Whether a `#[link_section]` places code in one of the executable regions.
+    pub fn is_executable_section(section: &str) -> bool {
+        [LinkRegion::Iram, LinkRegion::RtcFast]
+            .iter()
+            .filter_map(|region| region.section_prefix(true))
+            .any(|prefix| section == prefix || section.starts_with(&format!("{prefix}.")))
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, TyEncodable, TyDecodable, HashStable)]
 pub struct CodegenFnAttrFlags(u32);
 bitflags::bitflags! {
@@ -135,6 +211,15 @@ impl CodegenFnAttrFlags: u32 {
         const ALLOCATOR_ZEROED          = 1 << 18;
         /// `#[no_builtins]`: indicates that disable implicit builtin knowledge of functions for the function.
         const NO_BUILTINS               = 1 << 19;
+        /// `#[no_panic]`: the function must not reach a panic, checked after
+        /// monomorphization.
+        const NO_PANIC                  = 1 << 20;
+        /// `#[interrupt_context]`: the function runs in interrupt context and must not reach a
+        /// `#[may_block]` function.
+        const INTERRUPT_CONTEXT         = 1 << 21;
+        /// `#[may_block]`: the function may block or allocate, and must not be called in
+        /// interrupt context.
+        const MAY_BLOCK                 = 1 << 22;
     }
 }
 rustc_data_structures::external_bitflags_debug! { CodegenFnAttrFlags }
@@ -158,6 +243,8 @@ pub const fn new() -> CodegenFnAttrs {
             instruction_set: None,
             alignment: None,
             patchable_function_entry: None,
+            link_region: None,
+            max_stack_frame: None,
         }
     }
 
@@ -176,4 +263,13 @@ pub fn contains_extern_indicator(&self) -> bool {
                 Some(_) => true,
             }
     }
+
+    /// Returns `true` if code placed in IRAM or RTC fast memory can safely call this function
+    /// while the flash cache is disabled, because the function is in RAM too or is always
+    /// inlined.
+    pub fn callable_from_ram(&self) -> bool {
+        self.inline == InlineAttr::Always
+            || self.link_region.is_some_and(LinkRegion::is_executable)
+            || self.link_section.is_some_and(|s| LinkRegion::is_executable_section(s.as_str()))
+    }
 }
@@ -0,0 +1,14 @@
+//! Summaries of the functions that may block or allocate, used to check the functions called from
+//! interrupt handlers across crates.
+
+use rustc_hir::def_id::DefId;
+use rustc_macros::{HashStable, TyDecodable, TyEncodable};
+use rustc_span::Span;
+
+/// How a function ends up calling a `#[may_block]` function.
+#[derive(Clone, Debug, TyEncodable, TyDecodable, HashStable)]
+pub struct BlockingCall {
+    /// The chain of calls to the `#[may_block]` function, starting with the call made by the
+    /// function itself: each function called, along with the span of the call.
+    pub path: Vec<(DefId, Span)>,
+}
@@ -249,6 +249,7 @@ impl EraseType for $ty {
     Option<rustc_span::Span>,
     Option<rustc_target::abi::FieldIdx>,
     Option<rustc_target::spec::PanicStrategy>,
+    Option<rustc_target::spec::XtensaAbi>,
     Option<usize>,
     Option<rustc_middle::ty::IntrinsicDef>,
     Result<(), rustc_errors::ErrorGuaranteed>,
@@ -41,7 +41,7 @@
 use rustc_span::symbol::Symbol;
 use rustc_span::{Span, DUMMY_SP};
 use rustc_target::abi;
-use rustc_target::spec::PanicStrategy;
+use rustc_target::spec::{PanicStrategy, XtensaAbi};
 use {rustc_ast as ast, rustc_attr as attr, rustc_hir as hir};
 
 use crate::infer::canonical::{self, Canonical};
@@ -50,6 +50,7 @@
 use crate::middle::codegen_fn_attrs::{CodegenFnAttrs, TargetFeature};
 use crate::middle::debugger_visualizer::DebuggerVisualizerFile;
 use crate::middle::exported_symbols::{ExportedSymbol, SymbolExportInfo};
+use crate::middle::interrupt_context::BlockingCall;
 use crate::middle::lib_features::LibFeatures;
 use crate::middle::privacy::EffectiveVisibilities;
 use crate::middle::resolve_bound_vars::{ObjectLifetimeDefault, ResolveBoundVars, ResolvedArg};
@@ -1514,6 +1515,11 @@
         desc { "getting a crate's configured panic-in-drop strategy" }
         separate_provide_extern
     }
+    query xtensa_abi(_: CrateNum) -> Option<XtensaAbi> {
+        fatal_cycle
+        desc { "getting the Xtensa calling convention a crate was compiled for" }
+        separate_provide_extern
+    }
     query is_no_builtins(_: CrateNum) -> bool {
         fatal_cycle
         desc { "getting whether a crate has `#![no_builtins]`" }
@@ -2279,6 +2285,22 @@
         desc { "whether the item should be made inlinable across crates" }
         separate_provide_extern
     }
+
+    /// The non-generic functions of the local crate that may call a `#[may_block]` function,
+    /// with the shortest chain of calls leading to it.
+    query blocking_calls(_: ()) -> &'tcx FxIndexMap<LocalDefId, BlockingCall> {
+        arena_cache
+        desc { "finding the functions that may block" }
+    }
+
+    /// How a non-generic function may call a `#[may_block]` function, if it does. This is encoded
+    /// in the metadata, so that functions called from interrupt handlers can be checked without
+    /// the MIR of the other crates.
+    query blocking_call(def_id: DefId) -> &'tcx Option<BlockingCall> {
+        arena_cache
+        desc { |tcx| "checking whether `{}` may block", tcx.def_path_str(def_id) }
+        separate_provide_extern
+    }
 }
 
 rustc_query_append! { define_callbacks! }
@@ -1214,6 +1214,7 @@ pub fn fn_can_unwind(tcx: TyCtxt<'_>, fn_def_id: Option<DefId>, abi: SpecAbi) ->
         | AvrNonBlockingInterrupt
         | RiscvInterruptM
         | RiscvInterruptS
+        | XtensaInterrupt { .. }
         | CCmseNonSecureCall
         | Unadjusted => false,
         Rust | RustCall | RustCold | RustIntrinsic => {
@@ -4,15 +4,40 @@ monomorphize_couldnt_dump_mono_stats =
 monomorphize_encountered_error_while_instantiating =
     the above error was encountered while instantiating `{$formatted_item}`
 
+monomorphize_interrupt_context =
+    `{$function}` may block or allocate, but runs in interrupt context
+    .label = functions running in interrupt context must not call `#[may_block]` functions
+
+monomorphize_interrupt_context_blocks = `{$caller}` calls `{$callee}`, which may block or allocate
+
+monomorphize_interrupt_context_call = `{$caller}` calls `{$callee}`
+
 monomorphize_large_assignments =
     moving {$size} bytes
     .label = value moved from here
     .note = The current maximum size is {$limit}, but it can be customized with the move_size_limit attribute: `#![move_size_limit = "..."]`
 
+monomorphize_no_panic =
+    `{$function}` may panic
+    .label = `#[no_panic]` requires this function to never reach a panic
+
+monomorphize_no_panic_call = `{$caller}` calls `{$callee}`
+
+monomorphize_no_panic_indirect = `{$caller}` calls through a function pointer or vtable, which can't be checked
+
+monomorphize_no_panic_panics = `{$caller}` calls `{$callee}`, which panics
+
+monomorphize_no_panic_unknown = `{$caller}` calls `{$callee}`, which can't be checked because its MIR is not available
+
 monomorphize_no_optimized_mir =
     missing optimized MIR for an item in the crate `{$crate_name}`
     .note = missing optimized MIR for this item (was the crate `{$crate_name}` compiled with `--emit=metadata`?)
 
+monomorphize_ram_code_calls_flash =
+    function in `{$region}` calls `{$callee}`, which is not in RAM
+    .label = `{$callee}` is placed in flash
+    .note = code in `{$region}` can run while the flash cache is disabled, and calling into flash then crashes
+
 monomorphize_recursion_limit =
     reached the recursion limit while instantiating `{$shrunk}`
     .note = `{$def_path_str}` defined here
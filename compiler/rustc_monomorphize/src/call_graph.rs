@@ -0,0 +1,84 @@
+//! The calls made by each mono item, for `--print stack-usage` and `#[no_panic]`.
+//!
+//! The collector only records which items are *used* by an item, which includes functions that are
+//! merely reified or put in a vtable. The stack usage analysis needs actual call edges, and needs
+//! to know about calls that can't be resolved statically, so they are recomputed here from the
+//! MIR of the instance.
+
+use rustc_hir::lang_items::LangItem;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, Instance, InstanceKind, TyCtxt};
+use rustc_span::Span;
+
+/// A call made by a function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Callee<'tcx> {
+    /// A call to a statically known function.
+    Direct(Instance<'tcx>),
+    /// A call through a function pointer or a vtable, whose target isn't known.
+    Indirect,
+}
+
+/// Returns the calls made by the body of `instance`, including calls to drop glue and to the
+/// panic functions of assertions.
+pub fn callees<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Vec<Callee<'tcx>> {
+    let mut callees: Vec<_> = calls(tcx, instance).into_iter().map(|(callee, _)| callee).collect();
+    callees.dedup();
+    callees
+}
+
+/// Like [`callees`], but returns every call along with the span of the call site.
+pub fn calls<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> Vec<(Callee<'tcx>, Span)> {
+    let body = tcx.instance_mir(instance.def);
+    let monomorphize = |ty| {
+        instance.instantiate_mir_and_normalize_erasing_regions(
+            tcx,
+            ty::ParamEnv::reveal_all(),
+            ty::EarlyBinder::bind(ty),
+        )
+    };
+    let lang_item = |item: LangItem| {
+        tcx.lang_items().get(item).map(|def_id| Callee::Direct(Instance::mono(tcx, def_id)))
+    };
+
+    let mut callees = Vec::new();
+    for data in body.basic_blocks.iter() {
+        let callee = match &data.terminator().kind {
+            mir::TerminatorKind::Call { func, .. } | mir::TerminatorKind::TailCall { func, .. } => {
+                match *monomorphize(func.ty(body, tcx)).kind() {
+                    ty::FnDef(def_id, args) => {
+                        match Instance::try_resolve(tcx, ty::ParamEnv::reveal_all(), def_id, args) {
+                            Ok(Some(callee)) => match callee.def {
+                                InstanceKind::Virtual(..) => Some(Callee::Indirect),
+                                // Intrinsics are lowered to inline code.
+                                InstanceKind::Intrinsic(..) => None,
+                                _ => Some(Callee::Direct(callee)),
+                            },
+                            _ => None,
+                        }
+                    }
+                    ty::FnPtr(..) => Some(Callee::Indirect),
+                    _ => None,
+                }
+            }
+            mir::TerminatorKind::Drop { place, .. } => {
+                let ty = monomorphize(place.ty(body, tcx).ty);
+                let glue = Instance::resolve_drop_in_place(tcx, ty);
+                match glue.def {
+                    InstanceKind::DropGlue(_, None) => None,
+                    _ => Some(Callee::Direct(glue)),
+                }
+            }
+            mir::TerminatorKind::Assert { msg, .. } => match &**msg {
+                mir::AssertKind::BoundsCheck { .. } => lang_item(LangItem::PanicBoundsCheck),
+                mir::AssertKind::MisalignedPointerDereference { .. } => {
+                    lang_item(LangItem::PanicMisalignedPointerDereference)
+                }
+                _ => lang_item(msg.panic_function()),
+            },
+            _ => None,
+        };
+        callees.extend(callee.map(|callee| (callee, data.terminator().source_info.span)));
+    }
+    callees
+}
@@ -0,0 +1,64 @@
+use rustc_session::lint::builtin::RAM_CODE_CALLS_FLASH;
+use tracing::debug;
+
+use super::*;
+use crate::errors::RamCodeCallsFlashLint;
+
+impl<'a, 'tcx> MirUsedCollector<'a, 'tcx> {
+    /// Checks that a function placed in IRAM or RTC fast memory with `#[link_region]` only
+    /// calls functions that can run while the flash cache is disabled.
+    pub(super) fn check_link_region_call(
+        &self,
+        callee_ty: Ty<'tcx>,
+        fn_span: Span,
+        location: Location,
+    ) {
+        let InstanceKind::Item(caller) = self.instance.def else {
+            return;
+        };
+        let Some(region) = self.tcx.codegen_fn_attrs(caller).link_region else {
+            return;
+        };
+        if !region.is_executable() {
+            return;
+        }
+
+        let ty::FnDef(def_id, args) = *callee_ty.kind() else {
+            // Function pointers can't be checked.
+            return;
+        };
+        let Ok(Some(instance)) =
+            Instance::try_resolve(self.tcx, ty::ParamEnv::reveal_all(), def_id, args)
+        else {
+            return;
+        };
+        // Intrinsics are lowered inline, and shims and virtual calls don't correspond to a
+        // function the user could place.
+        let InstanceKind::Item(callee) = instance.def else {
+            return;
+        };
+        // Foreign functions are often in ROM, we have no way of knowing.
+        if self.tcx.is_foreign_item(callee) || self.tcx.codegen_fn_attrs(callee).callable_from_ram()
+        {
+            return;
+        }
+
+        debug!(?caller, ?callee, ?fn_span);
+        let source_info = self.body.source_info(location);
+        let Some(lint_root) = source_info.scope.lint_root(&self.body.source_scopes) else {
+            // Like for `large_assignments`, there is nothing to attach the lint to when the
+            // caller was instantiated from another crate.
+            return;
+        };
+        self.tcx.emit_node_span_lint(
+            RAM_CODE_CALLS_FLASH,
+            lint_root,
+            fn_span,
+            RamCodeCallsFlashLint {
+                span: fn_span,
+                callee: with_no_trimmed_paths!(self.tcx.def_path_str(callee)),
+                region: region.name(),
+            },
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use std::path::PathBuf;
 
 use rustc_errors::{Diag, DiagCtxtHandle, Diagnostic, EmissionGuarantee, Level};
-use rustc_macros::{Diagnostic, LintDiagnostic};
+use rustc_macros::{Diagnostic, LintDiagnostic, Subdiagnostic};
 use rustc_span::{Span, Symbol};
 
 use crate::fluent_generated as fluent;
@@ -50,6 +50,35 @@ fn into_diag(self, dcx: DiagCtxtHandle<'_>, level: Level) -> Diag<'_, G> {
     }
 }
 
+#[derive(Diagnostic)]
+#[diag(monomorphize_interrupt_context)]
+pub(crate) struct InterruptContext {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub function: String,
+    #[subdiagnostic]
+    pub steps: Vec<InterruptContextStep>,
+}
+
+#[derive(Subdiagnostic)]
+pub(crate) enum InterruptContextStep {
+    #[note(monomorphize_interrupt_context_call)]
+    Call {
+        #[primary_span]
+        span: Span,
+        caller: String,
+        callee: String,
+    },
+    #[note(monomorphize_interrupt_context_blocks)]
+    Blocks {
+        #[primary_span]
+        span: Span,
+        caller: String,
+        callee: String,
+    },
+}
+
 #[derive(LintDiagnostic)]
 #[diag(monomorphize_large_assignments)]
 #[note]
@@ -60,6 +89,58 @@ pub(crate) struct LargeAssignmentsLint {
     pub limit: u64,
 }
 
+#[derive(Diagnostic)]
+#[diag(monomorphize_no_panic)]
+pub(crate) struct NoPanic {
+    #[primary_span]
+    #[label]
+    pub span: Span,
+    pub function: String,
+    #[subdiagnostic]
+    pub steps: Vec<NoPanicStep>,
+}
+
+#[derive(Subdiagnostic)]
+pub(crate) enum NoPanicStep {
+    #[note(monomorphize_no_panic_call)]
+    Call {
+        #[primary_span]
+        span: Span,
+        caller: String,
+        callee: String,
+    },
+    #[note(monomorphize_no_panic_panics)]
+    Panics {
+        #[primary_span]
+        span: Span,
+        caller: String,
+        callee: String,
+    },
+    #[note(monomorphize_no_panic_unknown)]
+    Unknown {
+        #[primary_span]
+        span: Span,
+        caller: String,
+        callee: String,
+    },
+    #[note(monomorphize_no_panic_indirect)]
+    Indirect {
+        #[primary_span]
+        span: Span,
+        caller: String,
+    },
+}
+
+#[derive(LintDiagnostic)]
+#[diag(monomorphize_ram_code_calls_flash)]
+#[note]
+pub(crate) struct RamCodeCallsFlashLint {
+    #[label]
+    pub span: Span,
+    pub callee: String,
+    pub region: &'static str,
+}
+
 #[derive(Diagnostic)]
 #[diag(monomorphize_symbol_already_defined)]
 pub(crate) struct SymbolAlreadyDefined {
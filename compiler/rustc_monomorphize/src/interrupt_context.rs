@@ -0,0 +1,240 @@
+//! Checks that the functions running in interrupt context can't block or allocate.
+//!
+//! The entry points are the functions marked `#[interrupt_context]` and the `xtensa-interrupt`
+//! handlers. Their calls are walked after monomorphization, and an error is reported with the
+//! shortest chain of calls to a function marked `#[may_block]`, like the lock and allocation
+//! functions of the standard library.
+//!
+//! The functions of other crates are checked without their MIR: every crate doing codegen encodes
+//! in its metadata how each of its non-generic functions may reach a `#[may_block]` function, in
+//! the `blocking_call` query. Only generic and inlinable functions of other crates are walked
+//! through, with the types they are used with.
+//!
+//! Foreign functions are assumed not to block unless they are marked `#[may_block]`. Calls through
+//! function pointers and vtables are not followed.
+
+use std::collections::VecDeque;
+
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap, FxIndexSet};
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::{DefId, LocalDefId};
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::middle::interrupt_context::BlockingCall;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, InstanceKind, TyCtxt};
+use rustc_middle::util::Providers;
+use rustc_span::Span;
+use rustc_target::spec::abi::Abi;
+
+use crate::call_graph::{self, Callee};
+use crate::errors::{InterruptContext, InterruptContextStep};
+
+/// What a call means for the search.
+enum Step {
+    /// The callee may block, through the given chain of calls, which is empty if the callee is
+    /// `#[may_block]` itself.
+    Blocks(Vec<(DefId, Span)>),
+    /// The calls of the callee have to be walked.
+    Follow,
+    /// The callee doesn't block, or can't be checked.
+    Skip,
+}
+
+/// The first call of the shortest chain from a function to a `#[may_block]` function.
+#[derive(Clone, Copy)]
+enum Next<'tcx> {
+    /// A call to a function that may block.
+    Call(Instance<'tcx>, Span),
+    /// The end of the chain, an index into [`CallGraph::blocking`].
+    Blocks(usize),
+}
+
+/// The calls between the functions reachable from a set of roots.
+struct CallGraph<'tcx> {
+    /// The functions reached, with the functions calling them and the span of each call.
+    callers: FxIndexMap<Instance<'tcx>, Vec<(Instance<'tcx>, Span)>>,
+    /// The functions calling a function that may block, with the chain of calls from there.
+    blocking: Vec<(Instance<'tcx>, Vec<(DefId, Span)>)>,
+}
+
+impl<'tcx> CallGraph<'tcx> {
+    fn build(tcx: TyCtxt<'tcx>, roots: impl IntoIterator<Item = Instance<'tcx>>) -> Self {
+        let mut graph = CallGraph {
+            callers: roots.into_iter().map(|root| (root, Vec::new())).collect(),
+            blocking: Vec::new(),
+        };
+        let mut index = 0;
+        while let Some((&function, _)) = graph.callers.get_index(index) {
+            index += 1;
+            for (callee, span) in call_graph::calls(tcx, function) {
+                let Callee::Direct(callee) = callee else { continue };
+                match step(tcx, callee) {
+                    Step::Blocks(rest) => {
+                        let mut path = vec![(callee.def_id(), span)];
+                        path.extend(rest);
+                        graph.blocking.push((function, path));
+                    }
+                    Step::Follow => {
+                        graph.callers.entry(callee).or_default().push((function, span))
+                    }
+                    Step::Skip => {}
+                }
+            }
+        }
+        graph
+    }
+
+    /// Finds the shortest chain to a `#[may_block]` function from each function that may block,
+    /// with a breadth-first search going backwards from the calls of such functions.
+    fn shortest_paths(&self) -> FxHashMap<Instance<'tcx>, Next<'tcx>> {
+        let mut next = FxHashMap::default();
+        let mut queue = VecDeque::new();
+        for (i, &(function, _)) in self.blocking.iter().enumerate() {
+            if !next.contains_key(&function) {
+                next.insert(function, Next::Blocks(i));
+                queue.push_back(function);
+            }
+        }
+        while let Some(callee) = queue.pop_front() {
+            for &(caller, span) in &self.callers[&callee] {
+                if !next.contains_key(&caller) {
+                    next.insert(caller, Next::Call(callee, span));
+                    queue.push_back(caller);
+                }
+            }
+        }
+        next
+    }
+
+    /// The chain of calls from `function` to a `#[may_block]` function, found by
+    /// [`CallGraph::shortest_paths`].
+    fn path(
+        &self,
+        next: &FxHashMap<Instance<'tcx>, Next<'tcx>>,
+        mut function: Instance<'tcx>,
+    ) -> Vec<(DefId, Span)> {
+        let mut path = Vec::new();
+        loop {
+            match next[&function] {
+                Next::Call(callee, span) => {
+                    path.push((callee.def_id(), span));
+                    function = callee;
+                }
+                Next::Blocks(i) => {
+                    path.extend_from_slice(&self.blocking[i].1);
+                    return path;
+                }
+            }
+        }
+    }
+}
+
+fn step<'tcx>(tcx: TyCtxt<'tcx>, callee: Instance<'tcx>) -> Step {
+    // Shims are built by the compiler, and their calls can always be followed.
+    let InstanceKind::Item(def_id) = callee.def else { return Step::Follow };
+    if tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::MAY_BLOCK) {
+        return Step::Blocks(Vec::new());
+    }
+    if tcx.is_foreign_item(def_id) {
+        return Step::Skip;
+    }
+    if !def_id.is_local()
+        && matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        && !tcx.generics_of(def_id).requires_monomorphization(tcx)
+    {
+        return match tcx.blocking_call(def_id) {
+            Some(call) => Step::Blocks(call.path.clone()),
+            None => Step::Skip,
+        };
+    }
+    if tcx.is_mir_available(def_id) { Step::Follow } else { Step::Skip }
+}
+
+fn name(tcx: TyCtxt<'_>, def_id: DefId) -> String {
+    with_no_trimmed_paths!(tcx.def_path_str(def_id))
+}
+
+fn blocking_calls(tcx: TyCtxt<'_>, (): ()) -> FxIndexMap<LocalDefId, BlockingCall> {
+    let roots: FxIndexSet<_> = tcx
+        .mir_keys(())
+        .iter()
+        .filter(|&&def_id| {
+            matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+                && !tcx.generics_of(def_id).requires_monomorphization(tcx)
+        })
+        .map(|&def_id| Instance::mono(tcx, def_id.to_def_id()))
+        .collect();
+    let graph = CallGraph::build(tcx, roots.iter().copied());
+    let next = graph.shortest_paths();
+    roots
+        .into_iter()
+        .filter(|root| next.contains_key(root))
+        .map(|root| {
+            let def_id = root.def_id().expect_local();
+            (def_id, BlockingCall { path: graph.path(&next, root) })
+        })
+        .collect()
+}
+
+pub(crate) fn check_interrupt_context<'tcx>(tcx: TyCtxt<'tcx>, items: &[MonoItem<'tcx>]) {
+    let mut roots: Vec<_> = items
+        .iter()
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance @ Instance { def: InstanceKind::Item(def_id), .. })
+                if is_interrupt_handler(tcx, def_id) =>
+            {
+                Some(instance)
+            }
+            _ => None,
+        })
+        .collect();
+    // Report the errors in source order.
+    roots.sort_by_cached_key(|&instance| {
+        (tcx.def_span(instance.def_id()), with_no_trimmed_paths!(instance.to_string()))
+    });
+
+    let graph = CallGraph::build(tcx, roots.iter().copied());
+    let next = graph.shortest_paths();
+    for root in roots {
+        if !next.contains_key(&root) {
+            continue;
+        }
+        let path = graph.path(&next, root);
+        let mut caller = root.def_id();
+        let mut steps: Vec<_> = path
+            .into_iter()
+            .map(|(callee, span)| {
+                let step = InterruptContextStep::Call {
+                    span,
+                    caller: name(tcx, caller),
+                    callee: name(tcx, callee),
+                };
+                caller = callee;
+                step
+            })
+            .collect();
+        if let Some(InterruptContextStep::Call { span, caller, callee }) = steps.pop() {
+            steps.push(InterruptContextStep::Blocks { span, caller, callee });
+        }
+        tcx.dcx().emit_err(InterruptContext {
+            span: tcx.def_span(root.def_id()),
+            function: name(tcx, root.def_id()),
+            steps,
+        });
+    }
+}
+
+/// Whether `def_id` is marked `#[interrupt_context]` or is an `xtensa-interrupt` handler.
+fn is_interrupt_handler(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    if tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::INTERRUPT_CONTEXT) {
+        return true;
+    }
+    matches!(tcx.def_kind(def_id), DefKind::Fn | DefKind::AssocFn)
+        && matches!(tcx.fn_sig(def_id).skip_binder().abi(), Abi::XtensaInterrupt { .. })
+}
+
+pub(crate) fn provide(providers: &mut Providers) {
+    providers.blocking_calls = blocking_calls;
+    providers.blocking_call = |tcx, def_id| tcx.blocking_calls(()).get(&def_id).cloned();
+}
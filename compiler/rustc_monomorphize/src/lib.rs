@@ -11,8 +11,11 @@
 use rustc_middle::{bug, traits};
 use rustc_span::ErrorGuaranteed;
 
+pub mod call_graph;
 mod collector;
 mod errors;
+mod interrupt_context;
+mod no_panic;
 mod partitioning;
 mod polymorphize;
 mod util;
@@ -42,6 +45,7 @@ fn custom_coerce_unsize_info<'tcx>(
 }
 
 pub fn provide(providers: &mut Providers) {
+    interrupt_context::provide(providers);
     partitioning::provide(providers);
     polymorphize::provide(providers);
 }
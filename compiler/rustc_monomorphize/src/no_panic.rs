@@ -0,0 +1,141 @@
+//! Checks that the functions marked `#[no_panic]` can't reach a panic.
+//!
+//! This walks the calls of every instance of such a function after monomorphization, so that
+//! generic code is checked with the types it is actually used with, and reports the shortest call
+//! chain to a function of `core::panicking` or `std::panicking`, or to any other panic entry point
+//! of the standard library. The walk is done on MIR, before LLVM had a chance to prove that some of
+//! the panics are unreachable, which makes the check independent of the optimization level and
+//! LTO.
+//!
+//! Foreign functions are assumed not to panic. Calls that can't be followed, through function
+//! pointers or vtables or to functions of other crates without MIR, are reported as well.
+
+use std::collections::VecDeque;
+
+use rustc_data_structures::fx::FxHashMap;
+use rustc_hir::def::DefKind;
+use rustc_hir::def_id::DefId;
+use rustc_hir::LangItem;
+use rustc_middle::middle::codegen_fn_attrs::CodegenFnAttrFlags;
+use rustc_middle::mir::mono::MonoItem;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{Instance, InstanceKind, TyCtxt};
+use rustc_span::{sym, Span};
+
+use crate::call_graph::{self, Callee};
+use crate::errors::{NoPanic, NoPanicStep};
+
+/// Why a call chain can't be proven not to panic.
+enum Reason<'tcx> {
+    /// The call of a panic entry point.
+    Panics(Instance<'tcx>),
+    /// The call of a function whose MIR isn't available.
+    Unknown(Instance<'tcx>),
+    /// A call through a function pointer or a vtable.
+    Indirect,
+}
+
+pub(crate) fn check_no_panic<'tcx>(tcx: TyCtxt<'tcx>, items: &[MonoItem<'tcx>]) {
+    let mut roots: Vec<_> = items
+        .iter()
+        .filter_map(|item| match *item {
+            MonoItem::Fn(instance @ Instance { def: InstanceKind::Item(def_id), .. })
+                if tcx.codegen_fn_attrs(def_id).flags.contains(CodegenFnAttrFlags::NO_PANIC) =>
+            {
+                Some(instance)
+            }
+            _ => None,
+        })
+        .collect();
+    // Report the errors in source order.
+    roots.sort_by_cached_key(|&instance| {
+        (tcx.def_span(instance.def_id()), with_no_trimmed_paths!(instance.to_string()))
+    });
+    for root in roots {
+        check_instance(tcx, root);
+    }
+}
+
+fn check_instance<'tcx>(tcx: TyCtxt<'tcx>, root: Instance<'tcx>) {
+    // The caller of each function reached so far, with the span of the call.
+    let mut callers: FxHashMap<Instance<'tcx>, (Instance<'tcx>, Span)> = FxHashMap::default();
+    let mut queue = VecDeque::from([root]);
+
+    // A breadth-first search, so that the shortest chain is reported.
+    let (mut caller, mut span, reason) = 'search: {
+        while let Some(function) = queue.pop_front() {
+            for (callee, span) in call_graph::calls(tcx, function) {
+                let callee = match callee {
+                    Callee::Indirect => break 'search (function, span, Reason::Indirect),
+                    Callee::Direct(callee) => callee,
+                };
+                if is_panic_entry(tcx, callee) {
+                    break 'search (function, span, Reason::Panics(callee));
+                }
+                if callee == root || callers.contains_key(&callee) || is_foreign(tcx, callee) {
+                    continue;
+                }
+                if !has_mir(tcx, callee) {
+                    break 'search (function, span, Reason::Unknown(callee));
+                }
+                callers.insert(callee, (function, span));
+                queue.push_back(callee);
+            }
+        }
+        return;
+    };
+
+    let name = |instance: Instance<'tcx>| with_no_trimmed_paths!(instance.to_string());
+    let mut steps = vec![match reason {
+        Reason::Panics(callee) => {
+            NoPanicStep::Panics { span, caller: name(caller), callee: name(callee) }
+        }
+        Reason::Unknown(callee) => {
+            NoPanicStep::Unknown { span, caller: name(caller), callee: name(callee) }
+        }
+        Reason::Indirect => NoPanicStep::Indirect { span, caller: name(caller) },
+    }];
+    while caller != root {
+        let callee = caller;
+        (caller, span) = callers[&callee];
+        steps.push(NoPanicStep::Call { span, caller: name(caller), callee: name(callee) });
+    }
+    steps.reverse();
+
+    tcx.dcx().emit_err(NoPanic { span: tcx.def_span(root.def_id()), function: name(root), steps });
+}
+
+/// Whether calling `instance` means panicking: the functions of `core::panicking` and
+/// `std::panicking`, and the panic lang items like the `panic_fmt` and `begin_panic`.
+fn is_panic_entry<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+    let def_id = instance.def_id();
+    if let Some(item) = tcx.lang_items().from_def_id(def_id)
+        && item.name().as_str().starts_with("panic")
+    {
+        return true;
+    }
+    if tcx.is_lang_item(def_id, LangItem::BeginPanic) {
+        return true;
+    }
+    is_in_panicking_module(tcx, def_id)
+}
+
+fn is_in_panicking_module(tcx: TyCtxt<'_>, def_id: DefId) -> bool {
+    let parent = tcx.parent(def_id);
+    tcx.def_kind(parent) == DefKind::Mod
+        && tcx.opt_item_name(parent) == Some(sym::panicking)
+        && matches!(tcx.crate_name(def_id.krate), sym::core | sym::std)
+}
+
+fn is_foreign<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+    matches!(instance.def, InstanceKind::Item(def_id) if tcx.is_foreign_item(def_id))
+}
+
+/// Whether the calls made by `instance` can be followed. Shims are built by the compiler, items
+/// only have MIR in other crates if they are generic or inlinable.
+fn has_mir<'tcx>(tcx: TyCtxt<'tcx>, instance: Instance<'tcx>) -> bool {
+    match instance.def {
+        InstanceKind::Item(def_id) => tcx.is_mir_available(def_id),
+        _ => true,
+    }
+}
@@ -123,6 +123,7 @@
 
 use crate::collector::{self, MonoItemCollectionStrategy, UsageMap};
 use crate::errors::{CouldntDumpMonoStats, SymbolAlreadyDefined, UnknownCguCollectionMode};
+use crate::{interrupt_context, no_panic};
 
 struct PartitioningCx<'a, 'tcx> {
     tcx: TyCtxt<'tcx>,
@@ -1123,6 +1124,13 @@ fn collect_and_partition_mono_items(tcx: TyCtxt<'_>, (): ()) -> (&DefIdSet, &[Co
 
     let (items, usage_map) = collector::collect_crate_mono_items(tcx, collection_strategy);
 
+    // Check `#[no_panic]` and the interrupt handlers before stopping on errors, so that a
+    // violation stops the compilation before codegen like the errors of the collection do.
+    tcx.sess.time("check_no_panic", || no_panic::check_no_panic(tcx, &items));
+    tcx.sess.time("check_interrupt_context", || {
+        interrupt_context::check_interrupt_context(tcx, &items)
+    });
+
     // If there was an error during collection (e.g. from one of the constants we evaluated),
     // then we stop here. This way codegen does not have to worry about failing constants.
     // (codegen relies on this and ICEs will happen if this is violated.)
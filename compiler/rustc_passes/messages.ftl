@@ -425,6 +425,24 @@ passes_link_ordinal =
     attribute should be applied to a foreign function or static
     .label = not a foreign function or static
 
+passes_link_region_invalid =
+    expected one of `iram`, `dram`, `rtc_fast` or `rtc_slow`
+
+passes_link_region_kind =
+    `{$region}` cannot hold {$kind}
+    .label = cannot be placed in `{$region}`
+
+passes_link_region_target =
+    attribute should be applied to a function or static
+    .label = not a function or static
+
+passes_link_region_unsupported =
+    `#[link_region]` is not supported on this target
+
+passes_link_region_with_link_section =
+    `#[link_region]` cannot be combined with `#[link_section]`
+    .label = section set here
+
 passes_link_section =
     attribute should be applied to a function or static
     .warn = {-passes_previously_accepted}
@@ -21,6 +21,7 @@
 };
 use rustc_macros::LintDiagnostic;
 use rustc_middle::hir::nested_filter;
+use rustc_middle::middle::codegen_fn_attrs::LinkRegion;
 use rustc_middle::middle::resolve_bound_vars::ObjectLifetimeDefault;
 use rustc_middle::query::Providers;
 use rustc_middle::traits::ObligationCause;
@@ -214,7 +215,14 @@ fn check_attributes(
                 [sym::link, ..] => self.check_link(hir_id, attr, span, target),
                 [sym::link_name, ..] => self.check_link_name(hir_id, attr, span, target),
                 [sym::link_section, ..] => self.check_link_section(hir_id, attr, span, target),
+                [sym::link_region, ..] => self.check_link_region(attr, span, target, attrs),
                 [sym::no_mangle, ..] => self.check_no_mangle(hir_id, attr, span, target),
+                [sym::no_panic, ..]
+                | [sym::interrupt_context, ..]
+                | [sym::max_stack_frame, ..] => {
+                    self.check_fn_only_attr(hir_id, attr, span, target)
+                }
+                [sym::may_block, ..] => self.check_may_block(hir_id, attr, span, target),
                 [sym::deprecated, ..] => self.check_deprecated(hir_id, attr, span, target),
                 [sym::macro_use, ..] | [sym::macro_escape, ..] => {
                     self.check_macro_use(hir_id, attr, target)
@@ -1749,6 +1757,86 @@ fn check_link_section(&self, hir_id: HirId, attr: &Attribute, span: Span, target
         }
     }
 
+    /// Checks if `#[link_region]` names a single region that can hold the function or static it
+    /// is applied to, on a target that has such regions.
+    fn check_link_region(&self, attr: &Attribute, span: Span, target: Target, attrs: &[Attribute]) {
+        let is_fn = match target {
+            Target::Fn | Target::Method(..) | Target::Closure => true,
+            Target::Static => false,
+            _ => {
+                self.dcx().emit_err(errors::LinkRegionTarget { attr_span: attr.span, span });
+                return;
+            }
+        };
+
+        let region = match attr.meta_item_list().as_deref() {
+            Some([NestedMetaItem::MetaItem(region)]) => {
+                LinkRegion::from_symbol(region.name_or_empty())
+            }
+            _ => None,
+        };
+        let Some(region) = region else {
+            self.dcx().emit_err(errors::LinkRegionInvalid { span: attr.span });
+            return;
+        };
+
+        if region.section_prefix(is_fn).is_none() {
+            self.dcx().emit_err(errors::LinkRegionKind {
+                attr_span: attr.span,
+                span,
+                region: region.name(),
+                kind: if is_fn { "functions" } else { "statics" },
+            });
+        }
+
+        // The regions are laid out by the linker script of the firmware, which only exists on
+        // bare-metal targets (including the RISC-V ones used by `esp-hal`) and on ESP-IDF.
+        let target = &self.tcx.sess.target;
+        if target.os != "none" && target.os != "espidf" {
+            self.dcx().emit_err(errors::LinkRegionUnsupported { span: attr.span });
+        }
+
+        if let Some(link_section) = attrs.iter().find(|attr| attr.has_name(sym::link_section)) {
+            self.dcx().emit_err(errors::LinkRegionWithLinkSection {
+                attr_span: attr.span,
+                link_section_span: link_section.span,
+            });
+        }
+    }
+
+    /// Checks if an attribute that only makes sense on a function with a body, like
+    /// `#[no_panic]`, `#[interrupt_context]` or `#[max_stack_frame]`, is applied to one.
+    fn check_fn_only_attr(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Target) {
+        match target {
+            Target::Fn
+            | Target::Closure
+            | Target::Method(MethodKind::Trait { body: true } | MethodKind::Inherent) => {}
+            _ => {
+                self.dcx().emit_err(errors::AttrShouldBeAppliedToFn {
+                    attr_span: attr.span,
+                    defn_span: span,
+                    on_crate: hir_id == CRATE_HIR_ID,
+                });
+            }
+        }
+    }
+
+    /// Checks if `#[may_block]` is applied to a function with a body or a foreign function.
+    fn check_may_block(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Target) {
+        match target {
+            Target::Fn
+            | Target::ForeignFn
+            | Target::Method(MethodKind::Trait { body: true } | MethodKind::Inherent) => {}
+            _ => {
+                self.dcx().emit_err(errors::AttrShouldBeAppliedToFn {
+                    attr_span: attr.span,
+                    defn_span: span,
+                    on_crate: hir_id == CRATE_HIR_ID,
+                });
+            }
+        }
+    }
+
     /// Checks if `#[no_mangle]` is applied to a function or static.
     fn check_no_mangle(&self, hir_id: HirId, attr: &Attribute, span: Span, target: Target) {
         match target {
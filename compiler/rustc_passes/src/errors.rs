@@ -654,6 +654,49 @@ pub(crate) struct CoroutineOnNonClosure {
     pub span: Span,
 }
 
+#[derive(Diagnostic)]
+#[diag(passes_link_region_target)]
+pub(crate) struct LinkRegionTarget {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_link_region_invalid)]
+pub(crate) struct LinkRegionInvalid {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_link_region_kind)]
+pub(crate) struct LinkRegionKind {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub span: Span,
+    pub region: &'static str,
+    pub kind: &'static str,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_link_region_unsupported)]
+pub(crate) struct LinkRegionUnsupported {
+    #[primary_span]
+    pub span: Span,
+}
+
+#[derive(Diagnostic)]
+#[diag(passes_link_region_with_link_section)]
+pub(crate) struct LinkRegionWithLinkSection {
+    #[primary_span]
+    pub attr_span: Span,
+    #[label]
+    pub link_section_span: Span,
+}
+
 #[derive(Diagnostic)]
 #[diag(passes_linkage)]
 pub(crate) struct Linkage {
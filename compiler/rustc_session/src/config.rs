@@ -824,11 +824,13 @@ pub enum PrintKind {
     TargetFeatures,
     RelocationModels,
     CodeModels,
+    CodeSize,
     TlsModels,
     TargetSpec,
     AllTargetSpecs,
     NativeStaticLibs,
     StackProtectorStrategies,
+    StackUsage,
     LinkArgs,
     SplitDebuginfo,
     DeploymentTarget,
@@ -1933,6 +1935,7 @@ fn collect_print_requests(
         ("cfg", PrintKind::Cfg),
         ("check-cfg", PrintKind::CheckCfg),
         ("code-models", PrintKind::CodeModels),
+        ("code-size", PrintKind::CodeSize),
         ("crate-name", PrintKind::CrateName),
         ("deployment-target", PrintKind::DeploymentTarget),
         ("file-names", PrintKind::FileNames),
@@ -1941,6 +1944,7 @@ fn collect_print_requests(
         ("relocation-models", PrintKind::RelocationModels),
         ("split-debuginfo", PrintKind::SplitDebuginfo),
         ("stack-protector-strategies", PrintKind::StackProtectorStrategies),
+        ("stack-usage", PrintKind::StackUsage),
         ("sysroot", PrintKind::Sysroot),
         ("target-cpus", PrintKind::TargetCPUs),
         ("target-features", PrintKind::TargetFeatures),
@@ -1991,6 +1995,26 @@ fn collect_print_requests(
                     );
                 }
             }
+            Some((_, PrintKind::CodeSize)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::CodeSize
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the code-size print option",
+                    );
+                }
+            }
+            Some((_, PrintKind::StackUsage)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::StackUsage
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the stack-usage print option",
+                    );
+                }
+            }
             Some(&(_, print_kind)) => print_kind,
             None => {
                 let prints =
@@ -251,7 +251,7 @@ macro_rules! ins_sym {
         (64, layout.i64_align.abi),
         (128, layout.i128_align.abi),
     ] {
-        if i >= sess.target.min_atomic_width() && i <= sess.target.max_atomic_width() {
+        if i >= sess.target.min_atomic_width() && i <= sess.target.max_emulated_atomic_width() {
             if !has_atomic {
                 has_atomic = true;
                 if sess.is_nightly_build() {
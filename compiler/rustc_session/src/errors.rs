@@ -479,6 +479,18 @@ pub(crate) struct IncompatibleLinkerFlavor {
 #[diag(session_function_return_thunk_extern_requires_non_large_code_model)]
 pub(crate) struct FunctionReturnThunkExternRequiresNonLargeCodeModel;
 
+#[derive(Diagnostic)]
+#[diag(session_deferred_strings_incompatible_with_incremental)]
+pub(crate) struct DeferredStringsIncompatibleWithIncremental;
+
+#[derive(Diagnostic)]
+#[diag(session_deferred_strings_unsupported_target)]
+pub(crate) struct DeferredStringsUnsupportedTarget;
+
+#[derive(Diagnostic)]
+#[diag(session_max_stack_frame_unsupported_target)]
+pub(crate) struct MaxStackFrameUnsupportedTarget;
+
 #[derive(Diagnostic)]
 #[diag(session_failed_to_create_profiler)]
 pub(crate) struct FailedToCreateProfiler {
@@ -1683,6 +1683,10 @@ pub(crate) fn parse_mir_include_spans(slot: &mut MirIncludeSpans, v: Option<&str
         "deduplicate identical diagnostics (default: yes)"),
     default_hidden_visibility: Option<bool> = (None, parse_opt_bool, [TRACKED],
         "overrides the `default_hidden_visibility` setting of the target"),
+    deferred_strings: bool = (false, parse_bool, [TRACKED],
+        "replace the format strings of panic and log messages and panic location file names with \
+        references into a table in a non-loaded section, and format the arguments of the messages \
+        on the host (default: no)"),
     dep_info_omit_d_target: bool = (false, parse_bool, [TRACKED],
         "in dep-info output, omit targets for tracking dependencies of the dep-info files \
         themselves (default: no)"),
@@ -1715,7 +1719,7 @@ pub(crate) fn parse_mir_include_spans(slot: &mut MirIncludeSpans, v: Option<&str
         parse_switch_with_opt_path, [UNTRACKED],
         "output statistics about monomorphization collection"),
     dump_mono_stats_format: DumpMonoStatsFormat = (DumpMonoStatsFormat::Markdown, parse_dump_mono_stats, [UNTRACKED],
-        "the format to use for -Z dump-mono-stats (`markdown` (default) or `json`)"),
+        "the format to use for -Z dump-mono-stats and --print code-size (`markdown` (default) or `json`)"),
     dwarf_version: Option<u32> = (None, parse_opt_number, [TRACKED],
         "version of DWARF debug information to emit (default: 2 or 4, depending on platform)"),
     dylib_lto: bool = (false, parse_bool, [UNTRACKED],
@@ -1814,6 +1818,9 @@ pub(crate) fn parse_mir_include_spans(slot: &mut MirIncludeSpans, v: Option<&str
          `=skip-entry`
          `=skip-exit`
          Multiple options can be combined with commas."),
+    interrupt_context_summaries: bool = (false, parse_bool, [TRACKED],
+        "record in the metadata which functions may block, for the `#[interrupt_context]` \
+        checks of the crates depending on this one (default: no)"),
     layout_seed: Option<u64> = (None, parse_opt_number, [TRACKED],
         "seed layout randomization"),
     link_directives: bool = (true, parse_bool, [TRACKED],
@@ -1843,6 +1850,9 @@ pub(crate) fn parse_mir_include_spans(slot: &mut MirIncludeSpans, v: Option<&str
         (space separated)"),
     macro_backtrace: bool = (false, parse_bool, [UNTRACKED],
         "show macro backtraces (default: no)"),
+    max_stack_frame: Option<u64> = (None, parse_opt_number, [TRACKED],
+        "report an error for every function whose stack frame is larger than this many bytes, \
+        as computed by the backend (default: no limit)"),
     maximal_hir_to_mir_coverage: bool = (false, parse_bool, [TRACKED],
         "save as much information as possible about the correspondence between MIR and HIR \
         as source scopes (default: no)"),
@@ -1,4 +1,5 @@
 use std::any::Any;
+use std::hash::Hash;
 use std::ops::{Div, Mul};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
@@ -8,9 +9,10 @@
 use std::{env, fmt, io};
 
 use rustc_data_structures::flock;
-use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
+use rustc_data_structures::fx::{FxHashMap, FxIndexMap, FxIndexSet};
 use rustc_data_structures::jobserver::{self, Client};
 use rustc_data_structures::profiling::{SelfProfiler, SelfProfilerRef};
+use rustc_data_structures::stable_hasher::{Hash64, StableHasher};
 use rustc_data_structures::sync::{
     AtomicU64, DynSend, DynSync, Lock, Lrc, MappedReadGuard, ReadGuard, RwLock,
 };
@@ -27,11 +29,12 @@
 pub use rustc_span::def_id::StableCrateId;
 use rustc_span::edition::Edition;
 use rustc_span::source_map::{FilePathMapping, SourceMap};
+use rustc_span::symbol::sym;
 use rustc_span::{FileNameDisplayPreference, RealFileName, Span, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{
     CodeModel, DebuginfoKind, PanicStrategy, RelocModel, RelroLevel, SanitizerSet, SplitDebuginfo,
-    StackProtector, Target, TargetTriple, TlsModel,
+    StackProtector, Target, TargetTriple, TlsModel, XtensaAbi,
 };
 
 use crate::code_stats::CodeStats;
@@ -45,6 +48,9 @@
 use crate::search_paths::{PathKind, SearchPath};
 use crate::{errors, filesearch, lint};
 
+/// The length of the references replacing strings with `-Z deferred-strings`.
+pub const DEFERRED_STRING_REF_LEN: usize = 17;
+
 struct OptimizationFuel {
     /// If `-zfuel=crate=n` is specified, initially set to `n`, otherwise `0`.
     remaining: u64,
@@ -159,6 +165,9 @@ pub struct Session {
     /// Data about code being compiled, gathered during compilation.
     pub code_stats: CodeStats,
 
+    /// The strings replaced by references with `-Z deferred-strings`, by hash.
+    pub deferred_strings: Lock<FxIndexMap<u64, Symbol>>,
+
     /// Tracks fuel info if `-zfuel=crate=n` is specified.
     optimization_fuel: Lock<OptimizationFuel>,
 
@@ -343,6 +352,52 @@ pub fn enable_internal_lints(&self) -> bool {
         self.unstable_options() && !self.opts.actually_rustdoc
     }
 
+    /// With `-Z deferred-strings`, records `text` to be moved to the table of deferred strings and
+    /// returns the reference that replaces it in the binary: the ASCII unit separator (`\x1f`)
+    /// followed by the 16 hex digits of the hash of the text.
+    ///
+    /// Returns `None` when the option is disabled, or when the reference wouldn't be shorter than
+    /// the text itself.
+    pub fn defer_string(&self, text: &str) -> Option<Symbol> {
+        if !self.opts.unstable_opts.deferred_strings || text.len() <= DEFERRED_STRING_REF_LEN {
+            return None;
+        }
+        let hash = self.record_deferred_string(text);
+        Some(Symbol::intern(&format!("\x1f{hash:016x}")))
+    }
+
+    /// Records the format string of a panic or log message to be moved to the table of deferred
+    /// strings, and returns the reference that replaces it: the ASCII file separator (`\x1c`)
+    /// followed by the 16 hex digits of the hash of the format string. The arguments of the message
+    /// are written after the reference and formatted on the host.
+    ///
+    /// Unlike [`Session::defer_string`], this always returns a reference, since the host needs the
+    /// format string to format the arguments.
+    pub fn defer_format_string(&self, template: &str) -> Symbol {
+        let hash = self.record_deferred_string(template);
+        Symbol::intern(&format!("\x1c{hash:016x}"))
+    }
+
+    fn record_deferred_string(&self, text: &str) -> u64 {
+        let mut hasher = StableHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish::<Hash64>().as_u64();
+        self.deferred_strings.lock().insert(hash, Symbol::intern(text));
+        hash
+    }
+
+    /// Returns the string a reference returned by [`Session::defer_string`] stands for, or `s`
+    /// itself if it is not such a reference. Used where the compiler shows the string itself, such
+    /// as in const-eval panic messages.
+    pub fn resolve_deferred_string(&self, s: Symbol) -> Symbol {
+        let Some(hash) = s.as_str().strip_prefix('\x1f') else { return s };
+        if hash.len() != DEFERRED_STRING_REF_LEN - 1 {
+            return s;
+        }
+        let Ok(hash) = u64::from_str_radix(hash, 16) else { return s };
+        self.deferred_strings.lock().get(&hash).copied().unwrap_or(s)
+    }
+
     pub fn instrument_coverage(&self) -> bool {
         self.opts.cg.instrument_coverage() != InstrumentCoverage::No
     }
@@ -774,6 +829,20 @@ pub fn split_debuginfo(&self) -> SplitDebuginfo {
         self.opts.cg.split_debuginfo.unwrap_or(self.target.split_debuginfo)
     }
 
+    /// Returns the Xtensa calling convention for this compile session, or `None` when not
+    /// targeting Xtensa. The ABI follows the `windowed` target feature, which the target selects
+    /// through `llvm-abiname` and which can be overridden with `-C target-feature`.
+    pub fn xtensa_abi(&self) -> Option<XtensaAbi> {
+        if self.target.arch != "xtensa" {
+            return None;
+        }
+        Some(if self.unstable_target_features.contains(&sym::windowed) {
+            XtensaAbi::Windowed
+        } else {
+            XtensaAbi::Call0
+        })
+    }
+
     pub fn stack_protector(&self) -> StackProtector {
         if self.target.options.supports_stack_protector {
             self.opts.unstable_opts.stack_protector
@@ -1111,6 +1180,7 @@ pub fn build_session(
         incr_comp_session: RwLock::new(IncrCompSession::NotInitialized),
         prof,
         code_stats: Default::default(),
+        deferred_strings: Default::default(),
         optimization_fuel,
         print_fuel,
         jobserver: jobserver::client(),
@@ -1340,6 +1410,36 @@ fn validate_commandline_args_with_session_available(sess: &Session) {
             }
         }
     }
+
+    // The deferred strings are collected during AST lowering and emitted with each codegen unit,
+    // which doesn't work when either can be reused from the incremental cache. The table is
+    // emitted with global assembly, into an ELF section group.
+    if sess.opts.unstable_opts.deferred_strings {
+        if sess.opts.incremental.is_some() {
+            sess.dcx().emit_err(errors::DeferredStringsIncompatibleWithIncremental);
+        }
+        let target = &sess.target;
+        if sess.asm_arch.is_none()
+            || target.is_like_osx
+            || target.is_like_windows
+            || target.is_like_wasm
+            || target.is_like_aix
+        {
+            sess.dcx().emit_err(errors::DeferredStringsUnsupportedTarget);
+        }
+    }
+
+    // The frame sizes are read from the `.stack_sizes` sections, which LLVM only emits for ELF.
+    if sess.opts.unstable_opts.max_stack_frame.is_some() {
+        let target = &sess.target;
+        if target.is_like_osx
+            || target.is_like_windows
+            || target.is_like_wasm
+            || target.is_like_aix
+        {
+            sess.dcx().emit_err(errors::MaxStackFrameUnsupportedTarget);
+        }
+    }
 }
 
 /// Holds data on the current incremental compilation session, if there is one.
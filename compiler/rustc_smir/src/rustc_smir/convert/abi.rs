@@ -117,6 +117,7 @@ fn stable(&self, _tables: &mut Tables<'_>) -> Self::T {
             Conv::AvrInterrupt => CallConvention::AvrInterrupt,
             Conv::AvrNonBlockingInterrupt => CallConvention::AvrNonBlockingInterrupt,
             Conv::RiscvInterrupt { .. } => CallConvention::RiscvInterrupt,
+            Conv::XtensaInterrupt { .. } => CallConvention::XtensaInterrupt,
         }
     }
 }
@@ -917,6 +917,7 @@ fn stable(&self, _: &mut Tables<'_>) -> Self::T {
             abi::Abi::RustCold => Abi::RustCold,
             abi::Abi::RiscvInterruptM => Abi::RiscvInterruptM,
             abi::Abi::RiscvInterruptS => Abi::RiscvInterruptS,
+            abi::Abi::XtensaInterrupt { level } => Abi::XtensaInterrupt { level },
         }
     }
 }
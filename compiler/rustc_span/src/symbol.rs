@@ -371,7 +371,9 @@
         abi_unadjusted,
         abi_vectorcall,
         abi_x86_interrupt,
+        abi_xtensa_interrupt,
         abort,
+        accx,
         add,
         add_assign,
         add_with_overflow,
@@ -426,7 +428,9 @@
         assert_eq_macro,
         assert_inhabited,
         assert_macro,
+        assert_matches,
         assert_mem_uninitialized_valid,
+        assert_ne,
         assert_ne_macro,
         assert_receiver_is_total_eq,
         assert_zero_valid,
@@ -761,6 +765,7 @@
         dotdot: "..",
         dotdot_in_tuple_patterns,
         dotdoteq_in_patterns,
+        dram,
         dreg,
         dreg_low16,
         dreg_low8,
@@ -796,6 +801,7 @@
         eprintln_macro,
         eq,
         ermsb_target_feature,
+        esp32s3,
         exact_div,
         except,
         exception,
@@ -1060,6 +1066,7 @@
         integral,
         intel,
         interrupt,
+        interrupt_context,
         into_async_iter_into_iter,
         into_future,
         into_iter,
@@ -1069,6 +1076,7 @@
         intrinsics_unaligned_volatile_store,
         io_stderr,
         io_stdout,
+        iram,
         irrefutable_let_patterns,
         is,
         is_val_statically_known,
@@ -1122,6 +1130,7 @@
         link_llvm_intrinsics,
         link_name,
         link_ordinal,
+        link_region,
         link_section,
         linkage,
         linker,
@@ -1131,6 +1140,7 @@
         loaded_from_disk,
         local,
         local_inner_macros,
+        log,
         log10f128,
         log10f16,
         log10f32,
@@ -1171,10 +1181,12 @@
         match_beginning_vert,
         match_default_bindings,
         matches_macro,
+        max_stack_frame,
         maxnumf128,
         maxnumf16,
         maxnumf32,
         maxnumf64,
+        may_block,
         may_dangle,
         may_unwind,
         maybe_uninit,
@@ -1299,6 +1311,15 @@
         new_const,
         new_debug,
         new_debug_noop,
+        new_deferred_binary,
+        new_deferred_debug,
+        new_deferred_display,
+        new_deferred_lower_exp,
+        new_deferred_lower_hex,
+        new_deferred_octal,
+        new_deferred_pointer,
+        new_deferred_upper_exp,
+        new_deferred_upper_hex,
         new_display,
         new_lower_exp,
         new_lower_hex,
@@ -1324,6 +1345,7 @@
         no_link,
         no_main,
         no_mangle,
+        no_panic,
         no_sanitize,
         no_stack_check,
         no_start,
@@ -1601,6 +1623,8 @@
         roundf32,
         roundf64,
         rt,
+        rtc_fast,
+        rtc_slow,
         rtm_target_feature,
         rust,
         rust_2015,
@@ -1929,6 +1953,7 @@
         to_string,
         to_string_method,
         to_vec,
+        todo,
         todo_macro,
         tool_attributes,
         tool_lints,
@@ -2018,6 +2043,7 @@
         underscore_imports,
         underscore_lifetimes,
         uniform_paths,
+        unimplemented,
         unimplemented_macro,
         unit,
         universal_impl_trait,
@@ -799,6 +799,8 @@ pub enum Conv {
     AvrNonBlockingInterrupt,
 
     RiscvInterrupt { kind: RiscvInterruptKind },
+
+    XtensaInterrupt { level: u8 },
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, HashStable_Generic)]
@@ -992,7 +994,15 @@ fn from_str(s: &str) -> Result<Self, Self::Err> {
             "RiscvInterrupt(supervisor)" => {
                 Ok(Conv::RiscvInterrupt { kind: RiscvInterruptKind::Supervisor })
             }
-            _ => Err(format!("'{s}' is not a valid value for entry function call convention.")),
+            _ => match s.strip_prefix("XtensaInterrupt(").and_then(|s| s.strip_suffix(')')) {
+                Some(level) => match level.parse() {
+                    Ok(level @ 1..=7) => Ok(Conv::XtensaInterrupt { level }),
+                    _ => Err(format!("'{level}' is not a valid Xtensa interrupt level.")),
+                },
+                None => {
+                    Err(format!("'{s}' is not a valid value for entry function call convention."))
+                }
+            },
         }
     }
 }
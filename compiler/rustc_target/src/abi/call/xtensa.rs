@@ -4,8 +4,13 @@
 //!
 //! Section 8.1.4 & 8.1.5 of the Xtensa ISA reference manual, as well as snippets from
 //! Section 2.3 from the Xtensa programmers guide.
+//!
+//! The windowed and call0 ABIs differ in which registers survive a call, but as seen by the
+//! callee both pass arguments in a2-a7 and return values in a2-a5, so the classification
+//! below is shared. The register differences are handled by the backend and by the
+//! `clobber_abi` lists in `asm/mod.rs`.
 
-use crate::abi::call::{ArgAbi, FnAbi, Reg, Uniform};
+use crate::abi::call::{ArgAbi, Conv, FnAbi, Reg, Uniform};
 use crate::abi::{Abi, HasDataLayout, Size, TyAbiInterface};
 use crate::spec::HasTargetSpec;
 
@@ -101,6 +106,12 @@ pub fn compute_abi_info<'a, Ty, C>(_cx: &C, fn_abi: &mut FnAbi<'a, Ty>)
     Ty: TyAbiInterface<'a, C> + Copy,
     C: HasDataLayout + HasTargetSpec,
 {
+    // Interrupt handlers are entered from the exception vector with no arguments and must
+    // not return a value; typeck rejects any other signature, so there is nothing to classify.
+    if let Conv::XtensaInterrupt { .. } = fn_abi.conv {
+        return;
+    }
+
     if !fn_abi.ret.is_ignore() {
         classify_ret_ty(&mut fn_abi.ret);
     }
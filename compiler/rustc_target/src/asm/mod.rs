@@ -3,7 +3,7 @@
 
 use rustc_data_structures::fx::{FxHashMap, FxIndexSet};
 use rustc_macros::{Decodable, Encodable, HashStable_Generic};
-use rustc_span::Symbol;
+use rustc_span::{sym, Symbol};
 
 use crate::abi::Size;
 use crate::spec::{RelocModel, Target};
@@ -921,6 +921,8 @@ pub enum InlineAsmClobberAbi {
     AArch64NoX18,
     RiscV,
     LoongArch,
+    XtensaWindowed,
+    XtensaCall0,
 }
 
 impl InlineAsmClobberAbi {
@@ -929,6 +931,7 @@ impl InlineAsmClobberAbi {
     pub fn parse(
         arch: InlineAsmArch,
         target: &Target,
+        target_features: &FxIndexSet<Symbol>,
         name: Symbol,
     ) -> Result<Self, &'static [&'static str]> {
         let name = name.as_str();
@@ -970,6 +973,14 @@ pub fn parse(
                 "C" | "system" => Ok(InlineAsmClobberAbi::LoongArch),
                 _ => Err(&["C", "system"]),
             },
+            InlineAsmArch::Xtensa => match name {
+                "C" | "system" => Ok(if target_features.contains(&sym::windowed) {
+                    InlineAsmClobberAbi::XtensaWindowed
+                } else {
+                    InlineAsmClobberAbi::XtensaCall0
+                }),
+                _ => Err(&["C", "system"]),
+            },
             _ => Err(&[]),
         }
     }
@@ -1127,6 +1138,41 @@ macro_rules! clobbered_regs {
                     f16, f17, f18, f19, f20, f21, f22, f23,
                 }
             },
+            InlineAsmClobberAbi::XtensaWindowed => clobbered_regs! {
+                Xtensa XtensaInlineAsmReg {
+                    // A `CALL8` rotates the window so that the callee may use a8-a15, the
+                    // rest of the caller's window is preserved.
+                    a8, a9, a10, a11, a12, a13, a14, a15,
+
+                    sar,
+
+                    f0, f1, f2, f3, f4, f5, f6, f7,
+                    f8, f9, f10, f11, f12, f13, f14, f15,
+
+                    b0, b1, b2, b3, b4, b5, b6, b7,
+                    b8, b9, b10, b11, b12, b13, b14, b15,
+
+                    // The Q registers are caller-saved. ACCX is not part of the calling
+                    // convention, code that uses it declares it as clobbered itself.
+                    q0, q1, q2, q3, q4, q5, q6, q7,
+                }
+            },
+            InlineAsmClobberAbi::XtensaCall0 => clobbered_regs! {
+                Xtensa XtensaInlineAsmReg {
+                    // a12-a15 are callee-saved, a0 (the return address) cannot be an operand.
+                    a2, a3, a4, a5, a6, a7, a8, a9, a10, a11,
+
+                    sar,
+
+                    f0, f1, f2, f3, f4, f5, f6, f7,
+                    f8, f9, f10, f11, f12, f13, f14, f15,
+
+                    b0, b1, b2, b3, b4, b5, b6, b7,
+                    b8, b9, b10, b11, b12, b13, b14, b15,
+
+                    q0, q1, q2, q3, q4, q5, q6, q7,
+                }
+            },
         }
     }
 }
@@ -9,6 +9,8 @@
         reg,
         freg,
         breg,
+        qreg,
+        accx,
     }
 }
 
@@ -41,21 +43,25 @@ pub fn supported_types(
             Self::reg => types! { _: I8, I16, I32; },
             Self::breg => types! { bool: I1; },
             Self::freg => types! { fp: F32; dfpaccel: F64; },
+            Self::qreg => types! { esp32s3: VecI8(16), VecI16(8), VecI32(4); },
+            Self::accx => &[],
         }
     }
 }
 
 // Xtensa has lots of features - macro to reduce boiler plate
 macro_rules! feature {
-    ($fnname:ident, $feature:expr) => {
+    ($fnname:ident, $feature:ident) => {
         fn $fnname(
             _arch: InlineAsmArch,
             _reloc_model: RelocModel,
             target_features: &FxIndexSet<Symbol>,
             _target: &Target,
-            _is_clobber: bool,
+            is_clobber: bool,
         ) -> Result<(), &'static str> {
-            if target_features.contains(&$feature) {
+            // Clobbering a register the target doesn't have is harmless, which lets
+            // `clobber_abi` list the FP and boolean registers unconditionally.
+            if target_features.contains(&sym::$feature) || is_clobber {
                 Ok(())
             } else {
                 Err(concat!("target does not support ", stringify!($feature), " registers"))
@@ -64,26 +70,27 @@ fn $fnname(
     };
 }
 
-feature!(has_fp, sym::fp);
-feature!(has_dfpaccel, sym::dfpaccel);
-feature!(has_bool, sym::bool);
-feature!(has_xloop, sym::xloop);
-feature!(has_extendedl32r, sym::extendedl32r);
-feature!(has_s32c1i, sym::s32c1i);
-feature!(has_mac16, sym::mac16);
-feature!(has_windowed, sym::windowed);
-feature!(has_debug, sym::debug);
-feature!(has_memctl, sym::memctl);
-feature!(has_atomctl, sym::atomctl);
-feature!(has_exception, sym::exception);
-feature!(has_highpriinterrupts, sym::highpriinterrupts);
-feature!(has_coprocessor, sym::coprocessor);
-feature!(has_rvector, sym::rvector);
-feature!(has_timerint, sym::timerint);
-feature!(has_interrupt, sym::interrupt);
-feature!(has_prid, sym::prid);
-feature!(has_miscsr, sym::miscsr);
-feature!(has_threadptr, sym::threadptr);
+feature!(has_fp, fp);
+feature!(has_dfpaccel, dfpaccel);
+feature!(has_bool, bool);
+feature!(has_xloop, xloop);
+feature!(has_extendedl32r, extendedl32r);
+feature!(has_s32c1i, s32c1i);
+feature!(has_mac16, mac16);
+feature!(has_windowed, windowed);
+feature!(has_debug, debug);
+feature!(has_memctl, memctl);
+feature!(has_atomctl, atomctl);
+feature!(has_exception, exception);
+feature!(has_highpriinterrupts, highpriinterrupts);
+feature!(has_coprocessor, coprocessor);
+feature!(has_rvector, rvector);
+feature!(has_timerint, timerint);
+feature!(has_interrupt, interrupt);
+feature!(has_prid, prid);
+feature!(has_miscsr, miscsr);
+feature!(has_threadptr, threadptr);
+feature!(has_esp32s3, esp32s3);
 
 fn has_expstate(
     _arch: InlineAsmArch,
@@ -271,6 +278,18 @@ fn frame_pointer_a15(
         b13: breg = ["b13"] % has_bool,
         b14: breg = ["b14"] % has_bool,
         b15: breg = ["b15"] % has_bool,
+        q0: qreg = ["q0"] % has_esp32s3,
+        q1: qreg = ["q1"] % has_esp32s3,
+        q2: qreg = ["q2"] % has_esp32s3,
+        q3: qreg = ["q3"] % has_esp32s3,
+        q4: qreg = ["q4"] % has_esp32s3,
+        q5: qreg = ["q5"] % has_esp32s3,
+        q6: qreg = ["q6"] % has_esp32s3,
+        q7: qreg = ["q7"] % has_esp32s3,
+        // The vector accumulator is only accessible through the PIE instructions, so it can
+        // only be clobbered.
+        accx_0: accx = ["accx_0"] % has_esp32s3,
+        accx_1: accx = ["accx_1"] % has_esp32s3,
 
         // Custom TIE extensions - https://en.wikipedia.org/wiki/Tensilica_Instruction_Extension
         gpio_out: reg = ["gpio_out"] % has_gpio_out,
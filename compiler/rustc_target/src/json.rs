@@ -118,6 +118,10 @@ fn to_json(&self) -> Json {
                 buf = format!("RiscvInterrupt({})", kind.as_str());
                 &buf
             }
+            Self::XtensaInterrupt { level } => {
+                buf = format!("XtensaInterrupt({level})");
+                &buf
+            }
         };
         Json::String(s.to_owned())
     }
@@ -62,6 +62,11 @@ pub enum Abi {
     RustCold,
     RiscvInterruptM,
     RiscvInterruptS,
+    /// An Xtensa interrupt handler for the given interrupt level. Level 1 handlers return with
+    /// `RFE`, high-priority handlers (levels 2 to 7) with `RFI <level>`.
+    XtensaInterrupt {
+        level: u8,
+    },
 }
 
 impl Abi {
@@ -132,6 +137,13 @@ pub struct AbiData {
     AbiData { abi: Abi::RustCold, name: "rust-cold" },
     AbiData { abi: Abi::RiscvInterruptM, name: "riscv-interrupt-m" },
     AbiData { abi: Abi::RiscvInterruptS, name: "riscv-interrupt-s" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 1 }, name: "xtensa-interrupt" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 2 }, name: "xtensa-interrupt-2" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 3 }, name: "xtensa-interrupt-3" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 4 }, name: "xtensa-interrupt-4" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 5 }, name: "xtensa-interrupt-5" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 6 }, name: "xtensa-interrupt-6" },
+    AbiData { abi: Abi::XtensaInterrupt { level: 7 }, name: "xtensa-interrupt-7" },
 ];
 
 #[derive(Copy, Clone, Debug)]
@@ -149,6 +161,9 @@ pub fn lookup(name: &str) -> Result<Abi, AbiUnsupported> {
         "riscv-interrupt-u" => AbiUnsupported::Reason {
             explain: "user-mode interrupt handlers have been removed from LLVM pending standardization, see: https://reviews.llvm.org/D149314",
         },
+        "xtensa-interrupt-1" => AbiUnsupported::Reason {
+            explain: "level-1 interrupt handlers use the plain xtensa-interrupt ABI",
+        },
         "wasm" => AbiUnsupported::Reason {
             explain: "non-standard wasm ABI is no longer supported",
         },
@@ -240,6 +255,13 @@ pub fn is_stable(name: &str) -> Result<(), AbiDisabled> {
             feature: sym::abi_riscv_interrupt,
             explain: "riscv-interrupt ABIs are experimental and subject to change",
         }),
+        "xtensa-interrupt" | "xtensa-interrupt-2" | "xtensa-interrupt-3" | "xtensa-interrupt-4"
+        | "xtensa-interrupt-5" | "xtensa-interrupt-6" | "xtensa-interrupt-7" => {
+            Err(AbiDisabled::Unstable {
+                feature: sym::abi_xtensa_interrupt,
+                explain: "xtensa-interrupt ABIs are experimental and subject to change",
+            })
+        }
         "C-cmse-nonsecure-call" => Err(AbiDisabled::Unstable {
             feature: sym::abi_c_cmse_nonsecure_call,
             explain: "C-cmse-nonsecure-call ABI is experimental and subject to change",
@@ -295,6 +317,7 @@ pub fn index(self) -> usize {
             RustCold => 31,
             RiscvInterruptM => 32,
             RiscvInterruptS => 33,
+            XtensaInterrupt { level } => 33 + level as usize,
         };
         debug_assert!(
             AbiDatas
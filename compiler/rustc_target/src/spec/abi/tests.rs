@@ -27,3 +27,11 @@ fn indices_are_correct() {
         assert_eq!(i, abi_data.abi.index());
     }
 }
+
+#[test]
+fn lookup_xtensa_interrupt_levels() {
+    assert_matches!(lookup("xtensa-interrupt"), Ok(Abi::XtensaInterrupt { level: 1 }));
+    assert_matches!(lookup("xtensa-interrupt-5"), Ok(Abi::XtensaInterrupt { level: 5 }));
+    assert_matches!(lookup("xtensa-interrupt-1"), Err(AbiUnsupported::Reason { .. }));
+    assert_matches!(lookup("xtensa-interrupt-8"), Err(AbiUnsupported::Unrecognized));
+}
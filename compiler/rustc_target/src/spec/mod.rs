@@ -774,6 +774,27 @@ pub enum PanicStrategy {
     Abort,
 }
 
+/// The register convention used for calls on Xtensa targets.
+///
+/// The windowed ABI is the default. Targets select call0 by setting `llvm-abiname` to `call0`,
+/// which is equivalent to disabling the `windowed` target feature.
+#[derive(Clone, Copy, Debug, PartialEq, Hash, Encodable, Decodable, HashStable_Generic)]
+pub enum XtensaAbi {
+    /// Calls rotate the register window with `CALL4/8/12`, `ENTRY` and `RETW`.
+    Windowed,
+    /// Calls use `CALL0`/`RET` on a flat register file, with `a12`-`a15` callee-saved.
+    Call0,
+}
+
+impl XtensaAbi {
+    pub fn desc(&self) -> &'static str {
+        match *self {
+            XtensaAbi::Windowed => "windowed",
+            XtensaAbi::Call0 => "call0",
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Hash, Encodable, Decodable, HashStable_Generic)]
 pub enum OnBrokenPipe {
     Default,
@@ -1761,6 +1782,8 @@ fn $module() {
     ("riscv32imc-esp-espidf", riscv32imc_esp_espidf),
     ("riscv32imac-esp-espidf", riscv32imac_esp_espidf),
     ("riscv32imafc-esp-espidf", riscv32imafc_esp_espidf),
+    ("riscv32imc-espulp-none-elf", riscv32imc_espulp_none_elf),
+    ("riscv32imac-esplp-none-elf", riscv32imac_esplp_none_elf),
 
     ("riscv32imac-unknown-none-elf", riscv32imac_unknown_none_elf),
     ("riscv32imafc-unknown-none-elf", riscv32imafc_unknown_none_elf),
@@ -2236,6 +2259,9 @@ pub struct TargetOptions {
     /// Don't use this field; instead use the `.max_atomic_width()` method.
     pub max_atomic_width: Option<u64>,
 
+    /// Don't use this field; instead use the `.max_emulated_atomic_width()` method.
+    pub max_emulated_atomic_width: Option<u64>,
+
     /// Whether the target supports atomic CAS operations natively
     pub atomic_cas: bool,
 
@@ -2565,6 +2591,7 @@ fn default() -> TargetOptions {
             bitcode_llvm_cmdline: "".into(),
             min_atomic_width: None,
             max_atomic_width: None,
+            max_emulated_atomic_width: None,
             atomic_cas: true,
             panic_strategy: PanicStrategy::Unwind,
             crt_static_allows_dylibs: false,
@@ -2682,6 +2709,7 @@ pub fn is_abi_supported(&self, abi: Abi) -> Option<bool> {
             PtxKernel => self.arch == "nvptx64",
             Msp430Interrupt => self.arch == "msp430",
             RiscvInterruptM | RiscvInterruptS => ["riscv32", "riscv64"].contains(&&self.arch[..]),
+            XtensaInterrupt { .. } => self.arch == "xtensa",
             AvrInterrupt | AvrNonBlockingInterrupt => self.arch == "avr",
             Thiscall { .. } => self.arch == "x86",
             // On windows these fall-back to platform native calling convention (C) when the
@@ -2736,6 +2764,16 @@ pub fn max_atomic_width(&self) -> u64 {
         self.max_atomic_width.unwrap_or_else(|| self.pointer_width.into())
     }
 
+    /// Maximum integer size in bits that atomic operations are available for.
+    ///
+    /// Atomic operations wider than `max_atomic_width` but no wider than this are lowered
+    /// to calls to the `__atomic_*_N` functions of the libatomic ABI. The runtime has to
+    /// provide these, typically by disabling interrupts or taking a spinlock around the
+    /// access, and must use the same mechanism for every access to a given location.
+    pub fn max_emulated_atomic_width(&self) -> u64 {
+        self.max_emulated_atomic_width.unwrap_or_else(|| self.max_atomic_width())
+    }
+
     /// Loads a target descriptor from a JSON object.
     pub fn from_json(obj: Json) -> Result<(Target, TargetWarnings), String> {
         // While ugly, this code must remain this way to retain
@@ -3282,6 +3320,7 @@ macro_rules! key {
         key!(forces_embed_bitcode, bool);
         key!(bitcode_llvm_cmdline);
         key!(max_atomic_width, Option<u64>);
+        key!(max_emulated_atomic_width, Option<u64>);
         key!(min_atomic_width, Option<u64>);
         key!(atomic_cas, bool);
         key!(panic_strategy, PanicStrategy)?;
@@ -3538,6 +3577,7 @@ macro_rules! target_option_val {
         target_option_val!(bitcode_llvm_cmdline);
         target_option_val!(min_atomic_width);
         target_option_val!(max_atomic_width);
+        target_option_val!(max_emulated_atomic_width);
         target_option_val!(atomic_cas);
         target_option_val!(panic_strategy);
         target_option_val!(crt_static_allows_dylibs);
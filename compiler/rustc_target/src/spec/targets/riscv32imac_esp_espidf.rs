@@ -24,6 +24,8 @@ pub fn target() -> Target {
             // As RiscV32IMAC architecture does natively support atomics,
             // automatically enable the support for the Rust STD library.
             max_atomic_width: Some(32),
+            // ESP-IDF implements the `__atomic_*_8` functions with critical sections.
+            max_emulated_atomic_width: Some(64),
             atomic_cas: true,
 
             features: "+m,+a,+c".into(),
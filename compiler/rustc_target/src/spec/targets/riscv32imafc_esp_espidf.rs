@@ -22,6 +22,8 @@ pub fn target() -> Target {
             cpu: "generic-rv32".into(),
 
             max_atomic_width: Some(32),
+            // ESP-IDF implements the `__atomic_*_8` functions with critical sections.
+            max_emulated_atomic_width: Some(64),
             atomic_cas: true,
 
             llvm_abiname: "ilp32f".into(),
@@ -27,6 +27,8 @@ pub fn target() -> Target {
             //
             // Support for atomics is necessary for the Rust STD library, which is supported by the ESP-IDF framework.
             max_atomic_width: Some(32),
+            // ESP-IDF implements the `__atomic_*_8` functions with critical sections.
+            max_emulated_atomic_width: Some(64),
             atomic_cas: true,
 
             features: "+m,+c".into(),
@@ -0,0 +1,37 @@
+use crate::spec::{
+    Cc, CodeModel, LinkerFlavor, Lld, PanicStrategy, RelocModel, Target, TargetOptions,
+};
+
+pub fn target() -> Target {
+    Target {
+        data_layout: "e-m:e-p:32:32-i64:64-n32-S128".into(),
+        llvm_target: "riscv32".into(),
+        metadata: crate::spec::TargetMetadata {
+            description: Some("ULP-RISC-V coprocessor of the ESP32-S2/S3".into()),
+            tier: Some(3),
+            host_tools: Some(false),
+            std: Some(false),
+        },
+        pointer_width: 32,
+        arch: "riscv32".into(),
+
+        options: TargetOptions {
+            vendor: "espressif".into(),
+            linker_flavor: LinkerFlavor::Gnu(Cc::No, Lld::Yes),
+            linker: Some("rust-lld".into()),
+            cpu: "generic-rv32".into(),
+            max_atomic_width: Some(32),
+            atomic_cas: false,
+            features: "+m,+c,+forced-atomics".into(),
+            panic_strategy: PanicStrategy::Abort,
+            // The program is linked at a fixed address in RTC slow memory, which the
+            // coprocessor sees at address 0.
+            relocation_model: RelocModel::Static,
+            // The whole program has to fit into a few kilobytes of RTC memory.
+            code_model: Some(CodeModel::Small),
+            emit_debug_gdb_scripts: false,
+            eh_frame_header: false,
+            ..Default::default()
+        },
+    }
+}
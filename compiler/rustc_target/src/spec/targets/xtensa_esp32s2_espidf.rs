@@ -36,6 +36,8 @@ pub fn target() -> Target {
             //
             // Support for atomics is necessary for the Rust STD library, which is supported by ESP-IDF.
             max_atomic_width: Some(32),
+            // ESP-IDF implements the `__atomic_*_8` functions with critical sections.
+            max_emulated_atomic_width: Some(64),
             atomic_cas: true,
 
             ..xtensa::opts()
@@ -18,6 +18,8 @@ pub fn target() -> Target {
             cpu: "esp32-s2".into(),
             linker: Some("xtensa-esp32s2-elf-gcc".into()),
             max_atomic_width: Some(32),
+            // The HAL provides the `__atomic_*_8` functions backing 64-bit atomics.
+            max_emulated_atomic_width: Some(64),
             ..xtensa::opts()
         },
     }
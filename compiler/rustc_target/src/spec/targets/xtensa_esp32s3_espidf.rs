@@ -29,6 +29,8 @@ pub fn target() -> Target {
 
             // The esp32s3 only supports native 32bit atomics.
             max_atomic_width: Some(32),
+            // ESP-IDF implements the `__atomic_*_8` functions with critical sections.
+            max_emulated_atomic_width: Some(64),
             atomic_cas: true,
 
             ..xtensa::opts()
@@ -15,8 +15,11 @@ pub fn target() -> Target {
 
         options: TargetOptions {
             cpu: "esp8266".into(),
+            llvm_abiname: "call0".into(),
             linker: Some("xtensa-lx106-elf-gcc".into()),
             max_atomic_width: Some(32),
+            // The HAL provides the `__atomic_*_8` functions backing 64-bit atomics.
+            max_emulated_atomic_width: Some(64),
             ..xtensa::opts()
         },
     }
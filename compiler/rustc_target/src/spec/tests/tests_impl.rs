@@ -152,6 +152,12 @@ fn check_consistency(&self) {
         if self.crt_static_default || self.crt_static_allows_dylibs {
             assert!(self.crt_static_respected);
         }
+
+        // Emulated atomics only make sense on top of the native ones.
+        if let Some(width) = self.max_emulated_atomic_width {
+            assert!(width > self.max_atomic_width());
+            assert!(width.is_power_of_two() && width <= 128);
+        }
     }
 
     // Add your target to the whitelist if it has `std` library
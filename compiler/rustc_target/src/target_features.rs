@@ -524,6 +524,7 @@ pub fn is_stable(self) -> bool {
     ("prid", Unstable(sym::xtensa_target_feature), &[]),
     ("regprotect", Unstable(sym::xtensa_target_feature), &[]),
     ("miscsr", Unstable(sym::xtensa_target_feature), &[]),
+    ("esp32s3", Unstable(sym::xtensa_target_feature), &[]),
 ];
 
 /// When rustdoc is running, provide a list of all known features so that all their respective
@@ -566,6 +567,7 @@ pub fn supported_target_features(
             "csky" => CSKY_ALLOWED_FEATURES,
             "loongarch64" => LOONGARCH_ALLOWED_FEATURES,
             "s390x" => IBMZ_ALLOWED_FEATURES,
+            "xtensa" => XTENSA_ALLOWED_FEATURES,
             _ => &[],
         }
     }
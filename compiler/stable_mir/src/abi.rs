@@ -451,4 +451,6 @@ pub enum CallConvention {
     AvrNonBlockingInterrupt,
 
     RiscvInterrupt,
+
+    XtensaInterrupt,
 }
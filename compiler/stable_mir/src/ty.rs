@@ -1069,6 +1069,7 @@ pub enum Abi {
     RustCold,
     RiscvInterruptM,
     RiscvInterruptS,
+    XtensaInterrupt { level: u8 },
 }
 
 /// A binder represents a possibly generic type and its bound vars.
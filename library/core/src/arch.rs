@@ -4,6 +4,11 @@
 #[stable(feature = "simd_arch", since = "1.27.0")]
 pub use crate::core_arch::arch::*;
 
+#[cfg(any(target_arch = "xtensa", doc))]
+#[doc(cfg(target_arch = "xtensa"))]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub mod xtensa;
+
 #[cfg(bootstrap)]
 #[allow(dead_code)]
 #[unstable(feature = "sha512_sm_x86", issue = "126624")]
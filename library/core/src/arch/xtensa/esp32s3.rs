@@ -0,0 +1,163 @@
+//! ESP32-S3 processor instruction extensions (PIE), the 128-bit SIMD unit of the ESP32-S3.
+//!
+//! The vector operations here work on 16-byte blocks in memory: both operands are loaded into
+//! `q0` and `q1`, the result is computed into `q2` and then stored back. All pointers must be
+//! 16-byte aligned, as the PIE load/store instructions ignore the low four address bits.
+//!
+//! The Q registers can also hold `qreg` operands of inline assembly, so every function here
+//! declares the ones it uses as clobbered. `ACCX` is never allocated by the compiler, but it is
+//! global state that interrupt handlers using it must save and restore.
+
+use crate::arch::asm;
+
+macro_rules! binary_ops {
+    ($($(#[$attr:meta])* fn $name:ident($ty:ty) = $insn:literal;)*) => {
+        $(
+            $(#[$attr])*
+            ///
+            /// # Safety
+            ///
+            /// `a` and `b` must be valid for reads and `dst` valid for writes of 16 bytes, and
+            /// all three must be 16-byte aligned.
+            #[inline]
+            #[target_feature(enable = "esp32s3")]
+            #[unstable(feature = "stdarch_xtensa", issue = "none")]
+            pub unsafe fn $name(a: *const $ty, b: *const $ty, dst: *mut $ty) {
+                // SAFETY: guaranteed by the caller.
+                unsafe {
+                    asm!(
+                        "ee.vld.128.ip q0, {a}, 0",
+                        "ee.vld.128.ip q1, {b}, 0",
+                        concat!($insn, " q2, q0, q1"),
+                        "ee.vst.128.ip q2, {dst}, 0",
+                        a = in(reg) a,
+                        b = in(reg) b,
+                        dst = in(reg) dst,
+                        out("q0") _,
+                        out("q1") _,
+                        out("q2") _,
+                        options(nostack),
+                    )
+                };
+            }
+        )*
+    };
+}
+
+macro_rules! mul_ops {
+    ($($(#[$attr:meta])* fn $name:ident($ty:ty) = $insn:literal;)*) => {
+        $(
+            $(#[$attr])*
+            ///
+            /// # Safety
+            ///
+            /// `a` and `b` must be valid for reads and `dst` valid for writes of 16 bytes, and
+            /// all three must be 16-byte aligned.
+            #[inline]
+            #[target_feature(enable = "esp32s3")]
+            #[unstable(feature = "stdarch_xtensa", issue = "none")]
+            pub unsafe fn $name(a: *const $ty, b: *const $ty, dst: *mut $ty, shift: u32) {
+                // SAFETY: guaranteed by the caller. The instruction takes the shift from `SAR`,
+                // which the compiler uses for its own shifts, so it is restored afterwards.
+                unsafe {
+                    asm!(
+                        "rsr {sar}, sar",
+                        "wsr {shift}, sar",
+                        "ee.vld.128.ip q0, {a}, 0",
+                        "ee.vld.128.ip q1, {b}, 0",
+                        concat!($insn, " q2, q0, q1"),
+                        "ee.vst.128.ip q2, {dst}, 0",
+                        "wsr {sar}, sar",
+                        a = in(reg) a,
+                        b = in(reg) b,
+                        dst = in(reg) dst,
+                        shift = in(reg) shift,
+                        sar = out(reg) _,
+                        out("q0") _,
+                        out("q1") _,
+                        out("q2") _,
+                        options(nostack),
+                    )
+                };
+            }
+        )*
+    };
+}
+
+binary_ops! {
+    /// Adds sixteen `i8` lanes with signed saturation (`EE.VADDS.S8`).
+    fn vadds_s8(i8) = "ee.vadds.s8";
+    /// Adds eight `i16` lanes with signed saturation (`EE.VADDS.S16`).
+    fn vadds_s16(i16) = "ee.vadds.s16";
+    /// Adds four `i32` lanes with signed saturation (`EE.VADDS.S32`).
+    fn vadds_s32(i32) = "ee.vadds.s32";
+    /// Subtracts sixteen `i8` lanes with signed saturation (`EE.VSUBS.S8`).
+    fn vsubs_s8(i8) = "ee.vsubs.s8";
+    /// Subtracts eight `i16` lanes with signed saturation (`EE.VSUBS.S16`).
+    fn vsubs_s16(i16) = "ee.vsubs.s16";
+    /// Subtracts four `i32` lanes with signed saturation (`EE.VSUBS.S32`).
+    fn vsubs_s32(i32) = "ee.vsubs.s32";
+}
+
+mul_ops! {
+    /// Multiplies sixteen `i8` lanes, shifting each product right by `shift` (`EE.VMUL.S8`).
+    fn vmul_s8(i8) = "ee.vmul.s8";
+    /// Multiplies eight `i16` lanes, shifting each product right by `shift` (`EE.VMUL.S16`).
+    fn vmul_s16(i16) = "ee.vmul.s16";
+}
+
+/// Clears the 40-bit vector accumulator `ACCX` (`EE.ZERO.ACCX`).
+#[inline]
+#[target_feature(enable = "esp32s3")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn zero_accx() {
+    // SAFETY: only `ACCX` is modified.
+    unsafe { asm!("ee.zero.accx", out("accx_0") _, out("accx_1") _, options(nomem, nostack)) };
+}
+
+/// Adds the dot product of eight `i16` lanes of `a` and `b` to `ACCX`
+/// (`EE.VMULAS.S16.ACCX`).
+///
+/// # Safety
+///
+/// `a` and `b` must be valid for reads of 16 bytes and 16-byte aligned.
+#[inline]
+#[target_feature(enable = "esp32s3")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub unsafe fn vmulas_s16_accx(a: *const i16, b: *const i16) {
+    // SAFETY: guaranteed by the caller.
+    unsafe {
+        asm!(
+            "ee.vld.128.ip q0, {a}, 0",
+            "ee.vld.128.ip q1, {b}, 0",
+            "ee.vmulas.s16.accx q0, q1",
+            a = in(reg) a,
+            b = in(reg) b,
+            out("q0") _,
+            out("q1") _,
+            out("accx_0") _,
+            out("accx_1") _,
+            options(readonly, nostack),
+        )
+    };
+}
+
+/// Returns the 40-bit vector accumulator `ACCX`, sign-extended to 64 bits.
+#[inline]
+#[target_feature(enable = "esp32s3")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn read_accx() -> i64 {
+    let (lo, hi): (u32, u32);
+    // SAFETY: reading the user registers has no side effects.
+    unsafe {
+        asm!(
+            "rur.accx_0 {lo}",
+            "rur.accx_1 {hi}",
+            lo = out(reg) lo,
+            hi = out(reg) hi,
+            options(nomem, nostack),
+        )
+    };
+    // `ACCX_1` holds the upper 8 bits of the accumulator.
+    ((hi as i8 as i64) << 32) | lo as i64
+}
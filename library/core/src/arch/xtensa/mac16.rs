@@ -0,0 +1,85 @@
+//! MAC16 option: 16x16-bit multiplies into the 40-bit accumulator `ACC`.
+//!
+//! The `_ll`, `_lh`, `_hl` and `_hh` suffixes select the low or high halves of the first and
+//! second operand respectively. The accumulator is a single piece of global state, so
+//! interrupt handlers using these must save and restore [`ACCLO`] and [`ACCHI`].
+//!
+//! [`ACCLO`]: super::sr::ACCLO
+//! [`ACCHI`]: super::sr::ACCHI
+
+use super::{rsr, sr, wsr};
+use crate::arch::asm;
+
+macro_rules! mac16_ops {
+    ($($(#[$attr:meta])* fn $name:ident = $insn:literal;)*) => {
+        $(
+            $(#[$attr])*
+            #[inline]
+            #[target_feature(enable = "mac16")]
+            #[unstable(feature = "stdarch_xtensa", issue = "none")]
+            pub fn $name(a: u32, b: u32) {
+                // SAFETY: the instruction only reads `a` and `b` and updates `ACC`.
+                unsafe { asm!(concat!($insn, " {0}, {1}"), in(reg) a, in(reg) b, options(nomem, nostack)) };
+            }
+        )*
+    };
+}
+
+mac16_ops! {
+    /// Sets `ACC` to the signed product of the low halves of `a` and `b`.
+    fn mul_aa_ll = "mul.aa.ll";
+    /// Sets `ACC` to the signed product of the low half of `a` and the high half of `b`.
+    fn mul_aa_lh = "mul.aa.lh";
+    /// Sets `ACC` to the signed product of the high half of `a` and the low half of `b`.
+    fn mul_aa_hl = "mul.aa.hl";
+    /// Sets `ACC` to the signed product of the high halves of `a` and `b`.
+    fn mul_aa_hh = "mul.aa.hh";
+    /// Adds the signed product of the low halves of `a` and `b` to `ACC`.
+    fn mula_aa_ll = "mula.aa.ll";
+    /// Adds the signed product of the low half of `a` and the high half of `b` to `ACC`.
+    fn mula_aa_lh = "mula.aa.lh";
+    /// Adds the signed product of the high half of `a` and the low half of `b` to `ACC`.
+    fn mula_aa_hl = "mula.aa.hl";
+    /// Adds the signed product of the high halves of `a` and `b` to `ACC`.
+    fn mula_aa_hh = "mula.aa.hh";
+    /// Subtracts the signed product of the low halves of `a` and `b` from `ACC`.
+    fn muls_aa_ll = "muls.aa.ll";
+    /// Subtracts the signed product of the low half of `a` and the high half of `b` from `ACC`.
+    fn muls_aa_lh = "muls.aa.lh";
+    /// Subtracts the signed product of the high half of `a` and the low half of `b` from `ACC`.
+    fn muls_aa_hl = "muls.aa.hl";
+    /// Subtracts the signed product of the high halves of `a` and `b` from `ACC`.
+    fn muls_aa_hh = "muls.aa.hh";
+    /// Sets `ACC` to the unsigned product of the low halves of `a` and `b`.
+    fn umul_aa_ll = "umul.aa.ll";
+    /// Sets `ACC` to the unsigned product of the low half of `a` and the high half of `b`.
+    fn umul_aa_lh = "umul.aa.lh";
+    /// Sets `ACC` to the unsigned product of the high half of `a` and the low half of `b`.
+    fn umul_aa_hl = "umul.aa.hl";
+    /// Sets `ACC` to the unsigned product of the high halves of `a` and `b`.
+    fn umul_aa_hh = "umul.aa.hh";
+}
+
+/// Returns the 40-bit accumulator, sign-extended to 64 bits.
+#[inline]
+#[target_feature(enable = "mac16")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn read_acc() -> i64 {
+    // SAFETY: `ACCLO` and `ACCHI` are present whenever the MAC16 option is.
+    let (lo, hi) = unsafe { (rsr::<{ sr::ACCLO }>(), rsr::<{ sr::ACCHI }>()) };
+    // Only the low 8 bits of `ACCHI` are implemented, the rest mirror its sign bit.
+    ((hi as i8 as i64) << 32) | lo as i64
+}
+
+/// Sets the 40-bit accumulator to the low 40 bits of `value`.
+#[inline]
+#[target_feature(enable = "mac16")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn write_acc(value: i64) {
+    // SAFETY: `ACCLO` and `ACCHI` are present whenever the MAC16 option is, and only
+    // affect MAC16 instructions.
+    unsafe {
+        wsr::<{ sr::ACCLO }>(value as u32);
+        wsr::<{ sr::ACCHI }>((value >> 32) as u8 as u32);
+    }
+}
@@ -0,0 +1,160 @@
+//! Intrinsics for the Xtensa architecture.
+//!
+//! This covers the core ISA instructions needed for low-level firmware (special register
+//! access, synchronization barriers, `WAITI`, the cycle counter and `NSAU`), the MAC16
+//! option in [`mac16`], and the ESP32-S3 PIE vector extension in [`esp32s3`].
+//!
+//! Special registers are selected with a const generic holding their number, see the
+//! constants in [`sr`]. Which of them exist depends on the configured Xtensa options, and
+//! accessing one the core doesn't implement raises an illegal instruction exception.
+
+use crate::arch::asm;
+
+pub mod esp32s3;
+pub mod mac16;
+pub mod sr;
+
+/// Reads the special register `SR` (`RSR`).
+///
+/// # Safety
+///
+/// `SR` must name a special register implemented by the current core.
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub unsafe fn rsr<const SR: u32>() -> u32 {
+    const { assert!(SR < 256, "special register numbers are 8 bits") };
+    let value: u32;
+    // SAFETY: the caller guarantees that `SR` exists on this core.
+    unsafe { asm!("rsr {0}, {sr}", out(reg) value, sr = const SR, options(nomem, nostack)) };
+    value
+}
+
+/// Writes `value` to the special register `SR` (`WSR`).
+///
+/// Some registers need a [`rsync`], [`isync`] or [`esync`] before the new value is guaranteed
+/// to be visible, see the Xtensa ISA reference for the register in question.
+///
+/// # Safety
+///
+/// `SR` must name a special register implemented by the current core, and writing `value`
+/// to it must not break invariants the rest of the program relies on (interrupt levels,
+/// window state, exception vectors, ...).
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub unsafe fn wsr<const SR: u32>(value: u32) {
+    const { assert!(SR < 256, "special register numbers are 8 bits") };
+    // SAFETY: guaranteed by the caller.
+    unsafe { asm!("wsr {0}, {sr}", in(reg) value, sr = const SR, options(nostack)) };
+}
+
+/// Atomically swaps `value` with the contents of the special register `SR` (`XSR`) and
+/// returns the previous contents.
+///
+/// # Safety
+///
+/// Same as [`wsr`].
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub unsafe fn xsr<const SR: u32>(value: u32) -> u32 {
+    const { assert!(SR < 256, "special register numbers are 8 bits") };
+    let mut value = value;
+    // SAFETY: guaranteed by the caller.
+    unsafe { asm!("xsr {0}, {sr}", inout(reg) value, sr = const SR, options(nostack)) };
+    value
+}
+
+/// Returns the current value of the cycle counter (`CCOUNT`).
+#[inline]
+#[target_feature(enable = "timerint")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn ccount() -> u32 {
+    // SAFETY: `CCOUNT` is present whenever the timer interrupt option is.
+    unsafe { rsr::<{ sr::CCOUNT }>() }
+}
+
+/// Sets the interrupt level to `LEVEL` and suspends the core until an interrupt above that
+/// level is taken (`WAITI`).
+///
+/// # Safety
+///
+/// Lowering the interrupt level allows pending interrupts to be taken as soon as this is
+/// executed, which must be acceptable at the call site.
+#[inline]
+#[target_feature(enable = "interrupt")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub unsafe fn waiti<const LEVEL: u32>() {
+    const { assert!(LEVEL < 16, "interrupt levels are 4 bits") };
+    // SAFETY: guaranteed by the caller. Not `nomem`: the interrupt handlers that run before
+    // `WAITI` returns may write to memory the caller reads afterwards.
+    unsafe { asm!("waiti {level}", level = const LEVEL, options(nostack)) };
+}
+
+/// Orders all preceding memory accesses before all following ones (`MEMW`).
+///
+/// This is required between accesses to memory-mapped peripherals that must not be
+/// reordered by the load/store unit.
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn memw() {
+    // SAFETY: `MEMW` only constrains the ordering of memory accesses.
+    unsafe { asm!("memw", options(nostack, preserves_flags)) };
+}
+
+/// Waits until all preceding instruction fetches and special register writes have taken
+/// effect (`ISYNC`).
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn isync() {
+    // SAFETY: `ISYNC` only stalls the pipeline.
+    unsafe { asm!("isync", options(nostack, preserves_flags)) };
+}
+
+/// Waits until all preceding special register writes are visible to following
+/// register reads (`RSYNC`).
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn rsync() {
+    // SAFETY: `RSYNC` only stalls the pipeline.
+    unsafe { asm!("rsync", options(nomem, nostack, preserves_flags)) };
+}
+
+/// Waits until all preceding special register writes affecting exceptions have taken
+/// effect (`ESYNC`).
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn esync() {
+    // SAFETY: `ESYNC` only stalls the pipeline.
+    unsafe { asm!("esync", options(nomem, nostack, preserves_flags)) };
+}
+
+/// Waits until all preceding special register writes affecting data accesses have taken
+/// effect (`DSYNC`).
+#[inline]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn dsync() {
+    // SAFETY: `DSYNC` only stalls the pipeline.
+    unsafe { asm!("dsync", options(nostack, preserves_flags)) };
+}
+
+/// Returns the number of leading zero bits of `value`, or 32 if it is zero (`NSAU`).
+#[inline]
+#[target_feature(enable = "nsa")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn nsau(value: u32) -> u32 {
+    let shift: u32;
+    // SAFETY: `NSAU` is a pure computation on registers.
+    unsafe { asm!("nsau {0}, {1}", out(reg) shift, in(reg) value, options(pure, nomem, nostack)) };
+    shift
+}
+
+/// Returns the number of redundant sign bits of `value`, that is the left shift that
+/// normalizes it without overflow, or 31 if it is 0 or -1 (`NSA`).
+#[inline]
+#[target_feature(enable = "nsa")]
+#[unstable(feature = "stdarch_xtensa", issue = "none")]
+pub fn nsa(value: i32) -> u32 {
+    let shift: u32;
+    // SAFETY: `NSA` is a pure computation on registers.
+    unsafe { asm!("nsa {0}, {1}", out(reg) shift, in(reg) value, options(pure, nomem, nostack)) };
+    shift
+}
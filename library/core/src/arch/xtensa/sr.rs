@@ -0,0 +1,142 @@
+//! Special register numbers, for use with [`rsr`], [`wsr`] and [`xsr`].
+//!
+//! [`rsr`]: super::rsr
+//! [`wsr`]: super::wsr
+//! [`xsr`]: super::xsr
+
+macro_rules! special_registers {
+    ($($(#[$attr:meta])* $name:ident = $num:literal;)*) => {
+        $(
+            $(#[$attr])*
+            #[unstable(feature = "stdarch_xtensa", issue = "none")]
+            pub const $name: u32 = $num;
+        )*
+    };
+}
+
+special_registers! {
+    /// Shift amount register.
+    SAR = 3;
+    /// Literal base for `L32R` (extended L32R option).
+    LITBASE = 5;
+    /// Comparison value for `S32C1I`.
+    SCOMPARE1 = 12;
+    /// Low 32 bits of the MAC16 accumulator.
+    ACCLO = 16;
+    /// High 8 bits of the MAC16 accumulator.
+    ACCHI = 17;
+    /// MAC16 data register 0.
+    M0 = 32;
+    /// MAC16 data register 1.
+    M1 = 33;
+    /// MAC16 data register 2.
+    M2 = 34;
+    /// MAC16 data register 3.
+    M3 = 35;
+    /// Current register window base.
+    WINDOWBASE = 72;
+    /// Valid register window bitmap.
+    WINDOWSTART = 73;
+    /// Instruction breakpoint enable bits.
+    IBREAKENABLE = 96;
+    /// Memory control.
+    MEMCTL = 97;
+    /// Atomic operation control.
+    ATOMCTL = 99;
+    /// Debug data register.
+    DDR = 104;
+    /// Instruction breakpoint address 0.
+    IBREAKA0 = 128;
+    /// Instruction breakpoint address 1.
+    IBREAKA1 = 129;
+    /// Data breakpoint address 0.
+    DBREAKA0 = 144;
+    /// Data breakpoint address 1.
+    DBREAKA1 = 145;
+    /// Data breakpoint control 0.
+    DBREAKC0 = 160;
+    /// Data breakpoint control 1.
+    DBREAKC1 = 161;
+    /// Exception program counter for level 1.
+    EPC1 = 177;
+    /// Exception program counter for level 2.
+    EPC2 = 178;
+    /// Exception program counter for level 3.
+    EPC3 = 179;
+    /// Exception program counter for level 4.
+    EPC4 = 180;
+    /// Exception program counter for level 5.
+    EPC5 = 181;
+    /// Exception program counter for level 6.
+    EPC6 = 182;
+    /// Exception program counter for level 7.
+    EPC7 = 183;
+    /// Double exception program counter.
+    DEPC = 192;
+    /// Saved `PS` for level 2.
+    EPS2 = 194;
+    /// Saved `PS` for level 3.
+    EPS3 = 195;
+    /// Saved `PS` for level 4.
+    EPS4 = 196;
+    /// Saved `PS` for level 5.
+    EPS5 = 197;
+    /// Saved `PS` for level 6.
+    EPS6 = 198;
+    /// Saved `PS` for level 7.
+    EPS7 = 199;
+    /// Exception save register for level 1.
+    EXCSAVE1 = 209;
+    /// Exception save register for level 2.
+    EXCSAVE2 = 210;
+    /// Exception save register for level 3.
+    EXCSAVE3 = 211;
+    /// Exception save register for level 4.
+    EXCSAVE4 = 212;
+    /// Exception save register for level 5.
+    EXCSAVE5 = 213;
+    /// Exception save register for level 6.
+    EXCSAVE6 = 214;
+    /// Exception save register for level 7.
+    EXCSAVE7 = 215;
+    /// Coprocessor enable bits.
+    CPENABLE = 224;
+    /// Pending interrupts (read), software interrupt set (write).
+    INTERRUPT = 226;
+    /// Clears pending edge-triggered and software interrupts.
+    INTCLEAR = 227;
+    /// Interrupt enable mask.
+    INTENABLE = 228;
+    /// Processor state.
+    PS = 230;
+    /// Base of the relocatable exception vectors.
+    VECBASE = 231;
+    /// Cause of the last exception.
+    EXCCAUSE = 232;
+    /// Cause of the last debug exception.
+    DEBUGCAUSE = 233;
+    /// Cycle counter.
+    CCOUNT = 234;
+    /// Processor ID.
+    PRID = 235;
+    /// Instruction counter.
+    ICOUNT = 236;
+    /// Interrupt level at or below which `ICOUNT` increments.
+    ICOUNTLEVEL = 237;
+    /// Virtual address of the last memory exception.
+    EXCVADDR = 238;
+    /// Timer 0 compare value.
+    CCOMPARE0 = 240;
+    /// Timer 1 compare value.
+    CCOMPARE1 = 241;
+    /// Timer 2 compare value.
+    CCOMPARE2 = 242;
+    /// Miscellaneous scratch register 0.
+    MISC0 = 244;
+    /// Miscellaneous scratch register 1.
+    MISC1 = 245;
+    /// Miscellaneous scratch register 2.
+    MISC2 = 246;
+    /// Miscellaneous scratch register 3.
+    MISC3 = 247;
+}
@@ -150,6 +150,42 @@ pub fn new_upper_exp<'b, T: UpperExp>(x: &'b T) -> Argument<'_> {
         Self::new(x, UpperExp::fmt)
     }
     #[inline(always)]
+    pub fn new_deferred_display<'b, T: Display>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, Display::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_debug<'b, T: Debug>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, Debug::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_octal<'b, T: Octal>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, Octal::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_lower_hex<'b, T: LowerHex>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, LowerHex::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_upper_hex<'b, T: UpperHex>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, UpperHex::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_pointer<'b, T: Pointer>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, Pointer::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_binary<'b, T: Binary>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, Binary::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_lower_exp<'b, T: LowerExp>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, LowerExp::fmt))
+    }
+    #[inline(always)]
+    pub fn new_deferred_upper_exp<'b, T: UpperExp>(x: &'b T) -> Argument<'b> {
+        Self::new(x, |x, f| deferred::write(x, f, UpperExp::fmt))
+    }
+    #[inline(always)]
     pub fn from_usize(x: &usize) -> Argument<'_> {
         Argument { ty: ArgumentType::Count(*x) }
     }
@@ -204,6 +240,80 @@ pub(super) fn as_usize(&self) -> Option<usize> {
     }
 }
 
+/// The arguments of panic and log messages with `-Z deferred-strings`.
+///
+/// The format string of such a message is replaced by a reference into a table that is not loaded
+/// on the target, and the arguments are written after it to be formatted on the host. Each argument
+/// is written as the record separator (`\x1e`) followed by a tag and the value: the raw bytes of
+/// integers, floats, `bool` and `char` in little endian as hex digits, or the text formatted with
+/// the trait of the placeholder for all other types. The message ends with the group separator
+/// (`\x1d`), written by the last piece of the format string.
+mod deferred {
+    use super::*;
+
+    pub(super) fn write<T: ?Sized>(
+        x: &T,
+        f: &mut Formatter<'_>,
+        text: fn(&T, &mut Formatter<'_>) -> Result,
+    ) -> Result {
+        match x.write_raw(f) {
+            Some(result) => result,
+            None => {
+                f.write_str("\x1es")?;
+                text(x, f)
+            }
+        }
+    }
+
+    trait RawArgument {
+        /// Writes the tag and the raw bytes of the value, if it has a raw encoding.
+        fn write_raw(&self, f: &mut Formatter<'_>) -> Option<Result>;
+    }
+
+    impl<T: ?Sized> RawArgument for T {
+        default fn write_raw(&self, _: &mut Formatter<'_>) -> Option<Result> {
+            None
+        }
+    }
+
+    fn write_bytes(f: &mut Formatter<'_>, tag: char, bytes: &[u8]) -> Result {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+        f.write_char('\x1e')?;
+        f.write_char(tag)?;
+        for &byte in bytes {
+            f.write_char(HEX[(byte >> 4) as usize] as char)?;
+            f.write_char(HEX[(byte & 0xf) as usize] as char)?;
+        }
+        Ok(())
+    }
+
+    macro_rules! raw_argument {
+        ($tag:literal: $($t:ty)*) => {$(
+            impl RawArgument for $t {
+                fn write_raw(&self, f: &mut Formatter<'_>) -> Option<Result> {
+                    Some(write_bytes(f, $tag, &self.to_le_bytes()))
+                }
+            }
+        )*};
+    }
+
+    raw_argument!('u': u8 u16 u32 u64 u128 usize);
+    raw_argument!('i': i8 i16 i32 i64 i128 isize);
+    raw_argument!('f': f32 f64);
+
+    impl RawArgument for bool {
+        fn write_raw(&self, f: &mut Formatter<'_>) -> Option<Result> {
+            Some(write_bytes(f, 'b', &[*self as u8]))
+        }
+    }
+
+    impl RawArgument for char {
+        fn write_raw(&self, f: &mut Formatter<'_>) -> Option<Result> {
+            Some(write_bytes(f, 'c', &(*self as u32).to_le_bytes()))
+        }
+    }
+}
+
 /// This struct represents the unsafety of constructing an `Arguments`.
 /// It exists, rather than an unsafe function, in order to simplify the expansion
 /// of `format_args!(..)` and reduce the scope of the `unsafe` block.
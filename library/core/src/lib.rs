@@ -266,6 +266,7 @@
 #![feature(tbm_target_feature)]
 #![feature(wasm_target_feature)]
 #![feature(x86_amx_intrinsics)]
+#![cfg_attr(not(bootstrap), feature(xtensa_target_feature))]
 // tidy-alphabetical-end
 
 // allow using `core::` in intra-doc links
@@ -273,6 +273,7 @@
 // Language features:
 // tidy-alphabetical-start
 #![cfg_attr(bootstrap, feature(min_exhaustive_patterns))]
+#![cfg_attr(not(bootstrap), feature(interrupt_context))]
 #![feature(alloc_error_handler)]
 #![feature(allocator_internals)]
 #![feature(allow_internal_unsafe)]
@@ -0,0 +1,309 @@
+//! ESP-IDF-specific allocators, placing allocations in a given kind of memory.
+//!
+//! The global allocator of ESP-IDF, used by [`Box`], [`Vec`] and the other collections by default,
+//! places allocations in whatever memory `malloc` is configured to use in menuconfig. The
+//! allocators of this module instead request memory with given capabilities from
+//! `heap_caps_malloc`, and can be used with the `*_in` constructors of the collections:
+//!
+//! ```no_run
+//! #![feature(allocator_api, espidf_alloc)]
+//! use std::os::espidf::alloc::{Dma, Psram};
+//!
+//! // A large frame buffer in external PSRAM.
+//! let mut frame = Vec::with_capacity_in(320 * 240, Psram);
+//! frame.resize(320 * 240, 0u16);
+//!
+//! // A buffer the SPI peripheral can read from.
+//! let mut tx = Vec::with_capacity_in(64, Dma);
+//! tx.extend_from_slice(b"hello");
+//! ```
+//!
+//! When no memory with the capabilities is left, or the chip has none (like PSRAM on a board
+//! without it), the allocators return an [`AllocError`], and the collections call
+//! [`handle_alloc_error`] as usual. The `try_*` methods of the collections can be used to handle
+//! this instead.
+//!
+//! The instruction RAM of the chip only supports 32-bit accesses, so it can't back the collections,
+//! which copy their elements byte by byte. [`Iram32`] allocates arrays of words there instead.
+//!
+//! [`Box`]: crate::boxed::Box
+//! [`Vec`]: crate::vec::Vec
+//! [`handle_alloc_error`]: crate::alloc::handle_alloc_error
+
+#![unstable(feature = "espidf_alloc", issue = "none")]
+
+#[cfg(test)]
+mod tests;
+
+use crate::alloc::{AllocError, Allocator, Layout};
+use crate::ptr::{self, NonNull};
+
+/// The alignment of the memory returned by `heap_caps_malloc`.
+const MIN_ALIGN: usize = 4;
+
+/// The `MALLOC_CAP_*` flags requested by an allocator.
+#[derive(Clone, Copy)]
+struct Caps {
+    caps: u32,
+}
+
+impl Caps {
+    fn allocate(self, layout: Layout, zeroed: bool) -> Result<NonNull<[u8]>, AllocError> {
+        match layout.size() {
+            0 => Ok(NonNull::slice_from_raw_parts(layout.dangling(), 0)),
+            // SAFETY: `size` is non-zero, and `heap_caps_aligned_alloc` gets a power of two.
+            size => unsafe {
+                let raw_ptr = if layout.align() <= MIN_ALIGN {
+                    if zeroed {
+                        ffi::heap_caps_calloc(1, size, self.caps)
+                    } else {
+                        ffi::heap_caps_malloc(size, self.caps)
+                    }
+                } else {
+                    let raw_ptr = ffi::heap_caps_aligned_alloc(layout.align(), size, self.caps);
+                    if zeroed && !raw_ptr.is_null() {
+                        ptr::write_bytes(raw_ptr as *mut u8, 0, size);
+                    }
+                    raw_ptr
+                };
+                let ptr = NonNull::new(raw_ptr as *mut u8).ok_or(AllocError)?;
+                Ok(NonNull::slice_from_raw_parts(ptr, size))
+            },
+        }
+    }
+
+    // SAFETY: Same as `Allocator::deallocate`
+    unsafe fn deallocate(self, ptr: NonNull<u8>, layout: Layout) {
+        if layout.size() == 0 {
+            return;
+        }
+        // SAFETY: `ptr` was returned by the function matching the alignment, as the caller
+        // guarantees `layout` is the layout it was allocated with.
+        unsafe {
+            if layout.align() <= MIN_ALIGN {
+                ffi::heap_caps_free(ptr.as_ptr().cast());
+            } else {
+                ffi::heap_caps_aligned_free(ptr.as_ptr().cast());
+            }
+        }
+    }
+
+    // SAFETY: Same as `Allocator::grow` and `Allocator::shrink`
+    unsafe fn reallocate(
+        self,
+        ptr: NonNull<u8>,
+        old_layout: Layout,
+        new_layout: Layout,
+        zeroed: bool,
+    ) -> Result<NonNull<[u8]>, AllocError> {
+        let old_size = old_layout.size();
+        let new_size = new_layout.size();
+
+        if old_size != 0
+            && new_size != 0
+            && old_layout.align() <= MIN_ALIGN
+            && new_layout.align() <= MIN_ALIGN
+        {
+            // SAFETY: `ptr` was allocated by `heap_caps_malloc` or `heap_caps_calloc` with the
+            // same capabilities, which `heap_caps_realloc` keeps.
+            unsafe {
+                let raw_ptr = ffi::heap_caps_realloc(ptr.as_ptr().cast(), new_size, self.caps);
+                let ptr = NonNull::new(raw_ptr as *mut u8).ok_or(AllocError)?;
+                if zeroed && new_size > old_size {
+                    ptr.as_ptr().add(old_size).write_bytes(0, new_size - old_size);
+                }
+                return Ok(NonNull::slice_from_raw_parts(ptr, new_size));
+            }
+        }
+
+        // SAFETY: the new allocation is valid for `min(old_size, new_size)` bytes and can't
+        // overlap the old one, which the caller guarantees is valid for `old_size` bytes.
+        unsafe {
+            let new_ptr = self.allocate(new_layout, zeroed)?;
+            ptr::copy_nonoverlapping(ptr.as_ptr(), new_ptr.as_mut_ptr(), old_size.min(new_size));
+            self.deallocate(ptr, old_layout);
+            Ok(new_ptr)
+        }
+    }
+}
+
+macro_rules! caps_allocator {
+    ($(#[$attr:meta])* $name:ident => $caps:expr) => {
+        $(#[$attr])*
+        #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        unsafe impl Allocator for $name {
+            #[inline]
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                $caps.allocate(layout, false)
+            }
+
+            #[inline]
+            fn allocate_zeroed(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                $caps.allocate(layout, true)
+            }
+
+            #[inline]
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                // SAFETY: all conditions must be upheld by the caller
+                unsafe { $caps.deallocate(ptr, layout) }
+            }
+
+            #[inline]
+            unsafe fn grow(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                // SAFETY: all conditions must be upheld by the caller
+                unsafe { $caps.reallocate(ptr, old_layout, new_layout, false) }
+            }
+
+            #[inline]
+            unsafe fn grow_zeroed(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                // SAFETY: all conditions must be upheld by the caller
+                unsafe { $caps.reallocate(ptr, old_layout, new_layout, true) }
+            }
+
+            #[inline]
+            unsafe fn shrink(
+                &self,
+                ptr: NonNull<u8>,
+                old_layout: Layout,
+                new_layout: Layout,
+            ) -> Result<NonNull<[u8]>, AllocError> {
+                // SAFETY: all conditions must be upheld by the caller
+                unsafe { $caps.reallocate(ptr, old_layout, new_layout, false) }
+            }
+        }
+    };
+}
+
+caps_allocator! {
+    /// Allocates in the internal RAM of the chip, even when `malloc` is configured to use PSRAM.
+    ///
+    /// Internal RAM is faster than PSRAM, and stays accessible while the flash cache is disabled,
+    /// for example during flash writes.
+    Internal => Caps { caps: ffi::MALLOC_CAP_INTERNAL | ffi::MALLOC_CAP_8BIT }
+}
+
+caps_allocator! {
+    /// Allocates in the external PSRAM (SPIRAM) of the module.
+    ///
+    /// PSRAM has to be enabled in menuconfig. Allocations fail on modules without PSRAM, or when
+    /// it is not added to the heap (`CONFIG_SPIRAM_USE_MALLOC` or `CONFIG_SPIRAM_USE_CAPS_ALLOC`).
+    Psram => Caps { caps: ffi::MALLOC_CAP_SPIRAM | ffi::MALLOC_CAP_8BIT }
+}
+
+caps_allocator! {
+    /// Allocates in memory that the DMA engines of the peripherals can access.
+    ///
+    /// This is internal RAM. Some peripherals have additional alignment requirements for their
+    /// buffers, which have to be requested through the alignment of the layout.
+    Dma => Caps { caps: ffi::MALLOC_CAP_DMA | ffi::MALLOC_CAP_8BIT }
+}
+
+/// Allocates arrays of words in memory that only needs to support aligned 32-bit accesses, which
+/// lets the otherwise unused instruction RAM (IRAM) of the chip be used.
+///
+/// Byte and half-word accesses to IRAM raise a `LoadStoreError` exception. This is not an
+/// [`Allocator`], as the collections and most code copying values would access the memory byte by
+/// byte: the words allocated must only be accessed through `*mut u32` pointers, for example with
+/// [`ptr::read_volatile`] and [`ptr::write_volatile`].
+///
+/// ```no_run
+/// #![feature(espidf_alloc, slice_ptr_get)]
+/// use std::os::espidf::alloc::Iram32;
+///
+/// // SAFETY: the words are only accessed with 32-bit loads and stores, and are freed once.
+/// unsafe {
+///     let words = Iram32.allocate_zeroed(256).expect("not enough IRAM");
+///     let first = words.as_mut_ptr();
+///     first.write_volatile(first.read_volatile() + 1);
+///     Iram32.deallocate(words);
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Iram32;
+
+impl Iram32 {
+    const CAPS: Caps = Caps { caps: ffi::MALLOC_CAP_32BIT };
+
+    /// Allocates `len` uninitialized words.
+    ///
+    /// An empty array is dangling, and doesn't allocate.
+    ///
+    /// # Safety
+    ///
+    /// The words must only be accessed with aligned 32-bit loads and stores, and never through
+    /// references or functions copying memory like [`ptr::copy`], which may use narrower accesses.
+    pub unsafe fn allocate(&self, len: usize) -> Result<NonNull<[u32]>, AllocError> {
+        let layout = Layout::array::<u32>(len).map_err(|_| AllocError)?;
+        let ptr = Self::CAPS.allocate(layout, false)?;
+        Ok(NonNull::slice_from_raw_parts(ptr.cast(), len))
+    }
+
+    /// Allocates `len` words set to zero.
+    ///
+    /// # Safety
+    ///
+    /// Same as [`Iram32::allocate`].
+    pub unsafe fn allocate_zeroed(&self, len: usize) -> Result<NonNull<[u32]>, AllocError> {
+        // SAFETY: the caller upholds the conditions of `allocate`.
+        let words = unsafe { self.allocate(len)? };
+        // `heap_caps_calloc` clears the memory with `memset`, which can't be relied on to only
+        // store words, so this is done word by word.
+        for i in 0..len {
+            // SAFETY: `i` is in bounds of the allocation, which is aligned to 4 bytes.
+            unsafe { words.as_mut_ptr().add(i).write_volatile(0) };
+        }
+        Ok(words)
+    }
+
+    /// Frees an array of words.
+    ///
+    /// # Safety
+    ///
+    /// `words` must have been returned by [`Iram32::allocate`] or [`Iram32::allocate_zeroed`],
+    /// with the same length, and not have been freed yet.
+    pub unsafe fn deallocate(&self, words: NonNull<[u32]>) {
+        // SAFETY: the layout was valid when the words were allocated.
+        let layout = unsafe { Layout::array::<u32>(words.len()).unwrap_unchecked() };
+        // SAFETY: the words were allocated with the same capabilities and layout.
+        unsafe { Self::CAPS.deallocate(words.cast(), layout) }
+    }
+}
+
+#[allow(non_camel_case_types)]
+mod ffi {
+    use libc::{c_void, size_t};
+
+    pub const MALLOC_CAP_32BIT: u32 = 1 << 1;
+    pub const MALLOC_CAP_8BIT: u32 = 1 << 2;
+    pub const MALLOC_CAP_DMA: u32 = 1 << 3;
+    pub const MALLOC_CAP_SPIRAM: u32 = 1 << 10;
+    pub const MALLOC_CAP_INTERNAL: u32 = 1 << 11;
+
+    extern "C" {
+        #[cfg_attr(not(bootstrap), may_block)]
+        pub fn heap_caps_malloc(size: size_t, caps: u32) -> *mut c_void;
+        #[cfg_attr(not(bootstrap), may_block)]
+        pub fn heap_caps_calloc(n: size_t, size: size_t, caps: u32) -> *mut c_void;
+        #[cfg_attr(not(bootstrap), may_block)]
+        pub fn heap_caps_realloc(ptr: *mut c_void, size: size_t, caps: u32) -> *mut c_void;
+        #[cfg_attr(not(bootstrap), may_block)]
+        pub fn heap_caps_free(ptr: *mut c_void);
+        #[cfg_attr(not(bootstrap), may_block)]
+        pub fn heap_caps_aligned_alloc(alignment: size_t, size: size_t, caps: u32) -> *mut c_void;
+        // Deprecated in favor of `heap_caps_free` since ESP-IDF v5.0, but needed by v4.4.
+        #[cfg_attr(not(bootstrap), may_block)]
+        pub fn heap_caps_aligned_free(ptr: *mut c_void);
+    }
+}
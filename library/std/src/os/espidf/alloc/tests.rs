@@ -0,0 +1,119 @@
+use super::{Dma, Internal, Iram32, Psram};
+use crate::alloc::{AllocError, Allocator, Layout};
+use crate::ptr::NonNull;
+
+/// Allocates with `layout`, checks the memory returned, and fills it with a pattern.
+fn allocate_filled<A: Allocator>(alloc: &A, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+    let ptr = alloc.allocate(layout)?;
+    assert_eq!(ptr.len(), layout.size());
+    assert_eq!(ptr.cast::<u8>().as_ptr().addr() % layout.align(), 0);
+    for i in 0..layout.size() {
+        // SAFETY: `i` is in bounds of the allocation.
+        unsafe { ptr.as_mut_ptr().add(i).write(i as u8) };
+    }
+    Ok(ptr)
+}
+
+fn check_allocator<A: Allocator>(alloc: A) {
+    for layout in [
+        Layout::new::<u8>(),
+        Layout::new::<u64>(),
+        Layout::from_size_align(100, 1).unwrap(),
+        Layout::from_size_align(64, 64).unwrap(),
+    ] {
+        let ptr = allocate_filled(&alloc, layout).unwrap();
+        // SAFETY: `ptr` was allocated by `alloc` with `layout`.
+        unsafe { alloc.deallocate(ptr.cast(), layout) };
+    }
+
+    let zeroed = Layout::from_size_align(256, 16).unwrap();
+    let ptr = alloc.allocate_zeroed(zeroed).unwrap();
+    // SAFETY: the memory was zeroed, and is freed with the layout it was allocated with.
+    unsafe {
+        assert!((*ptr.as_ptr()).iter().all(|&b| b == 0));
+        alloc.deallocate(ptr.cast(), zeroed);
+    }
+}
+
+#[test]
+fn internal() {
+    check_allocator(Internal);
+}
+
+#[test]
+fn dma() {
+    check_allocator(Dma);
+}
+
+#[test]
+fn psram() {
+    // Allocating fails on modules without PSRAM, which is not an error of the allocator.
+    let layout = Layout::new::<u8>();
+    let Ok(ptr) = Psram.allocate(layout) else { return };
+    // SAFETY: `ptr` was allocated with `layout`.
+    unsafe { Psram.deallocate(ptr.cast(), layout) };
+    check_allocator(Psram);
+}
+
+#[test]
+fn zero_sized() {
+    let layout = Layout::from_size_align(0, 16).unwrap();
+    let ptr = Internal.allocate(layout).unwrap();
+    assert_eq!(ptr.len(), 0);
+    assert_eq!(ptr.cast::<u8>().as_ptr().addr() % 16, 0);
+    // SAFETY: `ptr` was allocated with `layout`.
+    unsafe { Internal.deallocate(ptr.cast(), layout) };
+}
+
+#[test]
+fn grow_and_shrink_keep_contents() {
+    for align in [1, 64] {
+        let small = Layout::from_size_align(16, align).unwrap();
+        let large = Layout::from_size_align(1024, align).unwrap();
+        let ptr = allocate_filled(&Internal, small).unwrap();
+        // SAFETY: each pointer is used with the layout it was last allocated with.
+        unsafe {
+            let ptr = Internal.grow_zeroed(ptr.cast(), small, large).unwrap();
+            let bytes = &*ptr.as_ptr();
+            assert!(bytes[..16].iter().enumerate().all(|(i, &b)| b == i as u8));
+            assert!(bytes[16..].iter().all(|&b| b == 0));
+
+            let ptr = Internal.shrink(ptr.cast(), large, small).unwrap();
+            assert!((*ptr.as_ptr()).iter().enumerate().all(|(i, &b)| b == i as u8));
+            Internal.deallocate(ptr.cast(), small);
+        }
+    }
+}
+
+#[test]
+fn collections() {
+    let mut v = Vec::with_capacity_in(4, Internal);
+    v.extend(0..1000u32);
+    assert_eq!(v.iter().sum::<u32>(), 499500);
+    assert!(Internal.allocate(Layout::from_size_align(usize::MAX / 2, 1).unwrap()).is_err());
+}
+
+#[test]
+fn iram32_words() {
+    // SAFETY: the words are only accessed through `*mut u32`, and are freed once.
+    unsafe {
+        let words = Iram32.allocate_zeroed(64).unwrap();
+        assert_eq!(words.len(), 64);
+        let ptr = words.as_mut_ptr();
+        assert!(ptr.is_aligned());
+        for i in 0..64 {
+            assert_eq!(ptr.add(i).read_volatile(), 0);
+            ptr.add(i).write_volatile(i as u32 * 0x0101_0101);
+        }
+        for i in 0..64 {
+            assert_eq!(ptr.add(i).read_volatile(), i as u32 * 0x0101_0101);
+        }
+        Iram32.deallocate(words);
+
+        let empty = Iram32.allocate(0).unwrap();
+        assert_eq!(empty.len(), 0);
+        Iram32.deallocate(empty);
+
+        assert!(Iram32.allocate(usize::MAX).is_err());
+    }
+}
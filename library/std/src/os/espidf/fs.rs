@@ -1,9 +1,17 @@
 #![stable(feature = "metadata_ext", since = "1.1.0")]
 
+#[cfg(test)]
+mod tests;
+
+use crate::ffi::CString;
 use crate::fs::Metadata;
+use crate::io::{self, Read, Seek, SeekFrom, Write};
 #[allow(deprecated)]
 use crate::os::espidf::raw;
+use crate::os::unix::ffi::OsStrExt;
+use crate::path::Path;
 use crate::sys_common::AsInner;
+use crate::{cmp, fmt, mem, ptr};
 
 /// OS-specific extensions to [`fs::Metadata`].
 ///
@@ -114,3 +122,1095 @@ fn st_blocks(&self) -> u64 {
         [spare4[0] as u32, spare4[1] as u32]
     }
 }
+
+/// A file system that can be mounted from a flash partition with [`mount`].
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum FileSystem {
+    /// FAT on top of the wear levelling layer.
+    ///
+    /// This requires ESP-IDF v5.0 or later.
+    Fat,
+    /// SPIFFS.
+    Spiffs,
+    /// LittleFS, provided by the `joltwallet/littlefs` component.
+    ///
+    /// Mounting fails with [`io::ErrorKind::Unsupported`] if the component is not
+    /// part of the firmware. LittleFS has no limit on open files, so
+    /// [`MountOptions::max_files`] is ignored.
+    ///
+    /// [`io::ErrorKind::Unsupported`]: crate::io::ErrorKind::Unsupported
+    LittleFs,
+}
+
+/// Options for mounting a file system, in the style of [`fs::OpenOptions`].
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(espidf_fs_ext)]
+/// use std::fs;
+/// use std::os::espidf::fs::{FileSystem, MountOptions};
+///
+/// let mount = MountOptions::new()
+///     .format_if_mount_failed(true)
+///     .max_files(4)
+///     .mount(FileSystem::Fat, "storage", "/data")?;
+///
+/// fs::write("/data/hello.txt", "Hello, world!")?;
+/// mount.unmount()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`fs::OpenOptions`]: crate::fs::OpenOptions
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+#[derive(Debug, Clone)]
+pub struct MountOptions {
+    format_if_mount_failed: bool,
+    max_files: usize,
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl MountOptions {
+    /// Creates a blank set of options: the partition is not formatted if it
+    /// can't be mounted, and at most 5 files can be open at once.
+    pub fn new() -> MountOptions {
+        MountOptions { format_if_mount_failed: false, max_files: 5 }
+    }
+
+    /// Sets whether the partition is formatted when it doesn't contain a valid
+    /// file system. All data on it is lost when that happens.
+    pub fn format_if_mount_failed(&mut self, format: bool) -> &mut MountOptions {
+        self.format_if_mount_failed = format;
+        self
+    }
+
+    /// Sets the maximum number of files that can be open on the file system at
+    /// once.
+    pub fn max_files(&mut self, max_files: usize) -> &mut MountOptions {
+        self.max_files = max_files;
+        self
+    }
+
+    /// Mounts the file system stored in the data partition labelled `partition`
+    /// at `base_path`, after which paths below `base_path` can be used with
+    /// [`std::fs`].
+    ///
+    /// The file system stays mounted until the returned [`Mount`] is dropped.
+    /// Use [`mem::forget`] on it to keep it mounted for the rest of the program.
+    ///
+    /// [`std::fs`]: crate::fs
+    /// [`mem::forget`]: crate::mem::forget
+    pub fn mount<P: AsRef<Path>>(
+        &self,
+        fs: FileSystem,
+        partition: &str,
+        base_path: P,
+    ) -> io::Result<Mount> {
+        self._mount(fs, partition, base_path.as_ref())
+    }
+
+    fn _mount(&self, fs: FileSystem, partition: &str, base_path: &Path) -> io::Result<Mount> {
+        let base_path = CString::new(base_path.as_os_str().as_bytes())?;
+        let partition = CString::new(partition)?;
+        let mut wl_handle = ffi::WL_INVALID_HANDLE;
+
+        match fs {
+            FileSystem::Fat => {
+                let config = ffi::esp_vfs_fat_mount_config_t {
+                    format_if_mount_failed: self.format_if_mount_failed,
+                    max_files: self.max_files as libc::c_int,
+                    allocation_unit_size: 0,
+                    disk_status_check_enable: false,
+                    use_one_fat: false,
+                };
+                cvt_esp(
+                    unsafe {
+                        ffi::esp_vfs_fat_spiflash_mount_rw_wl(
+                            base_path.as_ptr(),
+                            partition.as_ptr(),
+                            &config,
+                            &mut wl_handle,
+                        )
+                    },
+                    Op::Mount,
+                )?;
+            }
+            FileSystem::Spiffs => {
+                let config = ffi::esp_vfs_spiffs_conf_t {
+                    base_path: base_path.as_ptr(),
+                    partition_label: partition.as_ptr(),
+                    max_files: self.max_files,
+                    format_if_mount_failed: self.format_if_mount_failed,
+                };
+                cvt_esp(unsafe { ffi::esp_vfs_spiffs_register(&config) }, Op::Mount)?;
+            }
+            FileSystem::LittleFs => {
+                let Some(register) = (unsafe { ffi::esp_vfs_littlefs_register }) else {
+                    return Err(io::const_io_error!(
+                        io::ErrorKind::Unsupported,
+                        "the LittleFS component is not part of the firmware",
+                    ));
+                };
+                let config = ffi::esp_vfs_littlefs_conf_t {
+                    base_path: base_path.as_ptr(),
+                    partition_label: partition.as_ptr(),
+                    partition: ptr::null(),
+                    flags: if self.format_if_mount_failed {
+                        ffi::LITTLEFS_FORMAT_IF_MOUNT_FAILED
+                    } else {
+                        0
+                    },
+                };
+                cvt_esp(unsafe { register(&config) }, Op::Mount)?;
+            }
+        }
+
+        Ok(Mount { fs, base_path, partition, wl_handle })
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Default for MountOptions {
+    fn default() -> MountOptions {
+        MountOptions::new()
+    }
+}
+
+/// Mounts the file system stored in the data partition labelled `partition` at
+/// `base_path`, with the default [`MountOptions`].
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(espidf_fs_ext)]
+/// use std::fs;
+/// use std::os::espidf::fs::{self as espidf_fs, FileSystem};
+///
+/// let _mount = espidf_fs::mount(FileSystem::Spiffs, "storage", "/spiffs")?;
+/// let config = fs::read_to_string("/spiffs/config.toml")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub fn mount<P: AsRef<Path>>(fs: FileSystem, partition: &str, base_path: P) -> io::Result<Mount> {
+    MountOptions::new().mount(fs, partition, base_path)
+}
+
+/// A mounted file system, returned by [`mount`] and [`MountOptions::mount`].
+///
+/// The file system is unmounted when this value is dropped, ignoring any
+/// errors. Use [`Mount::unmount`] to handle them.
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub struct Mount {
+    fs: FileSystem,
+    base_path: CString,
+    partition: CString,
+    wl_handle: ffi::wl_handle_t,
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Mount {
+    /// Returns the kind of the mounted file system.
+    pub fn file_system(&self) -> FileSystem {
+        self.fs
+    }
+
+    /// Returns the path the file system is mounted at.
+    pub fn base_path(&self) -> &Path {
+        Path::new(crate::ffi::OsStr::from_bytes(self.base_path.to_bytes()))
+    }
+
+    /// Unmounts the file system.
+    ///
+    /// Files that are still open on it must not be used afterwards.
+    pub fn unmount(self) -> io::Result<()> {
+        let ret = self.unmount_ref();
+        mem::forget(self);
+        ret
+    }
+
+    fn unmount_ref(&self) -> io::Result<()> {
+        let err = unsafe {
+            match self.fs {
+                FileSystem::Fat => {
+                    ffi::esp_vfs_fat_spiflash_unmount_rw_wl(self.base_path.as_ptr(), self.wl_handle)
+                }
+                FileSystem::Spiffs => ffi::esp_vfs_spiffs_unregister(self.partition.as_ptr()),
+                // Mounting succeeded, so the component is linked.
+                FileSystem::LittleFs => {
+                    ffi::esp_vfs_littlefs_unregister.unwrap()(self.partition.as_ptr())
+                }
+            }
+        };
+        cvt_esp(err, Op::Unmount)
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Drop for Mount {
+    fn drop(&mut self) {
+        let _ = self.unmount_ref();
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl fmt::Debug for Mount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Mount")
+            .field("fs", &self.fs)
+            .field("base_path", &self.base_path())
+            .field("partition", &self.partition)
+            .finish()
+    }
+}
+
+/// A handle to the raw contents of a flash partition.
+///
+/// This reads and writes the partition directly, without a file system, which
+/// is useful for firmware images, calibration data or custom storage formats.
+/// Like flash memory itself, a region must be erased with
+/// [`Partition::erase_range`] before it can be written.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(espidf_fs_ext)]
+/// use std::io::{Read, Seek, SeekFrom};
+/// use std::os::espidf::fs::Partition;
+///
+/// let mut partition = Partition::open("calibration")?;
+/// let mut header = [0; 16];
+/// partition.seek(SeekFrom::Start(0x100))?;
+/// partition.read_exact(&mut header)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub struct Partition {
+    partition: *const ffi::esp_partition_t,
+    label: String,
+    pos: u64,
+}
+
+// SAFETY: partitions are entries of the partition table, which is loaded once and
+// never freed, and the ESP-IDF partition functions can be called from any task.
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+unsafe impl Send for Partition {}
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+unsafe impl Sync for Partition {}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Partition {
+    /// Opens the partition with the given label in the partition table.
+    pub fn open(label: &str) -> io::Result<Partition> {
+        let c_label = CString::new(label)?;
+        let partition = unsafe {
+            ffi::esp_partition_find_first(
+                ffi::ESP_PARTITION_TYPE_ANY,
+                ffi::ESP_PARTITION_SUBTYPE_ANY,
+                c_label.as_ptr(),
+            )
+        };
+        if partition.is_null() {
+            return Err(io::const_io_error!(io::ErrorKind::NotFound, "partition not found"));
+        }
+        Ok(Partition { partition, label: label.to_owned(), pos: 0 })
+    }
+
+    /// Returns the label of the partition.
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Returns the size of the partition in bytes.
+    pub fn size(&self) -> u64 {
+        unsafe { (*self.partition).size as u64 }
+    }
+
+    /// Erases `len` bytes starting at `offset`, setting them to `0xff`.
+    ///
+    /// Both `offset` and `len` must be multiples of the flash sector size,
+    /// usually 4096 bytes.
+    pub fn erase_range(&self, offset: u64, len: u64) -> io::Result<()> {
+        let (offset, len) = (self.checked_range(offset, len)?, len as usize);
+        cvt_esp(
+            unsafe { ffi::esp_partition_erase_range(self.partition, offset, len) },
+            Op::Partition,
+        )
+    }
+
+    fn checked_range(&self, offset: u64, len: u64) -> io::Result<usize> {
+        match offset.checked_add(len) {
+            Some(end) if end <= self.size() => Ok(offset as usize),
+            _ => Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "range is out of the bounds of the partition",
+            )),
+        }
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Read for Partition {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = cmp::min(buf.len() as u64, self.size().saturating_sub(self.pos));
+        if len == 0 {
+            return Ok(0);
+        }
+        let offset = self.checked_range(self.pos, len)?;
+        cvt_esp(
+            unsafe {
+                ffi::esp_partition_read(
+                    self.partition,
+                    offset,
+                    buf.as_mut_ptr().cast(),
+                    len as usize,
+                )
+            },
+            Op::Partition,
+        )?;
+        self.pos += len;
+        Ok(len as usize)
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Write for Partition {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = cmp::min(buf.len() as u64, self.size().saturating_sub(self.pos));
+        if len == 0 {
+            return Ok(0);
+        }
+        let offset = self.checked_range(self.pos, len)?;
+        cvt_esp(
+            unsafe {
+                ffi::esp_partition_write(self.partition, offset, buf.as_ptr().cast(), len as usize)
+            },
+            Op::Partition,
+        )?;
+        self.pos += len;
+        Ok(len as usize)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Seek for Partition {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            }
+            SeekFrom::End(n) => (self.size(), n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            }
+            None => Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl fmt::Debug for Partition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Partition")
+            .field("label", &self.label())
+            .field("size", &self.size())
+            .field("pos", &self.pos)
+            .finish()
+    }
+}
+
+/// An initialized NVS (non-volatile storage) partition.
+///
+/// NVS is the key-value store ESP-IDF keeps in flash. It is not a file system,
+/// but Wi-Fi, Bluetooth and many other components keep their settings in the
+/// default NVS partition, labelled `nvs`, and need it to be initialized first.
+///
+/// The partition is deinitialized when this value is dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(espidf_fs_ext)]
+/// use std::os::espidf::fs::Nvs;
+///
+/// let nvs = Nvs::init("nvs", true)?;
+/// // Keep NVS available for the rest of the program.
+/// std::mem::forget(nvs);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub struct Nvs {
+    partition: CString,
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Nvs {
+    /// Initializes the NVS partition labelled `partition`.
+    ///
+    /// If `erase_if_invalid` is set and the partition has no free pages left or
+    /// was written by a newer version of NVS, it is erased and initialized
+    /// again. All keys stored in it are lost when that happens.
+    pub fn init(partition: &str, erase_if_invalid: bool) -> io::Result<Nvs> {
+        let partition = CString::new(partition)?;
+        let mut err = unsafe { ffi::nvs_flash_init_partition(partition.as_ptr()) };
+        if erase_if_invalid
+            && (err == ffi::ESP_ERR_NVS_NO_FREE_PAGES || err == ffi::ESP_ERR_NVS_NEW_VERSION_FOUND)
+        {
+            cvt_esp(unsafe { ffi::nvs_flash_erase_partition(partition.as_ptr()) }, Op::Nvs)?;
+            err = unsafe { ffi::nvs_flash_init_partition(partition.as_ptr()) };
+        }
+        cvt_esp(err, Op::Nvs)?;
+        Ok(Nvs { partition })
+    }
+
+    /// Returns the label of the partition.
+    pub fn partition(&self) -> &str {
+        // The label was created from a `&str`.
+        self.partition.to_str().unwrap()
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Drop for Nvs {
+    fn drop(&mut self) {
+        let _ = unsafe { ffi::nvs_flash_deinit_partition(self.partition.as_ptr()) };
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl fmt::Debug for Nvs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nvs").field("partition", &self.partition()).finish()
+    }
+}
+
+/// A file system driver implemented in Rust.
+///
+/// A driver registered with [`VfsExt::register`] handles all paths below its
+/// base path, so that [`std::fs`] can be used on them. Paths are passed to the
+/// driver relative to the base path, and file descriptors are local to the
+/// driver: ESP-IDF maps them to the ones returned to the program.
+///
+/// Errors are reported to the program with their raw OS error if they have
+/// one, and with the `errno` value closest to their [`io::ErrorKind`]
+/// otherwise. The operations that have a default implementation fail with
+/// [`io::ErrorKind::Unsupported`]. Listing directories is not supported.
+///
+/// [`std::fs`]: crate::fs
+/// [`io::ErrorKind`]: crate::io::ErrorKind
+/// [`io::ErrorKind::Unsupported`]: crate::io::ErrorKind::Unsupported
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub trait Vfs: Send + Sync + 'static {
+    /// Opens the file at `path` with the `open` `flags` and `mode`, and returns
+    /// its file descriptor.
+    fn open(&self, path: &Path, flags: i32, mode: u32) -> io::Result<i32>;
+
+    /// Closes the file descriptor `fd`.
+    fn close(&self, fd: i32) -> io::Result<()>;
+
+    /// Reads from `fd` into `buf`, returning the number of bytes read.
+    fn read(&self, fd: i32, buf: &mut [u8]) -> io::Result<usize>;
+
+    /// Writes `buf` to `fd`, returning the number of bytes written.
+    fn write(&self, fd: i32, buf: &[u8]) -> io::Result<usize>;
+
+    /// Moves the position of `fd` and returns the new position.
+    fn seek(&self, fd: i32, pos: SeekFrom) -> io::Result<u64> {
+        let _ = (fd, pos);
+        Err(unsupported())
+    }
+
+    /// Returns the metadata of the file open as `fd`.
+    fn file_metadata(&self, fd: i32) -> io::Result<VfsMetadata> {
+        let _ = fd;
+        Err(unsupported())
+    }
+
+    /// Returns the metadata of the file or directory at `path`.
+    fn metadata(&self, path: &Path) -> io::Result<VfsMetadata> {
+        let _ = path;
+        Err(unsupported())
+    }
+
+    /// Flushes the data written to `fd` to the storage.
+    fn sync(&self, fd: i32) -> io::Result<()> {
+        let _ = fd;
+        Err(unsupported())
+    }
+
+    /// Removes the file at `path`.
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        let _ = path;
+        Err(unsupported())
+    }
+
+    /// Renames the file or directory at `from` to `to`.
+    fn rename(&self, from: &Path, to: &Path) -> io::Result<()> {
+        let _ = (from, to);
+        Err(unsupported())
+    }
+
+    /// Creates a directory at `path` with the given `mode`.
+    fn create_dir(&self, path: &Path, mode: u32) -> io::Result<()> {
+        let _ = (path, mode);
+        Err(unsupported())
+    }
+
+    /// Removes the empty directory at `path`.
+    fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        let _ = path;
+        Err(unsupported())
+    }
+}
+
+/// Metadata returned by a [`Vfs`] driver.
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VfsMetadata {
+    is_dir: bool,
+    len: u64,
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl VfsMetadata {
+    /// Metadata of a file of `len` bytes.
+    pub fn file(len: u64) -> VfsMetadata {
+        VfsMetadata { is_dir: false, len }
+    }
+
+    /// Metadata of a directory.
+    pub fn dir() -> VfsMetadata {
+        VfsMetadata { is_dir: true, len: 0 }
+    }
+}
+
+/// Extension methods for [`Vfs`] drivers.
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub trait VfsExt: Vfs + Sized {
+    /// Registers the driver for all paths below `base_path`.
+    ///
+    /// The base path must start with `/`, must not end with `/` and can be at
+    /// most 15 bytes long. The driver stays registered until the returned
+    /// [`VfsRegistration`] is dropped.
+    ///
+    /// The driver table passed to ESP-IDF is laid out for
+    /// `CONFIG_VFS_SUPPORT_DIR`, which is enabled by default.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(espidf_fs_ext)]
+    /// use std::io;
+    /// use std::os::espidf::fs::{Vfs, VfsExt};
+    /// use std::path::Path;
+    ///
+    /// // A file system with a single, endless file of zeroes.
+    /// struct Zero;
+    ///
+    /// impl Vfs for Zero {
+    ///     fn open(&self, path: &Path, _flags: i32, _mode: u32) -> io::Result<i32> {
+    ///         if path == Path::new("/zero") { Ok(0) } else { Err(io::ErrorKind::NotFound.into()) }
+    ///     }
+    ///     fn close(&self, _fd: i32) -> io::Result<()> {
+    ///         Ok(())
+    ///     }
+    ///     fn read(&self, _fd: i32, buf: &mut [u8]) -> io::Result<usize> {
+    ///         buf.fill(0);
+    ///         Ok(buf.len())
+    ///     }
+    ///     fn write(&self, _fd: i32, _buf: &[u8]) -> io::Result<usize> {
+    ///         Err(io::ErrorKind::PermissionDenied.into())
+    ///     }
+    /// }
+    ///
+    /// let _registration = Zero.register("/dev")?;
+    /// let mut zeroes = [1; 16];
+    /// io::Read::read_exact(&mut std::fs::File::open("/dev/zero")?, &mut zeroes)?;
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    fn register<P: AsRef<Path>>(self, base_path: P) -> io::Result<VfsRegistration> {
+        VfsRegistration::new(Box::new(self), base_path.as_ref())
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl<V: Vfs> VfsExt for V {}
+
+/// A registered [`Vfs`] driver, returned by [`VfsExt::register`].
+///
+/// The driver is unregistered and dropped when this value is dropped, ignoring
+/// any errors. Use [`VfsRegistration::unregister`] to handle them.
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+pub struct VfsRegistration {
+    base_path: CString,
+    /// The context pointer ESP-IDF passes to the driver functions. It is only
+    /// freed once the driver has been unregistered.
+    vfs: *mut Box<dyn Vfs>,
+}
+
+// SAFETY: the driver is `Send + Sync`, and ESP-IDF's VFS functions can be called
+// from any task.
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+unsafe impl Send for VfsRegistration {}
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+unsafe impl Sync for VfsRegistration {}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl VfsRegistration {
+    fn new(vfs: Box<dyn Vfs>, base_path: &Path) -> io::Result<VfsRegistration> {
+        let base_path = CString::new(base_path.as_os_str().as_bytes())?;
+        let vfs = Box::into_raw(Box::new(vfs));
+        let err = unsafe { ffi::esp_vfs_register(base_path.as_ptr(), &vfs::TABLE, vfs.cast()) };
+        if let Err(err) = cvt_esp(err, Op::Mount) {
+            drop(unsafe { Box::from_raw(vfs) });
+            return Err(err);
+        }
+        Ok(VfsRegistration { base_path, vfs })
+    }
+
+    /// Returns the path the driver is registered at.
+    pub fn base_path(&self) -> &Path {
+        Path::new(crate::ffi::OsStr::from_bytes(self.base_path.to_bytes()))
+    }
+
+    /// Unregisters the driver and drops it.
+    ///
+    /// Files that are still open on it must not be used afterwards.
+    pub fn unregister(self) -> io::Result<()> {
+        let ret = self.unregister_ref();
+        mem::forget(self);
+        ret
+    }
+
+    fn unregister_ref(&self) -> io::Result<()> {
+        cvt_esp(unsafe { ffi::esp_vfs_unregister(self.base_path.as_ptr()) }, Op::Unmount)?;
+        // ESP-IDF no longer calls the driver.
+        drop(unsafe { Box::from_raw(self.vfs) });
+        Ok(())
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl Drop for VfsRegistration {
+    fn drop(&mut self) {
+        // If unregistering fails the driver may still be called, so it is leaked.
+        let _ = self.unregister_ref();
+    }
+}
+
+#[unstable(feature = "espidf_fs_ext", issue = "none")]
+impl fmt::Debug for VfsRegistration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VfsRegistration").field("base_path", &self.base_path()).finish()
+    }
+}
+
+fn unsupported() -> io::Error {
+    io::const_io_error!(io::ErrorKind::Unsupported, "operation not supported by the VFS driver")
+}
+
+/// The functions ESP-IDF calls for a registered [`Vfs`] driver. They get the
+/// driver as context pointer and report errors through `errno`.
+mod vfs {
+    use libc::{c_char, c_int, c_void, off_t, size_t, ssize_t};
+
+    use super::{ffi, Vfs};
+    use crate::ffi::{CStr, OsStr};
+    use crate::io::{self, SeekFrom};
+    use crate::os::unix::ffi::OsStrExt;
+    use crate::path::Path;
+    use crate::{mem, ptr, slice};
+
+    pub(super) static TABLE: ffi::esp_vfs_t = ffi::esp_vfs_t {
+        flags: ffi::ESP_VFS_FLAG_CONTEXT_PTR,
+        write_p: Some(write),
+        lseek_p: Some(lseek),
+        read_p: Some(read),
+        pread_p: None,
+        pwrite_p: None,
+        open_p: Some(open),
+        close_p: Some(close),
+        fstat_p: Some(fstat),
+        stat_p: Some(stat),
+        link_p: ptr::null(),
+        unlink_p: Some(unlink),
+        rename_p: Some(rename),
+        opendir_p: ptr::null(),
+        readdir_p: ptr::null(),
+        readdir_r_p: ptr::null(),
+        telldir_p: ptr::null(),
+        seekdir_p: ptr::null(),
+        closedir_p: ptr::null(),
+        mkdir_p: Some(mkdir),
+        rmdir_p: Some(rmdir),
+        fcntl_p: ptr::null(),
+        ioctl_p: ptr::null(),
+        fsync_p: Some(fsync),
+        access_p: ptr::null(),
+        truncate_p: ptr::null(),
+        ftruncate_p: ptr::null(),
+        utime_p: ptr::null(),
+        termios: [ptr::null(); 7],
+        select: [ptr::null(); 6],
+    };
+
+    unsafe fn driver<'a>(ctx: *mut c_void) -> &'a dyn Vfs {
+        unsafe { &**ctx.cast::<Box<dyn Vfs>>() }
+    }
+
+    unsafe fn path<'a>(path: *const c_char) -> &'a Path {
+        Path::new(OsStr::from_bytes(unsafe { CStr::from_ptr(path) }.to_bytes()))
+    }
+
+    /// Returns the `errno` value reported for `err`.
+    pub(super) fn errno(err: &io::Error) -> c_int {
+        if let Some(code) = err.raw_os_error() {
+            return code;
+        }
+        match err.kind() {
+            io::ErrorKind::NotFound => libc::ENOENT,
+            io::ErrorKind::PermissionDenied => libc::EACCES,
+            io::ErrorKind::AlreadyExists => libc::EEXIST,
+            io::ErrorKind::InvalidInput => libc::EINVAL,
+            io::ErrorKind::NotADirectory => libc::ENOTDIR,
+            io::ErrorKind::IsADirectory => libc::EISDIR,
+            io::ErrorKind::DirectoryNotEmpty => libc::ENOTEMPTY,
+            io::ErrorKind::StorageFull => libc::ENOSPC,
+            io::ErrorKind::OutOfMemory => libc::ENOMEM,
+            io::ErrorKind::Unsupported => libc::ENOSYS,
+            _ => libc::EIO,
+        }
+    }
+
+    /// Converts the result of a driver call into the return value of the C
+    /// function, setting `errno` and returning -1 on errors.
+    fn ret<T: TryFrom<u64> + From<i8>>(res: io::Result<u64>) -> T {
+        let err = match res {
+            Ok(n) => match T::try_from(n) {
+                Ok(n) => return n,
+                Err(_) => io::Error::from_raw_os_error(libc::EOVERFLOW),
+            },
+            Err(err) => err,
+        };
+        crate::sys::os::set_errno(errno(&err));
+        T::from(-1)
+    }
+
+    unsafe fn fill_stat(metadata: super::VfsMetadata, st: *mut libc::stat) {
+        unsafe {
+            *st = mem::zeroed();
+            (*st).st_mode =
+                if metadata.is_dir { libc::S_IFDIR | 0o755 } else { libc::S_IFREG | 0o644 };
+            (*st).st_size = metadata.len as off_t;
+        }
+    }
+
+    unsafe extern "C" fn open(
+        ctx: *mut c_void,
+        p: *const c_char,
+        flags: c_int,
+        mode: c_int,
+    ) -> c_int {
+        let res = unsafe { driver(ctx).open(path(p), flags, mode as u32) };
+        ret(res.and_then(|fd| u64::try_from(fd).map_err(|_| io::ErrorKind::InvalidData.into())))
+    }
+
+    unsafe extern "C" fn close(ctx: *mut c_void, fd: c_int) -> c_int {
+        ret(unsafe { driver(ctx) }.close(fd).map(|()| 0))
+    }
+
+    unsafe extern "C" fn read(
+        ctx: *mut c_void,
+        fd: c_int,
+        dst: *mut c_void,
+        size: size_t,
+    ) -> ssize_t {
+        let buf = unsafe { slice::from_raw_parts_mut(dst.cast::<u8>(), size) };
+        ret(unsafe { driver(ctx) }.read(fd, buf).map(|n| n as u64))
+    }
+
+    unsafe extern "C" fn write(
+        ctx: *mut c_void,
+        fd: c_int,
+        src: *const c_void,
+        size: size_t,
+    ) -> ssize_t {
+        let buf = unsafe { slice::from_raw_parts(src.cast::<u8>(), size) };
+        ret(unsafe { driver(ctx) }.write(fd, buf).map(|n| n as u64))
+    }
+
+    unsafe extern "C" fn lseek(ctx: *mut c_void, fd: c_int, offset: off_t, whence: c_int) -> off_t {
+        let pos = match whence {
+            libc::SEEK_SET if offset >= 0 => SeekFrom::Start(offset as u64),
+            libc::SEEK_CUR => SeekFrom::Current(offset.into()),
+            libc::SEEK_END => SeekFrom::End(offset.into()),
+            _ => return ret(Err(io::Error::from_raw_os_error(libc::EINVAL))),
+        };
+        ret(unsafe { driver(ctx) }.seek(fd, pos))
+    }
+
+    unsafe extern "C" fn fstat(ctx: *mut c_void, fd: c_int, st: *mut libc::stat) -> c_int {
+        let res = unsafe { driver(ctx) }.file_metadata(fd);
+        ret(res.map(|metadata| unsafe { fill_stat(metadata, st) }).map(|()| 0))
+    }
+
+    unsafe extern "C" fn stat(ctx: *mut c_void, p: *const c_char, st: *mut libc::stat) -> c_int {
+        let res = unsafe { driver(ctx).metadata(path(p)) };
+        ret(res.map(|metadata| unsafe { fill_stat(metadata, st) }).map(|()| 0))
+    }
+
+    unsafe extern "C" fn fsync(ctx: *mut c_void, fd: c_int) -> c_int {
+        ret(unsafe { driver(ctx) }.sync(fd).map(|()| 0))
+    }
+
+    unsafe extern "C" fn unlink(ctx: *mut c_void, p: *const c_char) -> c_int {
+        ret(unsafe { driver(ctx).remove_file(path(p)) }.map(|()| 0))
+    }
+
+    unsafe extern "C" fn rename(ctx: *mut c_void, from: *const c_char, to: *const c_char) -> c_int {
+        ret(unsafe { driver(ctx).rename(path(from), path(to)) }.map(|()| 0))
+    }
+
+    unsafe extern "C" fn mkdir(ctx: *mut c_void, p: *const c_char, mode: libc::mode_t) -> c_int {
+        ret(unsafe { driver(ctx).create_dir(path(p), mode as u32) }.map(|()| 0))
+    }
+
+    unsafe extern "C" fn rmdir(ctx: *mut c_void, p: *const c_char) -> c_int {
+        ret(unsafe { driver(ctx).remove_dir(path(p)) }.map(|()| 0))
+    }
+}
+
+/// The kind of ESP-IDF call an error code was returned from. Some codes mean
+/// different things depending on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    /// Mounting a file system or registering a VFS driver.
+    Mount,
+    /// Unmounting a file system or unregistering a VFS driver.
+    Unmount,
+    /// Reading, writing or erasing a raw partition.
+    Partition,
+    /// Initializing an NVS partition.
+    Nvs,
+}
+
+/// Converts an `esp_err_t` returned by an `op` call into an `io::Result`.
+fn cvt_esp(err: ffi::esp_err_t, op: Op) -> io::Result<()> {
+    let (kind, msg) = match (err, op) {
+        (ffi::ESP_OK, _) => return Ok(()),
+        (ffi::ESP_ERR_NO_MEM, _) => (io::ErrorKind::OutOfMemory, "out of memory"),
+        (ffi::ESP_ERR_INVALID_ARG | ffi::ESP_ERR_INVALID_SIZE, _) => {
+            (io::ErrorKind::InvalidInput, "invalid argument")
+        }
+        (ffi::ESP_ERR_INVALID_STATE, Op::Mount) => {
+            (io::ErrorKind::AlreadyExists, "a file system is already mounted there")
+        }
+        (ffi::ESP_ERR_INVALID_STATE, Op::Unmount) => {
+            (io::ErrorKind::NotFound, "the file system is not mounted")
+        }
+        (ffi::ESP_ERR_NOT_FOUND, Op::Mount | Op::Nvs) => {
+            (io::ErrorKind::NotFound, "partition not found")
+        }
+        (ffi::ESP_ERR_NOT_ALLOWED, Op::Partition) => {
+            (io::ErrorKind::PermissionDenied, "the partition is read-only")
+        }
+        (ffi::ESP_ERR_NVS_NO_FREE_PAGES | ffi::ESP_ERR_NVS_NEW_VERSION_FOUND, Op::Nvs) => {
+            (io::ErrorKind::InvalidData, "the NVS partition has to be erased")
+        }
+        (ffi::ESP_ERR_NOT_SUPPORTED, _) => (io::ErrorKind::Unsupported, "operation not supported"),
+        _ => return Err(io::Error::other(format!("ESP-IDF error {err:#x}"))),
+    };
+    Err(io::Error::new(kind, msg))
+}
+
+#[allow(non_camel_case_types)]
+mod ffi {
+    use libc::{c_char, c_int, c_void, mode_t, off_t, size_t, ssize_t};
+
+    pub type esp_err_t = c_int;
+    pub const ESP_OK: esp_err_t = 0;
+    pub const ESP_ERR_NO_MEM: esp_err_t = 0x101;
+    pub const ESP_ERR_INVALID_ARG: esp_err_t = 0x102;
+    pub const ESP_ERR_INVALID_STATE: esp_err_t = 0x103;
+    pub const ESP_ERR_INVALID_SIZE: esp_err_t = 0x104;
+    pub const ESP_ERR_NOT_FOUND: esp_err_t = 0x105;
+    pub const ESP_ERR_NOT_SUPPORTED: esp_err_t = 0x106;
+    pub const ESP_ERR_NOT_ALLOWED: esp_err_t = 0x10c;
+    pub const ESP_ERR_NVS_NO_FREE_PAGES: esp_err_t = 0x110d;
+    pub const ESP_ERR_NVS_NEW_VERSION_FOUND: esp_err_t = 0x1110;
+
+    pub type wl_handle_t = i32;
+    pub const WL_INVALID_HANDLE: wl_handle_t = -1;
+
+    // The fields after `allocation_unit_size` were added in later ESP-IDF versions.
+    // Older versions don't read them, so passing a larger struct is fine.
+    #[repr(C)]
+    pub struct esp_vfs_fat_mount_config_t {
+        pub format_if_mount_failed: bool,
+        pub max_files: c_int,
+        pub allocation_unit_size: size_t,
+        pub disk_status_check_enable: bool,
+        pub use_one_fat: bool,
+    }
+
+    #[repr(C)]
+    pub struct esp_vfs_spiffs_conf_t {
+        pub base_path: *const c_char,
+        pub partition_label: *const c_char,
+        pub max_files: size_t,
+        pub format_if_mount_failed: bool,
+    }
+
+    #[repr(C)]
+    pub struct esp_vfs_littlefs_conf_t {
+        pub base_path: *const c_char,
+        pub partition_label: *const c_char,
+        pub partition: *const esp_partition_t,
+        /// `format_if_mount_failed`, `read_only`, `dont_mount` and `grow_on_mount`
+        /// as bitfield.
+        pub flags: u8,
+    }
+    pub const LITTLEFS_FORMAT_IF_MOUNT_FAILED: u8 = 1 << 0;
+
+    pub const ESP_VFS_FLAG_CONTEXT_PTR: c_int = 1;
+
+    // The layout with `CONFIG_VFS_SUPPORT_DIR` enabled, using the `_p` variants
+    // of the function pointers that take a context pointer. The termios and
+    // select functions are never set.
+    #[repr(C)]
+    pub struct esp_vfs_t {
+        pub flags: c_int,
+        pub write_p:
+            Option<unsafe extern "C" fn(*mut c_void, c_int, *const c_void, size_t) -> ssize_t>,
+        pub lseek_p: Option<unsafe extern "C" fn(*mut c_void, c_int, off_t, c_int) -> off_t>,
+        pub read_p:
+            Option<unsafe extern "C" fn(*mut c_void, c_int, *mut c_void, size_t) -> ssize_t>,
+        pub pread_p:
+            Option<unsafe extern "C" fn(*mut c_void, c_int, *mut c_void, size_t, off_t) -> ssize_t>,
+        pub pwrite_p: Option<
+            unsafe extern "C" fn(*mut c_void, c_int, *const c_void, size_t, off_t) -> ssize_t,
+        >,
+        pub open_p: Option<unsafe extern "C" fn(*mut c_void, *const c_char, c_int, c_int) -> c_int>,
+        pub close_p: Option<unsafe extern "C" fn(*mut c_void, c_int) -> c_int>,
+        pub fstat_p: Option<unsafe extern "C" fn(*mut c_void, c_int, *mut libc::stat) -> c_int>,
+        pub stat_p:
+            Option<unsafe extern "C" fn(*mut c_void, *const c_char, *mut libc::stat) -> c_int>,
+        pub link_p: *const c_void,
+        pub unlink_p: Option<unsafe extern "C" fn(*mut c_void, *const c_char) -> c_int>,
+        pub rename_p:
+            Option<unsafe extern "C" fn(*mut c_void, *const c_char, *const c_char) -> c_int>,
+        pub opendir_p: *const c_void,
+        pub readdir_p: *const c_void,
+        pub readdir_r_p: *const c_void,
+        pub telldir_p: *const c_void,
+        pub seekdir_p: *const c_void,
+        pub closedir_p: *const c_void,
+        pub mkdir_p: Option<unsafe extern "C" fn(*mut c_void, *const c_char, mode_t) -> c_int>,
+        pub rmdir_p: Option<unsafe extern "C" fn(*mut c_void, *const c_char) -> c_int>,
+        pub fcntl_p: *const c_void,
+        pub ioctl_p: *const c_void,
+        pub fsync_p: Option<unsafe extern "C" fn(*mut c_void, c_int) -> c_int>,
+        pub access_p: *const c_void,
+        pub truncate_p: *const c_void,
+        // Missing in older ESP-IDF versions. `esp_vfs_register` copies `sizeof(esp_vfs_t)`
+        // bytes, so there the table is one pointer longer than needed, and the null fields from
+        // here on are read one slot earlier, which makes no difference.
+        pub ftruncate_p: *const c_void,
+        pub utime_p: *const c_void,
+        pub termios: [*const c_void; 7],
+        pub select: [*const c_void; 6],
+    }
+
+    // The table only holds function pointers and nulls.
+    unsafe impl Sync for esp_vfs_t {}
+
+    pub type esp_partition_type_t = c_int;
+    pub type esp_partition_subtype_t = c_int;
+    pub const ESP_PARTITION_TYPE_ANY: esp_partition_type_t = 0xff;
+    pub const ESP_PARTITION_SUBTYPE_ANY: esp_partition_subtype_t = 0xff;
+
+    // Only the fields that are at the same offset in all supported ESP-IDF versions.
+    // The struct is only ever accessed through pointers handed out by ESP-IDF.
+    #[repr(C)]
+    pub struct esp_partition_t {
+        pub flash_chip: *mut c_void,
+        pub type_: esp_partition_type_t,
+        pub subtype: esp_partition_subtype_t,
+        pub address: u32,
+        pub size: u32,
+    }
+
+    extern "C" {
+        pub fn esp_vfs_fat_spiflash_mount_rw_wl(
+            base_path: *const c_char,
+            partition_label: *const c_char,
+            mount_config: *const esp_vfs_fat_mount_config_t,
+            wl_handle: *mut wl_handle_t,
+        ) -> esp_err_t;
+        pub fn esp_vfs_fat_spiflash_unmount_rw_wl(
+            base_path: *const c_char,
+            wl_handle: wl_handle_t,
+        ) -> esp_err_t;
+
+        pub fn esp_vfs_spiffs_register(conf: *const esp_vfs_spiffs_conf_t) -> esp_err_t;
+        pub fn esp_vfs_spiffs_unregister(partition_label: *const c_char) -> esp_err_t;
+
+        pub fn nvs_flash_init_partition(partition_label: *const c_char) -> esp_err_t;
+        pub fn nvs_flash_deinit_partition(partition_label: *const c_char) -> esp_err_t;
+        pub fn nvs_flash_erase_partition(partition_label: *const c_char) -> esp_err_t;
+
+        pub fn esp_vfs_register(
+            base_path: *const c_char,
+            vfs: *const esp_vfs_t,
+            ctx: *mut c_void,
+        ) -> esp_err_t;
+        pub fn esp_vfs_unregister(base_path: *const c_char) -> esp_err_t;
+
+        pub fn esp_partition_find_first(
+            type_: esp_partition_type_t,
+            subtype: esp_partition_subtype_t,
+            label: *const c_char,
+        ) -> *const esp_partition_t;
+        pub fn esp_partition_read(
+            partition: *const esp_partition_t,
+            src_offset: size_t,
+            dst: *mut c_void,
+            size: size_t,
+        ) -> esp_err_t;
+        pub fn esp_partition_write(
+            partition: *const esp_partition_t,
+            dst_offset: size_t,
+            src: *const c_void,
+            size: size_t,
+        ) -> esp_err_t;
+        pub fn esp_partition_erase_range(
+            partition: *const esp_partition_t,
+            offset: size_t,
+            size: size_t,
+        ) -> esp_err_t;
+    }
+
+    // LittleFS is an external component, so it is linked weakly and mounting it
+    // fails if the firmware doesn't include it.
+    extern "C" {
+        #[linkage = "extern_weak"]
+        pub static esp_vfs_littlefs_register:
+            Option<unsafe extern "C" fn(conf: *const esp_vfs_littlefs_conf_t) -> esp_err_t>;
+        #[linkage = "extern_weak"]
+        pub static esp_vfs_littlefs_unregister:
+            Option<unsafe extern "C" fn(partition_label: *const c_char) -> esp_err_t>;
+    }
+}
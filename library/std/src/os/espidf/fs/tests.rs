@@ -0,0 +1,173 @@
+use super::{cvt_esp, ffi, vfs, Op, Vfs, VfsExt, VfsMetadata};
+use crate::collections::HashMap;
+use crate::fs;
+use crate::io::{self, Read, Seek, SeekFrom, Write};
+use crate::path::{Path, PathBuf};
+use crate::sync::Mutex;
+
+#[test]
+fn cvt_esp_depends_on_op() {
+    assert!(cvt_esp(ffi::ESP_OK, Op::Mount).is_ok());
+
+    let kind = |err, op| cvt_esp(err, op).unwrap_err().kind();
+    assert_eq!(kind(ffi::ESP_ERR_INVALID_STATE, Op::Mount), io::ErrorKind::AlreadyExists);
+    assert_eq!(kind(ffi::ESP_ERR_INVALID_STATE, Op::Unmount), io::ErrorKind::NotFound);
+    assert_eq!(kind(ffi::ESP_ERR_INVALID_STATE, Op::Partition), io::ErrorKind::Other);
+    assert_eq!(kind(ffi::ESP_ERR_NOT_FOUND, Op::Nvs), io::ErrorKind::NotFound);
+    assert_eq!(kind(ffi::ESP_ERR_NOT_ALLOWED, Op::Partition), io::ErrorKind::PermissionDenied);
+    assert_eq!(kind(ffi::ESP_ERR_NOT_ALLOWED, Op::Mount), io::ErrorKind::Other);
+    assert_eq!(kind(ffi::ESP_ERR_NVS_NO_FREE_PAGES, Op::Nvs), io::ErrorKind::InvalidData);
+    assert_eq!(kind(ffi::ESP_ERR_NO_MEM, Op::Unmount), io::ErrorKind::OutOfMemory);
+}
+
+#[test]
+fn vfs_errno() {
+    assert_eq!(vfs::errno(&io::Error::from_raw_os_error(libc::EBUSY)), libc::EBUSY);
+    assert_eq!(vfs::errno(&io::ErrorKind::NotFound.into()), libc::ENOENT);
+    assert_eq!(vfs::errno(&io::ErrorKind::Unsupported.into()), libc::ENOSYS);
+    assert_eq!(vfs::errno(&io::Error::other("driver error")), libc::EIO);
+}
+
+#[test]
+fn vfs_table_layout() {
+    use crate::mem::{offset_of, size_of};
+
+    // Every field is a word, so the offsets are the indices of the fields in `esp_vfs.h`.
+    let word = size_of::<*const libc::c_void>();
+    assert_eq!(size_of::<libc::c_int>(), word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, write_p), word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, fstat_p), 8 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, stat_p), 9 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, rmdir_p), 20 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, fcntl_p), 21 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, fsync_p), 23 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, access_p), 24 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, utime_p), 27 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, termios), 28 * word);
+    assert_eq!(offset_of!(ffi::esp_vfs_t, select), 35 * word);
+    assert_eq!(size_of::<ffi::esp_vfs_t>(), 41 * word);
+}
+
+/// A flat file system keeping its files in memory.
+#[derive(Default)]
+struct MemFs {
+    files: Mutex<HashMap<PathBuf, Vec<u8>>>,
+    /// The path and position of each open file, indexed by file descriptor.
+    open: Mutex<Vec<Option<(PathBuf, usize)>>>,
+}
+
+impl MemFs {
+    fn with_file<T>(
+        &self,
+        fd: i32,
+        f: impl FnOnce(&mut Vec<u8>, &mut usize) -> T,
+    ) -> io::Result<T> {
+        let mut open = self.open.lock().unwrap();
+        let Some(Some((path, pos))) = open.get_mut(fd as usize) else {
+            return Err(io::Error::from_raw_os_error(libc::EBADF));
+        };
+        Ok(f(self.files.lock().unwrap().get_mut(path).unwrap(), pos))
+    }
+}
+
+impl Vfs for MemFs {
+    fn open(&self, path: &Path, flags: i32, _mode: u32) -> io::Result<i32> {
+        let mut files = self.files.lock().unwrap();
+        if flags & libc::O_CREAT != 0 {
+            files.entry(path.to_owned()).or_default();
+        } else if !files.contains_key(path) {
+            return Err(io::ErrorKind::NotFound.into());
+        }
+        if flags & libc::O_TRUNC != 0 {
+            files.get_mut(path).unwrap().clear();
+        }
+        let mut open = self.open.lock().unwrap();
+        open.push(Some((path.to_owned(), 0)));
+        Ok(open.len() as i32 - 1)
+    }
+
+    fn close(&self, fd: i32) -> io::Result<()> {
+        match self.open.lock().unwrap().get_mut(fd as usize) {
+            Some(file @ Some(_)) => {
+                *file = None;
+                Ok(())
+            }
+            _ => Err(io::Error::from_raw_os_error(libc::EBADF)),
+        }
+    }
+
+    fn read(&self, fd: i32, buf: &mut [u8]) -> io::Result<usize> {
+        self.with_file(fd, |data, pos| {
+            let n = (&data[(*pos).min(data.len())..]).read(buf).unwrap();
+            *pos += n;
+            n
+        })
+    }
+
+    fn write(&self, fd: i32, buf: &[u8]) -> io::Result<usize> {
+        self.with_file(fd, |data, pos| {
+            let end = *pos + buf.len();
+            if data.len() < end {
+                data.resize(end, 0);
+            }
+            data[*pos..end].copy_from_slice(buf);
+            *pos = end;
+            buf.len()
+        })
+    }
+
+    fn seek(&self, fd: i32, pos: SeekFrom) -> io::Result<u64> {
+        self.with_file(fd, |data, cur| {
+            *cur = match pos {
+                SeekFrom::Start(n) => n as usize,
+                SeekFrom::Current(n) => (*cur as i64 + n) as usize,
+                SeekFrom::End(n) => (data.len() as i64 + n) as usize,
+            };
+            *cur as u64
+        })
+    }
+
+    fn file_metadata(&self, fd: i32) -> io::Result<VfsMetadata> {
+        self.with_file(fd, |data, _| VfsMetadata::file(data.len() as u64))
+    }
+
+    fn metadata(&self, path: &Path) -> io::Result<VfsMetadata> {
+        match self.files.lock().unwrap().get(path) {
+            Some(data) => Ok(VfsMetadata::file(data.len() as u64)),
+            None => Err(io::ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn remove_file(&self, path: &Path) -> io::Result<()> {
+        match self.files.lock().unwrap().remove(path) {
+            Some(_) => Ok(()),
+            None => Err(io::ErrorKind::NotFound.into()),
+        }
+    }
+}
+
+#[test]
+fn vfs_through_std_fs() {
+    let registration = MemFs::default().register("/memfs").unwrap();
+    assert_eq!(registration.base_path(), Path::new("/memfs"));
+
+    let mut file = fs::File::create("/memfs/hello.txt").unwrap();
+    file.write_all(b"hello, world").unwrap();
+    file.seek(SeekFrom::Start(7)).unwrap();
+    file.write_all(b"esp").unwrap();
+    drop(file);
+
+    assert_eq!(fs::metadata("/memfs/hello.txt").unwrap().len(), 12);
+    assert_eq!(fs::read_to_string("/memfs/hello.txt").unwrap(), "hello, esprld");
+
+    let err = fs::File::open("/memfs/missing.txt").unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+    let err = fs::create_dir("/memfs/dir").unwrap_err();
+    assert_eq!(err.raw_os_error(), Some(libc::ENOSYS));
+
+    fs::remove_file("/memfs/hello.txt").unwrap();
+    assert!(!fs::exists("/memfs/hello.txt").unwrap());
+
+    registration.unregister().unwrap();
+    assert!(fs::File::open("/memfs/hello.txt").is_err());
+}
@@ -2,5 +2,8 @@
 
 #![stable(feature = "raw_ext", since = "1.1.0")]
 
+pub mod alloc;
 pub mod fs;
+pub mod net;
 pub mod raw;
+pub mod thread;
@@ -0,0 +1,123 @@
+//! ESP-IDF-specific extensions to primitives in the [`std::net`] module.
+//!
+//! The network interfaces of ESP-IDF are lwIP netifs, named after the two letters of the netif and
+//! its number, like `st1` for the Wi-Fi station or `en3` for Ethernet. The IPv6 scope IDs of the
+//! [`SocketAddrV6`]s of link-local addresses are the indices of these netifs.
+//!
+//! [`std::net`]: crate::net
+//! [`SocketAddrV6`]: net::SocketAddrV6
+
+#![unstable(feature = "espidf_net_ext", issue = "none")]
+
+use crate::ffi::{CStr, CString};
+use crate::sealed::Sealed;
+use crate::sys_common::AsInner;
+use crate::{io, net};
+
+/// ESP-IDF-specific extensions to the sockets of [`std::net`].
+///
+/// [`std::net`]: crate::net
+pub trait SocketExt: Sealed {
+    /// Binds the socket to a network interface, like `SO_BINDTODEVICE` on Linux, or removes the
+    /// binding with `None`.
+    ///
+    /// The traffic of a bound socket only goes through the given netif, for example to reach a
+    /// server through Ethernet while the Wi-Fi station is also up.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(espidf_net_ext)]
+    /// use std::net::UdpSocket;
+    /// use std::os::espidf::net::SocketExt;
+    ///
+    /// let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+    /// socket.bind_to_netif(Some("en3")).expect("bind_to_netif failed");
+    /// ```
+    fn bind_to_netif(&self, netif: Option<&str>) -> io::Result<()>;
+}
+
+impl Sealed for net::TcpStream {}
+impl Sealed for net::TcpListener {}
+impl Sealed for net::UdpSocket {}
+
+impl SocketExt for net::TcpStream {
+    fn bind_to_netif(&self, netif: Option<&str>) -> io::Result<()> {
+        self.as_inner().socket().set_bind_to_netif(netif)
+    }
+}
+
+impl SocketExt for net::TcpListener {
+    fn bind_to_netif(&self, netif: Option<&str>) -> io::Result<()> {
+        self.as_inner().socket().set_bind_to_netif(netif)
+    }
+}
+
+impl SocketExt for net::UdpSocket {
+    fn bind_to_netif(&self, netif: Option<&str>) -> io::Result<()> {
+        self.as_inner().socket().set_bind_to_netif(netif)
+    }
+}
+
+/// Returns the name of the network interface an IPv6 scope ID refers to.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(espidf_net_ext)]
+/// use std::net::UdpSocket;
+/// use std::os::espidf::net::netif_name;
+///
+/// let socket = UdpSocket::bind("[::]:5353").unwrap();
+/// let mut buf = [0; 512];
+/// if let (_, std::net::SocketAddr::V6(peer)) = socket.recv_from(&mut buf).unwrap() {
+///     println!("received on {}", netif_name(peer.scope_id()).unwrap());
+/// }
+/// ```
+pub fn netif_name(scope_id: u32) -> io::Result<String> {
+    let mut name = [0; ffi::NETIF_NAMESIZE];
+    // SAFETY: `name` is large enough for any netif name and its nul terminator.
+    let ptr = unsafe { ffi::lwip_if_indextoname(scope_id, name.as_mut_ptr()) };
+    if ptr.is_null() {
+        return Err(io::const_io_error!(io::ErrorKind::NotFound, "no netif with this scope ID"));
+    }
+    // SAFETY: lwIP wrote a nul-terminated name into `name`.
+    let name = unsafe { CStr::from_ptr(name.as_ptr()) };
+    Ok(name.to_string_lossy().into_owned())
+}
+
+/// Returns the IPv6 scope ID of a network interface, to connect to the link-local addresses
+/// reachable through it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(espidf_net_ext)]
+/// use std::net::{Ipv6Addr, SocketAddrV6, TcpStream};
+/// use std::os::espidf::net::netif_scope_id;
+///
+/// let ip = Ipv6Addr::new(0xfe80, 0, 0, 0, 0x1234, 0x56ff, 0xfe78, 0x9abc);
+/// let scope_id = netif_scope_id("st1").unwrap();
+/// let stream = TcpStream::connect(SocketAddrV6::new(ip, 8080, 0, scope_id));
+/// ```
+pub fn netif_scope_id(netif: &str) -> io::Result<u32> {
+    let name = CString::new(netif)
+        .map_err(|_| io::const_io_error!(io::ErrorKind::InvalidInput, "invalid netif name"))?;
+    // SAFETY: `name` is nul-terminated.
+    match unsafe { ffi::lwip_if_nametoindex(name.as_ptr()) } {
+        0 => Err(io::const_io_error!(io::ErrorKind::NotFound, "no netif with this name")),
+        scope_id => Ok(scope_id),
+    }
+}
+
+mod ffi {
+    use libc::{c_char, c_uint};
+
+    pub const NETIF_NAMESIZE: usize = 6;
+
+    // `if_indextoname` and `if_nametoindex` are macros of lwIP, not functions.
+    extern "C" {
+        pub fn lwip_if_indextoname(ifindex: c_uint, ifname: *mut c_char) -> *mut c_char;
+        pub fn lwip_if_nametoindex(ifname: *const c_char) -> c_uint;
+    }
+}
@@ -0,0 +1,77 @@
+//! ESP-IDF-specific extensions to primitives in the [`std::thread`] module.
+//!
+//! [`std::thread`]: crate::thread
+
+#![unstable(feature = "espidf_thread_ext", issue = "none")]
+
+use crate::sealed::Sealed;
+use crate::sys_common::AsInnerMut;
+use crate::thread::Builder;
+
+/// ESP-IDF-specific extensions to [`thread::Builder`].
+///
+/// The options are handed to `esp_pthread_set_cfg` right before the thread is
+/// created, and only apply to that thread. Options that are not set keep the
+/// defaults configured in menuconfig.
+///
+/// [`thread::Builder`]: crate::thread::Builder
+pub trait BuilderExt: Sealed {
+    /// Pins the thread to the given CPU core.
+    ///
+    /// The core must exist on the chip, that is, it must be `0` on single-core
+    /// chips and `0` or `1` on dual-core chips. By default, threads may run on
+    /// any core.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(espidf_thread_ext)]
+    /// use std::os::espidf::thread::BuilderExt;
+    /// use std::thread;
+    ///
+    /// let handler = thread::Builder::new()
+    ///     .pin_to_core(1)
+    ///     .priority(10)
+    ///     .spawn(|| {
+    ///         // thread code
+    ///     })
+    ///     .unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    fn pin_to_core(self, core: usize) -> Self;
+
+    /// Sets the FreeRTOS priority of the thread.
+    ///
+    /// The priority must be below `configMAX_PRIORITIES`. Higher values
+    /// preempt lower ones.
+    fn priority(self, priority: u8) -> Self;
+
+    /// Allocates the stack of the thread in external PSRAM instead of internal
+    /// RAM.
+    ///
+    /// This requires ESP-IDF v5.3 or later, with PSRAM enabled in menuconfig;
+    /// spawning the thread fails with [`io::ErrorKind::Unsupported`] if `std`
+    /// was built for an older version. A thread with its stack in PSRAM must not run
+    /// while the flash cache is disabled, for example during flash writes.
+    ///
+    /// [`io::ErrorKind::Unsupported`]: crate::io::ErrorKind::Unsupported
+    fn stack_in_psram(self, psram: bool) -> Self;
+}
+
+impl BuilderExt for Builder {
+    fn pin_to_core(mut self, core: usize) -> Self {
+        self.as_inner_mut().core = Some(core);
+        self
+    }
+
+    fn priority(mut self, priority: u8) -> Self {
+        self.as_inner_mut().priority = Some(priority);
+        self
+    }
+
+    fn stack_in_psram(mut self, psram: bool) -> Self {
+        self.as_inner_mut().stack_in_psram = Some(psram);
+        self
+    }
+}
@@ -186,6 +186,7 @@ pub const fn new() -> Condvar {
     /// }
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
+    #[cfg_attr(not(bootstrap), may_block)]
     pub fn wait<'a, T>(&self, guard: MutexGuard<'a, T>) -> LockResult<MutexGuard<'a, T>> {
         let poisoned = unsafe {
             let lock = mutex::guard_lock(&guard);
@@ -378,6 +379,7 @@ pub fn wait_timeout_ms<'a, T>(
     /// }
     /// ```
     #[stable(feature = "wait_timeout", since = "1.5.0")]
+    #[cfg_attr(not(bootstrap), may_block)]
     pub fn wait_timeout<'a, T>(
         &self,
         guard: MutexGuard<'a, T>,
@@ -82,8 +82,12 @@
 use crate::os::unix::prelude::*;
 use crate::path::{Path, PathBuf};
 use crate::sync::Arc;
+#[cfg(not(target_os = "espidf"))]
 use crate::sys::common::small_c_string::run_path_with_cstr;
 use crate::sys::fd::FileDesc;
+// Relative paths are resolved against the working directory kept by std.
+#[cfg(target_os = "espidf")]
+use crate::sys::os::run_path_with_cwd as run_path_with_cstr;
 use crate::sys::time::SystemTime;
 #[cfg(all(target_os = "linux", target_env = "gnu"))]
 use crate::sys::weak::syscall;
@@ -1,3 +1,6 @@
+#[cfg(all(test, target_os = "espidf"))]
+mod tests;
+
 use libc::{c_int, c_void, size_t, sockaddr, socklen_t, MSG_PEEK};
 
 use crate::ffi::CStr;
@@ -50,13 +53,42 @@ pub fn cvt_gai(err: c_int) -> io::Result<()> {
         CStr::from_ptr(libc::gai_strerror(err)).to_string_lossy()
     };
 
+    #[cfg(not(target_os = "espidf"))]
+    let kind = io::ErrorKind::Uncategorized;
+
     #[cfg(target_os = "espidf")]
-    let detail = "";
+    let (kind, detail) = espidf_gai_error(err);
 
-    Err(io::Error::new(
-        io::ErrorKind::Uncategorized,
-        &format!("failed to lookup address information: {detail}")[..],
-    ))
+    Err(io::Error::new(kind, &format!("failed to lookup address information: {detail}")[..]))
+}
+
+/// The kind and description of an `EAI_*` error of lwIP, which has no `gai_strerror`.
+#[cfg(target_os = "espidf")]
+fn espidf_gai_error(err: c_int) -> (io::ErrorKind, &'static str) {
+    // lwIP's own values, from `lwip/netdb.h`, which differ from the ones of other platforms.
+    const EAI_NONAME: c_int = 200;
+    const EAI_SERVICE: c_int = 201;
+    const EAI_FAIL: c_int = 202;
+    const EAI_MEMORY: c_int = 203;
+    const EAI_FAMILY: c_int = 204;
+    const HOST_NOT_FOUND: c_int = 210;
+    const NO_DATA: c_int = 211;
+    const NO_RECOVERY: c_int = 212;
+    const TRY_AGAIN: c_int = 213;
+
+    match err {
+        EAI_NONAME | HOST_NOT_FOUND => (io::ErrorKind::NotFound, "name or service not known"),
+        NO_DATA => (io::ErrorKind::NotFound, "no address associated with name"),
+        EAI_SERVICE => (io::ErrorKind::InvalidInput, "service not supported for socket type"),
+        EAI_MEMORY => (io::ErrorKind::OutOfMemory, "memory allocation failure"),
+        EAI_FAMILY => (io::ErrorKind::Unsupported, "address family not supported"),
+        // lwIP reports DNS lookups that timed out or got no answer with `EAI_FAIL`, so it is as
+        // worth retrying as `TRY_AGAIN`.
+        EAI_FAIL => (io::ErrorKind::TimedOut, "failure in name resolution"),
+        TRY_AGAIN => (io::ErrorKind::TimedOut, "temporary failure in name resolution"),
+        NO_RECOVERY => (io::ErrorKind::Uncategorized, "non-recoverable failure in name resolution"),
+        _ => (io::ErrorKind::Uncategorized, "unknown error"),
+    }
 }
 
 impl Socket {
@@ -440,6 +472,29 @@ pub fn nodelay(&self) -> io::Result<bool> {
         Ok(raw != 0)
     }
 
+    #[cfg(target_os = "espidf")]
+    pub fn set_bind_to_netif(&self, netif: Option<&str>) -> io::Result<()> {
+        // lwIP's `struct ifreq`, which only holds the name of the netif.
+        #[repr(C)]
+        struct ifreq {
+            ifr_name: [libc::c_char; 6],
+        }
+        // lwIP's own values, which differ from the ones of other platforms.
+        const SOL_SOCKET: c_int = 0xfff;
+        const SO_BINDTODEVICE: c_int = 0x100b;
+
+        // An empty name removes the binding.
+        let mut ifreq = ifreq { ifr_name: [0; 6] };
+        let name = netif.unwrap_or("").as_bytes();
+        if name.len() >= ifreq.ifr_name.len() || name.contains(&0) {
+            return Err(io::const_io_error!(io::ErrorKind::InvalidInput, "invalid netif name"));
+        }
+        for (dst, &src) in ifreq.ifr_name.iter_mut().zip(name) {
+            *dst = src as libc::c_char;
+        }
+        setsockopt(self, SOL_SOCKET, SO_BINDTODEVICE, ifreq)
+    }
+
     #[cfg(any(target_os = "android", target_os = "linux",))]
     pub fn set_quickack(&self, quickack: bool) -> io::Result<()> {
         setsockopt(self, libc::IPPROTO_TCP, libc::TCP_QUICKACK, quickack as c_int)
@@ -0,0 +1,27 @@
+use super::espidf_gai_error;
+use crate::io::ErrorKind;
+
+#[test]
+fn espidf_gai_error_kinds() {
+    let cases = [
+        (200, ErrorKind::NotFound),      // EAI_NONAME
+        (201, ErrorKind::InvalidInput),  // EAI_SERVICE
+        (202, ErrorKind::TimedOut),      // EAI_FAIL
+        (203, ErrorKind::OutOfMemory),   // EAI_MEMORY
+        (204, ErrorKind::Unsupported),   // EAI_FAMILY
+        (210, ErrorKind::NotFound),      // HOST_NOT_FOUND
+        (211, ErrorKind::NotFound),      // NO_DATA
+        (212, ErrorKind::Uncategorized), // NO_RECOVERY
+        (213, ErrorKind::TimedOut),      // TRY_AGAIN
+        (-2, ErrorKind::Uncategorized),  // The `EAI_NONAME` of glibc, unknown to lwIP.
+    ];
+    for (err, kind) in cases {
+        assert_eq!(espidf_gai_error(err).0, kind, "EAI error {err}");
+    }
+}
+
+#[test]
+fn espidf_gai_error_details() {
+    assert_eq!(espidf_gai_error(213).1, "temporary failure in name resolution");
+    assert_eq!(espidf_gai_error(0).1, "unknown error");
+}
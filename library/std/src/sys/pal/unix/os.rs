@@ -129,9 +129,51 @@ pub fn error_string(errno: i32) -> String {
     }
 }
 
+/// The working directory on ESP-IDF, `None` being the root directory.
+///
+/// The ESP-IDF VFS has no notion of a working directory, so std keeps its own and
+/// resolves relative paths against it before they reach the VFS.
+#[cfg(target_os = "espidf")]
+static CWD: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Resolves `p` against `cwd`, also removing `.` and `..` components, which not
+/// every ESP-IDF file system understands.
+#[cfg(target_os = "espidf")]
+fn resolve(cwd: Option<&path::Path>, p: &path::Path) -> PathBuf {
+    use crate::path::Component;
+
+    let mut resolved = PathBuf::from("/");
+    for component in cwd.into_iter().chain([p]).flat_map(path::Path::components) {
+        match component {
+            Component::Prefix(_) | Component::CurDir => {}
+            Component::RootDir => resolved = PathBuf::from("/"),
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => resolved.push(name),
+        }
+    }
+    resolved
+}
+
+/// Like `run_path_with_cstr`, but resolves relative paths against the working
+/// directory set by `chdir`. Used by all file system operations on ESP-IDF.
+#[cfg(target_os = "espidf")]
+pub fn run_path_with_cwd<T>(
+    p: &path::Path,
+    f: &dyn Fn(&CStr) -> io::Result<T>,
+) -> io::Result<T> {
+    if p.is_absolute() {
+        return run_path_with_cstr(p, f);
+    }
+    let resolved = resolve(CWD.read().unwrap_or_else(PoisonError::into_inner).as_deref(), p);
+    run_path_with_cstr(&resolved, f)
+}
+
 #[cfg(target_os = "espidf")]
 pub fn getcwd() -> io::Result<PathBuf> {
-    Ok(PathBuf::from("/"))
+    let cwd = CWD.read().unwrap_or_else(PoisonError::into_inner);
+    Ok(cwd.clone().unwrap_or_else(|| PathBuf::from("/")))
 }
 
 #[cfg(not(target_os = "espidf"))]
@@ -162,8 +204,16 @@ pub fn getcwd() -> io::Result<PathBuf> {
 }
 
 #[cfg(target_os = "espidf")]
-pub fn chdir(_p: &path::Path) -> io::Result<()> {
-    super::unsupported::unsupported()
+pub fn chdir(p: &path::Path) -> io::Result<()> {
+    let mut cwd = CWD.write().unwrap_or_else(PoisonError::into_inner);
+    let dir = resolve(cwd.as_deref(), p);
+    // `dir` is absolute, so this doesn't take the lock again.
+    if !crate::sys::fs::stat(&dir)?.file_type().is_dir() {
+        return Err(io::Error::from_raw_os_error(libc::ENOTDIR));
+    }
+
+    *cwd = Some(dir);
+    Ok(())
 }
 
 #[cfg(not(target_os = "espidf"))]
@@ -21,3 +21,24 @@ fn test_parse_glibc_version() {
         assert_eq!(parsed, super::parse_glibc_version(version_str));
     }
 }
+
+#[test]
+#[cfg(target_os = "espidf")]
+fn test_resolve() {
+    use crate::path::Path;
+
+    let cases = [
+        (None, "a/b", "/a/b"),
+        (None, "/a/b", "/a/b"),
+        (None, "..", "/"),
+        (Some("/spiffs"), "a.txt", "/spiffs/a.txt"),
+        (Some("/spiffs"), "./a/./b", "/spiffs/a/b"),
+        (Some("/spiffs/logs"), "../a.txt", "/spiffs/a.txt"),
+        (Some("/spiffs"), "../../../a.txt", "/a.txt"),
+        (Some("/spiffs"), "/sdcard/a.txt", "/sdcard/a.txt"),
+        (Some("/spiffs/"), "a//b/", "/spiffs/a/b"),
+    ];
+    for (cwd, path, resolved) in cases {
+        assert_eq!(super::resolve(cwd.map(Path::new), Path::new(path)), Path::new(resolved));
+    }
+}
@@ -1,3 +1,6 @@
+#[cfg(all(test, target_os = "espidf"))]
+mod tests;
+
 use crate::ffi::CStr;
 use crate::mem::{self, ManuallyDrop};
 use crate::num::NonZero;
@@ -17,6 +20,78 @@
 #[cfg(target_os = "espidf")]
 pub const DEFAULT_MIN_STACK_SIZE: usize = 0; // 0 indicates that the stack size configured in the ESP-IDF menuconfig system should be used
 
+#[cfg(target_os = "espidf")]
+mod esp_pthread {
+    use libc::{c_char, c_int};
+
+    pub type esp_err_t = c_int;
+    pub const ESP_OK: esp_err_t = 0;
+
+    pub const MALLOC_CAP_8BIT: u32 = 1 << 2;
+    pub const MALLOC_CAP_SPIRAM: u32 = 1 << 10;
+    pub const MALLOC_CAP_INTERNAL: u32 = 1 << 11;
+
+    // The layout of `esp_pthread_cfg_t` depends on the ESP-IDF version, which can't be detected
+    // here, so the build has to pass it in the same cfgs that `esp-idf-sys` reports.
+    #[cfg(not(any(
+        esp_idf_version_major = "4",
+        all(
+            esp_idf_version_major = "5",
+            any(
+                esp_idf_version_minor = "0",
+                esp_idf_version_minor = "1",
+                esp_idf_version_minor = "2",
+                esp_idf_version_minor = "3",
+                esp_idf_version_minor = "4",
+                esp_idf_version_minor = "5"
+            )
+        )
+    )))]
+    compile_error!(
+        "unknown ESP-IDF version, build the standard library with \
+        `--cfg esp_idf_version_major=\"<major>\" --cfg esp_idf_version_minor=\"<minor>\"`"
+    );
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub struct esp_pthread_cfg_t {
+        pub stack_size: libc::size_t,
+        pub prio: libc::size_t,
+        pub inherit_cfg: bool,
+        pub thread_name: *const c_char,
+        pub pin_to_core: c_int,
+        // Only present since ESP-IDF v5.3.
+        #[cfg(not(any(
+            esp_idf_version_major = "4",
+            all(
+                esp_idf_version_major = "5",
+                any(
+                    esp_idf_version_minor = "0",
+                    esp_idf_version_minor = "1",
+                    esp_idf_version_minor = "2"
+                )
+            )
+        )))]
+        pub stack_alloc_caps: u32,
+    }
+
+    extern "C" {
+        pub fn esp_pthread_get_default_config() -> esp_pthread_cfg_t;
+        pub fn esp_pthread_get_cfg(cfg: *mut esp_pthread_cfg_t) -> esp_err_t;
+        pub fn esp_pthread_set_cfg(cfg: *const esp_pthread_cfg_t) -> esp_err_t;
+    }
+}
+
+/// FreeRTOS task options for a thread spawned on ESP-IDF, set through
+/// `std::os::espidf::thread::BuilderExt`. `None` keeps the ESP-IDF default.
+#[cfg(target_os = "espidf")]
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TaskConfig {
+    pub core: Option<usize>,
+    pub priority: Option<u8>,
+    pub stack_in_psram: Option<bool>,
+}
+
 #[cfg(target_os = "fuchsia")]
 mod zircon {
     type zx_handle_t = u32;
@@ -108,6 +183,87 @@ extern "C" fn thread_start(main: *mut libc::c_void) -> *mut libc::c_void {
         }
     }
 
+    // unsafe: see thread::Builder::spawn_unchecked for safety requirements
+    #[cfg(target_os = "espidf")]
+    pub unsafe fn new_with_config(
+        stack: usize,
+        config: &TaskConfig,
+        p: Box<dyn FnOnce()>,
+    ) -> io::Result<Thread> {
+        use esp_pthread::*;
+
+        if config.core.is_none() && config.priority.is_none() && config.stack_in_psram.is_none() {
+            return Thread::new(stack, p);
+        }
+
+        // ESP-IDF's `pthread_create` takes the task options from a configuration stored
+        // for the calling thread by `esp_pthread_set_cfg`. Restore the previous one once
+        // the thread is created, so that threads spawned later without options are
+        // unaffected.
+        let mut previous: esp_pthread_cfg_t = mem::zeroed();
+        let had_previous = esp_pthread_get_cfg(&mut previous) == ESP_OK;
+
+        let mut cfg = esp_pthread_get_default_config();
+        // Options are per thread, so threads spawned by the new thread must not pick
+        // them up.
+        cfg.inherit_cfg = false;
+        if let Some(core) = config.core {
+            cfg.pin_to_core = core as libc::c_int;
+        }
+        if let Some(priority) = config.priority {
+            cfg.prio = priority as libc::size_t;
+        }
+        if let Some(psram) = config.stack_in_psram {
+            #[cfg(not(any(
+                esp_idf_version_major = "4",
+                all(
+                    esp_idf_version_major = "5",
+                    any(
+                        esp_idf_version_minor = "0",
+                        esp_idf_version_minor = "1",
+                        esp_idf_version_minor = "2"
+                    )
+                )
+            )))]
+            {
+                let memory = if psram { MALLOC_CAP_SPIRAM } else { MALLOC_CAP_INTERNAL };
+                cfg.stack_alloc_caps = memory | MALLOC_CAP_8BIT;
+            }
+            #[cfg(any(
+                esp_idf_version_major = "4",
+                all(
+                    esp_idf_version_major = "5",
+                    any(
+                        esp_idf_version_minor = "0",
+                        esp_idf_version_minor = "1",
+                        esp_idf_version_minor = "2"
+                    )
+                )
+            ))]
+            {
+                let _ = psram;
+                return Err(io::const_io_error!(
+                    io::ErrorKind::Unsupported,
+                    "placing thread stacks in PSRAM requires ESP-IDF v5.3 or later",
+                ));
+            }
+        }
+        if esp_pthread_set_cfg(&cfg) != ESP_OK {
+            return Err(io::const_io_error!(
+                io::ErrorKind::InvalidInput,
+                "invalid ESP-IDF thread options",
+            ));
+        }
+
+        let ret = Thread::new(stack, p);
+        // There is no way to remove the configuration of a thread again. Without one,
+        // `pthread_create` uses the default configuration, so setting that is equivalent.
+        let restore = if had_previous { previous } else { esp_pthread_get_default_config() };
+        let res = esp_pthread_set_cfg(&restore);
+        debug_assert_eq!(res, ESP_OK);
+        ret
+    }
+
     pub fn yield_now() {
         let ret = unsafe { libc::sched_yield() };
         debug_assert_eq!(ret, 0);
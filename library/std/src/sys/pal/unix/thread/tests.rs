@@ -0,0 +1,52 @@
+use super::esp_pthread::*;
+use crate::ffi::c_void;
+use crate::mem;
+use crate::os::espidf::thread::BuilderExt;
+use crate::thread;
+
+extern "C" {
+    fn uxTaskPriorityGet(task: *mut c_void) -> u32;
+}
+
+fn current_priority() -> u32 {
+    unsafe { uxTaskPriorityGet(crate::ptr::null_mut()) }
+}
+
+fn current_cfg() -> Option<esp_pthread_cfg_t> {
+    let mut cfg: esp_pthread_cfg_t = unsafe { mem::zeroed() };
+    (unsafe { esp_pthread_get_cfg(&mut cfg) } == ESP_OK).then_some(cfg)
+}
+
+#[test]
+fn options_apply_to_spawned_thread_only() {
+    let mut previous = unsafe { esp_pthread_get_default_config() };
+    previous.prio = 7;
+    assert_eq!(unsafe { esp_pthread_set_cfg(&previous) }, ESP_OK);
+
+    let priority = thread::Builder::new().priority(10).spawn(current_priority).unwrap();
+    assert_eq!(priority.join().unwrap(), 10);
+
+    // The configuration of this thread is back to the one set above, so threads spawned
+    // without options use it again.
+    assert_eq!(current_cfg().unwrap().prio, 7);
+    let priority = thread::spawn(current_priority);
+    assert_eq!(priority.join().unwrap(), 7);
+}
+
+#[test]
+fn options_without_previous_cfg() {
+    // Threads spawned by std don't inherit a configuration, so this one has none until it
+    // spawns a thread with options.
+    thread::spawn(|| {
+        assert!(current_cfg().is_none());
+        let priority = thread::Builder::new().priority(10).spawn(current_priority).unwrap();
+        assert_eq!(priority.join().unwrap(), 10);
+
+        let default = unsafe { esp_pthread_get_default_config() };
+        let cfg = current_cfg().unwrap();
+        assert_eq!(cfg.prio, default.prio);
+        assert_eq!(cfg.pin_to_core, default.pin_to_core);
+    })
+    .join()
+    .unwrap();
+}
@@ -251,6 +251,9 @@ pub struct Builder {
     name: Option<String>,
     // The size of the stack for the spawned thread in bytes
     stack_size: Option<usize>,
+    // The FreeRTOS task options set through `std::os::espidf::thread::BuilderExt`
+    #[cfg(target_os = "espidf")]
+    task_config: imp::TaskConfig,
 }
 
 impl Builder {
@@ -274,7 +277,12 @@ impl Builder {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> Builder {
-        Builder { name: None, stack_size: None }
+        Builder {
+            name: None,
+            stack_size: None,
+            #[cfg(target_os = "espidf")]
+            task_config: imp::TaskConfig::default(),
+        }
     }
 
     /// Names the thread-to-be. Currently the name is used for identification
@@ -452,7 +460,12 @@ unsafe fn spawn_unchecked_<'scope, F, T>(
         F: Send,
         T: Send,
     {
-        let Builder { name, stack_size } = self;
+        let Builder {
+            name,
+            stack_size,
+            #[cfg(target_os = "espidf")]
+            task_config,
+        } = self;
 
         let stack_size = stack_size.unwrap_or_else(|| {
             static MIN: AtomicUsize = AtomicUsize::new(0);
@@ -558,13 +571,19 @@ fn drop(&mut self) {
             // Similarly, the `sys` implementation must guarantee that no references to the closure
             // exist after the thread has terminated, which is signaled by `Thread::join`
             // returning.
+            #[cfg(not(target_os = "espidf"))]
             native: unsafe { imp::Thread::new(stack_size, main)? },
+            #[cfg(target_os = "espidf")]
+            native: unsafe { imp::Thread::new_with_config(stack_size, &task_config, main)? },
             thread: my_thread,
             packet: my_packet,
         })
     }
 }
 
+#[unstable(feature = "sealed", issue = "none")]
+impl crate::sealed::Sealed for Builder {}
+
 ////////////////////////////////////////////////////////////////////////////////
 // Free functions
 ////////////////////////////////////////////////////////////////////////////////
@@ -910,6 +929,7 @@ pub fn sleep_ms(ms: u32) {
 /// assert!(now.elapsed() >= ten_millis);
 /// ```
 #[stable(feature = "thread_sleep", since = "1.4.0")]
+#[cfg_attr(not(bootstrap), may_block)]
 pub fn sleep(dur: Duration) {
     imp::Thread::sleep(dur)
 }
@@ -1103,6 +1123,7 @@ fn drop(&mut self) {
 /// [`thread::park_timeout`]: park_timeout
 /// [release sequence]: https://en.cppreference.com/w/cpp/atomic/memory_order#Release_sequence
 #[stable(feature = "rust1", since = "1.0.0")]
+#[cfg_attr(not(bootstrap), may_block)]
 pub fn park() {
     let guard = PanicGuard;
     // SAFETY: park_timeout is called on the parker owned by this thread.
@@ -1780,6 +1801,13 @@ pub fn is_finished(&self) -> bool {
     }
 }
 
+#[cfg(target_os = "espidf")]
+impl crate::sys_common::AsInnerMut<imp::TaskConfig> for Builder {
+    fn as_inner_mut(&mut self) -> &mut imp::TaskConfig {
+        &mut self.task_config
+    }
+}
+
 impl<T> AsInner<imp::Thread> for JoinHandle<T> {
     fn as_inner(&self) -> &imp::Thread {
         &self.0.native
@@ -1398,6 +1398,8 @@ fn run(self, builder: &Builder<'_>) {
 
 default_test!(Crashes { path: "tests/crashes", mode: "crashes", suite: "crashes" });
 
+default_test!(BuildOnly { path: "tests/build-only", mode: "build-only", suite: "build-only" });
+
 default_test!(RunPassValgrind {
     path: "tests/run-pass-valgrind",
     mode: "run-pass-valgrind",
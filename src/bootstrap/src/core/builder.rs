@@ -319,6 +319,7 @@ pub fn assert_single_path(&self) -> &TaskPath {
         &[
             // tidy-alphabetical-start
             "tests/assembly",
+            "tests/build-only",
             "tests/codegen",
             "tests/codegen-units",
             "tests/coverage",
@@ -861,6 +862,7 @@ macro_rules! describe {
                 test::Codegen,
                 test::CodegenUnits,
                 test::Assembly,
+                test::BuildOnly,
                 test::Incremental,
                 test::Debuginfo,
                 test::UiFullDeps,
@@ -2071,6 +2073,13 @@ fn cargo(
             rustflags.arg("-Zehcont-guard");
         }
 
+        // The interrupt handlers of ESP-IDF programs are checked with `#[interrupt_context]`,
+        // which needs to know which functions of the standard library may block.
+        // This is not needed for stage 0 artifacts, as for the flags above.
+        if mode == Mode::Std && target.contains("espidf") && compiler.stage >= 1 {
+            rustflags.arg("-Zinterrupt-context-summaries");
+        }
+
         // For `cargo doc` invocations, make rustdoc print the Rust version into the docs
         // This replaces spaces with tabs because RUSTDOCFLAGS does not
         // support arguments with regular spaces. Hopefully someday Cargo will
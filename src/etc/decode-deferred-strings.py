@@ -0,0 +1,285 @@
+#!/usr/bin/env python3
+
+"""
+Decode the output of a program built with `-Z deferred-strings`.
+
+The format strings of the panic and log messages of such a program, and the file names of panic
+locations, are replaced by references into the `.rust_deferred_strings` section of its ELF file,
+which is not loaded on the target. This reads the table from the ELF file and copies the standard
+input to the standard output, replacing the references by the strings and formatting the arguments
+of the messages.
+
+Usage: decode-deferred-strings.py <ELF file> < output.txt
+
+A reference is the ASCII unit separator (0x1f) followed by the 16 hex digits of the hash of the
+string. An entry of the table is the 64-bit hash in little endian, the length of the string as
+ULEB128, and the string.
+
+The format strings of messages with arguments are referenced with the ASCII file separator (0x1c)
+instead. Each argument follows as the record separator (0x1e), a tag and the value, up to the next
+record separator or the group separator (0x1d) that ends the message. The values of integers (`u` and `i`), floats
+(`f`), `bool` (`b`) and `char` (`c`) are their raw bytes in little endian as hex digits. Other
+values (`s`) were formatted on the target and are text, which can contain nested messages.
+"""
+
+import decimal
+import re
+import struct
+import sys
+
+SECTION = b".rust_deferred_strings"
+HASH = re.compile(rb"[0-9a-f]{16}")
+PLACEHOLDER = re.compile(r"\{\{|\}\}|\{(\d+)(?::([^}]*))?\}")
+SPEC = re.compile(r"^(?:(.)?([<^>]))?([+-])?(#)?(0)?(\d+)?(?:\.(\d+))?(x\?|X\?|[?eEopbxX])?$")
+
+
+def read_sections(data):
+    """Yields the name and contents of each section of an ELF file."""
+    if data[:4] != b"\x7fELF":
+        raise ValueError("not an ELF file")
+    is_64 = data[4] == 2
+    endian = "<" if data[5] == 1 else ">"
+    if is_64:
+        shoff, = struct.unpack_from(endian + "Q", data, 0x28)
+        shentsize, shnum, shstrndx = struct.unpack_from(endian + "HHH", data, 0x3a)
+        header = endian + "IIQQQQIIQQ"
+    else:
+        shoff, = struct.unpack_from(endian + "I", data, 0x20)
+        shentsize, shnum, shstrndx = struct.unpack_from(endian + "HHH", data, 0x2e)
+        header = endian + "IIIIIIIIII"
+
+    headers = [struct.unpack_from(header, data, shoff + i * shentsize) for i in range(shnum)]
+    names = headers[shstrndx]
+    for name, _, _, _, offset, size, _, _, _, _ in headers:
+        start = names[4] + name
+        name = data[start:data.index(b"\0", start)]
+        yield name, data[offset:offset + size]
+
+
+def read_strings(path):
+    """Reads the table of deferred strings from an ELF file."""
+    with open(path, "rb") as f:
+        data = f.read()
+
+    strings = {}
+    for name, table in read_sections(data):
+        if name != SECTION:
+            continue
+        offset = 0
+        while offset + 8 <= len(table):
+            hash, = struct.unpack_from("<Q", table, offset)
+            offset += 8
+            length = 0
+            shift = 0
+            while True:
+                byte = table[offset]
+                offset += 1
+                length |= (byte & 0x7f) << shift
+                shift += 7
+                if byte & 0x80 == 0:
+                    break
+            strings[b"%016x" % hash] = table[offset:offset + length]
+            offset += length
+    return strings
+
+
+class Incomplete(Exception):
+    """The input ends in the middle of a message."""
+
+
+def decode_value(tag, payload):
+    """Decodes the raw bytes of an argument."""
+    data = bytes.fromhex(payload.decode())
+    if tag == "u":
+        return int.from_bytes(data, "little"), 8 * len(data)
+    if tag == "i":
+        return int.from_bytes(data, "little", signed=True), 8 * len(data)
+    if tag == "f":
+        return struct.unpack("<f" if len(data) == 4 else "<d", data)[0], 8 * len(data)
+    if tag == "b":
+        return data[0] != 0, 8
+    if tag == "c":
+        return chr(int.from_bytes(data, "little")), 32
+    raise ValueError("unknown argument tag {!r}".format(tag))
+
+
+def shortest_float(value):
+    """Returns the digits and exponent of the shortest representation of a finite float."""
+    sign, digits, exponent = decimal.Decimal(repr(abs(value))).normalize().as_tuple()
+    return "".join(map(str, digits)), exponent
+
+
+def format_float(value, kind, precision):
+    """Formats a float like Rust does, without padding."""
+    if value != value:
+        return "NaN"
+    if value in (float("inf"), float("-inf")):
+        return "inf" if value > 0 else "-inf"
+    sign = "-" if value < 0 or (value == 0 and str(value).startswith("-")) else ""
+    if kind in ("e", "E"):
+        if precision is None:
+            digits, exponent = shortest_float(value)
+            exponent += len(digits) - 1
+            mantissa = digits[0] + ("." + digits[1:] if len(digits) > 1 else "")
+        else:
+            mantissa, exponent = "{:.{}e}".format(abs(value), precision).split("e")
+            exponent = int(exponent)
+        return sign + mantissa + kind + str(exponent)
+    if precision is not None:
+        return sign + "{:.{}f}".format(abs(value), precision)
+    digits, exponent = shortest_float(value)
+    if kind == "?" and value != 0 and not 1e-5 <= abs(value) < 1e16:
+        exponent += len(digits) - 1
+        mantissa = digits[0] + ("." + digits[1:] if len(digits) > 1 else "")
+        return sign + mantissa + "e" + str(exponent)
+    text = format(decimal.Decimal(repr(abs(value))), "f")
+    if "." in text:
+        text = text.rstrip("0").rstrip(".")
+    if kind == "?" and "." not in text:
+        text += ".0"
+    return sign + text
+
+
+def format_argument(tag, value, bits, spec):
+    """Formats a decoded argument with a format spec, like Rust does."""
+    match = SPEC.match(spec)
+    if not match:
+        raise ValueError("unsupported format spec {!r}".format(spec))
+    fill, align, sign, alternate, zero, width, precision, kind = match.groups()
+    kind = {"x?": "x", "X?": "X"}.get(kind, kind or "")
+    precision = None if precision is None else int(precision)
+    numeric = tag in "uif"
+
+    prefix = ""
+    if tag == "s":
+        text = value if precision is None else value[:precision]
+    elif tag in "ui" and kind in ("x", "X", "o", "b"):
+        base = {"x": "x", "X": "X", "o": "o", "b": "b"}[kind]
+        text = format(value % (1 << bits), base)
+        prefix = {"x": "0x", "X": "0x", "o": "0o", "b": "0b"}[kind] if alternate else ""
+    elif tag in "ui" and kind in ("e", "E"):
+        text = format_float(float(value), kind, precision)
+    elif tag in "ui":
+        text = str(value)
+    elif tag == "f":
+        text = format_float(value, kind, precision)
+    elif tag == "b":
+        text = "true" if value else "false"
+    elif kind == "?":
+        text = repr(value) if value not in "\\'" else "'\\" + value + "'"
+    else:
+        text = value
+
+    if numeric:
+        if text.startswith("-"):
+            prefix, text = "-" + prefix, text[1:]
+        elif sign == "+":
+            prefix = "+" + prefix
+
+    width = int(width or 0)
+    if zero and numeric:
+        return prefix + text.rjust(width - len(prefix), "0")
+    text = prefix + text
+    padding = width - len(text)
+    if padding <= 0:
+        return text
+    fill = fill or " "
+    align = align or (">" if numeric else "<")
+    if align == "<":
+        return text + fill * padding
+    if align == ">":
+        return fill * padding + text
+    return fill * (padding // 2) + text + fill * (padding - padding // 2)
+
+
+class Decoder:
+    def __init__(self, strings):
+        self.strings = strings
+
+    def decode(self, data, pos, nested):
+        """Decodes `data` from `pos`, up to the end of the enclosing argument if `nested`.
+
+        Returns the decoded text and the position where decoding stopped.
+        """
+        out = []
+        while pos < len(data):
+            byte = data[pos:pos + 1]
+            if nested and byte in (b"\x1e", b"\x1d"):
+                break
+            if byte in (b"\x1c", b"\x1f"):
+                text, pos = self.message(data, pos)
+                out.append(text)
+            else:
+                out.append(byte)
+                pos += 1
+        else:
+            if nested:
+                raise Incomplete()
+        return b"".join(out), pos
+
+    def message(self, data, pos):
+        """Decodes the reference at `pos`, and the arguments following it if it has any."""
+        if len(data) < pos + 17:
+            raise Incomplete()
+        hash = data[pos + 1:pos + 17]
+        string = self.strings.get(hash) if HASH.fullmatch(hash) else None
+        if string is None:
+            return data[pos:pos + 1], pos + 1
+        has_arguments = data[pos:pos + 1] == b"\x1c"
+        pos += 17
+        if not has_arguments:
+            return string, pos
+
+        args = []
+        while data[pos:pos + 1] == b"\x1e":
+            if pos + 1 >= len(data):
+                raise Incomplete()
+            tag = chr(data[pos + 1])
+            if tag == "s":
+                text, pos = self.decode(data, pos + 2, nested=True)
+                args.append(("s", text.decode(errors="replace"), 0))
+            else:
+                end = pos + 2
+                while end < len(data) and data[end:end + 1] not in (b"\x1e", b"\x1d"):
+                    end += 1
+                if end == len(data):
+                    raise Incomplete()
+                value, bits = decode_value(tag, data[pos + 2:end])
+                args.append((tag, value, bits))
+                pos = end
+        if pos == len(data):
+            raise Incomplete()
+        # Skip the group separator ending the message.
+        pos += 1
+
+        def replace(placeholder):
+            if placeholder.group(0) in ("{{", "}}"):
+                return placeholder.group(0)[0]
+            tag, value, bits = args[int(placeholder.group(1))]
+            return format_argument(tag, value, bits, placeholder.group(2) or "")
+
+        template = string.decode(errors="replace")
+        return PLACEHOLDER.sub(replace, template).encode(), pos
+
+
+def main():
+    if len(sys.argv) != 2:
+        sys.exit(__doc__)
+    decoder = Decoder(read_strings(sys.argv[1]))
+
+    # Messages can span lines, so incomplete ones are kept until the rest of them is read.
+    pending = b""
+    for line in sys.stdin.buffer:
+        pending += line
+        try:
+            text, _ = decoder.decode(pending, 0, nested=False)
+        except Incomplete:
+            continue
+        sys.stdout.buffer.write(text)
+        sys.stdout.buffer.flush()
+        pending = b""
+    sys.stdout.buffer.write(pending)
+
+
+if __name__ == "__main__":
+    main()
@@ -69,6 +69,7 @@ pub enum Mode {
         CoverageMap => "coverage-map",
         CoverageRun => "coverage-run",
         Crashes => "crashes",
+        BuildOnly => "build-only",
     }
 }
 
@@ -22,10 +22,11 @@
 
 use crate::common::{
     expected_output_path, incremental_dir, output_base_dir, output_base_name,
-    output_testname_unique, Assembly, Codegen, CodegenUnits, CompareMode, Config, CoverageMap,
-    CoverageRun, Crashes, DebugInfo, Debugger, FailMode, Incremental, JsDocTest, MirOpt, PassMode,
-    Pretty, RunMake, RunPassValgrind, Rustdoc, RustdocJson, TestPaths, Ui, UI_EXTENSIONS, UI_FIXED,
-    UI_RUN_STDERR, UI_RUN_STDOUT, UI_STDERR, UI_STDOUT, UI_SVG, UI_WINDOWS_SVG,
+    output_testname_unique, Assembly, BuildOnly, Codegen, CodegenUnits, CompareMode, Config,
+    CoverageMap, CoverageRun, Crashes, DebugInfo, Debugger, FailMode, Incremental, JsDocTest,
+    MirOpt, PassMode, Pretty, RunMake, RunPassValgrind, Rustdoc, RustdocJson, TestPaths, Ui,
+    UI_EXTENSIONS, UI_FIXED, UI_RUN_STDERR, UI_RUN_STDOUT, UI_STDERR, UI_STDOUT, UI_SVG,
+    UI_WINDOWS_SVG,
 };
 use crate::compute_diff::{write_diff, write_filtered_diff};
 use crate::errors::{self, Error, ErrorKind};
@@ -255,6 +256,7 @@ fn run_revision(&self) {
             CoverageMap => self.run_coverage_map_test(), // see self::coverage
             CoverageRun => self.run_coverage_run_test(), // see self::coverage
             Crashes => self.run_crash_test(),
+            BuildOnly => self.run_build_only_test(),
         }
     }
 
@@ -374,6 +376,16 @@ fn run_crash_test(&self) {
         }
     }
 
+    fn run_build_only_test(&self) {
+        // The targets these tests are for can't run anything on the host, so only check
+        // that the test compiles, codegens and (for binaries) links.
+        let proc_res = self.compile_test(WillExecute::No, Emit::None);
+
+        if !proc_res.status.success() {
+            self.fatal_proc_rec("compilation failed!", &proc_res);
+        }
+    }
+
     fn run_rfail_test(&self) {
         let pm = self.pass_mode();
         let should_run = self.run_if_enabled();
@@ -2213,7 +2225,7 @@ fn make_compile_args(
                 set_mir_dump_dir(&mut rustc);
             }
             RunPassValgrind | Pretty | DebugInfo | Rustdoc | RustdocJson | RunMake
-            | CodegenUnits | JsDocTest => {
+            | CodegenUnits | JsDocTest | BuildOnly => {
                 // do not use JSON output
             }
         }
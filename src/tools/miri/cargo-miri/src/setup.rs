@@ -136,7 +136,17 @@ pub fn setup(
     // But keep the overflow checks, they are cheap. This completely overwrites flags
     // the user might have set, which is consistent with normal `cargo build` that does
     // not apply `RUSTFLAGS` to the sysroot either.
-    let rustflags = &["-Cdebug-assertions=off", "-Coverflow-checks=on"];
+    let mut rustflags = vec!["-Cdebug-assertions=off", "-Coverflow-checks=on"];
+    if target.contains("espidf") {
+        // The standard library needs to know the ESP-IDF version for the layout of some of its
+        // structs. Miri's shims follow the latest one.
+        rustflags.extend([
+            "--cfg",
+            "esp_idf_version_major=\"5\"",
+            "--cfg",
+            "esp_idf_version_minor=\"3\"",
+        ]);
+    }
 
     let mut after_build_output = String::new(); // what should be printed when the build is done.
     let notify = || {
@@ -165,7 +175,7 @@ pub fn setup(
         .build_mode(BuildMode::Check)
         .rustc_version(rustc_version.clone())
         .sysroot_config(sysroot_config)
-        .rustflags(rustflags)
+        .rustflags(&rustflags[..])
         .cargo(cargo_cmd)
         .when_build_required(notify)
         .build_from_source(&rust_src);
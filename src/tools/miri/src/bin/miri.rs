@@ -601,6 +601,18 @@ fn main() {
         } else if let Some(param) = arg.strip_prefix("-Zmiri-preemption-rate=") {
             miri_config.preemption_rate =
                 parse_rate(param).unwrap_or_else(|err| show_error!("-Zmiri-preemption-rate {err}"));
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-record-schedule=") {
+            miri_config.record_schedule = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-replay-schedule=") {
+            if !std::path::Path::new(param).exists() {
+                show_error!("-Zmiri-replay-schedule `{}` does not exist", param);
+            }
+            miri_config.replay_schedule = Some(param.into());
+        } else if let Some(param) = arg.strip_prefix("-Zmiri-explore-schedules=") {
+            let bound = param.parse::<u32>().unwrap_or_else(|err| {
+                show_error!("-Zmiri-explore-schedules requires a `u32`: {}", err)
+            });
+            miri_config.explore_schedules = Some(bound);
         } else if arg == "-Zmiri-report-progress" {
             // This makes it take a few seconds between progress reports on my laptop.
             miri_config.report_progress = Some(1_000_000);
@@ -674,6 +686,11 @@ fn main() {
         );
     }
 
+    // Exploration and replay would both decide where threads get preempted.
+    if miri_config.explore_schedules.is_some() && miri_config.replay_schedule.is_some() {
+        show_error!("-Zmiri-explore-schedules cannot be used together with -Zmiri-replay-schedule");
+    }
+
     debug!("rustc arguments: {:?}", rustc_args);
     debug!("crate arguments: {:?}", miri_config.args);
     run_compiler(
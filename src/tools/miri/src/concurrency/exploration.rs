@@ -0,0 +1,174 @@
+//! Systematic exploration of thread interleavings.
+//!
+//! With `-Zmiri-explore-schedules=<bound>`, Miri runs the program over and over, each time with a
+//! different schedule, until every schedule with at most `<bound>` preemptions has been tried or an
+//! execution fails. This is the iterative context bounding approach of CHESS: most concurrency bugs
+//! only need very few preemptions to show up, and bounding them keeps the number of schedules
+//! polynomial in the length of the execution.
+//!
+//! Threads can only be preempted at *preemption points*: right after an atomic operation or fence,
+//! and right after spawning or unblocking a thread. Those are the only operations through which
+//! threads can observe each other without a data race, and the data race detector reports races
+//! between non-atomic accesses regardless of the order in which the threads perform them. Switches
+//! at points where the active thread blocks or terminates are explored as well, and do not count
+//! towards the bound. Explicit yields switch to the next thread round-robin, without branching.
+//!
+//! The exploration is a depth-first search over the decisions of the scheduler. Every execution
+//! replays the decisions of the previous one up to the last decision that still has an untried
+//! alternative, takes that alternative, and from then on makes the default decision: keep running
+//! the active thread, or switch to the next thread round-robin if it cannot run.
+
+use crate::*;
+
+#[derive(Debug)]
+struct Decision {
+    /// The threads that could run next, in the order they are tried. At preemption points, the
+    /// first one is the active thread.
+    options: Vec<ThreadId>,
+    /// Index into `options` of the thread that runs next.
+    chosen: usize,
+    /// Whether running any thread but the first one preempts the active thread.
+    preemptive: bool,
+    /// The number of preemptions made before this decision.
+    preemptions: u32,
+}
+
+#[derive(Debug)]
+pub struct Exploration {
+    /// The maximum number of preemptions per execution.
+    bound: u32,
+    /// The decisions made so far by the current execution, followed by the decisions it still
+    /// has to replay from the previous one.
+    decisions: Vec<Decision>,
+    /// Index of the next decision of the current execution.
+    pos: usize,
+    /// The number of preemptions made so far by the current execution.
+    preemptions: u32,
+    /// The number of executions that have been started.
+    executions: u64,
+}
+
+impl Exploration {
+    pub fn new(bound: u32) -> Self {
+        Exploration { bound, decisions: Vec::new(), pos: 0, preemptions: 0, executions: 1 }
+    }
+
+    /// The number of the current execution, starting at 1.
+    pub fn execution(&self) -> u64 {
+        self.executions
+    }
+
+    /// The number of preemptions made by the current execution.
+    pub fn preemptions(&self) -> u32 {
+        self.preemptions
+    }
+
+    /// Picks one of `options` to run next. If `preemptive` is set, the first option is the active
+    /// thread and picking any other option preempts it.
+    pub fn decide<'tcx>(
+        &mut self,
+        options: Vec<ThreadId>,
+        preemptive: bool,
+    ) -> InterpResult<'tcx, ThreadId> {
+        assert!(options.len() > 1, "there is nothing to decide");
+        let chosen = match self.decisions.get(self.pos) {
+            Some(decision) => {
+                // We have been here before, follow the path of the previous execution.
+                if decision.options != options || decision.preemptive != preemptive {
+                    throw_machine_stop!(TerminationInfo::Abort(format!(
+                        "schedule exploration found that the program behaves non-deterministically: \
+                        execution {} reached a different scheduling decision than the one before",
+                        self.executions
+                    )));
+                }
+                decision.chosen
+            }
+            None => {
+                self.decisions.push(Decision {
+                    options,
+                    chosen: 0,
+                    preemptive,
+                    preemptions: self.preemptions,
+                });
+                0
+            }
+        };
+        let decision = &self.decisions[self.pos];
+        self.pos += 1;
+        if decision.preemptive && chosen != 0 {
+            self.preemptions += 1;
+        }
+        Ok(decision.options[chosen])
+    }
+
+    /// Prepares the next execution. Returns `false` if all schedules within the bound have been
+    /// explored.
+    pub fn next_execution(&mut self) -> bool {
+        self.decisions.truncate(self.pos);
+        self.pos = 0;
+        self.preemptions = 0;
+        while let Some(decision) = self.decisions.last_mut() {
+            let next = decision.chosen + 1;
+            if next < decision.options.len()
+                && (!decision.preemptive || decision.preemptions < self.bound)
+            {
+                decision.chosen = next;
+                self.executions += 1;
+                return true;
+            }
+            self.decisions.pop();
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn threads(ids: &[u32]) -> Vec<ThreadId> {
+        ids.iter().map(|&id| ThreadId::from(id)).collect()
+    }
+
+    /// Runs a program with two preemption points on thread 0, each of which can switch to
+    /// thread 1, and returns the thread picked at each point for every execution.
+    fn explore(bound: u32) -> Vec<Vec<u32>> {
+        let mut exploration = Exploration::new(bound);
+        let mut executions = vec![];
+        loop {
+            let mut picks = vec![];
+            for _ in 0..2 {
+                let pick = exploration.decide(threads(&[0, 1]), true).unwrap();
+                picks.push(pick.to_u32());
+                if pick != ThreadId::MAIN_THREAD {
+                    break;
+                }
+            }
+            executions.push(picks);
+            if !exploration.next_execution() {
+                return executions;
+            }
+        }
+    }
+
+    #[test]
+    fn preemption_bound() {
+        assert_eq!(explore(0), [vec![0, 0]]);
+        assert_eq!(explore(1), [vec![0, 0], vec![0, 1], vec![1]]);
+    }
+
+    #[test]
+    fn forced_switches_are_free() {
+        let mut exploration = Exploration::new(0);
+        let mut picks = vec![];
+        loop {
+            picks.push(exploration.decide(threads(&[1, 2, 3]), false).unwrap().to_u32());
+            assert_eq!(exploration.preemptions(), 0);
+            if !exploration.next_execution() {
+                break;
+            }
+        }
+        assert_eq!(picks, [1, 2, 3]);
+        assert_eq!(exploration.execution(), 3);
+    }
+}
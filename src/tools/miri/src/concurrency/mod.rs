@@ -1,7 +1,9 @@
 pub mod cpu_affinity;
 pub mod data_race;
+pub mod exploration;
 pub mod init_once;
 mod range_object_map;
+pub mod schedule_trace;
 pub mod sync;
 pub mod thread;
 mod vector_clock;
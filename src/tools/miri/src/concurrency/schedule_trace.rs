@@ -0,0 +1,319 @@
+//! Recording and replaying of thread schedules.
+//!
+//! With `-Zmiri-record-schedule=<file>`, every point where the scheduler switches from one thread
+//! to another is written to a trace file, one switch per line:
+//!
+//! ```text
+//! <thread> <step> <reason> <next thread>
+//! ```
+//!
+//! Switches are keyed by the thread that was active and the number of steps that thread has
+//! executed so far, rather than by a global step count, so that a change to one thread's code does
+//! not shift the switch points of all the other threads.
+//!
+//! With `-Zmiri-replay-schedule=<file>`, random preemption is disabled and the scheduler instead
+//! preempts threads exactly where the trace says so, and switches to the thread the trace names.
+//! If the execution reaches a scheduling decision that does not agree with the trace, evaluation
+//! stops with a diff of the expected and actual switches. Once all switches of the trace have been
+//! replayed, the scheduler falls back to its regular round-robin policy (without preemption).
+
+use std::fmt;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use rustc_index::IndexVec;
+
+use crate::*;
+
+/// How many replayed switches to show before the divergence.
+const DIFF_CONTEXT: usize = 3;
+
+/// Why the active thread stopped running.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SwitchReason {
+    /// The thread yielded or was preempted.
+    Yield,
+    /// The thread blocked.
+    Blocked,
+    /// The thread terminated.
+    Terminated,
+}
+
+impl fmt::Display for SwitchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SwitchReason::Yield => "yield",
+            SwitchReason::Blocked => "blocked",
+            SwitchReason::Terminated => "terminated",
+        })
+    }
+}
+
+impl FromStr for SwitchReason {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        match s {
+            "yield" => Ok(SwitchReason::Yield),
+            "blocked" => Ok(SwitchReason::Blocked),
+            "terminated" => Ok(SwitchReason::Terminated),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A scheduling decision: after `step` steps, `thread` stopped running and `next` took over.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThreadSwitch {
+    pub thread: ThreadId,
+    pub step: u64,
+    pub reason: SwitchReason,
+    /// `None` if no other thread could run. Such decisions only show up in divergence reports,
+    /// they are never written to a trace.
+    pub next: Option<ThreadId>,
+}
+
+impl fmt::Display for ThreadSwitch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {} ", self.thread.to_u32(), self.step, self.reason)?;
+        match self.next {
+            Some(next) => write!(f, "{}", next.to_u32()),
+            None => f.write_str("none"),
+        }
+    }
+}
+
+impl FromStr for ThreadSwitch {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, ()> {
+        let mut fields = s.split_whitespace();
+        let mut field = || fields.next().ok_or(());
+        let thread = field()?.parse::<u32>().map_err(|_| ())?;
+        let step = field()?.parse::<u64>().map_err(|_| ())?;
+        let reason = field()?.parse::<SwitchReason>()?;
+        let next = field()?.parse::<u32>().map_err(|_| ())?;
+        if fields.next().is_some() {
+            return Err(());
+        }
+        Ok(ThreadSwitch { thread: thread.into(), step, reason, next: Some(next.into()) })
+    }
+}
+
+/// Parses the switches of a schedule trace, skipping empty lines and `#` comments.
+fn parse_trace(text: &str) -> Result<Vec<ThreadSwitch>, String> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(idx, line)| {
+            line.parse().map_err(|()| {
+                format!(
+                    "line {}: expected `<thread> <step> <reason> <next thread>`, found `{line}`",
+                    idx + 1
+                )
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Replay {
+    path: PathBuf,
+    switches: Vec<ThreadSwitch>,
+    /// Index of the next switch to replay.
+    pos: usize,
+}
+
+#[derive(Debug)]
+pub struct ScheduleTrace {
+    /// The number of steps each thread has executed so far.
+    steps: IndexVec<ThreadId, u64>,
+    /// The file switches are recorded to. This is not buffered, so that the trace is complete
+    /// even if Miri exits abruptly.
+    record: Option<(File, PathBuf)>,
+    replay: Option<Replay>,
+    /// The switches made so far.
+    switches: Vec<ThreadSwitch>,
+}
+
+impl ScheduleTrace {
+    /// Opens the trace files configured with `-Zmiri-record-schedule` and
+    /// `-Zmiri-replay-schedule`. Schedule exploration needs the switches to report a failing
+    /// schedule, so they are also traced when it is enabled. Returns `None` if there is nothing to
+    /// trace.
+    ///
+    /// This is called once per execution, so with schedule exploration every execution overwrites
+    /// the recorded trace, and the file ends up holding the schedule of the execution that reported
+    /// an error, or of the last one if none did.
+    pub fn new(config: &MiriConfig) -> Result<Option<Self>, String> {
+        if config.record_schedule.is_none()
+            && config.replay_schedule.is_none()
+            && config.explore_schedules.is_none()
+        {
+            return Ok(None);
+        }
+        let replay = config
+            .replay_schedule
+            .as_deref()
+            .map(|path| {
+                let text = std::fs::read_to_string(path).map_err(|err| {
+                    format!("failed to read schedule trace `{}`: {err}", path.display())
+                })?;
+                let switches = parse_trace(&text)
+                    .map_err(|err| format!("invalid schedule trace `{}`: {err}", path.display()))?;
+                Ok::<_, String>(Replay { path: path.to_owned(), switches, pos: 0 })
+            })
+            .transpose()?;
+        let record = config
+            .record_schedule
+            .as_deref()
+            .map(|path| {
+                let header = "# Miri schedule trace\n# <thread> <step> <reason> <next thread>\n";
+                let file = File::create(path).and_then(|mut file| {
+                    file.write_all(header.as_bytes())?;
+                    Ok(file)
+                });
+                let file = file.map_err(|err| {
+                    format!("failed to create schedule trace `{}`: {err}", path.display())
+                })?;
+                Ok::<_, String>((file, path.to_owned()))
+            })
+            .transpose()?;
+        Ok(Some(ScheduleTrace { steps: IndexVec::new(), record, replay, switches: Vec::new() }))
+    }
+
+    /// The switches made so far.
+    pub fn switches(&self) -> &[ThreadSwitch] {
+        &self.switches
+    }
+
+    /// Whether the scheduler follows a trace, rather than preempting threads randomly.
+    pub fn is_replaying(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    fn steps(&self, thread: ThreadId) -> u64 {
+        self.steps.get(thread).copied().unwrap_or(0)
+    }
+
+    /// Counts a step executed by `thread`.
+    pub fn count_step(&mut self, thread: ThreadId) {
+        let steps = self.steps.ensure_contains_elem(thread, || 0);
+        *steps = steps.strict_add(1);
+    }
+
+    /// Returns whether the trace preempts `thread` at its current step.
+    pub fn preempts(&self, thread: ThreadId) -> bool {
+        let Some(replay) = &self.replay else { return false };
+        replay.switches.get(replay.pos).is_some_and(|expected| {
+            expected.thread == thread
+                && expected.step == self.steps(thread)
+                && expected.reason == SwitchReason::Yield
+        })
+    }
+
+    /// Decides which thread runs after `thread` stopped running for `reason`. `regular_next` is the
+    /// thread the round-robin scheduler picked, `is_enabled` tells whether a thread can run.
+    /// When replaying, the trace overrides that choice; the switch that is eventually made is
+    /// recorded.
+    pub fn switch<'tcx>(
+        &mut self,
+        thread: ThreadId,
+        reason: SwitchReason,
+        regular_next: Option<ThreadId>,
+        is_enabled: impl Fn(ThreadId) -> bool,
+    ) -> InterpResult<'tcx, Option<ThreadId>> {
+        let step = self.steps(thread);
+        let mut next = regular_next;
+        if let Some(replay) = &mut self.replay {
+            if let Some(&expected) = replay.switches.get(replay.pos) {
+                let actual = ThreadSwitch { thread, step, reason, next: regular_next };
+                if expected.thread == thread && expected.step == step {
+                    let valid_next = expected.next.is_some_and(|id| id != thread && is_enabled(id));
+                    if expected.reason != reason || !valid_next {
+                        return Err(replay.diverged(actual).into());
+                    }
+                    next = expected.next;
+                    replay.pos = replay.pos.strict_add(1);
+                } else if regular_next.is_some()
+                    || (expected.thread == thread && expected.step < step)
+                {
+                    // Either the regular scheduler would switch where the trace does not, or this
+                    // thread ran past the point where the trace switches away from it.
+                    return Err(replay.diverged(actual).into());
+                }
+            }
+        }
+        if next.is_some() {
+            let switch = ThreadSwitch { thread, step, reason, next };
+            if let Some((file, path)) = &mut self.record {
+                if let Err(err) = writeln!(file, "{switch}") {
+                    throw_machine_stop!(TerminationInfo::Abort(format!(
+                        "failed to write schedule trace `{}`: {err}",
+                        path.display()
+                    )));
+                }
+            }
+            self.switches.push(switch);
+        }
+        Ok(next)
+    }
+}
+
+impl Replay {
+    /// Builds the error for a replay that reached `actual` instead of the next switch of the trace.
+    fn diverged<'tcx>(&self, actual: ThreadSwitch) -> InterpError<'tcx> {
+        let mut diff: Vec<String> = self.switches[self.pos.saturating_sub(DIFF_CONTEXT)..self.pos]
+            .iter()
+            .map(|switch| format!("  {switch}"))
+            .collect();
+        diff.push(format!("- {}", self.switches[self.pos]));
+        diff.push(format!("+ {actual}"));
+        err_machine_stop!(TerminationInfo::ScheduleReplayDiverged {
+            trace: self.path.clone(),
+            index: self.pos,
+            diff,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_print() {
+        let switches = parse_trace(
+            "# Miri schedule trace\n\n0 120 yield 1\n  1 7 blocked 0\n0 300 terminated 2\n",
+        )
+        .unwrap();
+        assert_eq!(switches.len(), 3);
+        assert_eq!(
+            switches[1],
+            ThreadSwitch {
+                thread: ThreadId::from(1),
+                step: 7,
+                reason: SwitchReason::Blocked,
+                next: Some(ThreadId::from(0)),
+            }
+        );
+        let printed: Vec<String> = switches.iter().map(ThreadSwitch::to_string).collect();
+        assert_eq!(printed, ["0 120 yield 1", "1 7 blocked 0", "0 300 terminated 2"]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse_trace("0 1 yield 1\n0 2 sleep 1").unwrap_err(),
+            "line 2: expected `<thread> <step> <reason> <next thread>`, found `0 2 sleep 1`"
+        );
+        assert!(parse_trace("0 1 yield").is_err());
+        assert!(parse_trace("0 1 yield 1 2").is_err());
+        assert!(parse_trace("0 -1 yield 1").is_err());
+        // Divergence reports write `none`, but a trace never switches to no thread.
+        assert!(parse_trace("0 1 yield none").is_err());
+    }
+}
@@ -18,6 +18,8 @@
 use rustc_target::spec::abi::Abi;
 
 use crate::concurrency::data_race;
+use crate::concurrency::exploration::Exploration;
+use crate::concurrency::schedule_trace::{ScheduleTrace, SwitchReason, ThreadSwitch};
 use crate::shims::tls;
 use crate::*;
 
@@ -174,6 +176,10 @@ pub enum BlockReason {
     InitOnce(InitOnceId),
     /// Blocked on epoll.
     Epoll,
+    /// Blocked on a socket of the virtual network.
+    Socket,
+    /// Blocked on a FreeRTOS queue, identified by the allocation of its storage.
+    FreeRtosQueue(AllocId),
 }
 
 /// The state of a thread.
@@ -462,6 +468,13 @@ pub struct ThreadManager<'tcx> {
     thread_local_allocs: FxHashMap<(DefId, ThreadId), StrictPointer>,
     /// A flag that indicates that we should change the active thread.
     yield_active_thread: bool,
+    /// The schedule being recorded or replayed, if any.
+    schedule_trace: Option<ScheduleTrace>,
+    /// The state of schedule exploration, if enabled.
+    exploration: Option<Exploration>,
+    /// Whether the active thread did something other threads can observe in its last step, so that
+    /// schedule exploration may preempt it.
+    preemption_point: bool,
 }
 
 impl VisitProvenance for ThreadManager<'_> {
@@ -471,6 +484,9 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             thread_local_allocs,
             active_thread: _,
             yield_active_thread: _,
+            schedule_trace: _,
+            exploration: _,
+            preemption_point: _,
         } = self;
 
         for thread in threads {
@@ -482,8 +498,8 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
     }
 }
 
-impl<'tcx> Default for ThreadManager<'tcx> {
-    fn default() -> Self {
+impl<'tcx> ThreadManager<'tcx> {
+    pub(crate) fn new(schedule_trace: Option<ScheduleTrace>) -> Self {
         let mut threads = IndexVec::new();
         // Create the main thread and add it to the list of threads.
         threads.push(Thread::new(Some("main"), None));
@@ -492,11 +508,26 @@ fn default() -> Self {
             threads,
             thread_local_allocs: Default::default(),
             yield_active_thread: false,
+            schedule_trace,
+            exploration: None,
+            preemption_point: false,
         }
     }
-}
 
-impl<'tcx> ThreadManager<'tcx> {
+    /// Let `exploration` decide where threads get preempted.
+    pub(crate) fn start_exploration(&mut self, exploration: Exploration) {
+        self.exploration = Some(exploration);
+    }
+
+    pub(crate) fn take_exploration(&mut self) -> Option<Exploration> {
+        self.exploration.take()
+    }
+
+    /// The switches made so far, if they are being traced.
+    pub(crate) fn recorded_schedule(&self) -> Option<&[ThreadSwitch]> {
+        self.schedule_trace.as_ref().map(ScheduleTrace::switches)
+    }
+
     pub(crate) fn init(
         ecx: &mut MiriInterpCx<'tcx>,
         on_main_stack_empty: StackEmptyCallback<'tcx>,
@@ -721,6 +752,27 @@ fn block_thread(
         *state = ThreadState::Blocked { reason, timeout, callback: Box::new(callback) }
     }
 
+    /// Whether threads are preempted according to a trace or by schedule exploration, rather than
+    /// randomly.
+    fn is_schedule_controlled(&self) -> bool {
+        self.exploration.is_some()
+            || self.schedule_trace.as_ref().is_some_and(ScheduleTrace::is_replaying)
+    }
+
+    /// Mark the current step as one where schedule exploration may preempt the active thread.
+    fn preemption_point(&mut self) {
+        if self.exploration.is_some() {
+            self.preemption_point = true;
+        }
+    }
+
+    /// Count a step of the active thread, so that recorded switches can refer to it.
+    fn count_step(&mut self) {
+        if let Some(trace) = &mut self.schedule_trace {
+            trace.count_step(self.active_thread);
+        }
+    }
+
     /// Change the active thread to some enabled thread.
     fn yield_active_thread(&mut self) {
         // We do not yield immediately, as swapping out the current stack while executing a MIR statement
@@ -748,10 +800,19 @@ fn next_callback_wait_time(&self, clock: &Clock) -> Option<Duration> {
     /// The currently implemented scheduling policy is the one that is commonly
     /// used in stateless model checkers such as Loom: run the active thread as
     /// long as we can and switch only when we have to (the active thread was
-    /// blocked, terminated, or has explicitly asked to be preempted).
+    /// blocked, terminated, or has explicitly asked to be preempted). When a schedule is being
+    /// replayed, the trace decides where threads get preempted and which thread runs next. When
+    /// schedules are explored, the exploration makes these decisions at preemption points and
+    /// whenever the active thread blocks or terminates.
     fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
+        if self.schedule_trace.as_ref().is_some_and(|trace| trace.preempts(self.active_thread)) {
+            self.yield_active_thread = true;
+        }
         // This thread and the program can keep going.
-        if self.threads[self.active_thread].state.is_enabled() && !self.yield_active_thread {
+        if self.threads[self.active_thread].state.is_enabled()
+            && !self.yield_active_thread
+            && !self.preemption_point
+        {
             // The currently active thread is still enabled, just continue with it.
             return Ok(SchedulingAction::ExecuteStep);
         }
@@ -773,23 +834,53 @@ fn schedule(&mut self, clock: &Clock) -> InterpResult<'tcx, SchedulingAction> {
         // `skip(N)` means we start iterating at thread N, so we skip 1 more to start just *after*
         // the active thread. Then after that we look at `take(N)`, i.e., the threads *before* the
         // active thread.
-        let threads = self
+        let mut others = self
             .threads
             .iter_enumerated()
             .skip(self.active_thread.index() + 1)
-            .chain(self.threads.iter_enumerated().take(self.active_thread.index()));
-        for (id, thread) in threads {
-            debug_assert_ne!(self.active_thread, id);
-            if thread.state.is_enabled() {
-                info!(
-                    "---------- Now executing on thread `{}` (previous: `{}`) ----------------------------------------",
-                    self.get_thread_display_name(id),
-                    self.get_thread_display_name(self.active_thread)
-                );
-                self.active_thread = id;
-                break;
+            .chain(self.threads.iter_enumerated().take(self.active_thread.index()))
+            .filter(|(_, thread)| thread.state.is_enabled())
+            .map(|(id, _)| id);
+        let mut next = others.next();
+        self.preemption_point = false;
+        if let Some(exploration) = &mut self.exploration {
+            // Explicit yields just go to the next thread; only preemption points and threads that
+            // cannot continue are worth exploring.
+            if !self.yield_active_thread {
+                let preemptive = self.threads[self.active_thread].state.is_enabled();
+                let mut options = Vec::new();
+                if preemptive {
+                    // Not switching at all is the first option.
+                    options.push(self.active_thread);
+                }
+                options.extend(next);
+                options.extend(others);
+                if options.len() > 1 {
+                    let chosen = exploration.decide(options, preemptive)?;
+                    next = (chosen != self.active_thread).then_some(chosen);
+                }
             }
         }
+        if let Some(trace) = &mut self.schedule_trace {
+            let reason = match self.threads[self.active_thread].state {
+                ThreadState::Enabled => SwitchReason::Yield,
+                ThreadState::Blocked { .. } => SwitchReason::Blocked,
+                ThreadState::Terminated => SwitchReason::Terminated,
+            };
+            let threads = &self.threads;
+            next = trace.switch(self.active_thread, reason, next, |id| {
+                threads.get(id).is_some_and(|thread| thread.state.is_enabled())
+            })?;
+        }
+        if let Some(id) = next {
+            debug_assert!(self.threads[id].state.is_enabled());
+            info!(
+                "---------- Now executing on thread `{}` (previous: `{}`) ----------------------------------------",
+                self.get_thread_display_name(id),
+                self.get_thread_display_name(self.active_thread)
+            );
+            self.active_thread = id;
+        }
         self.yield_active_thread = false;
         if self.threads[self.active_thread].state.is_enabled() {
             return Ok(SchedulingAction::ExecuteStep);
@@ -957,6 +1048,8 @@ fn start_regular_thread(
 
         // Restore the old active thread frame.
         this.machine.threads.set_active_thread_id(old_thread_id);
+        // The new thread could run before the active one continues.
+        this.machine.threads.preemption_point();
 
         Ok(new_thread_id)
     }
@@ -1072,6 +1165,8 @@ fn unblock_thread(&mut self, thread: ThreadId, reason: BlockReason) -> InterpRes
         let old_thread = this.machine.threads.set_active_thread_id(thread);
         callback.unblock(this)?;
         this.machine.threads.set_active_thread_id(old_thread);
+        // The unblocked thread could run before the active one continues.
+        this.machine.threads.preemption_point();
         Ok(())
     }
 
@@ -1171,12 +1266,22 @@ fn yield_active_thread(&mut self) {
         self.eval_context_mut().machine.threads.yield_active_thread();
     }
 
+    /// Let schedule exploration preempt the active thread after the current step, since it did
+    /// something that other threads can observe.
+    #[inline]
+    fn preemption_point(&mut self) {
+        self.eval_context_mut().machine.threads.preemption_point();
+    }
+
     #[inline]
     fn maybe_preempt_active_thread(&mut self) {
         use rand::Rng as _;
 
         let this = self.eval_context_mut();
-        if this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate) {
+        // Draw a random number even when the schedule is controlled otherwise, so that the rest of
+        // the execution sees the same random numbers as a recorded one.
+        let preempt = this.machine.rng.get_mut().gen_bool(this.machine.preemption_rate);
+        if preempt && !this.machine.threads.is_schedule_controlled() {
             this.yield_active_thread();
         }
     }
@@ -1192,6 +1297,7 @@ fn run_threads(&mut self) -> InterpResult<'tcx, !> {
             }
             match this.machine.threads.schedule(&this.machine.clock)? {
                 SchedulingAction::ExecuteStep => {
+                    this.machine.threads.count_step();
                     if !this.step()? {
                         // See if this thread can do something else.
                         match this.run_on_stack_empty()? {
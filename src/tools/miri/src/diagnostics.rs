@@ -29,6 +29,12 @@ pub enum TerminationInfo {
     },
     Int2PtrWithStrictProvenance,
     Deadlock,
+    ScheduleReplayDiverged {
+        trace: std::path::PathBuf,
+        /// Index of the first switch of the trace that could not be replayed.
+        index: usize,
+        diff: Vec<String>,
+    },
     MultipleSymbolDefinitions {
         link_name: Symbol,
         first: SpanData,
@@ -72,6 +78,13 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
             StackedBorrowsUb { msg, .. } => write!(f, "{msg}"),
             TreeBorrowsUb { title, .. } => write!(f, "{title}"),
             Deadlock => write!(f, "the evaluated program deadlocked"),
+            ScheduleReplayDiverged { trace, index, .. } =>
+                write!(
+                    f,
+                    "the execution diverged from switch #{} of the schedule trace `{}`",
+                    index + 1,
+                    trace.display()
+                ),
             MultipleSymbolDefinitions { link_name, .. } =>
                 write!(f, "multiple definitions of symbol `{link_name}`"),
             SymbolShimClashing { link_name, .. } =>
@@ -230,6 +243,7 @@ pub fn report_error<'tcx>(
             StackedBorrowsUb { .. } | TreeBorrowsUb { .. } | DataRace { .. } =>
                 Some("Undefined Behavior"),
             Deadlock => Some("deadlock"),
+            ScheduleReplayDiverged { .. } => Some("schedule replay divergence"),
             MultipleSymbolDefinitions { .. } | SymbolShimClashing { .. } => None,
         };
         #[rustfmt::skip]
@@ -281,6 +295,14 @@ pub fn report_error<'tcx>(
                 ],
             SymbolShimClashing { link_name, span } =>
                 vec![note_span!(*span, "the `{link_name}` symbol is defined here")],
+            ScheduleReplayDiverged { diff, .. } => {
+                let mut helps = vec![note!("the trace expected the switch marked `-`, but the execution reached the one marked `+` instead (`<thread> <step> <reason> <next thread>`):")];
+                for line in diff {
+                    helps.push(note!("{line}"));
+                }
+                helps.push(note!("this usually means that the program, its inputs, or the Miri flags changed since the trace was recorded"));
+                helps
+            }
             Int2PtrWithStrictProvenance =>
                 vec![note!("use Strict Provenance APIs (https://doc.rust-lang.org/nightly/std/ptr/index.html#strict-provenance, https://crates.io/crates/sptr) instead")],
             DataRace { op1, extra, retag_explain, .. } => {
@@ -404,7 +426,7 @@ pub fn report_error<'tcx>(
         MachineStop(info) => {
             let info = info.downcast_ref::<TerminationInfo>().expect("invalid MachineStop payload");
             match info {
-                TerminationInfo::Deadlock => {
+                TerminationInfo::Deadlock | TerminationInfo::ScheduleReplayDiverged { .. } => {
                     show_all_threads = true;
                 }
                 _ => {}
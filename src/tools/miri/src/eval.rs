@@ -7,6 +7,7 @@
 use std::task::Poll;
 use std::thread;
 
+use crate::concurrency::exploration::Exploration;
 use crate::concurrency::thread::TlsAllocAction;
 use crate::diagnostics::report_leaks;
 use rustc_data_structures::fx::{FxHashMap, FxHashSet};
@@ -145,6 +146,12 @@ pub struct MiriConfig {
     pub mute_stdout_stderr: bool,
     /// The probability of the active thread being preempted at the end of each basic block.
     pub preemption_rate: f64,
+    /// Write every thread switch to this file.
+    pub record_schedule: Option<PathBuf>,
+    /// Switch threads as listed in this file, instead of preempting them randomly.
+    pub replay_schedule: Option<PathBuf>,
+    /// Run the program once for every schedule with at most this many preemptions, until one fails.
+    pub explore_schedules: Option<u32>,
     /// Report the current instruction being executed every N basic blocks.
     pub report_progress: Option<u32>,
     /// Whether Stacked Borrows and Tree Borrows retagging should recurse into fields of datatypes.
@@ -193,6 +200,9 @@ fn default() -> MiriConfig {
             provenance_mode: ProvenanceMode::Default,
             mute_stdout_stderr: false,
             preemption_rate: 0.01, // 1%
+            record_schedule: None,
+            replay_schedule: None,
+            explore_schedules: None,
             report_progress: None,
             retag_fields: RetagFields::Yes,
             native_lib: None,
@@ -431,6 +441,9 @@ pub fn create_ecx<'tcx>(
 /// Evaluates the entry function specified by `entry_id`.
 /// Returns `Some(return_code)` if program executed completed.
 /// Returns `None` if an evaluation error occurred.
+/// With `-Zmiri-explore-schedules`, the program is evaluated once per schedule, until one of these
+/// executions reports an error or all schedules have been explored. Exit codes do not stop the
+/// exploration, but the first non-zero one is returned.
 #[allow(clippy::needless_lifetimes)]
 pub fn eval_entry<'tcx>(
     tcx: TyCtxt<'tcx>,
@@ -438,17 +451,54 @@ pub fn eval_entry<'tcx>(
     entry_type: EntryFnType,
     config: MiriConfig,
 ) -> Option<i64> {
-    // Copy setting before we move `config`.
-    let ignore_leaks = config.ignore_leaks;
+    let Some(bound) = config.explore_schedules else {
+        let mut ecx = create_ecx_or_abort(tcx, entry_id, entry_type, &config);
+        return run_entry(&mut ecx, config.ignore_leaks);
+    };
 
-    let mut ecx = match create_ecx(tcx, entry_id, entry_type, &config) {
+    let mut exploration = Exploration::new(bound);
+    let mut return_code = 0;
+    loop {
+        let mut ecx = create_ecx_or_abort(tcx, entry_id, entry_type, &config);
+        ecx.machine.threads.start_exploration(exploration);
+        let res = run_entry(&mut ecx, config.ignore_leaks);
+        exploration = ecx.machine.threads.take_exploration().unwrap();
+        let Some(code) = res else {
+            report_failing_schedule(&ecx, &exploration);
+            return None;
+        };
+        if return_code == 0 {
+            return_code = code;
+        }
+        if !exploration.next_execution() {
+            tcx.dcx().note(format!(
+                "explored {} schedules with at most {bound} preemptions, none of them failed",
+                exploration.execution()
+            ));
+            return Some(return_code);
+        }
+    }
+}
+
+fn create_ecx_or_abort<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    entry_id: DefId,
+    entry_type: EntryFnType,
+    config: &MiriConfig,
+) -> MiriInterpCx<'tcx> {
+    match create_ecx(tcx, entry_id, entry_type, config) {
         Ok(v) => v,
         Err(err) => {
             let (kind, backtrace) = err.into_parts();
             backtrace.print_backtrace();
             panic!("Miri initialization error: {kind:?}")
         }
-    };
+    }
+}
+
+/// Runs the program set up in `ecx` to completion and reports the result.
+fn run_entry<'tcx>(ecx: &mut MiriInterpCx<'tcx>, ignore_leaks: bool) -> Option<i64> {
+    let tcx = ecx.tcx.tcx;
 
     // Perform the main execution.
     let res: thread::Result<InterpResult<'_, !>> =
@@ -474,11 +524,11 @@ pub fn eval_entry<'tcx>(
         // might not have joined the main thread (https://github.com/rust-lang/miri/issues/2020,
         // https://github.com/rust-lang/miri/issues/2508).
         ecx.allow_data_races_all_threads_done();
-        EnvVars::cleanup(&mut ecx).expect("error during env var cleanup");
+        EnvVars::cleanup(ecx).expect("error during env var cleanup");
     }
 
     // Process the result.
-    let (return_code, leak_check) = report_error(&ecx, res)?;
+    let (return_code, leak_check) = report_error(ecx, res)?;
     if leak_check && !ignore_leaks {
         // Check for thread leaks.
         if !ecx.have_all_terminated() {
@@ -490,7 +540,7 @@ pub fn eval_entry<'tcx>(
         info!("Additional static roots: {:?}", ecx.machine.static_roots);
         let leaks = ecx.find_leaked_allocations(&ecx.machine.static_roots);
         if !leaks.is_empty() {
-            report_leaks(&ecx, leaks);
+            report_leaks(ecx, leaks);
             tcx.dcx().note("set `MIRIFLAGS=-Zmiri-ignore-leaks` to disable this check");
             // Ignore the provided return code - let the reported error
             // determine the return code.
@@ -500,6 +550,31 @@ pub fn eval_entry<'tcx>(
     Some(return_code)
 }
 
+/// Reports the thread switches of the execution in `ecx`, which failed during schedule exploration.
+fn report_failing_schedule<'tcx>(ecx: &MiriInterpCx<'tcx>, exploration: &Exploration) {
+    let schedule = ecx
+        .machine
+        .threads
+        .recorded_schedule()
+        .expect("thread switches are traced during schedule exploration");
+    let mut msg = format!(
+        "this is execution {} of the schedule exploration; it preempted threads {} times",
+        exploration.execution(),
+        exploration.preemptions()
+    );
+    if schedule.is_empty() {
+        msg.push_str(" and never switched threads");
+    } else {
+        msg.push_str(
+            " and made these thread switches (pass them to `-Zmiri-replay-schedule` to reproduce this execution):",
+        );
+        for switch in schedule {
+            msg.push_str(&format!("\n{switch}"));
+        }
+    }
+    ecx.tcx.dcx().note(msg);
+}
+
 /// Turns an array of arguments into a Windows command line string.
 ///
 /// The string will be UTF-16 encoded and NUL terminated.
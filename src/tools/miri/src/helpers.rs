@@ -1416,6 +1416,42 @@ pub(crate) fn simd_element_to_bool(elem: ImmTy<'_>) -> InterpResult<'_, bool> {
     })
 }
 
+/// Computes the CRC of the low `bit_size` bits of `data` with the 33-bit `polynomial`, continuing
+/// from `crc`. Like the x86 and AArch64 CRC instructions, this works on bit-reflected values and
+/// does not invert the checksum before or after.
+pub(crate) fn compute_crc32(crc: u32, data: u64, bit_size: u32, polynomial: u128) -> u32 {
+    assert!(matches!(bit_size, 8 | 16 | 32 | 64));
+    let crc = u128::from(crc.reverse_bits());
+    let v = u128::from(data.reverse_bits() >> 64u32.strict_sub(bit_size));
+
+    // Perform polynomial division modulo 2.
+    // The algorithm for the division is an adapted version of the
+    // schoolbook division algorithm used for normal integer or polynomial
+    // division. In this context, the quotient is not calculated, since
+    // only the remainder is needed.
+    //
+    // The algorithm works as follows:
+    // 1. Pull down digits until division can be performed. In the context of division
+    //    modulo 2 it means locating the most significant digit of the dividend and shifting
+    //    the divisor such that the position of the divisors most significand digit and the
+    //    dividends most significand digit match.
+    // 2. Perform a division and determine the remainder. Since it is arithmetic modulo 2,
+    //    this operation is a simple bitwise exclusive or.
+    // 3. Repeat steps 1. and 2. until the full remainder is calculated. This is the case
+    //    once the degree of the remainder polynomial is smaller than the degree of the
+    //    divisor polynomial. In other words, the number of leading zeros of the remainder
+    //    is larger than the number of leading zeros of the divisor. It is important to
+    //    note that standard arithmetic comparison is not applicable here:
+    //    0b10011 / 0b11111 = 0b01100 is a valid division, even though the dividend is
+    //    smaller than the divisor.
+    let mut dividend = (crc << bit_size) ^ (v << 32);
+    while dividend.leading_zeros() <= polynomial.leading_zeros() {
+        dividend ^= (polynomial << polynomial.leading_zeros()) >> dividend.leading_zeros();
+    }
+
+    u32::try_from(dividend).unwrap().reverse_bits()
+}
+
 /// Check whether an operation that writes to a target buffer was successful.
 /// Accordingly select return value.
 /// Local helper function to be used in Windows shims.
@@ -116,6 +116,8 @@ fn fence_ord(ord: &str) -> AtomicFenceOrd {
 
             _ => return Ok(EmulateItemResult::NotSupported),
         }
+        // Other threads can observe the effect of this operation.
+        this.preemption_point();
         Ok(EmulateItemResult::NeedsReturn)
     }
 }
@@ -1,7 +1,7 @@
 #![warn(clippy::arithmetic_side_effects)]
 
 mod atomic;
-mod simd;
+pub(crate) mod simd;
 
 use std::iter;
 
@@ -458,6 +458,10 @@ pub struct MiriMachine<'tcx> {
 
     /// The list of all EpollEventInterest.
     pub(crate) epoll_interests: shims::EpollInterestTable,
+    /// The sockets bound to addresses of the in-process network.
+    pub(crate) network: shims::VirtualNetwork,
+    /// The FreeRTOS and ESP-IDF objects of the program.
+    pub(crate) espidf: shims::EspIdfState,
 
     /// This machine's monotone clock.
     pub(crate) clock: Clock,
@@ -632,7 +636,8 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             cpu_affinity::MAX_CPUS,
             config.num_cpus
         );
-        let threads = ThreadManager::default();
+        let schedule_trace = ScheduleTrace::new(config).unwrap_or_else(|err| tcx.dcx().fatal(err));
+        let threads = ThreadManager::new(schedule_trace);
         let mut thread_cpu_affinity = FxHashMap::default();
         if matches!(&*tcx.sess.target.os, "linux" | "freebsd" | "android") {
             thread_cpu_affinity
@@ -654,6 +659,8 @@ pub(crate) fn new(config: &MiriConfig, layout_cx: LayoutCx<'tcx, TyCtxt<'tcx>>)
             validation: config.validation,
             fds: shims::FdTable::init(config.mute_stdout_stderr),
             epoll_interests: shims::EpollInterestTable::new(),
+            network: Default::default(),
+            espidf: Default::default(),
             dirs: Default::default(),
             layouts,
             threads,
@@ -793,6 +800,8 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
             alloc_addresses,
             fds,
             epoll_interests:_,
+            network: _,
+            espidf,
             tcx: _,
             isolated_op: _,
             validation: _,
@@ -833,6 +842,7 @@ fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
         env_vars.visit_provenance(visit);
         dirs.visit_provenance(visit);
         fds.visit_provenance(visit);
+        espidf.visit_provenance(visit);
         data_race.visit_provenance(visit);
         borrow_tracker.visit_provenance(visit);
         alloc_addresses.visit_provenance(visit);
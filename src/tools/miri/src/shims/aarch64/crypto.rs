@@ -0,0 +1,261 @@
+//! Implements the AES and SHA instructions of AArch64 targets.
+//!
+//! The SHA instructions follow the pseudocode of the Arm Architecture Reference Manual. Like
+//! there, the hash state and message schedule are kept in 32-bit lanes, the first lane being the
+//! least significant one.
+
+use rustc_span::Symbol;
+use rustc_target::spec::abi::Abi;
+
+use crate::*;
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub(super) trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn emulate_aarch64_crypto_intrinsic(
+        &mut self,
+        link_name: Symbol,
+        abi: Abi,
+        args: &[OpTy<'tcx>],
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, EmulateItemResult> {
+        let this = self.eval_context_mut();
+        // Prefix should have already been checked.
+        let unprefixed_name = link_name.as_str().strip_prefix("llvm.aarch64.crypto.").unwrap();
+
+        match unprefixed_name {
+            // Used to implement the vaeseq_u8 function.
+            // Performs AddRoundKey, ShiftRows and SubBytes, that is, an AES encryption
+            // round without MixColumns.
+            "aese" => {
+                this.expect_target_feature_for_intrinsic(link_name, "aes")?;
+                let [data, key] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                aes_op(this, data, Some(key), dest, |state| {
+                    // `aes::hazmat::cipher_round` does the following operations:
+                    // state = ShiftRows(state)
+                    // state = SubBytes(state)
+                    // state = MixColumns(state)
+                    // state = state ^ key
+                    // Use a zeroed key to skip the XOR and undo the MixColumns.
+                    aes::hazmat::cipher_round(state, &aes::Block::from([0; 16]));
+                    aes::hazmat::inv_mix_columns(state);
+                })?;
+            }
+            // Used to implement the vaesdq_u8 function.
+            // Performs AddRoundKey, InvShiftRows and InvSubBytes, that is, an AES decryption
+            // round without InvMixColumns.
+            "aesd" => {
+                this.expect_target_feature_for_intrinsic(link_name, "aes")?;
+                let [data, key] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                aes_op(this, data, Some(key), dest, |state| {
+                    // `aes::hazmat::equiv_inv_cipher_round` does the following operations:
+                    // state = InvShiftRows(state)
+                    // state = InvSubBytes(state)
+                    // state = InvMixColumns(state)
+                    // state = state ^ key
+                    // Use a zeroed key to skip the XOR and undo the InvMixColumns.
+                    aes::hazmat::equiv_inv_cipher_round(state, &aes::Block::from([0; 16]));
+                    aes::hazmat::mix_columns(state);
+                })?;
+            }
+            // Used to implement the vaesmcq_u8 function.
+            "aesmc" => {
+                this.expect_target_feature_for_intrinsic(link_name, "aes")?;
+                let [data] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                aes_op(this, data, None, dest, aes::hazmat::mix_columns)?;
+            }
+            // Used to implement the vaesimcq_u8 function.
+            "aesimc" => {
+                this.expect_target_feature_for_intrinsic(link_name, "aes")?;
+                let [data] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                aes_op(this, data, None, dest, aes::hazmat::inv_mix_columns)?;
+            }
+
+            // Used to implement the vsha1{c,p,m}q_u32 functions.
+            // Performs four SHA-1 rounds with the choose, parity or majority function.
+            "sha1c" | "sha1p" | "sha1m" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [hash_abcd, hash_e, wk] =
+                    this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let f: fn(u32, u32, u32) -> u32 = match unprefixed_name {
+                    "sha1c" => |x: u32, y: u32, z: u32| (x & y) | (!x & z),
+                    "sha1p" => |x: u32, y: u32, z: u32| x ^ y ^ z,
+                    "sha1m" => |x: u32, y: u32, z: u32| (x & y) | (x & z) | (y & z),
+                    _ => unreachable!(),
+                };
+                let [mut a, mut b, mut c, mut d] = read_u32x4(this, hash_abcd)?;
+                let mut e = this.read_scalar(hash_e)?.to_u32()?;
+                for wk in read_u32x4(this, wk)? {
+                    let t =
+                        a.rotate_left(5).wrapping_add(f(b, c, d)).wrapping_add(e).wrapping_add(wk);
+                    (a, b, c, d, e) = (t, a, b.rotate_left(30), c, d);
+                }
+                write_u32x4(this, dest, [a, b, c, d])?;
+            }
+            // Used to implement the vsha1h_u32 function.
+            "sha1h" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [hash_e] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let hash_e = this.read_scalar(hash_e)?.to_u32()?;
+                this.write_scalar(Scalar::from_u32(hash_e.rotate_left(30)), dest)?;
+            }
+            // Used to implement the vsha1su0q_u32 function.
+            "sha1su0" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [w0_3, w4_7, w8_11] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let w0_3 = read_u32x4(this, w0_3)?;
+                let w4_7 = read_u32x4(this, w4_7)?;
+                let w8_11 = read_u32x4(this, w8_11)?;
+                let t = [w0_3[2], w0_3[3], w4_7[0], w4_7[1]];
+                let res = std::array::from_fn(|i| t[i] ^ w0_3[i] ^ w8_11[i]);
+                write_u32x4(this, dest, res)?;
+            }
+            // Used to implement the vsha1su1q_u32 function.
+            "sha1su1" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [tw0_3, w12_15] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let tw0_3 = read_u32x4(this, tw0_3)?;
+                let w12_15 = read_u32x4(this, w12_15)?;
+                let t =
+                    [tw0_3[0] ^ w12_15[1], tw0_3[1] ^ w12_15[2], tw0_3[2] ^ w12_15[3], tw0_3[3]];
+                let mut res = t.map(|x| x.rotate_left(1));
+                res[3] ^= t[0].rotate_left(2);
+                write_u32x4(this, dest, res)?;
+            }
+
+            // Used to implement the vsha256hq_u32 and vsha256h2q_u32 functions.
+            // Both perform four SHA-256 rounds; the first returns the updated `abcd`
+            // half of the state and the second the updated `efgh` half.
+            "sha256h" | "sha256h2" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [first, second, wk] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let first = read_u32x4(this, first)?;
+                let second = read_u32x4(this, second)?;
+                let wk = read_u32x4(this, wk)?;
+                let res = if unprefixed_name == "sha256h" {
+                    sha256_rounds(first, second, wk).0
+                } else {
+                    sha256_rounds(second, first, wk).1
+                };
+                write_u32x4(this, dest, res)?;
+            }
+            // Used to implement the vsha256su0q_u32 function.
+            "sha256su0" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [w0_3, w4_7] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let w0_3 = read_u32x4(this, w0_3)?;
+                let w4_7 = read_u32x4(this, w4_7)?;
+                let t = [w0_3[1], w0_3[2], w0_3[3], w4_7[0]];
+                let res = std::array::from_fn(|i| w0_3[i].wrapping_add(sigma0(t[i])));
+                write_u32x4(this, dest, res)?;
+            }
+            // Used to implement the vsha256su1q_u32 function.
+            "sha256su1" => {
+                this.expect_target_feature_for_intrinsic(link_name, "sha2")?;
+                let [tw0_3, w8_11, w12_15] =
+                    this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let tw0_3 = read_u32x4(this, tw0_3)?;
+                let w8_11 = read_u32x4(this, w8_11)?;
+                let w12_15 = read_u32x4(this, w12_15)?;
+                let t0 = [w8_11[1], w8_11[2], w8_11[3], w12_15[0]];
+                let mut res = [0; 4];
+                for i in 0..4 {
+                    // The last two words depend on the first two results.
+                    let prev = if i < 2 { w12_15[i.strict_add(2)] } else { res[i.strict_sub(2)] };
+                    res[i] = sigma1(prev).wrapping_add(tw0_3[i]).wrapping_add(t0[i]);
+                }
+                write_u32x4(this, dest, res)?;
+            }
+            _ => return Ok(EmulateItemResult::NotSupported),
+        }
+        Ok(EmulateItemResult::NeedsReturn)
+    }
+}
+
+/// Applies `f` to the AES state in `data`, after XOR-ing it with `key` if there is one.
+fn aes_op<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    data: &OpTy<'tcx>,
+    key: Option<&OpTy<'tcx>>,
+    dest: &MPlaceTy<'tcx>,
+    f: impl FnOnce(&mut aes::Block),
+) -> InterpResult<'tcx, ()> {
+    // Transmute to `u128`
+    let data = data.transmute(this.machine.layouts.u128, this)?;
+    let dest = dest.transmute(this.machine.layouts.u128, this)?;
+
+    let mut state = this.read_scalar(&data)?.to_u128()?;
+    if let Some(key) = key {
+        let key = key.transmute(this.machine.layouts.u128, this)?;
+        state ^= this.read_scalar(&key)?.to_u128()?;
+    }
+    let mut state = aes::Block::from(state.to_le_bytes());
+    f(&mut state);
+
+    this.write_scalar(Scalar::from_u128(u128::from_le_bytes(state.into())), &dest)?;
+    Ok(())
+}
+
+fn read_u32x4<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    op: &OpTy<'tcx>,
+) -> InterpResult<'tcx, [u32; 4]> {
+    let (op, op_len) = this.operand_to_simd(op)?;
+    assert_eq!(op_len, 4);
+
+    let mut res = [0; 4];
+    for (i, dst) in (0..).zip(res.iter_mut()) {
+        *dst = this.read_scalar(&this.project_index(&op, i)?)?.to_u32()?;
+    }
+    Ok(res)
+}
+
+fn write_u32x4<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+    val: [u32; 4],
+) -> InterpResult<'tcx, ()> {
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+    assert_eq!(dest_len, 4);
+
+    for (i, part) in (0..).zip(val) {
+        this.write_scalar(Scalar::from_u32(part), &this.project_index(&dest, i)?)?;
+    }
+    Ok(())
+}
+
+fn sigma0(x: u32) -> u32 {
+    x.rotate_right(7) ^ x.rotate_right(18) ^ (x >> 3)
+}
+
+fn sigma1(x: u32) -> u32 {
+    x.rotate_right(17) ^ x.rotate_right(19) ^ (x >> 10)
+}
+
+/// Performs four SHA-256 rounds on the state `abcd` and `efgh` with the message words `wk`, to
+/// which the round constants have already been added. Returns the updated halves of the state.
+fn sha256_rounds(abcd: [u32; 4], efgh: [u32; 4], wk: [u32; 4]) -> ([u32; 4], [u32; 4]) {
+    let [mut a, mut b, mut c, mut d] = abcd;
+    let [mut e, mut f, mut g, mut h] = efgh;
+    for wk in wk {
+        let ch = (e & f) ^ (!e & g);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let big_sigma0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let big_sigma1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let t1 = h.wrapping_add(big_sigma1).wrapping_add(ch).wrapping_add(wk);
+        let t2 = big_sigma0.wrapping_add(maj);
+        (h, g, f, e) = (g, f, e, d.wrapping_add(t1));
+        (d, c, b, a) = (c, b, a, t1.wrapping_add(t2));
+    }
+    ([a, b, c, d], [e, f, g, h])
+}
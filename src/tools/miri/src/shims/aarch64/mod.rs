@@ -0,0 +1,76 @@
+use rustc_span::Symbol;
+use rustc_target::spec::abi::Abi;
+
+use crate::*;
+use helpers::compute_crc32;
+
+mod crypto;
+mod neon;
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub(super) trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn emulate_aarch64_intrinsic(
+        &mut self,
+        link_name: Symbol,
+        abi: Abi,
+        args: &[OpTy<'tcx>],
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, EmulateItemResult> {
+        let this = self.eval_context_mut();
+        // Prefix should have already been checked.
+        let unprefixed_name = link_name.as_str().strip_prefix("llvm.aarch64.").unwrap();
+        match unprefixed_name {
+            "isb" => {
+                let [arg] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+                let arg = this.read_scalar(arg)?.to_i32()?;
+                match arg {
+                    // SY ("full system scope")
+                    15 => {
+                        this.yield_active_thread();
+                    }
+                    _ => {
+                        throw_unsup_format!("unsupported llvm.aarch64.isb argument {}", arg);
+                    }
+                }
+            }
+
+            // Used to implement the `__crc32{b,h,w,d}` and `__crc32c{b,h,w,d}` functions.
+            // The former use `0x104C11DB7` as the polynomial (like zlib), the latter
+            // `0x11EDC6F41`, also known as CRC32C.
+            "crc32b" | "crc32h" | "crc32w" | "crc32x" | "crc32cb" | "crc32ch" | "crc32cw"
+            | "crc32cx" => {
+                this.expect_target_feature_for_intrinsic(link_name, "crc")?;
+                let [crc, data] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+                let crc = this.read_scalar(crc)?.to_u32()?;
+                let data = this.read_scalar(data)?;
+
+                // The 8- and 16-bit versions take a 32-bit `data` and ignore its upper bits.
+                let (data, bit_size) = match unprefixed_name.as_bytes().last().unwrap() {
+                    b'b' => (u64::from(data.to_u32()? & 0xFF), 8),
+                    b'h' => (u64::from(data.to_u32()? & 0xFFFF), 16),
+                    b'w' => (u64::from(data.to_u32()?), 32),
+                    b'x' => (data.to_u64()?, 64),
+                    _ => unreachable!(),
+                };
+                let polynomial =
+                    if unprefixed_name.starts_with("crc32c") { 0x11EDC6F41 } else { 0x104C11DB7 };
+
+                let result = compute_crc32(crc, data, bit_size, polynomial);
+                this.write_scalar(Scalar::from_u32(result), dest)?;
+            }
+
+            name if name.starts_with("neon.") => {
+                return neon::EvalContextExt::emulate_aarch64_neon_intrinsic(
+                    this, link_name, abi, args, dest,
+                );
+            }
+            name if name.starts_with("crypto.") => {
+                return crypto::EvalContextExt::emulate_aarch64_crypto_intrinsic(
+                    this, link_name, abi, args, dest,
+                );
+            }
+            _ => return Ok(EmulateItemResult::NotSupported),
+        }
+        Ok(EmulateItemResult::NeedsReturn)
+    }
+}
@@ -0,0 +1,577 @@
+use std::cmp::Ordering;
+
+use rustc_apfloat::Float;
+use rustc_middle::mir;
+use rustc_middle::ty::{self, FloatTy};
+use rustc_span::Symbol;
+use rustc_target::abi::Size;
+use rustc_target::spec::abi::Abi;
+
+use crate::*;
+use helpers::bool_to_simd_element;
+use intrinsics::simd::{EvalContextExt as _, MinMax};
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub(super) trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn emulate_aarch64_neon_intrinsic(
+        &mut self,
+        link_name: Symbol,
+        abi: Abi,
+        args: &[OpTy<'tcx>],
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, EmulateItemResult> {
+        let this = self.eval_context_mut();
+        this.expect_target_feature_for_intrinsic(link_name, "neon")?;
+        // Prefix should have already been checked.
+        let unprefixed_name = link_name.as_str().strip_prefix("llvm.aarch64.neon.").unwrap();
+        // The suffix only encodes the types of the arguments (e.g. `.v16i8` or `.i32.v4i16`),
+        // which we get from the layouts instead. For integer operations, the `s` or `u` prefix
+        // determines the signedness, as the Rust types in the declaration are not always accurate.
+        let op_name = unprefixed_name.split_once('.').map_or(unprefixed_name, |(name, _)| name);
+
+        match op_name {
+            // Used to implement the vqadd{,q}_*, vqsub{,q}_*, vhadd{,q}_*, vrhadd{,q}_*,
+            // vhsub{,q}_*, vabd{,q}_*, vmax{,q}_*, vmin{,q}_* and vshl{,q}_* functions on
+            // integers.
+            "sqadd" | "uqadd" | "sqsub" | "uqsub" | "shadd" | "uhadd" | "srhadd" | "urhadd"
+            | "shsub" | "uhsub" | "sabd" | "uabd" | "smax" | "umax" | "smin" | "umin" | "sshl"
+            | "ushl" => {
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let signed = op_name.starts_with('s');
+                let f: fn(i128, i128, Size, bool) -> i128 = match &op_name[1..] {
+                    "qadd" =>
+                        |l: i128, r: i128, size, signed| saturate(l.strict_add(r), size, signed),
+                    "qsub" =>
+                        |l: i128, r: i128, size, signed| saturate(l.strict_sub(r), size, signed),
+                    "hadd" => |l: i128, r: i128, _, _| l.strict_add(r) >> 1,
+                    "rhadd" => |l: i128, r: i128, _, _| l.strict_add(r).strict_add(1) >> 1,
+                    "hsub" => |l: i128, r: i128, _, _| l.strict_sub(r) >> 1,
+                    "abd" => |l: i128, r: i128, _, _| i128::try_from(l.abs_diff(r)).unwrap(),
+                    "max" => |l: i128, r: i128, _, _| l.max(r),
+                    "min" => |l: i128, r: i128, _, _| l.min(r),
+                    "shl" => shift_left,
+                    _ => unreachable!(),
+                };
+                int_binary_op(this, left, right, dest, signed, f)?;
+            }
+            // Used to implement the vmull_* functions on integers.
+            // Multiplies the lanes, producing results twice as wide.
+            "smull" | "umull" => {
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let signed = op_name == "smull";
+                int_binary_op(this, left, right, dest, signed, |l, r, _, _| l.strict_mul(r))?;
+            }
+            // Used to implement the vmull_p8 function.
+            // Performs carry-less multiplications of the lanes, producing results twice as wide.
+            "pmull" => {
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                int_binary_op(this, left, right, dest, false, |l, r, _, _| {
+                    let res = carryless_mul(l.try_into().unwrap(), r.try_into().unwrap());
+                    res.try_into().unwrap()
+                })?;
+            }
+            // Used to implement the vmull_p64 function.
+            "pmull64" => {
+                this.expect_target_feature_for_intrinsic(link_name, "aes")?;
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let left = this.read_scalar(left)?.to_u64()?;
+                let right = this.read_scalar(right)?.to_u64()?;
+                let dest = dest.transmute(this.machine.layouts.u128, this)?;
+
+                let res = carryless_mul(left, right);
+                this.write_scalar(Scalar::from_u128(res), &dest)?;
+            }
+
+            // Used to implement the vabs{,q}_*, vqabs{,q}_* and vqneg{,q}_* functions on
+            // integers. `abs` wraps around, while the others saturate.
+            "abs" | "sqabs" | "sqneg" => {
+                let [op] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                int_unary_op(this, op, dest, true, |val, size| match op_name {
+                    "abs" => val.abs(),
+                    "sqabs" => saturate(val.abs(), size, true),
+                    "sqneg" => saturate(val.strict_neg(), size, true),
+                    _ => unreachable!(),
+                })?;
+            }
+            // Used to implement the vqmovn_*, vqmovun_* functions.
+            // Narrows the lanes to half their width, saturating the values that do not fit.
+            "sqxtn" | "uqxtn" | "sqxtun" => {
+                let [op] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let signed = op_name.starts_with('s');
+                let dest_signed = op_name == "sqxtn";
+                int_unary_op(this, op, dest, signed, |val, size| saturate(val, size, dest_signed))?;
+            }
+
+            // Used to implement the vmax{,q}_f*, vmin{,q}_f*, vmaxnm{,q}_f*, vminnm{,q}_f* and
+            // vabd{,q}_f* functions.
+            "fmax" | "fmin" | "fmaxnm" | "fminnm" | "fabd" => {
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let (left, left_len) = this.operand_to_simd(left)?;
+                let (right, right_len) = this.operand_to_simd(right)?;
+                let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+                assert_eq!(dest_len, left_len);
+                assert_eq!(dest_len, right_len);
+
+                let op = LaneOp::from_name(op_name).unwrap();
+                for i in 0..dest_len {
+                    let left = this.read_immediate(&this.project_index(&left, i)?)?;
+                    let right = this.read_immediate(&this.project_index(&right, i)?)?;
+                    let dest = this.project_index(&dest, i)?;
+
+                    let res = lane_op(this, op, &left, &right)?;
+                    this.write_scalar(res, &dest)?;
+                }
+            }
+            // Used to implement the vcage{,q}_f* and vcagt{,q}_f* functions.
+            // Compares the absolute values of the lanes.
+            "facge" | "facgt" => {
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let (left, left_len) = this.operand_to_simd(left)?;
+                let (right, right_len) = this.operand_to_simd(right)?;
+                let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+                assert_eq!(dest_len, left_len);
+                assert_eq!(dest_len, right_len);
+
+                for i in 0..dest_len {
+                    let left = this.read_immediate(&this.project_index(&left, i)?)?;
+                    let right = this.read_immediate(&this.project_index(&right, i)?)?;
+                    let dest = this.project_index(&dest, i)?;
+
+                    let res = match float_abs_cmp(&left, &right)? {
+                        Some(Ordering::Greater) => true,
+                        Some(Ordering::Equal) => op_name == "facge",
+                        // Comparisons with NaN are false.
+                        Some(Ordering::Less) | None => false,
+                    };
+                    this.write_scalar(bool_to_simd_element(res, dest.layout.size), &dest)?;
+                }
+            }
+
+            // Used to implement the vpadd{,q}_*, vpmax{,q}_*, vpmin{,q}_*, vpmaxnm{,q}_* and
+            // vpminnm{,q}_* functions.
+            // Combines adjacent pairs of lanes of the concatenation of `left` and `right`.
+            "addp" | "smaxp" | "umaxp" | "sminp" | "uminp" | "faddp" | "fmaxp" | "fminp"
+            | "fmaxnmp" | "fminnmp" => {
+                let [left, right] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let (left, left_len) = this.operand_to_simd(left)?;
+                let (right, right_len) = this.operand_to_simd(right)?;
+                let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+                assert_eq!(dest_len, left_len);
+                assert_eq!(dest_len, right_len);
+
+                let op = LaneOp::from_name(op_name.strip_suffix('p').unwrap()).unwrap();
+                let half_len = dest_len.strict_div(2);
+                for i in 0..dest_len {
+                    let (src, j) =
+                        if i < half_len { (&left, i) } else { (&right, i.strict_sub(half_len)) };
+                    let first = this.read_immediate(&this.project_index(src, j.strict_mul(2))?)?;
+                    let second = this
+                        .read_immediate(&this.project_index(src, j.strict_mul(2).strict_add(1))?)?;
+                    let dest = this.project_index(&dest, i)?;
+
+                    let res = lane_op(this, op, &first, &second)?;
+                    this.write_scalar(res, &dest)?;
+                }
+            }
+            // Used to implement the vaddv{,q}_*, vmaxv{,q}_*, vminv{,q}_*, vmaxnmv{,q}_* and
+            // vminnmv{,q}_* functions.
+            // Reduces all lanes to a single value. Integer results are extended to the return
+            // type, which can be wider than the lanes.
+            "saddv" | "uaddv" | "smaxv" | "umaxv" | "sminv" | "uminv" | "faddv" | "fmaxv"
+            | "fminv" | "fmaxnmv" | "fminnmv" => {
+                let [op] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let (op, op_len) = this.operand_to_simd(op)?;
+
+                let op_kind = LaneOp::from_name(op_name.strip_suffix('v').unwrap()).unwrap();
+                let signed = op_name.starts_with('s');
+
+                // Initialize with first lane, then proceed with the rest.
+                let mut res = this.read_immediate(&this.project_index(&op, 0)?)?;
+                for i in 1..op_len {
+                    let lane = this.read_immediate(&this.project_index(&op, i)?)?;
+                    res = ImmTy::from_scalar(lane_op(this, op_kind, &res, &lane)?, res.layout);
+                }
+                let res = if matches!(res.layout.ty.kind(), ty::Float(_)) {
+                    res.to_scalar()
+                } else {
+                    let val = read_int(&res, signed)?;
+                    truncate_to_lane(val, dest.layout.size)
+                };
+                this.write_scalar(res, dest)?;
+            }
+            // Used to implement the vaddlv{,q}_* functions.
+            // Adds all lanes in a type wide enough to not overflow.
+            "saddlv" | "uaddlv" => {
+                let [op] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let (op, op_len) = this.operand_to_simd(op)?;
+                let signed = op_name == "saddlv";
+
+                let mut sum: i128 = 0;
+                for i in 0..op_len {
+                    let lane = this.read_immediate(&this.project_index(&op, i)?)?;
+                    sum = sum.strict_add(read_int(&lane, signed)?);
+                }
+                this.write_scalar(truncate_to_lane(sum, dest.layout.size), dest)?;
+            }
+
+            // Used to implement the vqtbl{1,2,3,4}{,q}_* and vtbl{1,2,3,4}_* functions.
+            // Looks up the bytes of a table of up to four vectors, using 0 for out-of-range
+            // indices.
+            "tbl1" | "tbl2" | "tbl3" | "tbl4" => {
+                let table_count = usize::from(op_name.as_bytes()[3].strict_sub(b'0'));
+                let args =
+                    check_variable_shim(this, abi, link_name, args, table_count.strict_add(1))?;
+
+                let (index, tables) = args.split_last().unwrap();
+                table_lookup(this, None, tables, index, dest)?;
+            }
+            // Used to implement the vqtbx{1,2,3,4}{,q}_* and vtbx{1,2,3,4}_* functions.
+            // Like `tbl`, but keeps the lanes of the first argument for out-of-range indices.
+            "tbx1" | "tbx2" | "tbx3" | "tbx4" => {
+                let table_count = usize::from(op_name.as_bytes()[3].strict_sub(b'0'));
+                let args =
+                    check_variable_shim(this, abi, link_name, args, table_count.strict_add(2))?;
+
+                let (fallback, args) = args.split_first().unwrap();
+                let (index, tables) = args.split_last().unwrap();
+                table_lookup(this, Some(fallback), tables, index, dest)?;
+            }
+
+            // Used to implement the vld1{,q}_*_x{2,3,4} and vld{2,3,4}{,q}_* functions.
+            // Loads vectors from consecutive memory, with the `ld{2,3,4}` variants
+            // de-interleaving the elements. The pointer does not need to be aligned.
+            "ld1x2" | "ld1x3" | "ld1x4" | "ld2" | "ld3" | "ld4" => {
+                let [ptr] = this.check_shim(abi, Abi::Unadjusted, link_name, args)?;
+
+                let ptr = this.read_pointer(ptr)?;
+                let interleaved = !op_name.starts_with("ld1");
+                let count = usize::from(op_name.as_bytes().last().unwrap().strict_sub(b'0'));
+                assert_eq!(dest.layout.fields.count(), count);
+
+                for k in 0..count {
+                    let (vector, len) = this.mplace_to_simd(&this.project_field(dest, k)?)?;
+                    for i in 0..len {
+                        let lane = this.project_index(&vector, i)?;
+                        let offset =
+                            element_offset(i, k, count, len, interleaved, lane.layout.size);
+                        let ptr = ptr.wrapping_offset(offset, &this.tcx);
+                        // Unaligned copy, which is what we want.
+                        this.mem_copy(
+                            ptr,
+                            lane.ptr(),
+                            lane.layout.size,
+                            /*nonoverlapping*/ true,
+                        )?;
+                    }
+                }
+            }
+            // Used to implement the vst1{,q}_*_x{2,3,4} and vst{2,3,4}{,q}_* functions.
+            // The counterpart of the loads above.
+            "st1x2" | "st1x3" | "st1x4" | "st2" | "st3" | "st4" => {
+                let count = usize::from(op_name.as_bytes().last().unwrap().strict_sub(b'0'));
+                let args = check_variable_shim(this, abi, link_name, args, count.strict_add(1))?;
+
+                let (ptr, vectors) = args.split_last().unwrap();
+                let ptr = this.read_pointer(ptr)?;
+                let interleaved = !op_name.starts_with("st1");
+
+                for (k, vector) in vectors.iter().enumerate() {
+                    let (vector, len) = this.operand_to_simd(vector)?;
+                    for i in 0..len {
+                        let lane = this.project_index(&vector, i)?;
+                        let offset =
+                            element_offset(i, k, count, len, interleaved, lane.layout.size);
+                        let ptr = ptr.wrapping_offset(offset, &this.tcx);
+                        // Unaligned copy, which is what we want.
+                        this.mem_copy(
+                            lane.ptr(),
+                            ptr,
+                            lane.layout.size,
+                            /*nonoverlapping*/ true,
+                        )?;
+                    }
+                }
+            }
+            _ => return Ok(EmulateItemResult::NotSupported),
+        }
+        Ok(EmulateItemResult::NeedsReturn)
+    }
+}
+
+/// Checks the ABI of an intrinsic whose number of arguments depends on its name,
+/// and that there are `count` arguments.
+fn check_variable_shim<'a, 'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    abi: Abi,
+    link_name: Symbol,
+    args: &'a [OpTy<'tcx>],
+    count: usize,
+) -> InterpResult<'tcx, &'a [OpTy<'tcx>]> {
+    this.check_abi_and_shim_symbol_clash(abi, Abi::Unadjusted, link_name)?;
+    if args.len() != count {
+        throw_ub_format!("incorrect number of arguments: got {}, expected {}", args.len(), count);
+    }
+    Ok(args)
+}
+
+/// Reads an integer lane as signed or unsigned.
+fn read_int<'tcx>(lane: &ImmTy<'tcx>, signed: bool) -> InterpResult<'tcx, i128> {
+    let scalar = lane.to_scalar();
+    let size = lane.layout.size;
+    Ok(if signed { scalar.to_int(size)? } else { scalar.to_uint(size)?.try_into().unwrap() })
+}
+
+/// Converts `val` to a lane of `size`, wrapping around if it does not fit.
+fn truncate_to_lane(val: i128, size: Size) -> Scalar {
+    #[allow(clippy::cast_sign_loss)]
+    Scalar::from_uint(size.truncate(val as u128), size)
+}
+
+/// Clamps `val` to the range of a signed or unsigned integer of `size`.
+fn saturate(val: i128, size: Size, signed: bool) -> i128 {
+    if signed {
+        val.clamp(size.signed_int_min(), size.signed_int_max())
+    } else {
+        val.clamp(0, size.unsigned_int_max().try_into().unwrap())
+    }
+}
+
+/// Shifts `val` left by the signed value of the lowest byte of `shift`, or right if that is
+/// negative. The right shift is arithmetic if `signed` is true.
+fn shift_left(val: i128, shift: i128, _size: Size, _signed: bool) -> i128 {
+    #[allow(clippy::cast_possible_truncation)]
+    let shift = shift as i8;
+    // `val` is at most 64 bits wide, so larger shifts do not change the result.
+    if shift >= 0 { val << shift.min(64) } else { val >> shift.unsigned_abs().min(64) }
+}
+
+/// Multiplies `left` and `right` as polynomials over GF(2).
+fn carryless_mul(left: u64, right: u64) -> u128 {
+    let mut result: u128 = 0;
+    for i in 0..64 {
+        if (right & (1 << i)) != 0 {
+            result ^= u128::from(left) << i;
+        }
+    }
+    result
+}
+
+/// Applies `f` to each pair of lanes of `left` and `right`, read as signed or unsigned integers,
+/// and stores the results, truncated to the lanes of `dest`, in `dest`. `f` also gets the
+/// size of the lanes of `dest` and `signed`.
+fn int_binary_op<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+    signed: bool,
+    f: impl Fn(i128, i128, Size, bool) -> i128,
+) -> InterpResult<'tcx, ()> {
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, left_len);
+    assert_eq!(dest_len, right_len);
+
+    for i in 0..dest_len {
+        let left = this.read_immediate(&this.project_index(&left, i)?)?;
+        let right = this.read_immediate(&this.project_index(&right, i)?)?;
+        let dest = this.project_index(&dest, i)?;
+
+        let res = f(read_int(&left, signed)?, read_int(&right, signed)?, dest.layout.size, signed);
+        this.write_scalar(truncate_to_lane(res, dest.layout.size), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Like `int_binary_op`, but for operations with a single operand.
+fn int_unary_op<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    op: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+    signed: bool,
+    f: impl Fn(i128, Size) -> i128,
+) -> InterpResult<'tcx, ()> {
+    let (op, op_len) = this.operand_to_simd(op)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, op_len);
+
+    for i in 0..dest_len {
+        let op = this.read_immediate(&this.project_index(&op, i)?)?;
+        let dest = this.project_index(&dest, i)?;
+
+        let res = f(read_int(&op, signed)?, dest.layout.size);
+        this.write_scalar(truncate_to_lane(res, dest.layout.size), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// An operation combining two lanes of the same type, shared by the lane-wise, pairwise and
+/// reducing intrinsics.
+#[derive(Copy, Clone)]
+enum LaneOp {
+    /// Wrapping addition of integers, or addition of floats.
+    Add,
+    /// Integer maximum or minimum, with the signedness given by the intrinsic.
+    IntMinMax(MinMax, bool),
+    /// Float maximum or minimum that returns NaN if either operand is NaN.
+    FMinMax(MinMax),
+    /// Float maximum or minimum that ignores a NaN operand.
+    FMinMaxNum(MinMax),
+    /// Absolute difference of floats.
+    FAbd,
+}
+
+impl LaneOp {
+    /// Parses the name of the operation, without the suffix that distinguishes
+    /// the pairwise and reducing variants.
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "add" | "sadd" | "uadd" | "fadd" => LaneOp::Add,
+            "smax" => LaneOp::IntMinMax(MinMax::Max, true),
+            "umax" => LaneOp::IntMinMax(MinMax::Max, false),
+            "smin" => LaneOp::IntMinMax(MinMax::Min, true),
+            "umin" => LaneOp::IntMinMax(MinMax::Min, false),
+            "fmax" => LaneOp::FMinMax(MinMax::Max),
+            "fmin" => LaneOp::FMinMax(MinMax::Min),
+            "fmaxnm" => LaneOp::FMinMaxNum(MinMax::Max),
+            "fminnm" => LaneOp::FMinMaxNum(MinMax::Min),
+            "fabd" => LaneOp::FAbd,
+            _ => return None,
+        })
+    }
+}
+
+fn lane_op<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    op: LaneOp,
+    left: &ImmTy<'tcx>,
+    right: &ImmTy<'tcx>,
+) -> InterpResult<'tcx, Scalar> {
+    Ok(match op {
+        // This wraps around for integers, and does NaN adjustments for floats.
+        LaneOp::Add => this.binary_op(mir::BinOp::Add, left, right)?.to_scalar(),
+        LaneOp::IntMinMax(min_max, signed) => {
+            let l = read_int(left, signed)?;
+            let r = read_int(right, signed)?;
+            let pick_left = match min_max {
+                MinMax::Max => l >= r,
+                MinMax::Min => l <= r,
+            };
+            if pick_left { left.to_scalar() } else { right.to_scalar() }
+        }
+        LaneOp::FMinMax(min_max) => {
+            if float_is_nan(left)? || float_is_nan(right)? {
+                // Adding propagates the NaN, with the usual NaN adjustments.
+                this.binary_op(mir::BinOp::Add, left, right)?.to_scalar()
+            } else {
+                this.fminmax_op(min_max, left, right)?
+            }
+        }
+        LaneOp::FMinMaxNum(min_max) => this.fminmax_op(min_max, left, right)?,
+        LaneOp::FAbd => {
+            let diff = this.binary_op(mir::BinOp::Sub, left, right)?;
+            let ty::Float(float_ty) = diff.layout.ty.kind() else {
+                bug!("fabd operand is not a float")
+            };
+            let diff = diff.to_scalar();
+            match float_ty {
+                FloatTy::F32 => Scalar::from_f32(diff.to_f32()?.abs()),
+                FloatTy::F64 => Scalar::from_f64(diff.to_f64()?.abs()),
+                FloatTy::F16 | FloatTy::F128 => unimplemented!("f16_f128"),
+            }
+        }
+    })
+}
+
+fn float_is_nan<'tcx>(op: &ImmTy<'tcx>) -> InterpResult<'tcx, bool> {
+    let ty::Float(float_ty) = op.layout.ty.kind() else { bug!("operand is not a float") };
+    Ok(match float_ty {
+        FloatTy::F32 => op.to_scalar().to_f32()?.is_nan(),
+        FloatTy::F64 => op.to_scalar().to_f64()?.is_nan(),
+        FloatTy::F16 | FloatTy::F128 => unimplemented!("f16_f128"),
+    })
+}
+
+/// Compares the absolute values of two floats. Returns `None` if either is NaN.
+fn float_abs_cmp<'tcx>(
+    left: &ImmTy<'tcx>,
+    right: &ImmTy<'tcx>,
+) -> InterpResult<'tcx, Option<Ordering>> {
+    let ty::Float(float_ty) = left.layout.ty.kind() else { bug!("operand is not a float") };
+    let (left, right) = (left.to_scalar(), right.to_scalar());
+    Ok(match float_ty {
+        FloatTy::F32 => left.to_f32()?.abs().partial_cmp(&right.to_f32()?.abs()),
+        FloatTy::F64 => left.to_f64()?.abs().partial_cmp(&right.to_f64()?.abs()),
+        FloatTy::F16 | FloatTy::F128 => unimplemented!("f16_f128"),
+    })
+}
+
+/// Looks up the bytes of `index` in the concatenation of `tables`. Lanes with an index past
+/// the end of the tables are taken from `fallback` if there is one, and are 0 otherwise.
+fn table_lookup<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    fallback: Option<&OpTy<'tcx>>,
+    tables: &[OpTy<'tcx>],
+    index: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let mut table = Vec::new();
+    for op in tables {
+        let (op, op_len) = this.operand_to_simd(op)?;
+        for i in 0..op_len {
+            table.push(this.read_scalar(&this.project_index(&op, i)?)?.to_u8()?);
+        }
+    }
+    let (index, index_len) = this.operand_to_simd(index)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, index_len);
+
+    let fallback = fallback.map(|fallback| this.operand_to_simd(fallback)).transpose()?;
+    for i in 0..dest_len {
+        let index = this.read_scalar(&this.project_index(&index, i)?)?.to_u8()?;
+        let dest = this.project_index(&dest, i)?;
+
+        let res = match (table.get(usize::from(index)), &fallback) {
+            (Some(&byte), _) => Scalar::from_u8(byte),
+            (None, Some((fallback, _))) => this.read_scalar(&this.project_index(fallback, i)?)?,
+            (None, None) => Scalar::from_u8(0),
+        };
+        this.write_scalar(res, &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Returns the memory offset of lane `i` of vector `k` of a multi-vector load or store of
+/// `count` vectors with `len` lanes each.
+fn element_offset(i: u64, k: usize, count: usize, len: u64, interleaved: bool, size: Size) -> Size {
+    let k = u64::try_from(k).unwrap();
+    let count = u64::try_from(count).unwrap();
+    let index = if interleaved {
+        // Lane `i` of all vectors are next to each other.
+        i.strict_mul(count).strict_add(k)
+    } else {
+        k.strict_mul(len).strict_add(i)
+    };
+    size * index
+}
@@ -71,6 +71,12 @@ fn clock_gettime(
                 // https://docs.oracle.com/cd/E23824_01/html/821-1465/clock-gettime-3c.html
                 relative_clocks = vec![this.eval_libc_i32("CLOCK_MONOTONIC")];
             }
+            "espidf" => {
+                // The REALTIME clock is only meaningful once the program set it, e.g. with SNTP,
+                // and MONOTONIC counts from boot.
+                absolute_clocks = vec![this.eval_libc_i32("CLOCK_REALTIME")];
+                relative_clocks = vec![this.eval_libc_i32("CLOCK_MONOTONIC")];
+            }
             target => throw_unsup_format!("`clock_gettime` is not supported on target OS {target}"),
         }
 
@@ -0,0 +1,214 @@
+use std::time::Duration;
+
+use rustc_span::Symbol;
+use rustc_target::abi::{Align, Size};
+use rustc_target::spec::abi::Abi;
+
+use crate::shims::alloc::EvalContextExt as _;
+use crate::*;
+use shims::unix::espidf::freertos::EvalContextExt as _;
+use shims::unix::espidf::pthread::EvalContextExt as _;
+
+pub fn is_dyn_sym(_name: &str) -> bool {
+    false
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn emulate_foreign_item_inner(
+        &mut self,
+        link_name: Symbol,
+        abi: Abi,
+        args: &[OpTy<'tcx>],
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, EmulateItemResult> {
+        let this = self.eval_context_mut();
+        match link_name.as_str() {
+            // Threading
+            "esp_pthread_get_default_config" => {
+                let [] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.esp_pthread_get_default_config(dest)?;
+            }
+            "esp_pthread_set_cfg" => {
+                let [cfg] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.esp_pthread_set_cfg(cfg)?;
+                this.write_scalar(result, dest)?;
+            }
+            "esp_pthread_get_cfg" => {
+                let [cfg] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.esp_pthread_get_cfg(cfg)?;
+                this.write_scalar(result, dest)?;
+            }
+
+            // FreeRTOS queues and semaphores
+            "xQueueGenericCreate" => {
+                let [length, item_size, queue_type] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let queue = this.xQueueGenericCreate(length, item_size, queue_type)?;
+                this.write_pointer(queue, dest)?;
+            }
+            "xQueueCreateMutex" => {
+                let [queue_type] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let queue = this.xQueueCreateMutex(queue_type)?;
+                this.write_pointer(queue, dest)?;
+            }
+            "xQueueCreateCountingSemaphore" => {
+                let [max_count, initial_count] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let queue = this.xQueueCreateCountingSemaphore(max_count, initial_count)?;
+                this.write_pointer(queue, dest)?;
+            }
+            "vQueueDelete" => {
+                let [queue] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.vQueueDelete(queue)?;
+            }
+            "xQueueGenericSend" => {
+                let [queue, item, ticks, position] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.xQueueGenericSend(queue, item, ticks, position, dest)?;
+            }
+            "xQueueReceive" => {
+                let [queue, buf, ticks] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.xQueueReceive(queue, buf, ticks, dest)?;
+            }
+            "xQueueSemaphoreTake" => {
+                let [queue, ticks] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.xQueueSemaphoreTake(queue, ticks, dest)?;
+            }
+            "xQueueTakeMutexRecursive" => {
+                let [mutex, ticks] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.xQueueTakeMutexRecursive(mutex, ticks, dest)?;
+            }
+            "xQueueGiveMutexRecursive" => {
+                let [mutex] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.xQueueGiveMutexRecursive(mutex, dest)?;
+            }
+            "uxQueueMessagesWaiting" => {
+                let [queue] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.uxQueueMessagesWaiting(queue)?;
+                this.write_scalar(result, dest)?;
+            }
+            "uxQueueSpacesAvailable" => {
+                let [queue] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.uxQueueSpacesAvailable(queue)?;
+                this.write_scalar(result, dest)?;
+            }
+            "vTaskDelay" => {
+                let [ticks] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.vTaskDelay(ticks)?;
+            }
+            "xTaskGetTickCount" => {
+                let [] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.xTaskGetTickCount()?;
+                this.write_scalar(result, dest)?;
+            }
+
+            // Time
+            "esp_timer_get_time" => {
+                let [] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                // The time since boot, in microseconds.
+                let duration = this.machine.clock.now().duration_since(this.machine.clock.epoch());
+                let micros = i64::try_from(duration.as_micros()).unwrap();
+                this.write_scalar(Scalar::from_i64(micros), dest)?;
+            }
+            "usleep" => {
+                let [usec] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let usec = this.read_scalar(usec)?.to_u32()?;
+                this.block_thread(
+                    BlockReason::Sleep,
+                    Some((
+                        TimeoutClock::Monotonic,
+                        TimeoutAnchor::Relative,
+                        Duration::from_micros(usec.into()),
+                    )),
+                    callback!(
+                        @capture<'tcx> {}
+                        @unblock = |_this| { panic!("sleeping thread unblocked before time is up") }
+                        @timeout = |_this| { Ok(()) }
+                    ),
+                );
+                this.write_null(dest)?;
+            }
+
+            // Random data generation
+            "esp_fill_random" => {
+                let [buf, len] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let buf = this.read_pointer(buf)?;
+                let len = this.read_target_usize(len)?;
+                this.gen_random(buf, len)?;
+            }
+
+            // Allocation with capabilities.
+            // Miri has a single heap, so the capabilities are ignored.
+            "heap_caps_malloc" => {
+                let [size, _caps] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let size = this.read_target_usize(size)?;
+                let res = this.malloc(size, /*zero_init:*/ false)?;
+                this.write_pointer(res, dest)?;
+            }
+            "heap_caps_calloc" => {
+                let [items, len, _caps] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let items = this.read_target_usize(items)?;
+                let len = this.read_target_usize(len)?;
+                let size = items
+                    .checked_mul(len)
+                    .ok_or_else(|| err_ub_format!("overflow during calloc size computation"))?;
+                let res = this.malloc(size, /*zero_init:*/ true)?;
+                this.write_pointer(res, dest)?;
+            }
+            "heap_caps_realloc" => {
+                let [old_ptr, new_size, _caps] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let old_ptr = this.read_pointer(old_ptr)?;
+                let new_size = this.read_target_usize(new_size)?;
+                // Unlike `realloc`, this frees the memory when the size is zero.
+                if new_size == 0 {
+                    this.free(old_ptr)?;
+                    this.write_null(dest)?;
+                } else {
+                    let res = this.realloc(old_ptr, new_size)?;
+                    this.write_pointer(res, dest)?;
+                }
+            }
+            "heap_caps_aligned_alloc" => {
+                let [align, size, _caps] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let align = this.read_target_usize(align)?;
+                let size = this.read_target_usize(size)?;
+                // Unlike `aligned_alloc`, the size does not need to be a multiple of the alignment.
+                if align.is_power_of_two() {
+                    let align = align.max(this.malloc_align(size).bytes());
+                    let ptr = this.allocate_ptr(
+                        Size::from_bytes(size),
+                        Align::from_bytes(align).unwrap(),
+                        MiriMemoryKind::C.into(),
+                    )?;
+                    this.write_pointer(ptr, dest)?;
+                } else {
+                    this.write_null(dest)?;
+                }
+            }
+            "heap_caps_free" | "heap_caps_aligned_free" => {
+                let [ptr] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let ptr = this.read_pointer(ptr)?;
+                this.free(ptr)?;
+            }
+
+            // Miscellaneous
+            "__errno" => {
+                let [] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let errno_place = this.last_error_place()?;
+                this.write_scalar(errno_place.to_ref(this).to_scalar(), dest)?;
+            }
+
+            _ => return Ok(EmulateItemResult::NotSupported),
+        }
+        Ok(EmulateItemResult::NeedsReturn)
+    }
+}
@@ -0,0 +1,506 @@
+//! FreeRTOS queues, which FreeRTOS also uses to implement semaphores and mutexes.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use rustc_target::abi::Size;
+
+use crate::concurrency::VClock;
+use crate::*;
+
+/// `configTICK_RATE_HZ` of the default ESP-IDF configuration.
+const TICK_RATE_HZ: u64 = 100;
+/// `portMAX_DELAY`: block without a timeout.
+const MAX_DELAY: u32 = u32::MAX;
+
+/// `pdTRUE`, also called `pdPASS`.
+const PD_TRUE: i32 = 1;
+/// `pdFALSE`, also called `pdFAIL`, `errQUEUE_FULL` and `errQUEUE_EMPTY`.
+const PD_FALSE: i32 = 0;
+
+// Values of `xCopyPosition`.
+const QUEUE_SEND_TO_BACK: i32 = 0;
+const QUEUE_SEND_TO_FRONT: i32 = 1;
+const QUEUE_OVERWRITE: i32 = 2;
+
+/// A FreeRTOS queue. Semaphores are queues of zero-sized items, and mutexes are semaphores that
+/// remember which thread took them.
+#[derive(Debug)]
+pub struct Queue {
+    /// The ring buffer holding `length` items of `item_size` bytes. Handles point to it.
+    storage: Pointer,
+    item_size: u64,
+    length: u64,
+    /// The index of the oldest item in `storage`.
+    head: u64,
+    /// The number of items in the queue.
+    count: u64,
+    /// Whether this queue is a mutex.
+    is_mutex: bool,
+    /// For mutexes, the thread that took it, and how many times it did so recursively.
+    holder: Option<(ThreadId, u32)>,
+    /// The threads waiting for space in the queue.
+    senders: VecDeque<ThreadId>,
+    /// The threads waiting for an item in the queue.
+    receivers: VecDeque<ThreadId>,
+    /// FreeRTOS accesses queues in a critical section, so every operation on a queue
+    /// synchronizes with the previous one. This is the clock of the last thread accessing it.
+    clock: VClock,
+}
+
+impl VisitProvenance for Queue {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        self.storage.visit_provenance(visit);
+    }
+}
+
+fn ticks_to_duration(ticks: u32) -> Duration {
+    Duration::from_millis(u64::from(ticks).strict_mul(1000).strict_div(TICK_RATE_HZ))
+}
+
+fn ticks_to_timeout(ticks: u32) -> Option<(TimeoutClock, TimeoutAnchor, Duration)> {
+    if ticks == MAX_DELAY {
+        return None;
+    }
+    Some((TimeoutClock::Monotonic, TimeoutAnchor::Relative, ticks_to_duration(ticks)))
+}
+
+impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Returns the ID of the queue `handle_op` points to.
+    fn queue_get_id(&self, handle_op: &OpTy<'tcx>) -> InterpResult<'tcx, AllocId> {
+        let this = self.eval_context_ref();
+        let handle = this.read_pointer(handle_op)?;
+        if let Ok((id, offset, _)) = this.ptr_try_get_alloc_id(handle, 0)
+            && offset == Size::ZERO
+            && this.machine.espidf.queues.contains_key(&id)
+        {
+            Ok(id)
+        } else {
+            throw_ub_format!("`{handle:?}` is not a FreeRTOS queue handle")
+        }
+    }
+
+    fn queue_create(
+        &mut self,
+        length: u64,
+        item_size: u64,
+        count: u64,
+        is_mutex: bool,
+    ) -> InterpResult<'tcx, Pointer> {
+        let this = self.eval_context_mut();
+        let Some(size) = length.checked_mul(item_size) else {
+            return Ok(Pointer::null());
+        };
+        let storage: Pointer = this
+            .allocate_ptr(
+                Size::from_bytes(size),
+                this.tcx.data_layout.pointer_align.abi,
+                MiriMemoryKind::Machine.into(),
+            )?
+            .into();
+        let (id, _, _) = this.ptr_get_alloc_id(storage, 0)?;
+        let queue = Queue {
+            storage,
+            item_size,
+            length,
+            head: 0,
+            count,
+            is_mutex,
+            holder: None,
+            senders: VecDeque::new(),
+            receivers: VecDeque::new(),
+            clock: VClock::default(),
+        };
+        this.machine.espidf.queues.insert(id, queue);
+        Ok(storage)
+    }
+
+    /// Enters the critical section of the queue.
+    fn queue_acquire(&self, id: AllocId) {
+        let this = self.eval_context_ref();
+        if let Some(data_race) = &this.machine.data_race {
+            data_race.acquire_clock(&this.machine.espidf.queues[&id].clock, &this.machine.threads);
+        }
+    }
+
+    /// Leaves the critical section of the queue.
+    fn queue_release(&mut self, id: AllocId) {
+        let this = self.eval_context_mut();
+        if let Some(data_race) = &this.machine.data_race {
+            let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+            queue.clock.clone_from(&data_race.release_clock(&this.machine.threads));
+        }
+    }
+
+    /// Copies an item into a queue that has space for it, and wakes up a thread waiting for it.
+    fn queue_push(&mut self, id: AllocId, item: Pointer, position: i32) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.queue_acquire(id);
+
+        let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+        let slot = match position {
+            QUEUE_SEND_TO_FRONT => {
+                queue.head =
+                    queue.head.strict_add(queue.length).strict_sub(1).strict_rem(queue.length);
+                queue.count = queue.count.strict_add(1);
+                queue.head
+            }
+            // Overwriting is only allowed on queues of length 1.
+            QUEUE_OVERWRITE if queue.count == 1 => queue.head,
+            _ => {
+                let slot = queue.head.strict_add(queue.count).strict_rem(queue.length);
+                queue.count = queue.count.strict_add(1);
+                slot
+            }
+        };
+        assert!(queue.count <= queue.length, "pushed to a full queue");
+        if queue.is_mutex {
+            queue.holder = None;
+        }
+        let (storage, item_size) = (queue.storage, queue.item_size);
+        if item_size != 0 {
+            let slot = storage.wrapping_offset(Size::from_bytes(slot.strict_mul(item_size)), this);
+            this.mem_copy(item, slot, Size::from_bytes(item_size), /*nonoverlapping*/ true)?;
+        }
+
+        this.queue_release(id);
+        let receiver = this.machine.espidf.queues.get_mut(&id).unwrap().receivers.pop_front();
+        if let Some(thread) = receiver {
+            this.unblock_thread(thread, BlockReason::FreeRtosQueue(id))?;
+        }
+        Ok(())
+    }
+
+    /// Copies the oldest item out of a queue that has one, and wakes up a thread waiting for space.
+    /// Semaphores are taken by passing a null `buf`.
+    fn queue_pop(&mut self, id: AllocId, buf: Pointer) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.queue_acquire(id);
+
+        let thread = this.active_thread();
+        let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+        let slot = queue.head;
+        queue.head = queue.head.strict_add(1).strict_rem(queue.length);
+        queue.count = queue.count.checked_sub(1).expect("popped from an empty queue");
+        if queue.is_mutex {
+            queue.holder = Some((thread, 1));
+        }
+        let (storage, item_size) = (queue.storage, queue.item_size);
+        if item_size != 0 {
+            let slot = storage.wrapping_offset(Size::from_bytes(slot.strict_mul(item_size)), this);
+            this.mem_copy(slot, buf, Size::from_bytes(item_size), /*nonoverlapping*/ true)?;
+        }
+
+        this.queue_release(id);
+        let sender = this.machine.espidf.queues.get_mut(&id).unwrap().senders.pop_front();
+        if let Some(thread) = sender {
+            this.unblock_thread(thread, BlockReason::FreeRtosQueue(id))?;
+        }
+        Ok(())
+    }
+
+    /// Receives an item from the queue, blocking for up to `ticks` if it is empty.
+    fn queue_receive(
+        &mut self,
+        id: AllocId,
+        buf: Pointer,
+        ticks: u32,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        if this.machine.espidf.queues[&id].count > 0 {
+            this.queue_pop(id, buf)?;
+            return this.write_scalar(Scalar::from_i32(PD_TRUE), dest);
+        }
+        if ticks == 0 {
+            return this.write_scalar(Scalar::from_i32(PD_FALSE), dest);
+        }
+
+        let thread = this.active_thread();
+        this.machine.espidf.queues.get_mut(&id).unwrap().receivers.push_back(thread);
+        let dest = dest.clone();
+        this.block_thread(
+            BlockReason::FreeRtosQueue(id),
+            ticks_to_timeout(ticks),
+            callback!(
+                @capture<'tcx> {
+                    id: AllocId,
+                    buf: Pointer,
+                    dest: MPlaceTy<'tcx>,
+                }
+                @unblock = |this| {
+                    this.queue_pop(id, buf)?;
+                    this.write_scalar(Scalar::from_i32(PD_TRUE), &dest)
+                }
+                @timeout = |this| {
+                    let thread = this.active_thread();
+                    let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+                    queue.receivers.retain(|&waiter| waiter != thread);
+                    this.write_scalar(Scalar::from_i32(PD_FALSE), &dest)
+                }
+            ),
+        );
+        Ok(())
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+#[allow(non_snake_case)]
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn xQueueGenericCreate(
+        &mut self,
+        length_op: &OpTy<'tcx>,
+        item_size_op: &OpTy<'tcx>,
+        _queue_type_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Pointer> {
+        let this = self.eval_context_mut();
+
+        let length = this.read_scalar(length_op)?.to_u32()?;
+        let item_size = this.read_scalar(item_size_op)?.to_u32()?;
+
+        if length == 0 {
+            return Ok(Pointer::null());
+        }
+        // Binary semaphores are created empty, like all other queues.
+        this.queue_create(length.into(), item_size.into(), 0, /*is_mutex*/ false)
+    }
+
+    fn xQueueCreateMutex(&mut self, _queue_type_op: &OpTy<'tcx>) -> InterpResult<'tcx, Pointer> {
+        let this = self.eval_context_mut();
+        // Recursive and non-recursive mutexes only differ in which functions take and give them.
+        this.queue_create(1, 0, 1, /*is_mutex*/ true)
+    }
+
+    fn xQueueCreateCountingSemaphore(
+        &mut self,
+        max_count_op: &OpTy<'tcx>,
+        initial_count_op: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Pointer> {
+        let this = self.eval_context_mut();
+
+        let max_count = this.read_scalar(max_count_op)?.to_u32()?;
+        let initial_count = this.read_scalar(initial_count_op)?.to_u32()?;
+
+        if max_count == 0 || initial_count > max_count {
+            return Ok(Pointer::null());
+        }
+        this.queue_create(max_count.into(), 0, initial_count.into(), /*is_mutex*/ false)
+    }
+
+    fn vQueueDelete(&mut self, queue_op: &OpTy<'tcx>) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(queue_op)?;
+        let queue = &this.machine.espidf.queues[&id];
+        if !queue.senders.is_empty() || !queue.receivers.is_empty() {
+            throw_ub_format!("deleted a FreeRTOS queue while threads were blocked on it");
+        }
+        let queue = this.machine.espidf.queues.remove(&id).unwrap();
+        this.deallocate_ptr(queue.storage, None, MiriMemoryKind::Machine.into())
+    }
+
+    fn xQueueGenericSend(
+        &mut self,
+        queue_op: &OpTy<'tcx>,
+        item_op: &OpTy<'tcx>,
+        ticks_op: &OpTy<'tcx>,
+        position_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(queue_op)?;
+        let item = this.read_pointer(item_op)?;
+        let ticks = this.read_scalar(ticks_op)?.to_u32()?;
+        let position = this.read_scalar(position_op)?.to_i32()?;
+
+        let thread = this.active_thread();
+        let queue = &this.machine.espidf.queues[&id];
+        match position {
+            QUEUE_SEND_TO_BACK | QUEUE_SEND_TO_FRONT => {}
+            QUEUE_OVERWRITE if queue.length == 1 => {}
+            QUEUE_OVERWRITE =>
+                throw_ub_format!("overwrote an item of a FreeRTOS queue longer than one item"),
+            _ => throw_ub_format!("invalid `xCopyPosition` {position} for `xQueueGenericSend`"),
+        }
+        if queue.is_mutex {
+            // Giving a mutex that one does not hold fails.
+            if queue.holder.map(|(holder, _)| holder) != Some(thread) {
+                return this.write_scalar(Scalar::from_i32(PD_FALSE), dest);
+            }
+        }
+
+        if queue.count < queue.length || position == QUEUE_OVERWRITE {
+            this.queue_push(id, item, position)?;
+            return this.write_scalar(Scalar::from_i32(PD_TRUE), dest);
+        }
+        if ticks == 0 {
+            return this.write_scalar(Scalar::from_i32(PD_FALSE), dest);
+        }
+
+        this.machine.espidf.queues.get_mut(&id).unwrap().senders.push_back(thread);
+        let dest = dest.clone();
+        this.block_thread(
+            BlockReason::FreeRtosQueue(id),
+            ticks_to_timeout(ticks),
+            callback!(
+                @capture<'tcx> {
+                    id: AllocId,
+                    item: Pointer,
+                    position: i32,
+                    dest: MPlaceTy<'tcx>,
+                }
+                @unblock = |this| {
+                    this.queue_push(id, item, position)?;
+                    this.write_scalar(Scalar::from_i32(PD_TRUE), &dest)
+                }
+                @timeout = |this| {
+                    let thread = this.active_thread();
+                    let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+                    queue.senders.retain(|&waiter| waiter != thread);
+                    this.write_scalar(Scalar::from_i32(PD_FALSE), &dest)
+                }
+            ),
+        );
+        Ok(())
+    }
+
+    fn xQueueReceive(
+        &mut self,
+        queue_op: &OpTy<'tcx>,
+        buf_op: &OpTy<'tcx>,
+        ticks_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(queue_op)?;
+        let buf = this.read_pointer(buf_op)?;
+        let ticks = this.read_scalar(ticks_op)?.to_u32()?;
+
+        this.queue_receive(id, buf, ticks, dest)
+    }
+
+    fn xQueueSemaphoreTake(
+        &mut self,
+        queue_op: &OpTy<'tcx>,
+        ticks_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(queue_op)?;
+        let ticks = this.read_scalar(ticks_op)?.to_u32()?;
+
+        if this.machine.espidf.queues[&id].item_size != 0 {
+            throw_ub_format!("took a FreeRTOS queue that is not a semaphore");
+        }
+        // Taking a mutex that the thread already holds blocks, and may deadlock.
+        this.queue_receive(id, Pointer::null(), ticks, dest)
+    }
+
+    fn xQueueTakeMutexRecursive(
+        &mut self,
+        mutex_op: &OpTy<'tcx>,
+        ticks_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(mutex_op)?;
+        let ticks = this.read_scalar(ticks_op)?.to_u32()?;
+
+        let thread = this.active_thread();
+        let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+        if !queue.is_mutex {
+            throw_ub_format!("took a FreeRTOS queue that is not a mutex recursively");
+        }
+        if let Some((holder, recursion)) = &mut queue.holder
+            && *holder == thread
+        {
+            *recursion = recursion.strict_add(1);
+            return this.write_scalar(Scalar::from_i32(PD_TRUE), dest);
+        }
+        this.queue_receive(id, Pointer::null(), ticks, dest)
+    }
+
+    fn xQueueGiveMutexRecursive(
+        &mut self,
+        mutex_op: &OpTy<'tcx>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(mutex_op)?;
+
+        let thread = this.active_thread();
+        let queue = this.machine.espidf.queues.get_mut(&id).unwrap();
+        if !queue.is_mutex {
+            throw_ub_format!("gave a FreeRTOS queue that is not a mutex recursively");
+        }
+        let Some((holder, recursion)) = &mut queue.holder else {
+            return this.write_scalar(Scalar::from_i32(PD_FALSE), dest);
+        };
+        if *holder != thread {
+            return this.write_scalar(Scalar::from_i32(PD_FALSE), dest);
+        }
+        *recursion = recursion.strict_sub(1);
+        if *recursion == 0 {
+            this.queue_push(id, Pointer::null(), QUEUE_SEND_TO_BACK)?;
+        }
+        this.write_scalar(Scalar::from_i32(PD_TRUE), dest)
+    }
+
+    fn uxQueueMessagesWaiting(&mut self, queue_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(queue_op)?;
+        this.queue_acquire(id);
+        let count = this.machine.espidf.queues[&id].count;
+        this.queue_release(id);
+        Ok(Scalar::from_u32(count.try_into().unwrap()))
+    }
+
+    fn uxQueueSpacesAvailable(&mut self, queue_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let id = this.queue_get_id(queue_op)?;
+        this.queue_acquire(id);
+        let queue = &this.machine.espidf.queues[&id];
+        let spaces = queue.length.strict_sub(queue.count);
+        this.queue_release(id);
+        Ok(Scalar::from_u32(spaces.try_into().unwrap()))
+    }
+
+    fn vTaskDelay(&mut self, ticks_op: &OpTy<'tcx>) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let ticks = this.read_scalar(ticks_op)?.to_u32()?;
+
+        if ticks == 0 {
+            this.yield_active_thread();
+            return Ok(());
+        }
+        // Unlike when blocking on a queue, `portMAX_DELAY` is just a very long delay here.
+        this.block_thread(
+            BlockReason::Sleep,
+            Some((TimeoutClock::Monotonic, TimeoutAnchor::Relative, ticks_to_duration(ticks))),
+            callback!(
+                @capture<'tcx> {}
+                @unblock = |_this| { panic!("sleeping thread unblocked before time is up") }
+                @timeout = |_this| { Ok(()) }
+            ),
+        );
+        Ok(())
+    }
+
+    fn xTaskGetTickCount(&mut self) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let duration = this.machine.clock.now().duration_since(this.machine.clock.epoch());
+        let ticks = duration.as_millis().strict_mul(TICK_RATE_HZ.into()).strict_div(1000);
+        // The tick count wraps around.
+        Ok(Scalar::from_u32(u32::try_from(ticks & u128::from(u32::MAX)).unwrap()))
+    }
+}
@@ -0,0 +1,29 @@
+use rustc_data_structures::fx::FxHashMap;
+
+use crate::*;
+
+pub mod foreign_items;
+pub mod freertos;
+pub mod pthread;
+
+/// The ESP-IDF and FreeRTOS objects that Miri keeps outside of the program's memory.
+#[derive(Debug, Default)]
+pub struct EspIdfState {
+    /// The FreeRTOS queues, keyed by the allocation their handle points to.
+    queues: FxHashMap<AllocId, freertos::Queue>,
+    /// A copy of the configuration each thread passed to `esp_pthread_set_cfg`.
+    pthread_cfgs: FxHashMap<ThreadId, Pointer>,
+}
+
+impl VisitProvenance for EspIdfState {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        let EspIdfState { queues, pthread_cfgs } = self;
+
+        for queue in queues.values() {
+            queue.visit_provenance(visit);
+        }
+        for cfg in pthread_cfgs.values() {
+            cfg.visit_provenance(visit);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+//! The `esp_pthread` configuration of the threads that a thread creates.
+//!
+//! The stack size, priority and core of a thread have no effect on Miri, so we only keep the
+//! configuration around for `esp_pthread_get_cfg`.
+
+use crate::*;
+
+const ESP_OK: i32 = 0;
+const ESP_ERR_INVALID_ARG: i32 = 0x102;
+const ESP_ERR_NOT_FOUND: i32 = 0x105;
+
+/// `CONFIG_PTHREAD_STACK_MIN` of the default ESP-IDF configuration.
+const PTHREAD_STACK_MIN: u64 = 768;
+/// `CONFIG_PTHREAD_TASK_STACK_SIZE_DEFAULT` of the default ESP-IDF configuration.
+const PTHREAD_STACK_SIZE_DEFAULT: i128 = 3072;
+/// `CONFIG_PTHREAD_TASK_PRIO_DEFAULT` of the default ESP-IDF configuration.
+const PTHREAD_PRIO_DEFAULT: i128 = 5;
+/// `tskNO_AFFINITY`.
+const NO_AFFINITY: i128 = 0x7fff_ffff;
+
+const MALLOC_CAP_8BIT: u32 = 1 << 2;
+const MALLOC_CAP_INTERNAL: u32 = 1 << 11;
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn esp_pthread_get_default_config(&mut self, dest: &MPlaceTy<'tcx>) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        this.write_int_fields_named(
+            &[
+                ("stack_size", PTHREAD_STACK_SIZE_DEFAULT),
+                ("prio", PTHREAD_PRIO_DEFAULT),
+                ("pin_to_core", NO_AFFINITY),
+                ("stack_alloc_caps", (MALLOC_CAP_8BIT | MALLOC_CAP_INTERNAL).into()),
+            ],
+            dest,
+        )?;
+        this.write_scalar(
+            Scalar::from_bool(false),
+            &this.project_field_named(dest, "inherit_cfg")?,
+        )?;
+        // A null name makes ESP-IDF use its default name.
+        this.write_null(&this.project_field_named(dest, "thread_name")?)?;
+        Ok(())
+    }
+
+    fn esp_pthread_set_cfg(&mut self, cfg_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let cfg = this.deref_pointer(cfg_op)?;
+        let stack_size = this.read_target_usize(&this.project_field_named(&cfg, "stack_size")?)?;
+        let caps =
+            this.read_scalar(&this.project_field_named(&cfg, "stack_alloc_caps")?)?.to_u32()?;
+        if stack_size < PTHREAD_STACK_MIN || caps & MALLOC_CAP_8BIT == 0 {
+            return Ok(Scalar::from_i32(ESP_ERR_INVALID_ARG));
+        }
+
+        // Keep a copy, as ESP-IDF does. The threads created afterwards do not inherit it.
+        let thread = this.active_thread();
+        let copy = match this.machine.espidf.pthread_cfgs.get(&thread) {
+            Some(&copy) => copy,
+            None => {
+                let copy = this.allocate_ptr(
+                    cfg.layout.size,
+                    cfg.layout.align.abi,
+                    MiriMemoryKind::Machine.into(),
+                )?;
+                let copy = copy.into();
+                this.machine.espidf.pthread_cfgs.insert(thread, copy);
+                copy
+            }
+        };
+        this.mem_copy(cfg.ptr(), copy, cfg.layout.size, /*nonoverlapping*/ true)?;
+        Ok(Scalar::from_i32(ESP_OK))
+    }
+
+    fn esp_pthread_get_cfg(&mut self, cfg_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let cfg = this.deref_pointer(cfg_op)?;
+        let Some(&copy) = this.machine.espidf.pthread_cfgs.get(&this.active_thread()) else {
+            return Ok(Scalar::from_i32(ESP_ERR_NOT_FOUND));
+        };
+        this.mem_copy(copy, cfg.ptr(), cfg.layout.size, /*nonoverlapping*/ true)?;
+        Ok(Scalar::from_i32(ESP_OK))
+    }
+}
@@ -97,6 +97,11 @@ fn flock<'tcx>(
         throw_unsup_format!("cannot flock {}", self.name());
     }
 
+    /// Sets whether reads and writes return `EWOULDBLOCK` instead of blocking.
+    fn set_nonblocking<'tcx>(&self, _nonblocking: bool) -> InterpResult<'tcx> {
+        throw_unsup_format!("cannot change the blocking mode of {}", self.name());
+    }
+
     fn is_tty(&self, _communicate_allowed: bool) -> bool {
         // Most FDs are not tty's and the consequence of a wrong `false` are minor,
         // so we use a default impl here.
@@ -500,6 +505,43 @@ fn fcntl(&mut self, args: &[OpTy<'tcx>]) -> InterpResult<'tcx, Scalar> {
         }
     }
 
+    fn ioctl(&mut self, args: &[OpTy<'tcx>]) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        if args.len() < 2 {
+            throw_ub_format!(
+                "incorrect number of arguments for ioctl: got {}, expected at least 2",
+                args.len()
+            );
+        }
+        let fd = this.read_scalar(&args[0])?.to_i32()?;
+        let request = this.read_scalar(&args[1])?.to_bits(args[1].layout.size)?;
+
+        let Some(file_description) = this.machine.fds.get(fd) else {
+            return Ok(Scalar::from_i32(this.fd_not_found()?));
+        };
+
+        let fionbio = this.eval_libc("FIONBIO");
+        let fioclex = this.eval_libc("FIOCLEX");
+        if request == fionbio.to_bits(fionbio.size())? {
+            if args.len() < 3 {
+                throw_ub_format!(
+                    "incorrect number of arguments for ioctl with request=`FIONBIO`: got {}, expected at least 3",
+                    args.len()
+                );
+            }
+            let nonblocking = this.deref_pointer_as(&args[2], this.machine.layouts.i32)?;
+            let nonblocking = this.read_scalar(&nonblocking)?.to_i32()?;
+            file_description.set_nonblocking(nonblocking != 0)?;
+            Ok(Scalar::from_i32(0))
+        } else if request == fioclex.to_bits(fioclex.size())? {
+            // We do not support `exec`, so there is nothing to do.
+            Ok(Scalar::from_i32(0))
+        } else {
+            throw_unsup_format!("the {:#x} request is not supported for `ioctl`", request);
+        }
+    }
+
     fn close(&mut self, fd_op: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
         let this = self.eval_context_mut();
 
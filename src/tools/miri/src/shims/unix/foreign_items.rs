@@ -12,6 +12,7 @@
 use crate::*;
 
 use shims::unix::android::foreign_items as android;
+use shims::unix::espidf::foreign_items as espidf;
 use shims::unix::freebsd::foreign_items as freebsd;
 use shims::unix::linux::foreign_items as linux;
 use shims::unix::macos::foreign_items as macos;
@@ -30,6 +31,7 @@ pub fn is_dyn_sym(name: &str, target_os: &str) -> bool {
         _ =>
             match target_os {
                 "android" => android::is_dyn_sym(name),
+                "espidf" => espidf::is_dyn_sym(name),
                 "freebsd" => freebsd::is_dyn_sym(name),
                 "linux" => linux::is_dyn_sym(name),
                 "macos" => macos::is_dyn_sym(name),
@@ -92,8 +94,11 @@ fn emulate_foreign_item_inner(
                 let fd = this.read_scalar(fd)?.to_i32()?;
                 let buf = this.read_pointer(buf)?;
                 let count = this.read_target_usize(count)?;
-                let result = this.read(fd, buf, count, None)?;
-                this.write_scalar(result, dest)?;
+                // Sockets can block the thread, which needs `dest`.
+                if !this.socket_read(fd, buf, count, dest)? {
+                    let result = this.read(fd, buf, count, None)?;
+                    this.write_scalar(result, dest)?;
+                }
             }
             "write" => {
                 let [fd, buf, n] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -101,9 +106,12 @@ fn emulate_foreign_item_inner(
                 let buf = this.read_pointer(buf)?;
                 let count = this.read_target_usize(n)?;
                 trace!("Called write({:?}, {:?}, {:?})", fd, buf, count);
-                let result = this.write(fd, buf, count, None)?;
-                // Now, `result` is the value we return back to the program.
-                this.write_scalar(result, dest)?;
+                // Sockets can block the thread, which needs `dest`.
+                if !this.socket_write(fd, buf, count, dest)? {
+                    let result = this.write(fd, buf, count, None)?;
+                    // Now, `result` is the value we return back to the program.
+                    this.write_scalar(result, dest)?;
+                }
             }
             "pread" => {
                 let [fd, buf, count, offset] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -157,6 +165,13 @@ fn emulate_foreign_item_inner(
                 let result = this.fcntl(args)?;
                 this.write_scalar(result, dest)?;
             }
+            "ioctl" => {
+                // `ioctl` is variadic. The argument count is checked based on the request
+                // in `this.ioctl()`, so we do not use `check_shim` here.
+                this.check_abi_and_shim_symbol_clash(abi, Abi::C { unwind: false }, link_name)?;
+                let result = this.ioctl(args)?;
+                this.write_scalar(result, dest)?;
+            }
             "dup" => {
                 let [old_fd] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
                 let old_fd = this.read_scalar(old_fd)?.to_i32()?;
@@ -308,6 +323,90 @@ fn emulate_foreign_item_inner(
                 this.write_scalar(result, dest)?;
             }
 
+            // TCP and UDP sockets on the virtual network
+            "socket" => {
+                let [domain, type_, protocol] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.socket(domain, type_, protocol)?;
+                this.write_scalar(result, dest)?;
+            }
+            "bind" => {
+                let [fd, addr, addrlen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.bind(fd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "listen" => {
+                let [fd, backlog] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.listen(fd, backlog)?;
+                this.write_scalar(result, dest)?;
+            }
+            "accept" => {
+                let [fd, addr, addrlen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.accept4(fd, addr, addrlen, /*flags*/ None, dest)?;
+            }
+            "accept4" if this.tcx.sess.target.os != "macos" => {
+                let [fd, addr, addrlen, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.accept4(fd, addr, addrlen, Some(flags), dest)?;
+            }
+            "connect" => {
+                let [fd, addr, addrlen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.connect(fd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "shutdown" => {
+                let [fd, how] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.shutdown(fd, how)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockname" => {
+                let [fd, addr, addrlen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockname(fd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getpeername" => {
+                let [fd, addr, addrlen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getpeername(fd, addr, addrlen)?;
+                this.write_scalar(result, dest)?;
+            }
+            "send" => {
+                let [fd, buf, len, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.sendto(fd, buf, len, flags, /*to*/ None, dest)?;
+            }
+            "sendto" => {
+                let [fd, buf, len, flags, to, tolen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.sendto(fd, buf, len, flags, Some((to, tolen)), dest)?;
+            }
+            "recv" => {
+                let [fd, buf, len, flags] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(fd, buf, len, flags, /*from*/ None, dest)?;
+            }
+            "recvfrom" => {
+                let [fd, buf, len, flags, from, fromlen] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                this.recvfrom(fd, buf, len, flags, Some((from, fromlen)), dest)?;
+            }
+            "setsockopt" => {
+                let [fd, level, name, value, len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.setsockopt(fd, level, name, value, len)?;
+                this.write_scalar(result, dest)?;
+            }
+            "getsockopt" => {
+                let [fd, level, name, value, len] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+                let result = this.getsockopt(fd, level, name, value, len)?;
+                this.write_scalar(result, dest)?;
+            }
+
             // Time
             "gettimeofday" => {
                 let [tv, tz] = this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
@@ -785,8 +884,8 @@ fn emulate_foreign_item_inner(
             }
             "getrandom" => {
                 // This function is non-standard but exists with the same signature and behavior on
-                // Linux, FreeBSD and Solaris/Illumos.
-                if !matches!(&*this.tcx.sess.target.os, "linux" | "freebsd" | "illumos" | "solaris" | "android") {
+                // Linux, FreeBSD, Solaris/Illumos and ESP-IDF.
+                if !matches!(&*this.tcx.sess.target.os, "linux" | "freebsd" | "illumos" | "solaris" | "android" | "espidf") {
                     throw_unsup_format!(
                         "`getrandom` is not supported on {}",
                         this.tcx.sess.target.os
@@ -936,6 +1035,7 @@ fn emulate_foreign_item_inner(
                 let target_os = &*this.tcx.sess.target.os;
                 return match target_os {
                     "android" => android::EvalContextExt::emulate_foreign_item_inner(this, link_name, abi, args, dest),
+                    "espidf" => espidf::EvalContextExt::emulate_foreign_item_inner(this, link_name, abi, args, dest),
                     "freebsd" => freebsd::EvalContextExt::emulate_foreign_item_inner(this, link_name, abi, args, dest),
                     "linux" => linux::EvalContextExt::emulate_foreign_item_inner(this, link_name, abi, args, dest),
                     "macos" => macos::EvalContextExt::emulate_foreign_item_inner(this, link_name, abi, args, dest),
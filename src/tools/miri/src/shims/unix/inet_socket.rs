@@ -0,0 +1,1520 @@
+//! This implements TCP and UDP sockets over IPv4 and IPv6 on top of a virtual network that lives
+//! entirely inside Miri. The only host of that network is the interpreted program itself: sockets
+//! can be bound to the loopback and unspecified addresses and talk to each other, but nothing
+//! ever reaches the host system.
+
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeMap, VecDeque};
+use std::io;
+use std::io::{ErrorKind, Read};
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6};
+use std::time::Duration;
+
+use rustc_target::abi::Size;
+
+use crate::shims::unix::fd::{FileDescriptionRef, WeakFileDescriptionRef};
+use crate::shims::unix::linux::epoll::{EpollReadyEvents, EvalContextExt as _};
+use crate::shims::unix::*;
+use crate::{concurrency::VClock, *};
+
+/// The maximum number of bytes buffered on the receiving side of a socket.
+/// Like for `socketpair`, this number is arbitrary.
+const MAX_SOCKET_BUFFER_CAPACITY: usize = 212992;
+
+/// The largest UDP payload that fits into an IPv4 packet.
+const MAX_DATAGRAM_SIZE: usize = 65507;
+
+/// The first port handed out to sockets that are bound to port 0 or implicitly bound by `connect`,
+/// `listen` or `sendto`. This is the start of the IANA dynamic port range, which ends at 65535.
+const FIRST_EPHEMERAL_PORT: u16 = 49152;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum SocketKind {
+    /// `SOCK_STREAM`, i.e., TCP.
+    Stream,
+    /// `SOCK_DGRAM`, i.e., UDP.
+    Datagram,
+}
+
+/// The sockets that are bound to an address of the virtual network, so that `connect` and
+/// `sendto` can find them.
+#[derive(Debug)]
+pub struct VirtualNetwork {
+    bound: BTreeMap<(SocketKind, SocketAddr), WeakFileDescriptionRef>,
+    next_ephemeral_port: u16,
+}
+
+impl Default for VirtualNetwork {
+    fn default() -> Self {
+        VirtualNetwork { bound: BTreeMap::new(), next_ephemeral_port: FIRST_EPHEMERAL_PORT }
+    }
+}
+
+impl VirtualNetwork {
+    /// Whether another socket of the same kind and address family already uses this port.
+    /// There is only one host on the network, so unlike on a real system, binding to different
+    /// addresses does not allow sockets to share a port.
+    fn is_port_in_use(&self, kind: SocketKind, addr: SocketAddr) -> bool {
+        self.bound.iter().any(|(&(bound_kind, bound_addr), socket)| {
+            bound_kind == kind
+                && bound_addr.is_ipv6() == addr.is_ipv6()
+                && bound_addr.port() == addr.port()
+                && socket.upgrade().is_some()
+        })
+    }
+
+    /// Finds the socket that receives what is sent to `addr`.
+    fn lookup(&self, kind: SocketKind, addr: SocketAddr) -> Option<FileDescriptionRef> {
+        self.bound.iter().find_map(|(&(bound_kind, bound_addr), socket)| {
+            let same_ip = bound_addr.ip() == addr.ip()
+                || (bound_addr.ip().is_unspecified() && bound_addr.is_ipv6() == addr.is_ipv6());
+            if bound_kind == kind && bound_addr.port() == addr.port() && same_ip {
+                socket.upgrade()
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Picks a free port, going through the dynamic port range in order.
+    fn ephemeral_port(&mut self, kind: SocketKind, addr: SocketAddr) -> Option<u16> {
+        let num_ephemeral_ports = u16::MAX.strict_sub(FIRST_EPHEMERAL_PORT).strict_add(1);
+        for _ in 0..num_ephemeral_ports {
+            let port = self.next_ephemeral_port;
+            self.next_ephemeral_port =
+                if port == u16::MAX { FIRST_EPHEMERAL_PORT } else { port.strict_add(1) };
+            if !self.is_port_in_use(kind, SocketAddr::new(addr.ip(), port)) {
+                return Some(port);
+            }
+        }
+        None
+    }
+}
+
+/// Since the program is the only host on the network, the unspecified address stands for the
+/// loopback address when connecting or sending to it, and when reporting the local address of a
+/// connected socket. This is also what Linux does.
+fn loopback_if_unspecified(addr: SocketAddr) -> SocketAddr {
+    if !addr.ip().is_unspecified() {
+        return addr;
+    }
+    let ip = match addr {
+        SocketAddr::V4(_) => IpAddr::V4(Ipv4Addr::LOCALHOST),
+        SocketAddr::V6(_) => IpAddr::V6(Ipv6Addr::LOCALHOST),
+    };
+    SocketAddr::new(ip, addr.port())
+}
+
+/// A TCP or UDP socket.
+#[derive(Debug)]
+struct InetSocket {
+    kind: SocketKind,
+    is_ipv6: bool,
+    is_nonblock: Cell<bool>,
+    /// The address this socket is bound to, or `None` if it is not bound yet.
+    local_addr: Cell<Option<SocketAddr>>,
+    /// The peer of a connected stream socket, or the default destination of a datagram socket.
+    peer_addr: Cell<Option<SocketAddr>>,
+    /// Whether this is a listening or connected stream socket. Datagram sockets are always
+    /// `Unconnected`, even when they have a default destination.
+    state: RefCell<SocketState>,
+    /// The datagrams that have been sent to this socket and were not received yet.
+    datagrams: RefCell<VecDeque<Datagram>>,
+    /// The timeouts set with `SO_RCVTIMEO` and `SO_SNDTIMEO`.
+    read_timeout: Cell<Option<Duration>>,
+    write_timeout: Cell<Option<Duration>>,
+    /// The value of `TCP_NODELAY`. Nothing is ever delayed on the virtual network.
+    nodelay: Cell<bool>,
+    /// The threads blocked until something happens to this socket.
+    blocked_threads: RefCell<Vec<ThreadId>>,
+}
+
+#[derive(Debug)]
+enum SocketState {
+    Unconnected,
+    /// The connections that were established with this socket and were not accepted yet,
+    /// together with the clock of the thread that connected.
+    Listening(VecDeque<(FileDescriptionRef, VClock)>),
+    Connected(Connection),
+}
+
+/// One end of a TCP connection.
+#[derive(Debug)]
+struct Connection {
+    /// The other end of the connection. This is a weak reference because the peer may be closed
+    /// before us; all future reads will then see EOF and all future writes will trigger EPIPE.
+    peer: WeakFileDescriptionRef,
+    /// The data sent by the peer that we have not read yet.
+    readbuf: VecDeque<u8>,
+    /// The clock of the writes to `readbuf`.
+    clock: VClock,
+    /// Whether reading or writing has been shut down with `shutdown`.
+    read_shutdown: bool,
+    write_shutdown: bool,
+}
+
+impl Connection {
+    fn new(peer: WeakFileDescriptionRef) -> Self {
+        Connection {
+            peer,
+            readbuf: VecDeque::new(),
+            clock: VClock::default(),
+            read_shutdown: false,
+            write_shutdown: false,
+        }
+    }
+
+    /// Whether the peer will not send any more data.
+    fn is_peer_write_closed(&self) -> bool {
+        match self.peer.upgrade() {
+            None => true,
+            Some(peer) => {
+                let peer_state = peer.downcast::<InetSocket>().unwrap().state.borrow();
+                match &*peer_state {
+                    SocketState::Connected(peer_conn) => peer_conn.write_shutdown,
+                    _ => unreachable!("the peer of a connected socket is always connected"),
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Datagram {
+    data: Vec<u8>,
+    from: SocketAddr,
+    clock: VClock,
+}
+
+impl InetSocket {
+    fn new(kind: SocketKind, is_ipv6: bool, is_nonblock: bool) -> Self {
+        InetSocket {
+            kind,
+            is_ipv6,
+            is_nonblock: Cell::new(is_nonblock),
+            local_addr: Cell::new(None),
+            peer_addr: Cell::new(None),
+            state: RefCell::new(SocketState::Unconnected),
+            datagrams: RefCell::new(VecDeque::new()),
+            read_timeout: Cell::new(None),
+            write_timeout: Cell::new(None),
+            nodelay: Cell::new(false),
+            blocked_threads: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// The address reported by `getsockname`.
+    fn sock_name(&self) -> SocketAddr {
+        let addr = self.local_addr.get().unwrap_or_else(|| {
+            let ip = if self.is_ipv6 {
+                IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+            } else {
+                IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+            };
+            SocketAddr::new(ip, 0)
+        });
+        if matches!(*self.state.borrow(), SocketState::Connected(_)) {
+            loopback_if_unspecified(addr)
+        } else {
+            addr
+        }
+    }
+
+    fn is_connected(&self) -> bool {
+        matches!(*self.state.borrow(), SocketState::Connected(_))
+    }
+
+    fn recv_stream<'tcx>(
+        &self,
+        bytes: &mut [u8],
+        peek: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let mut state = self.state.borrow_mut();
+        let SocketState::Connected(conn) = &mut *state else {
+            return Ok(Err(ErrorKind::NotConnected.into()));
+        };
+        if bytes.is_empty() {
+            return Ok(Ok(0));
+        }
+        if conn.readbuf.is_empty() {
+            if conn.read_shutdown || conn.is_peer_write_closed() {
+                // 0 bytes successfully read indicates end-of-file.
+                return Ok(Ok(0));
+            }
+            return Ok(Err(ErrorKind::WouldBlock.into()));
+        }
+
+        // Synchronize with all previous writes to this buffer.
+        // FIXME: this over-synchronizes, just like `socketpair` does.
+        ecx.acquire_clock(&conn.clock);
+        let read_size = if peek {
+            for (dest, src) in bytes.iter_mut().zip(&conn.readbuf) {
+                *dest = *src;
+            }
+            bytes.len().min(conn.readbuf.len())
+        } else {
+            conn.readbuf.read(bytes).unwrap()
+        };
+        let peer = conn.peer.upgrade();
+        drop(state);
+
+        // The peer can write again now that there is space in the buffer.
+        if !peek && let Some(peer) = peer {
+            ecx.socket_ready(&peer)?;
+        }
+        Ok(Ok(read_size))
+    }
+
+    fn send_stream<'tcx>(
+        &self,
+        bytes: &[u8],
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let peer = match &*self.state.borrow() {
+            SocketState::Connected(conn) if conn.write_shutdown =>
+                return Ok(Err(ErrorKind::BrokenPipe.into())),
+            SocketState::Connected(conn) => conn.peer.upgrade(),
+            _ => return Ok(Err(ErrorKind::NotConnected.into())),
+        };
+        let Some(peer) = peer else {
+            return Ok(Err(ErrorKind::BrokenPipe.into()));
+        };
+        if bytes.is_empty() {
+            return Ok(Ok(0));
+        }
+
+        let mut peer_state = peer.downcast::<InetSocket>().unwrap().state.borrow_mut();
+        let SocketState::Connected(peer_conn) = &mut *peer_state else {
+            unreachable!("the peer of a connected socket is always connected")
+        };
+        let available_space = MAX_SOCKET_BUFFER_CAPACITY.strict_sub(peer_conn.readbuf.len());
+        if available_space == 0 {
+            return Ok(Err(ErrorKind::WouldBlock.into()));
+        }
+        // Remember this clock so `recv` can synchronize with us.
+        if let Some(clock) = &ecx.release_clock() {
+            peer_conn.clock.join(clock);
+        }
+        let write_size = bytes.len().min(available_space);
+        peer_conn.readbuf.extend(&bytes[..write_size]);
+        drop(peer_state);
+
+        ecx.socket_ready(&peer)?;
+        Ok(Ok(write_size))
+    }
+
+    fn recv_datagram<'tcx>(
+        &self,
+        bytes: &mut [u8],
+        peek: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<(usize, SocketAddr)>> {
+        let mut datagrams = self.datagrams.borrow_mut();
+        let Some(datagram) = datagrams.front() else {
+            return Ok(Err(ErrorKind::WouldBlock.into()));
+        };
+        ecx.acquire_clock(&datagram.clock);
+        // Like on real systems, the part of the datagram that does not fit is discarded.
+        let read_size = bytes.len().min(datagram.data.len());
+        bytes[..read_size].copy_from_slice(&datagram.data[..read_size]);
+        let from = datagram.from;
+        if !peek {
+            datagrams.pop_front();
+        }
+        Ok(Ok((read_size, from)))
+    }
+
+    fn send_datagram<'tcx>(
+        &self,
+        self_ref: &FileDescriptionRef,
+        bytes: &[u8],
+        to: Option<SocketAddr>,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<usize>> {
+        let Some(to) = to.or(self.peer_addr.get()) else {
+            // FIXME: This should return EDESTADDRREQ, but there's no corresponding ErrorKind
+            // variant.
+            return Ok(Err(ErrorKind::NotConnected.into()));
+        };
+        if to.is_ipv6() != self.is_ipv6 || bytes.len() > MAX_DATAGRAM_SIZE {
+            // FIXME: Too large datagrams should return EMSGSIZE, but there's no corresponding
+            // ErrorKind variant.
+            return Ok(Err(ErrorKind::InvalidInput.into()));
+        }
+        if !to.ip().is_loopback() && !to.ip().is_unspecified() {
+            return Ok(Err(ErrorKind::NetworkUnreachable.into()));
+        }
+        let to = loopback_if_unspecified(to);
+
+        let from = match self.local_addr.get() {
+            Some(addr) => addr,
+            None =>
+                match ecx.bind_socket(self_ref, None)? {
+                    Ok(addr) => addr,
+                    Err(err) => return Ok(Err(err)),
+                },
+        };
+        let from = loopback_if_unspecified(from);
+
+        // Like on real networks, datagrams that cannot be delivered are silently dropped.
+        if let Some(target) = ecx.machine.network.lookup(SocketKind::Datagram, to) {
+            let target_socket = target.downcast::<InetSocket>().unwrap();
+            let mut datagrams = target_socket.datagrams.borrow_mut();
+            let buffered: usize = datagrams.iter().map(|datagram| datagram.data.len()).sum();
+            // A connected datagram socket only receives datagrams from its peer.
+            if target_socket.peer_addr.get().map_or(true, |peer| peer == from)
+                && buffered.strict_add(bytes.len()) <= MAX_SOCKET_BUFFER_CAPACITY
+            {
+                let mut clock = VClock::default();
+                if let Some(release_clock) = &ecx.release_clock() {
+                    clock.clone_from(release_clock);
+                }
+                datagrams.push_back(Datagram { data: bytes.to_owned(), from, clock });
+                drop(datagrams);
+                ecx.socket_ready(&target)?;
+            }
+        }
+        Ok(Ok(bytes.len()))
+    }
+}
+
+impl FileDescription for InetSocket {
+    fn name(&self) -> &'static str {
+        match self.kind {
+            SocketKind::Stream => "TCP socket",
+            SocketKind::Datagram => "UDP socket",
+        }
+    }
+
+    fn get_epoll_ready_events<'tcx>(&self) -> InterpResult<'tcx, EpollReadyEvents> {
+        let mut epoll_ready_events = EpollReadyEvents::new();
+        match (self.kind, &*self.state.borrow()) {
+            (SocketKind::Datagram, _) => {
+                epoll_ready_events.epollin = !self.datagrams.borrow().is_empty();
+                // Sending a datagram never blocks.
+                epoll_ready_events.epollout = true;
+            }
+            (SocketKind::Stream, SocketState::Unconnected) => {
+                // Like Linux, report a stream socket that was never connected as hung up.
+                epoll_ready_events.epollout = true;
+                epoll_ready_events.epollhup = true;
+            }
+            (SocketKind::Stream, SocketState::Listening(pending)) => {
+                epoll_ready_events.epollin = !pending.is_empty();
+            }
+            (SocketKind::Stream, SocketState::Connected(conn)) => {
+                let peer_write_closed = conn.is_peer_write_closed();
+                // Reads return EOF instead of blocking once the peer stopped writing.
+                epoll_ready_events.epollin =
+                    !conn.readbuf.is_empty() || conn.read_shutdown || peer_write_closed;
+                epoll_ready_events.epollrdhup = peer_write_closed;
+                match conn.peer.upgrade() {
+                    Some(peer) if !conn.write_shutdown => {
+                        let peer_state = peer.downcast::<InetSocket>().unwrap().state.borrow();
+                        let SocketState::Connected(peer_conn) = &*peer_state else {
+                            unreachable!("the peer of a connected socket is always connected")
+                        };
+                        epoll_ready_events.epollout =
+                            peer_conn.readbuf.len() < MAX_SOCKET_BUFFER_CAPACITY;
+                    }
+                    // Writes return EPIPE instead of blocking.
+                    _ => epoll_ready_events.epollout = true,
+                }
+                epoll_ready_events.epollhup =
+                    peer_write_closed && (conn.write_shutdown || conn.peer.upgrade().is_none());
+            }
+        }
+        Ok(epoll_ready_events)
+    }
+
+    fn close<'tcx>(
+        self: Box<Self>,
+        communicate_allowed: bool,
+        ecx: &mut MiriInterpCx<'tcx>,
+    ) -> InterpResult<'tcx, io::Result<()>> {
+        // This socket cannot be found on the network anymore, so its address is free again.
+        ecx.machine.network.bound.retain(|_, socket| socket.upgrade().is_some());
+
+        let InetSocket { state, blocked_threads, .. } = *self;
+        match state.into_inner() {
+            SocketState::Unconnected => {}
+            SocketState::Listening(pending) =>
+                for (conn, _clock) in pending {
+                    // Connections that were never accepted are closed with the listener.
+                    conn.close(communicate_allowed, ecx)?.unwrap();
+                },
+            SocketState::Connected(conn) =>
+                if let Some(peer) = conn.peer.upgrade() {
+                    // The peer will now see EOF, which can unblock it.
+                    ecx.socket_ready(&peer)?;
+                },
+        }
+
+        // Threads that are still blocked on this socket will find it closed.
+        for thread in blocked_threads.into_inner() {
+            ecx.unblock_thread(thread, BlockReason::Socket)?;
+        }
+        Ok(Ok(()))
+    }
+
+    fn set_nonblocking<'tcx>(&self, nonblocking: bool) -> InterpResult<'tcx> {
+        self.is_nonblock.set(nonblocking);
+        Ok(())
+    }
+}
+
+/// A socket operation that may have to wait for another thread.
+#[derive(Debug)]
+enum SocketOp {
+    Accept { addr: Pointer, addrlen: Pointer, nonblock: bool },
+    Recv { buf: Pointer, len: usize, peek: bool, addr: Pointer, addrlen: Pointer },
+    Send { bytes: Vec<u8>, to: Option<SocketAddr> },
+}
+
+/// A socket operation together with everything needed to retry it after blocking.
+#[derive(Debug)]
+struct BlockingSocketOp {
+    socket: WeakFileDescriptionRef,
+    op: SocketOp,
+    /// Whether `MSG_DONTWAIT` was passed.
+    dontwait: bool,
+    /// When the operation times out, measured on the monotone clock.
+    deadline: Option<Duration>,
+}
+
+impl VisitProvenance for BlockingSocketOp {
+    fn visit_provenance(&self, visit: &mut VisitWith<'_>) {
+        match &self.op {
+            SocketOp::Accept { addr, addrlen, .. } => {
+                addr.visit_provenance(visit);
+                addrlen.visit_provenance(visit);
+            }
+            SocketOp::Recv { buf, addr, addrlen, .. } => {
+                buf.visit_provenance(visit);
+                addr.visit_provenance(visit);
+                addrlen.visit_provenance(visit);
+            }
+            SocketOp::Send { .. } => {}
+        }
+    }
+}
+
+impl<'tcx> EvalContextExtPriv<'tcx> for crate::MiriInterpCx<'tcx> {}
+trait EvalContextExtPriv<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// Sets the last error to the libc error `name` and returns -1.
+    fn socket_error(&mut self, name: &str) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+        let errno = this.eval_libc(name);
+        this.set_last_error(errno)?;
+        Ok(Scalar::from_i32(-1))
+    }
+
+    /// Looks up the socket behind `fd`. Returns an error code if there is none.
+    fn get_socket(&mut self, fd: i32) -> InterpResult<'tcx, Result<FileDescriptionRef, Scalar>> {
+        let this = self.eval_context_mut();
+        let Some(fd_ref) = this.machine.fds.get(fd) else {
+            return Ok(Err(Scalar::from_i32(this.fd_not_found()?)));
+        };
+        if fd_ref.downcast::<InetSocket>().is_none() {
+            if fd_ref.name() == "socketpair" {
+                throw_unsup_format!("this socket operation is not supported for `socketpair`");
+            }
+            return Ok(Err(this.socket_error("ENOTSOCK")?));
+        }
+        Ok(Ok(fd_ref))
+    }
+
+    /// Reads a `sockaddr_in` or `sockaddr_in6`. Returns the name of the error code if the address
+    /// is invalid.
+    fn read_socket_addr(
+        &self,
+        addr: Pointer,
+        addrlen: u64,
+    ) -> InterpResult<'tcx, Result<SocketAddr, &'static str>> {
+        let this = self.eval_context_ref();
+
+        let sockaddr = this.ptr_to_mplace(addr, this.libc_ty_layout("sockaddr"));
+        if addrlen < sockaddr.layout.size.bytes() {
+            return Ok(Err("EINVAL"));
+        }
+        let family = this.project_field_named(&sockaddr, "sa_family")?;
+        let family = this.read_scalar(&family)?.to_int(family.layout.size)?;
+
+        if family == this.eval_libc_i32("AF_INET").into() {
+            let sockaddr = this.ptr_to_mplace(addr, this.libc_ty_layout("sockaddr_in"));
+            if addrlen < sockaddr.layout.size.bytes() {
+                return Ok(Err("EINVAL"));
+            }
+            // The port and address are stored in network byte order.
+            let port = this.project_field_named(&sockaddr, "sin_port")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+            let ip = this.project_field_named(&sockaddr, "sin_addr")?;
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), Size::from_bytes(4))?;
+            Ok(Ok(SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::from(<[u8; 4]>::try_from(ip).unwrap()),
+                u16::from_be_bytes(port.try_into().unwrap()),
+            ))))
+        } else if family == this.eval_libc_i32("AF_INET6").into() {
+            let sockaddr = this.ptr_to_mplace(addr, this.libc_ty_layout("sockaddr_in6"));
+            if addrlen < sockaddr.layout.size.bytes() {
+                return Ok(Err("EINVAL"));
+            }
+            let port = this.project_field_named(&sockaddr, "sin6_port")?;
+            let port = this.read_bytes_ptr_strip_provenance(port.ptr(), Size::from_bytes(2))?;
+            let ip = this.project_field_named(&sockaddr, "sin6_addr")?;
+            let ip = this.read_bytes_ptr_strip_provenance(ip.ptr(), Size::from_bytes(16))?;
+            let flowinfo = this.project_field_named(&sockaddr, "sin6_flowinfo")?;
+            let scope_id = this.project_field_named(&sockaddr, "sin6_scope_id")?;
+            Ok(Ok(SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(<[u8; 16]>::try_from(ip).unwrap()),
+                u16::from_be_bytes(port.try_into().unwrap()),
+                this.read_scalar(&flowinfo)?.to_u32()?,
+                this.read_scalar(&scope_id)?.to_u32()?,
+            ))))
+        } else {
+            Ok(Err("EAFNOSUPPORT"))
+        }
+    }
+
+    /// Writes `addr` as a `sockaddr_in` or `sockaddr_in6` to `addr_ptr` and its size to
+    /// `addrlen_ptr`, unless `addr_ptr` is null.
+    fn write_socket_addr(
+        &mut self,
+        addr: SocketAddr,
+        addr_ptr: Pointer,
+        addrlen_ptr: Pointer,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        if this.ptr_is_null(addr_ptr)? {
+            return Ok(());
+        }
+        let addrlen = this.ptr_to_mplace(addrlen_ptr, this.libc_ty_layout("socklen_t"));
+        let len = this.read_scalar(&addrlen)?.to_u32()?;
+
+        let layout = match addr {
+            SocketAddr::V4(_) => this.libc_ty_layout("sockaddr_in"),
+            SocketAddr::V6(_) => this.libc_ty_layout("sockaddr_in6"),
+        };
+        if u64::from(len) < layout.size.bytes() {
+            throw_unsup_format!("truncating socket addresses is not supported");
+        }
+        let sockaddr = this.ptr_to_mplace(addr_ptr, layout);
+        this.write_bytes_ptr(
+            sockaddr.ptr(),
+            std::iter::repeat(0).take(usize::try_from(layout.size.bytes()).unwrap()),
+        )?;
+
+        match addr {
+            SocketAddr::V4(addr) => {
+                if this.projectable_has_field(&sockaddr, "sin_len") {
+                    let size = i128::from(layout.size.bytes());
+                    this.write_int(size, &this.project_field_named(&sockaddr, "sin_len")?)?;
+                }
+                let family = this.eval_libc_i32("AF_INET");
+                this.write_int(family, &this.project_field_named(&sockaddr, "sin_family")?)?;
+                let port = this.project_field_named(&sockaddr, "sin_port")?;
+                this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+                let ip = this.project_field_named(&sockaddr, "sin_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+            }
+            SocketAddr::V6(addr) => {
+                if this.projectable_has_field(&sockaddr, "sin6_len") {
+                    let size = i128::from(layout.size.bytes());
+                    this.write_int(size, &this.project_field_named(&sockaddr, "sin6_len")?)?;
+                }
+                let family = this.eval_libc_i32("AF_INET6");
+                this.write_int(family, &this.project_field_named(&sockaddr, "sin6_family")?)?;
+                let port = this.project_field_named(&sockaddr, "sin6_port")?;
+                this.write_bytes_ptr(port.ptr(), addr.port().to_be_bytes())?;
+                let ip = this.project_field_named(&sockaddr, "sin6_addr")?;
+                this.write_bytes_ptr(ip.ptr(), addr.ip().octets())?;
+                this.write_int_fields_named(
+                    &[
+                        ("sin6_flowinfo", addr.flowinfo().into()),
+                        ("sin6_scope_id", addr.scope_id().into()),
+                    ],
+                    &sockaddr,
+                )?;
+            }
+        }
+        this.write_int(layout.size.bytes(), &addrlen)?;
+        Ok(())
+    }
+
+    /// Binds `socket` to `addr`, or to an ephemeral port of the unspecified address if `addr` is
+    /// `None`. Returns the address the socket was bound to.
+    fn bind_socket(
+        &mut self,
+        socket: &FileDescriptionRef,
+        addr: Option<SocketAddr>,
+    ) -> InterpResult<'tcx, io::Result<SocketAddr>> {
+        let this = self.eval_context_mut();
+        let inet = socket.downcast::<InetSocket>().unwrap();
+
+        if inet.local_addr.get().is_some() {
+            return Ok(Err(ErrorKind::InvalidInput.into()));
+        }
+        let mut addr = addr.unwrap_or_else(|| {
+            let ip = if inet.is_ipv6 {
+                IpAddr::V6(Ipv6Addr::UNSPECIFIED)
+            } else {
+                IpAddr::V4(Ipv4Addr::UNSPECIFIED)
+            };
+            SocketAddr::new(ip, 0)
+        });
+        if addr.is_ipv6() != inet.is_ipv6 {
+            return Ok(Err(ErrorKind::InvalidInput.into()));
+        }
+        // The program is the only host on the network.
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            return Ok(Err(ErrorKind::AddrNotAvailable.into()));
+        }
+
+        let network = &mut this.machine.network;
+        if addr.port() == 0 {
+            let Some(port) = network.ephemeral_port(inet.kind, addr) else {
+                return Ok(Err(ErrorKind::AddrInUse.into()));
+            };
+            addr.set_port(port);
+        } else if network.is_port_in_use(inet.kind, addr) {
+            return Ok(Err(ErrorKind::AddrInUse.into()));
+        }
+        network.bound.insert((inet.kind, addr), socket.downgrade());
+        inet.local_addr.set(Some(addr));
+        Ok(Ok(addr))
+    }
+
+    /// Wakes up the threads blocked on `socket` and updates its epoll readiness. This must be
+    /// called whenever something happens that could allow a blocked operation to proceed.
+    fn socket_ready(&mut self, socket: &FileDescriptionRef) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+        this.check_and_update_readiness(socket)?;
+        let inet = socket.downcast::<InetSocket>().unwrap();
+        // Unblocked threads retry their operation, which may block them again.
+        let threads = mem::take(&mut *inet.blocked_threads.borrow_mut());
+        for thread in threads {
+            this.unblock_thread(thread, BlockReason::Socket)?;
+        }
+        Ok(())
+    }
+
+    fn socket_accept(
+        &mut self,
+        socket: &FileDescriptionRef,
+        addr: Pointer,
+        addrlen: Pointer,
+        nonblock: bool,
+    ) -> InterpResult<'tcx, io::Result<i64>> {
+        let this = self.eval_context_mut();
+        let inet = socket.downcast::<InetSocket>().unwrap();
+
+        let mut state = inet.state.borrow_mut();
+        let SocketState::Listening(pending) = &mut *state else {
+            return Ok(Err(ErrorKind::InvalidInput.into()));
+        };
+        let Some((conn, clock)) = pending.pop_front() else {
+            return Ok(Err(ErrorKind::WouldBlock.into()));
+        };
+        drop(state);
+
+        // Synchronize with the thread that connected.
+        this.acquire_clock(&clock);
+        let conn_socket = conn.downcast::<InetSocket>().unwrap();
+        conn_socket.is_nonblock.set(nonblock);
+        let peer_addr = conn_socket.peer_addr.get().unwrap();
+        let fd = this.machine.fds.insert(conn);
+        this.write_socket_addr(peer_addr, addr, addrlen)?;
+        Ok(Ok(fd.into()))
+    }
+
+    fn socket_recv(
+        &mut self,
+        socket: &FileDescriptionRef,
+        buf: Pointer,
+        len: usize,
+        peek: bool,
+        addr: Pointer,
+        addrlen: Pointer,
+    ) -> InterpResult<'tcx, io::Result<i64>> {
+        let this = self.eval_context_mut();
+        let inet = socket.downcast::<InetSocket>().unwrap();
+
+        let mut bytes = vec![0; len];
+        let result = match inet.kind {
+            SocketKind::Stream =>
+                inet.recv_stream(&mut bytes, peek, this)?.map(|size| (size, None)),
+            SocketKind::Datagram =>
+                inet.recv_datagram(&mut bytes, peek, this)?.map(|(size, from)| (size, Some(from))),
+        };
+        let (size, from) = match result {
+            Ok(result) => result,
+            Err(err) => return Ok(Err(err)),
+        };
+        // Only write as many bytes as were received.
+        this.write_bytes_ptr(buf, bytes[..size].iter().copied())?;
+        // The source address is only reported for datagrams.
+        if let Some(from) = from {
+            this.write_socket_addr(from, addr, addrlen)?;
+        }
+        Ok(Ok(size.try_into().unwrap()))
+    }
+
+    /// Receives up to `len` bytes into `buf` for `recv`, `recvfrom` and `read`, blocking the
+    /// thread until there is something to receive unless `dontwait` is set or the socket is
+    /// non-blocking.
+    fn socket_recv_into(
+        &mut self,
+        socket: &FileDescriptionRef,
+        buf: Pointer,
+        len: u64,
+        peek: bool,
+        dontwait: bool,
+        addr: Pointer,
+        addrlen: Pointer,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        // Check that the *entire* buffer is actually valid memory.
+        this.check_ptr_access(buf, Size::from_bytes(len), CheckInAllocMsg::MemoryAccessTest)?;
+        // Nothing larger than the socket buffer can ever be received at once.
+        let len = usize::try_from(len).unwrap_or(usize::MAX).min(MAX_SOCKET_BUFFER_CAPACITY);
+
+        let deadline =
+            this.socket_deadline(socket.downcast::<InetSocket>().unwrap().read_timeout.get());
+        this.blocking_socket_op(
+            BlockingSocketOp {
+                socket: socket.downgrade(),
+                op: SocketOp::Recv { buf, len, peek, addr, addrlen },
+                dontwait,
+                deadline,
+            },
+            dest,
+        )
+    }
+
+    /// Sends the `len` bytes at `buf` for `send`, `sendto` and `write`, blocking the thread
+    /// while there is no space for them unless `dontwait` is set or the socket is non-blocking.
+    fn socket_send(
+        &mut self,
+        socket: &FileDescriptionRef,
+        buf: Pointer,
+        len: u64,
+        to: Option<SocketAddr>,
+        dontwait: bool,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let bytes = this.read_bytes_ptr_strip_provenance(buf, Size::from_bytes(len))?.to_owned();
+        let deadline =
+            this.socket_deadline(socket.downcast::<InetSocket>().unwrap().write_timeout.get());
+        this.blocking_socket_op(
+            BlockingSocketOp {
+                socket: socket.downgrade(),
+                op: SocketOp::Send { bytes, to },
+                dontwait,
+                deadline,
+            },
+            dest,
+        )
+    }
+
+    /// Performs `op.op`. If the operation has to wait for another thread, the current thread is
+    /// blocked and retries the operation whenever something happens to the socket.
+    fn blocking_socket_op(
+        &mut self,
+        op: BlockingSocketOp,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let Some(socket) = op.socket.upgrade() else {
+            throw_unsup_format!("a socket got closed while a thread was blocked on it");
+        };
+        let result = match &op.op {
+            &SocketOp::Accept { addr, addrlen, nonblock } =>
+                this.socket_accept(&socket, addr, addrlen, nonblock)?,
+            &SocketOp::Recv { buf, len, peek, addr, addrlen } =>
+                this.socket_recv(&socket, buf, len, peek, addr, addrlen)?,
+            SocketOp::Send { bytes, to } => {
+                let inet = socket.downcast::<InetSocket>().unwrap();
+                let result = match inet.kind {
+                    SocketKind::Stream => inet.send_stream(bytes, this)?,
+                    SocketKind::Datagram => inet.send_datagram(&socket, bytes, *to, this)?,
+                };
+                result.map(|size| size.try_into().unwrap())
+            }
+        };
+
+        let inet = socket.downcast::<InetSocket>().unwrap();
+        if let Err(err) = &result
+            && err.kind() == ErrorKind::WouldBlock
+            && !op.dontwait
+            && !inet.is_nonblock.get()
+        {
+            inet.blocked_threads.borrow_mut().push(this.active_thread());
+            let timeout = op
+                .deadline
+                .map(|deadline| (TimeoutClock::Monotonic, TimeoutAnchor::Absolute, deadline));
+            let dest = dest.clone();
+            this.block_thread(
+                BlockReason::Socket,
+                timeout,
+                callback!(
+                    @capture<'tcx> {
+                        op: BlockingSocketOp,
+                        dest: MPlaceTy<'tcx>,
+                    }
+                    @unblock = |this| {
+                        this.blocking_socket_op(op, &dest)
+                    }
+                    @timeout = |this| {
+                        if let Some(socket) = op.socket.upgrade() {
+                            let thread = this.active_thread();
+                            socket
+                                .downcast::<InetSocket>()
+                                .unwrap()
+                                .blocked_threads
+                                .borrow_mut()
+                                .retain(|&blocked| blocked != thread);
+                        }
+                        this.set_last_error_from_io_error(ErrorKind::WouldBlock.into())?;
+                        this.write_int(-1, &dest)
+                    }
+                ),
+            );
+            return Ok(());
+        }
+
+        let result = this.try_unwrap_io_result(result)?;
+        this.write_int(result, dest)
+    }
+
+    /// Turns a timeout of `SO_RCVTIMEO` or `SO_SNDTIMEO` into a deadline.
+    fn socket_deadline(&self, timeout: Option<Duration>) -> Option<Duration> {
+        let this = self.eval_context_ref();
+        let now = this.machine.clock.now().duration_since(this.machine.clock.epoch());
+        timeout.map(|timeout| now.saturating_add(timeout))
+    }
+}
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    /// For more information on the arguments see the socket manpage:
+    /// <https://man7.org/linux/man-pages/man2/socket.2.html>
+    fn socket(
+        &mut self,
+        domain: &OpTy<'tcx>,
+        type_: &OpTy<'tcx>,
+        protocol: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let domain = this.read_scalar(domain)?.to_i32()?;
+        let mut type_ = this.read_scalar(type_)?.to_i32()?;
+        let protocol = this.read_scalar(protocol)?.to_i32()?;
+
+        let mut is_sock_nonblock = false;
+
+        // Parse and remove the type flags that we support.
+        // These flags do not exist on macOS.
+        if this.tcx.sess.target.os != "macos" {
+            if type_ & this.eval_libc_i32("SOCK_NONBLOCK") == this.eval_libc_i32("SOCK_NONBLOCK") {
+                is_sock_nonblock = true;
+                type_ &= !(this.eval_libc_i32("SOCK_NONBLOCK"));
+            }
+            if type_ & this.eval_libc_i32("SOCK_CLOEXEC") == this.eval_libc_i32("SOCK_CLOEXEC") {
+                type_ &= !(this.eval_libc_i32("SOCK_CLOEXEC"));
+            }
+        }
+
+        let is_ipv6 = if domain == this.eval_libc_i32("AF_INET") {
+            false
+        } else if domain == this.eval_libc_i32("AF_INET6") {
+            true
+        } else {
+            throw_unsup_format!(
+                "socket: domain {:#x} is unsupported, only AF_INET and AF_INET6 are allowed",
+                domain
+            );
+        };
+        let kind = if type_ == this.eval_libc_i32("SOCK_STREAM")
+            && (protocol == 0 || protocol == this.eval_libc_i32("IPPROTO_TCP"))
+        {
+            SocketKind::Stream
+        } else if type_ == this.eval_libc_i32("SOCK_DGRAM")
+            && (protocol == 0 || protocol == this.eval_libc_i32("IPPROTO_UDP"))
+        {
+            SocketKind::Datagram
+        } else {
+            throw_unsup_format!(
+                "socket: type {:#x} with protocol {protocol} is unsupported, only TCP and UDP \
+                sockets are allowed",
+                type_
+            );
+        };
+
+        let fd = this.machine.fds.insert_new(InetSocket::new(kind, is_ipv6, is_sock_nonblock));
+        Ok(Scalar::from_i32(fd))
+    }
+
+    fn bind(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let addr = match this.read_socket_addr(addr, addrlen.into())? {
+            Ok(addr) => addr,
+            Err(errno) => return this.socket_error(errno),
+        };
+        let result = this.bind_socket(&socket, Some(addr))?.map(|_addr| 0);
+        Ok(Scalar::from_i32(this.try_unwrap_io_result(result)?))
+    }
+
+    fn listen(&mut self, fd: &OpTy<'tcx>, backlog: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        // Connections never time out, so we do not limit how many of them can be pending.
+        let _backlog = this.read_scalar(backlog)?.to_i32()?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+        if inet.kind != SocketKind::Stream {
+            return this.socket_error("EOPNOTSUPP");
+        }
+        match &*inet.state.borrow() {
+            SocketState::Unconnected => {}
+            SocketState::Listening(_) => return Ok(Scalar::from_i32(0)),
+            SocketState::Connected(_) => return this.socket_error("EINVAL"),
+        }
+        // Listening on an unbound socket binds it to an ephemeral port.
+        if inet.local_addr.get().is_none()
+            && let Err(err) = this.bind_socket(&socket, None)?
+        {
+            this.set_last_error_from_io_error(err)?;
+            return Ok(Scalar::from_i32(-1));
+        }
+        inet.state.replace(SocketState::Listening(VecDeque::new()));
+        Ok(Scalar::from_i32(0))
+    }
+
+    /// `flags` is `None` for `accept`, and the flags argument of `accept4` otherwise.
+    fn accept4(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+        flags: Option<&OpTy<'tcx>>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+
+        let mut nonblock = false;
+        if let Some(flags) = flags {
+            let mut flags = this.read_scalar(flags)?.to_i32()?;
+            if flags & this.eval_libc_i32("SOCK_NONBLOCK") == this.eval_libc_i32("SOCK_NONBLOCK") {
+                nonblock = true;
+                flags &= !(this.eval_libc_i32("SOCK_NONBLOCK"));
+            }
+            if flags & this.eval_libc_i32("SOCK_CLOEXEC") == this.eval_libc_i32("SOCK_CLOEXEC") {
+                flags &= !(this.eval_libc_i32("SOCK_CLOEXEC"));
+            }
+            if flags != 0 {
+                throw_unsup_format!("accept4: flag {:#x} is unsupported", flags);
+            }
+        }
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return this.write_scalar(error, dest),
+        };
+        let deadline =
+            this.socket_deadline(socket.downcast::<InetSocket>().unwrap().read_timeout.get());
+        this.blocking_socket_op(
+            BlockingSocketOp {
+                socket: socket.downgrade(),
+                op: SocketOp::Accept { addr, addrlen, nonblock },
+                dontwait: false,
+                deadline,
+            },
+            dest,
+        )
+    }
+
+    fn connect(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_scalar(addrlen)?.to_u32()?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+        let addr = match this.read_socket_addr(addr, addrlen.into())? {
+            Ok(addr) => addr,
+            Err(errno) => return this.socket_error(errno),
+        };
+        if addr.is_ipv6() != inet.is_ipv6 {
+            return this.socket_error("EAFNOSUPPORT");
+        }
+        if !addr.ip().is_loopback() && !addr.ip().is_unspecified() {
+            return this.socket_error("ENETUNREACH");
+        }
+        let addr = loopback_if_unspecified(addr);
+
+        if inet.kind == SocketKind::Stream {
+            if !matches!(*inet.state.borrow(), SocketState::Unconnected) {
+                return this.socket_error("EISCONN");
+            }
+            let Some(listener) = this.machine.network.lookup(SocketKind::Stream, addr) else {
+                return this.socket_error("ECONNREFUSED");
+            };
+            let listener_socket = listener.downcast::<InetSocket>().unwrap();
+            if !matches!(*listener_socket.state.borrow(), SocketState::Listening(_)) {
+                return this.socket_error("ECONNREFUSED");
+            }
+        }
+
+        // Connecting an unbound socket binds it to an ephemeral port.
+        let local_addr = match inet.local_addr.get() {
+            Some(local_addr) => local_addr,
+            None =>
+                match this.bind_socket(&socket, None)? {
+                    Ok(local_addr) => local_addr,
+                    Err(err) => {
+                        this.set_last_error_from_io_error(err)?;
+                        return Ok(Scalar::from_i32(-1));
+                    }
+                },
+        };
+        inet.peer_addr.set(Some(addr));
+
+        if inet.kind == SocketKind::Stream {
+            // The connection is established right away, and the other end waits in the backlog of
+            // the listener until it gets accepted.
+            let listener = this.machine.network.lookup(SocketKind::Stream, addr).unwrap();
+            let mut server = InetSocket::new(SocketKind::Stream, inet.is_ipv6, false);
+            server.local_addr = Cell::new(Some(addr));
+            server.peer_addr = Cell::new(Some(loopback_if_unspecified(local_addr)));
+            server.state =
+                RefCell::new(SocketState::Connected(Connection::new(socket.downgrade())));
+            let server = this.machine.fds.new_ref(server);
+            inet.state.replace(SocketState::Connected(Connection::new(server.downgrade())));
+
+            // `accept` synchronizes with `connect`.
+            let mut clock = VClock::default();
+            if let Some(release_clock) = &this.release_clock() {
+                clock.clone_from(release_clock);
+            }
+            let listener_socket = listener.downcast::<InetSocket>().unwrap();
+            let mut listener_state = listener_socket.state.borrow_mut();
+            let SocketState::Listening(pending) = &mut *listener_state else {
+                unreachable!("we checked above that this is a listening socket")
+            };
+            pending.push_back((server, clock));
+            drop(listener_state);
+
+            this.socket_ready(&listener)?;
+            // The socket is now writable.
+            this.socket_ready(&socket)?;
+        }
+        Ok(Scalar::from_i32(0))
+    }
+
+    fn shutdown(&mut self, fd: &OpTy<'tcx>, how: &OpTy<'tcx>) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let how = this.read_scalar(how)?.to_i32()?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+
+        let (read, write) = if how == this.eval_libc_i32("SHUT_RD") {
+            (true, false)
+        } else if how == this.eval_libc_i32("SHUT_WR") {
+            (false, true)
+        } else if how == this.eval_libc_i32("SHUT_RDWR") {
+            (true, true)
+        } else {
+            return this.socket_error("EINVAL");
+        };
+
+        let mut state = inet.state.borrow_mut();
+        let SocketState::Connected(conn) = &mut *state else {
+            return this.socket_error("ENOTCONN");
+        };
+        conn.read_shutdown |= read;
+        conn.write_shutdown |= write;
+        let peer = conn.peer.upgrade();
+        drop(state);
+
+        // Reads and writes on this socket do not block anymore, and neither do reads on the peer
+        // if writing was shut down.
+        this.socket_ready(&socket)?;
+        if let Some(peer) = peer {
+            this.socket_ready(&peer)?;
+        }
+        Ok(Scalar::from_i32(0))
+    }
+
+    fn getsockname(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let name = socket.downcast::<InetSocket>().unwrap().sock_name();
+        this.write_socket_addr(name, addr, addrlen)?;
+        Ok(Scalar::from_i32(0))
+    }
+
+    fn getpeername(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        addr: &OpTy<'tcx>,
+        addrlen: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let addr = this.read_pointer(addr)?;
+        let addrlen = this.read_pointer(addrlen)?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+        // A connected stream socket always has a peer address, but a datagram socket only has one
+        // after `connect`.
+        let peer_addr = match inet.kind {
+            SocketKind::Stream if !inet.is_connected() => None,
+            _ => inet.peer_addr.get(),
+        };
+        let Some(peer_addr) = peer_addr else {
+            return this.socket_error("ENOTCONN");
+        };
+        this.write_socket_addr(peer_addr, addr, addrlen)?;
+        Ok(Scalar::from_i32(0))
+    }
+
+    /// `to` and `tolen` are `None` for `send`.
+    fn sendto(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        to: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
+
+        let mut dontwait = false;
+        if flags & this.eval_libc_i32("MSG_DONTWAIT") == this.eval_libc_i32("MSG_DONTWAIT") {
+            dontwait = true;
+            flags &= !(this.eval_libc_i32("MSG_DONTWAIT"));
+        }
+        // There are no signals in Miri, so there is no `SIGPIPE` to suppress.
+        if this.tcx.sess.target.os != "macos"
+            && flags & this.eval_libc_i32("MSG_NOSIGNAL") == this.eval_libc_i32("MSG_NOSIGNAL")
+        {
+            flags &= !(this.eval_libc_i32("MSG_NOSIGNAL"));
+        }
+        if flags != 0 {
+            throw_unsup_format!("send: flag {:#x} is unsupported", flags);
+        }
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return this.write_scalar(error, dest),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+        let to = match to {
+            Some((to, tolen)) => {
+                let to = this.read_pointer(to)?;
+                let tolen = this.read_scalar(tolen)?.to_u32()?;
+                // Like Linux, ignore the address for connected stream sockets.
+                if this.ptr_is_null(to)? || inet.kind == SocketKind::Stream {
+                    None
+                } else {
+                    match this.read_socket_addr(to, tolen.into())? {
+                        Ok(to) => Some(to),
+                        Err(errno) => {
+                            let error = this.socket_error(errno)?;
+                            return this.write_scalar(error, dest);
+                        }
+                    }
+                }
+            }
+            None => None,
+        };
+        this.socket_send(&socket, buf, len, to, dontwait, dest)
+    }
+
+    /// `from` and `fromlen` are `None` for `recv`.
+    fn recvfrom(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        buf: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+        flags: &OpTy<'tcx>,
+        from: Option<(&OpTy<'tcx>, &OpTy<'tcx>)>,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let buf = this.read_pointer(buf)?;
+        let len = this.read_target_usize(len)?;
+        let mut flags = this.read_scalar(flags)?.to_i32()?;
+        let (addr, addrlen) = match from {
+            Some((from, fromlen)) => (this.read_pointer(from)?, this.read_pointer(fromlen)?),
+            None => (Pointer::null(), Pointer::null()),
+        };
+
+        let mut peek = false;
+        if flags & this.eval_libc_i32("MSG_PEEK") == this.eval_libc_i32("MSG_PEEK") {
+            peek = true;
+            flags &= !(this.eval_libc_i32("MSG_PEEK"));
+        }
+        let mut dontwait = false;
+        if flags & this.eval_libc_i32("MSG_DONTWAIT") == this.eval_libc_i32("MSG_DONTWAIT") {
+            dontwait = true;
+            flags &= !(this.eval_libc_i32("MSG_DONTWAIT"));
+        }
+        if flags != 0 {
+            throw_unsup_format!("recv: flag {:#x} is unsupported", flags);
+        }
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return this.write_scalar(error, dest),
+        };
+        this.socket_recv_into(&socket, buf, len, peek, dontwait, addr, addrlen, dest)
+    }
+
+    /// `read` on a socket, which behaves like `recv` without flags. Returns `false` if `fd` is
+    /// not a socket, leaving it to the `read` of other file descriptions.
+    fn socket_read(
+        &mut self,
+        fd: i32,
+        buf: Pointer,
+        count: u64,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, bool> {
+        let this = self.eval_context_mut();
+
+        let Some(socket) = this.machine.fds.get(fd) else { return Ok(false) };
+        if socket.downcast::<InetSocket>().is_none() {
+            return Ok(false);
+        }
+        let (addr, addrlen) = (Pointer::null(), Pointer::null());
+        let (peek, dontwait) = (false, false);
+        this.socket_recv_into(&socket, buf, count, peek, dontwait, addr, addrlen, dest)?;
+        Ok(true)
+    }
+
+    /// `write` on a socket, which behaves like `send` without flags. Returns `false` if `fd` is
+    /// not a socket, leaving it to the `write` of other file descriptions.
+    fn socket_write(
+        &mut self,
+        fd: i32,
+        buf: Pointer,
+        count: u64,
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, bool> {
+        let this = self.eval_context_mut();
+
+        let Some(socket) = this.machine.fds.get(fd) else { return Ok(false) };
+        if socket.downcast::<InetSocket>().is_none() {
+            return Ok(false);
+        }
+        this.socket_send(&socket, buf, count, /*to*/ None, /*dontwait*/ false, dest)?;
+        Ok(true)
+    }
+
+    fn setsockopt(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        name: &OpTy<'tcx>,
+        value: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let name = this.read_scalar(name)?.to_i32()?;
+        let value = this.read_pointer(value)?;
+        let len = this.read_scalar(len)?.to_u32()?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+
+        if level == this.eval_libc_i32("SOL_SOCKET")
+            && (name == this.eval_libc_i32("SO_RCVTIMEO")
+                || name == this.eval_libc_i32("SO_SNDTIMEO"))
+        {
+            let timeval = this.ptr_to_mplace(value, this.libc_ty_layout("timeval"));
+            if u64::from(len) < timeval.layout.size.bytes() {
+                return this.socket_error("EINVAL");
+            }
+            let seconds = this.project_field_named(&timeval, "tv_sec")?;
+            let seconds = this.read_scalar(&seconds)?.to_int(seconds.layout.size)?;
+            let micros = this.project_field_named(&timeval, "tv_usec")?;
+            let micros = this.read_scalar(&micros)?.to_int(micros.layout.size)?;
+            let (Ok(seconds), Ok(micros)) = (u64::try_from(seconds), u32::try_from(micros)) else {
+                return this.socket_error("EDOM");
+            };
+            if micros >= 1_000_000 {
+                return this.socket_error("EDOM");
+            }
+            // A timeout of zero means that operations never time out.
+            let timeout = Duration::new(seconds, micros.strict_mul(1000));
+            let timeout = if timeout.is_zero() { None } else { Some(timeout) };
+            if name == this.eval_libc_i32("SO_RCVTIMEO") {
+                inet.read_timeout.set(timeout);
+            } else {
+                inet.write_timeout.set(timeout);
+            }
+            return Ok(Scalar::from_i32(0));
+        }
+
+        // All other supported options have an `int` value.
+        let int = this.ptr_to_mplace(value, this.machine.layouts.i32);
+        if u64::from(len) < int.layout.size.bytes() {
+            return this.socket_error("EINVAL");
+        }
+        let int = this.read_scalar(&int)?.to_i32()?;
+
+        if level == this.eval_libc_i32("SOL_SOCKET")
+            && (name == this.eval_libc_i32("SO_REUSEADDR")
+                || name == this.eval_libc_i32("SO_KEEPALIVE")
+                || name == this.eval_libc_i32("SO_BROADCAST"))
+        {
+            // These have no effect on the virtual network: ports are free again as soon as their
+            // socket is closed, connections never break and there is nothing to broadcast to.
+        } else if this.tcx.sess.target.os == "macos"
+            && level == this.eval_libc_i32("SOL_SOCKET")
+            && name == this.eval_libc_i32("SO_NOSIGPIPE")
+        {
+            // There are no signals in Miri.
+        } else if level == this.eval_libc_i32("IPPROTO_TCP")
+            && name == this.eval_libc_i32("TCP_NODELAY")
+        {
+            inet.nodelay.set(int != 0);
+        } else if level == this.eval_libc_i32("IPPROTO_IPV6")
+            && name == this.eval_libc_i32("IPV6_V6ONLY")
+        {
+            // IPv4 and IPv6 sockets never talk to each other on the virtual network.
+        } else {
+            throw_unsup_format!("setsockopt: option {name:#x} at level {level:#x} is unsupported");
+        }
+        Ok(Scalar::from_i32(0))
+    }
+
+    fn getsockopt(
+        &mut self,
+        fd: &OpTy<'tcx>,
+        level: &OpTy<'tcx>,
+        name: &OpTy<'tcx>,
+        value: &OpTy<'tcx>,
+        len: &OpTy<'tcx>,
+    ) -> InterpResult<'tcx, Scalar> {
+        let this = self.eval_context_mut();
+
+        let fd = this.read_scalar(fd)?.to_i32()?;
+        let level = this.read_scalar(level)?.to_i32()?;
+        let name = this.read_scalar(name)?.to_i32()?;
+        let value = this.read_pointer(value)?;
+        let len = this.deref_pointer_as(len, this.libc_ty_layout("socklen_t"))?;
+
+        let socket = match this.get_socket(fd)? {
+            Ok(socket) => socket,
+            Err(error) => return Ok(error),
+        };
+        let inet = socket.downcast::<InetSocket>().unwrap();
+
+        let result = if level == this.eval_libc_i32("SOL_SOCKET")
+            && name == this.eval_libc_i32("SO_ERROR")
+        {
+            // Errors are always reported right away, so there is never a pending one.
+            0
+        } else if level == this.eval_libc_i32("SOL_SOCKET") && name == this.eval_libc_i32("SO_TYPE")
+        {
+            match inet.kind {
+                SocketKind::Stream => this.eval_libc_i32("SOCK_STREAM"),
+                SocketKind::Datagram => this.eval_libc_i32("SOCK_DGRAM"),
+            }
+        } else if level == this.eval_libc_i32("IPPROTO_TCP")
+            && name == this.eval_libc_i32("TCP_NODELAY")
+        {
+            inet.nodelay.get().into()
+        } else {
+            throw_unsup_format!("getsockopt: option {name:#x} at level {level:#x} is unsupported");
+        };
+
+        let int = this.ptr_to_mplace(value, this.machine.layouts.i32);
+        if u64::from(this.read_scalar(&len)?.to_u32()?) < int.layout.size.bytes() {
+            throw_unsup_format!("getsockopt: truncating option values is not supported");
+        }
+        this.write_scalar(Scalar::from_i32(result), &int)?;
+        this.write_int(int.layout.size.bytes(), &len)?;
+        Ok(Scalar::from_i32(0))
+    }
+}
@@ -3,25 +3,30 @@
 mod env;
 mod fd;
 mod fs;
+mod inet_socket;
 mod mem;
 mod sync;
 mod thread;
 mod unnamed_socket;
 
 mod android;
+mod espidf;
 mod freebsd;
 mod linux;
 mod macos;
 mod solarish;
 
 pub use env::UnixEnvVars;
+pub use espidf::EspIdfState;
 pub use fd::{FdTable, FileDescription};
 pub use fs::DirTable;
+pub use inet_socket::VirtualNetwork;
 pub use linux::epoll::EpollInterestTable;
 // All the Unix-specific extension traits
 pub use env::EvalContextExt as _;
 pub use fd::EvalContextExt as _;
 pub use fs::EvalContextExt as _;
+pub use inet_socket::EvalContextExt as _;
 pub use mem::EvalContextExt as _;
 pub use sync::EvalContextExt as _;
 pub use thread::EvalContextExt as _;
@@ -4,6 +4,34 @@
 
 use crate::*;
 
+/// Whether the static initializers of the target can be used with our layouts. On ESP-IDF they
+/// set every byte to `0xff`, which makes ESP-IDF create the object on first use. We don't support
+/// this, but the standard library always calls the `pthread_*_init` functions there.
+fn has_static_initializers<'tcx>(ecx: &MiriInterpCx<'tcx>) -> bool {
+    ecx.tcx.sess.target.os != "espidf"
+}
+
+/// Reports the objects set up by an ESP-IDF static initializer, whose id is all ones.
+fn check_not_statically_initialized<'tcx>(
+    ecx: &MiriInterpCx<'tcx>,
+    op: &OpTy<'tcx>,
+    ty: &str,
+    offset: u64,
+) -> InterpResult<'tcx, ()> {
+    if !has_static_initializers(ecx) {
+        let id = ecx
+            .deref_pointer_and_read(op, offset, ecx.libc_ty_layout(ty), ecx.machine.layouts.u32)?
+            .to_u32()?;
+        if id == u32::MAX {
+            throw_unsup_format!(
+                "`{ty}` set up by a static initializer is not supported on {}",
+                ecx.tcx.sess.target.os
+            );
+        }
+    }
+    Ok(())
+}
+
 // pthread_mutexattr_t is either 4 or 8 bytes, depending on the platform.
 // We ignore the platform layout and store our own fields:
 // - kind: i32
@@ -11,7 +39,7 @@
 #[inline]
 fn mutexattr_kind_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     Ok(match &*ecx.tcx.sess.target.os {
-        "linux" | "illumos" | "solaris" | "macos" => 0,
+        "linux" | "illumos" | "solaris" | "macos" | "espidf" => 0,
         os => throw_unsup_format!("`pthread_mutexattr` is not supported on {os}"),
     })
 }
@@ -66,7 +94,7 @@ fn is_mutex_kind_normal<'tcx>(ecx: &MiriInterpCx<'tcx>, kind: i32) -> InterpResu
 
 fn mutex_id_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     let offset = match &*ecx.tcx.sess.target.os {
-        "linux" | "illumos" | "solaris" => 0,
+        "linux" | "illumos" | "solaris" | "espidf" => 0,
         // macOS stores a signature in the first bytes, so we have to move to offset 4.
         "macos" => 4,
         os => throw_unsup_format!("`pthread_mutex` is not supported on {os}"),
@@ -75,7 +103,7 @@ fn mutex_id_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     // Sanity-check this against PTHREAD_MUTEX_INITIALIZER (but only once):
     // the id must start out as 0.
     static SANITY: AtomicBool = AtomicBool::new(false);
-    if !SANITY.swap(true, Ordering::Relaxed) {
+    if has_static_initializers(ecx) && !SANITY.swap(true, Ordering::Relaxed) {
         let static_initializer = ecx.eval_path(&["libc", "PTHREAD_MUTEX_INITIALIZER"]);
         let id_field = static_initializer
             .offset(Size::from_bytes(offset), ecx.machine.layouts.u32, ecx)
@@ -98,7 +126,7 @@ fn mutex_kind_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> u64 {
     // Sanity-check this against PTHREAD_MUTEX_INITIALIZER (but only once):
     // the kind must start out as PTHREAD_MUTEX_DEFAULT.
     static SANITY: AtomicBool = AtomicBool::new(false);
-    if !SANITY.swap(true, Ordering::Relaxed) {
+    if has_static_initializers(ecx) && !SANITY.swap(true, Ordering::Relaxed) {
         let static_initializer = ecx.eval_path(&["libc", "PTHREAD_MUTEX_INITIALIZER"]);
         let kind_field = static_initializer
             .offset(Size::from_bytes(mutex_kind_offset(ecx)), ecx.machine.layouts.i32, ecx)
@@ -118,6 +146,7 @@ fn mutex_get_id<'tcx>(
     ecx: &mut MiriInterpCx<'tcx>,
     mutex_op: &OpTy<'tcx>,
 ) -> InterpResult<'tcx, MutexId> {
+    check_not_statically_initialized(ecx, mutex_op, "pthread_mutex_t", mutex_id_offset(ecx)?)?;
     ecx.mutex_get_or_create_id(
         mutex_op,
         ecx.libc_ty_layout("pthread_mutex_t"),
@@ -171,7 +200,7 @@ fn mutex_set_kind<'tcx>(
 
 fn rwlock_id_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     let offset = match &*ecx.tcx.sess.target.os {
-        "linux" | "illumos" | "solaris" => 0,
+        "linux" | "illumos" | "solaris" | "espidf" => 0,
         // macOS stores a signature in the first bytes, so we have to move to offset 4.
         "macos" => 4,
         os => throw_unsup_format!("`pthread_rwlock` is not supported on {os}"),
@@ -180,7 +209,7 @@ fn rwlock_id_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     // Sanity-check this against PTHREAD_RWLOCK_INITIALIZER (but only once):
     // the id must start out as 0.
     static SANITY: AtomicBool = AtomicBool::new(false);
-    if !SANITY.swap(true, Ordering::Relaxed) {
+    if has_static_initializers(ecx) && !SANITY.swap(true, Ordering::Relaxed) {
         let static_initializer = ecx.eval_path(&["libc", "PTHREAD_RWLOCK_INITIALIZER"]);
         let id_field = static_initializer
             .offset(Size::from_bytes(offset), ecx.machine.layouts.u32, ecx)
@@ -199,6 +228,7 @@ fn rwlock_get_id<'tcx>(
     ecx: &mut MiriInterpCx<'tcx>,
     rwlock_op: &OpTy<'tcx>,
 ) -> InterpResult<'tcx, RwLockId> {
+    check_not_statically_initialized(ecx, rwlock_op, "pthread_rwlock_t", rwlock_id_offset(ecx)?)?;
     ecx.rwlock_get_or_create_id(
         rwlock_op,
         ecx.libc_ty_layout("pthread_rwlock_t"),
@@ -213,7 +243,7 @@ fn rwlock_get_id<'tcx>(
 #[inline]
 fn condattr_clock_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     Ok(match &*ecx.tcx.sess.target.os {
-        "linux" | "illumos" | "solaris" => 0,
+        "linux" | "illumos" | "solaris" | "espidf" => 0,
         // macOS does not have a clock attribute.
         os => throw_unsup_format!("`pthread_condattr` clock field is not supported on {os}"),
     })
@@ -253,7 +283,7 @@ fn condattr_set_clock_id<'tcx>(
 
 fn cond_id_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     let offset = match &*ecx.tcx.sess.target.os {
-        "linux" | "illumos" | "solaris" => 0,
+        "linux" | "illumos" | "solaris" | "espidf" => 0,
         // macOS stores a signature in the first bytes, so we have to move to offset 4.
         "macos" => 4,
         os => throw_unsup_format!("`pthread_cond` is not supported on {os}"),
@@ -262,7 +292,7 @@ fn cond_id_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> InterpResult<'tcx, u64> {
     // Sanity-check this against PTHREAD_COND_INITIALIZER (but only once):
     // the id must start out as 0.
     static SANITY: AtomicBool = AtomicBool::new(false);
-    if !SANITY.swap(true, Ordering::Relaxed) {
+    if has_static_initializers(ecx) && !SANITY.swap(true, Ordering::Relaxed) {
         let static_initializer = ecx.eval_path(&["libc", "PTHREAD_COND_INITIALIZER"]);
         let id_field = static_initializer
             .offset(Size::from_bytes(offset), ecx.machine.layouts.u32, ecx)
@@ -295,7 +325,7 @@ fn cond_clock_offset<'tcx>(ecx: &MiriInterpCx<'tcx>) -> u64 {
     // Sanity-check this against PTHREAD_COND_INITIALIZER (but only once):
     // the clock must start out as CLOCK_REALTIME.
     static SANITY: AtomicBool = AtomicBool::new(false);
-    if !SANITY.swap(true, Ordering::Relaxed) {
+    if has_static_initializers(ecx) && !SANITY.swap(true, Ordering::Relaxed) {
         let static_initializer = ecx.eval_path(&["libc", "PTHREAD_COND_INITIALIZER"]);
         let id_field = static_initializer
             .offset(Size::from_bytes(offset), ecx.machine.layouts.i32, ecx)
@@ -314,6 +344,7 @@ fn cond_get_id<'tcx>(
     ecx: &mut MiriInterpCx<'tcx>,
     cond_op: &OpTy<'tcx>,
 ) -> InterpResult<'tcx, CondvarId> {
+    check_not_statically_initialized(ecx, cond_op, "pthread_cond_t", cond_id_offset(ecx)?)?;
     ecx.condvar_get_or_create_id(
         cond_op,
         ecx.libc_ty_layout("pthread_cond_t"),
@@ -0,0 +1,690 @@
+use rustc_middle::ty::layout::LayoutOf as _;
+use rustc_middle::ty::Ty;
+use rustc_span::Symbol;
+use rustc_target::spec::abi::Abi;
+
+use super::{
+    packssdw, packsswb, packusdw, packuswb, permute, pmaddubsw, pmaddwd, pmulhrsw, psadbw, pshufb,
+    shift_simd_by_scalar, shift_simd_by_simd, ShiftOp,
+};
+use crate::*;
+
+impl<'tcx> EvalContextExt<'tcx> for crate::MiriInterpCx<'tcx> {}
+pub(super) trait EvalContextExt<'tcx>: crate::MiriInterpCxExt<'tcx> {
+    fn emulate_x86_avx512_intrinsic(
+        &mut self,
+        link_name: Symbol,
+        abi: Abi,
+        args: &[OpTy<'tcx>],
+        dest: &MPlaceTy<'tcx>,
+    ) -> InterpResult<'tcx, EmulateItemResult> {
+        let this = self.eval_context_mut();
+        // Prefix should have already been checked.
+        let unprefixed_name = link_name.as_str().strip_prefix("llvm.x86.avx512.").unwrap();
+
+        // Most AVX-512 functions are implemented with generic SIMD intrinsics, including the
+        // masking, which uses `simd_select_bitmask`. Only the operations below have their own
+        // LLVM intrinsics. The required target feature depends on the element type, so it is
+        // checked in each arm.
+        match unprefixed_name {
+            // Used to implement the _mm{,256,512}_ternarylogic_epi{32,64} functions.
+            // Computes an arbitrary bitwise function of `a`, `b` and `c`, given by its
+            // truth table in `imm8`.
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_ternarylogic_epi32
+            "pternlog.d.128" | "pternlog.d.256" | "pternlog.d.512" | "pternlog.q.128"
+            | "pternlog.q.256" | "pternlog.q.512" => {
+                expect_avx512_feature(this, link_name, "avx512f")?;
+
+                let [a, b, c, imm8] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                ternary_logic(this, a, b, c, imm8, dest)?;
+            }
+            // Used to implement the _mm512_madd_epi16 function.
+            // Multiplies packed signed 16-bit integers in `left` and `right`, producing
+            // intermediate signed 32-bit integers. Horizontally add adjacent pairs of
+            // intermediate 32-bit integers, and pack the results in `dest`.
+            "pmaddw.d.512" => {
+                expect_avx512_feature(this, link_name, "avx512bw")?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                pmaddwd(this, left, right, dest)?;
+            }
+            // Used to implement the _mm512_maddubs_epi16 function.
+            // Multiplies packed 8-bit unsigned integers from `left` and packed
+            // signed 8-bit integers from `right` into 16-bit signed integers. Then,
+            // the saturating sum of the products with indices `2*i` and `2*i+1`
+            // produces the output at index `i`.
+            "pmaddubs.w.512" => {
+                expect_avx512_feature(this, link_name, "avx512bw")?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                pmaddubsw(this, left, right, dest)?;
+            }
+            // Used to implement the _mm512_sad_epu8 function.
+            // Compute the absolute differences of packed unsigned 8-bit integers
+            // in `left` and `right`, then horizontally sum each consecutive 8
+            // differences to produce eight unsigned 16-bit integers, and pack
+            // these unsigned 16-bit integers in the low 16 bits of 64-bit elements
+            // in `dest`.
+            "psad.bw.512" => {
+                expect_avx512_feature(this, link_name, "avx512bw")?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                psadbw(this, left, right, dest)?;
+            }
+            // Used to implement the _mm512_mulhrs_epi16 function.
+            // Multiplies packed 16-bit signed integer values, truncates the 32-bit
+            // product to the 18 most significant bits by right-shifting, and then
+            // divides the 18-bit value by 2 (rounding to nearest) by first adding
+            // 1 and then taking the bits `1..=16`.
+            "pmul.hr.sw.512" => {
+                expect_avx512_feature(this, link_name, "avx512bw")?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                pmulhrsw(this, left, right, dest)?;
+            }
+            // Used to implement the _mm512_shuffle_epi8 function.
+            // Shuffles bytes from `left` using `right` as pattern.
+            // Each 128-bit block is shuffled independently.
+            "pshuf.b.512" => {
+                expect_avx512_feature(this, link_name, "avx512bw")?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                pshufb(this, left, right, dest)?;
+            }
+            // Used to implement the _mm512_packs_epi{16,32} and _mm512_packus_epi{16,32}
+            // functions.
+            // Converts two N-bit integer vectors to a single N/2-bit integer vector
+            // with signed or unsigned saturation.
+            "packsswb.512" | "packssdw.512" | "packuswb.512" | "packusdw.512" => {
+                expect_avx512_feature(this, link_name, "avx512bw")?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                match unprefixed_name {
+                    "packsswb.512" => packsswb(this, left, right, dest)?,
+                    "packssdw.512" => packssdw(this, left, right, dest)?,
+                    "packuswb.512" => packuswb(this, left, right, dest)?,
+                    "packusdw.512" => packusdw(this, left, right, dest)?,
+                    _ => unreachable!(),
+                }
+            }
+            // Used to implement the _mm512_{sll,srl,sra}_epi{16,32,64} and
+            // _mm{,256}_sra_epi64 functions.
+            // Shifts N-bit packed integers in left by the amount in right.
+            // `right` is as 128-bit vector. but it is interpreted as a single
+            // 64-bit integer (remaining bits are ignored).
+            "psll.w.512" | "psrl.w.512" | "psra.w.512" | "psll.d.512" | "psrl.d.512"
+            | "psra.d.512" | "psll.q.512" | "psrl.q.512" | "psra.q.512" | "psra.q.128"
+            | "psra.q.256" => {
+                let feature = if unprefixed_name.contains(".w.") { "avx512bw" } else { "avx512f" };
+                expect_avx512_feature(this, link_name, feature)?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                let which = match unprefixed_name.split('.').next().unwrap() {
+                    "psll" => ShiftOp::Left,
+                    "psrl" => ShiftOp::RightLogic,
+                    "psra" => ShiftOp::RightArith,
+                    _ => unreachable!(),
+                };
+
+                shift_simd_by_scalar(this, left, right, which, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_{sllv,srlv,srav}_epi{16,32,64} functions
+            // that are not available in AVX2.
+            // Shifts each element of `left` by the corresponding element of `right`.
+            "psllv.w.128" | "psllv.w.256" | "psllv.w.512" | "psllv.d.512" | "psllv.q.512"
+            | "psrlv.w.128" | "psrlv.w.256" | "psrlv.w.512" | "psrlv.d.512" | "psrlv.q.512"
+            | "psrav.w.128" | "psrav.w.256" | "psrav.w.512" | "psrav.d.512" | "psrav.q.128"
+            | "psrav.q.256" | "psrav.q.512" => {
+                let feature = if unprefixed_name.contains(".w.") { "avx512bw" } else { "avx512f" };
+                expect_avx512_feature(this, link_name, feature)?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                let which = match unprefixed_name.split('.').next().unwrap() {
+                    "psllv" => ShiftOp::Left,
+                    "psrlv" => ShiftOp::RightLogic,
+                    "psrav" => ShiftOp::RightArith,
+                    _ => unreachable!(),
+                };
+
+                shift_simd_by_simd(this, left, right, which, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_permutexvar_{epi8,epi16,epi32,epi64,ps,pd}
+            // functions.
+            // Shuffles `left` across the whole vector, using the low bits of each
+            // element of `right` as indices.
+            "permvar.qi.128" | "permvar.qi.256" | "permvar.qi.512" | "permvar.hi.128"
+            | "permvar.hi.256" | "permvar.hi.512" | "permvar.si.512" | "permvar.sf.512"
+            | "permvar.di.256" | "permvar.di.512" | "permvar.df.256" | "permvar.df.512" => {
+                let feature = match unprefixed_name.split('.').nth(1).unwrap() {
+                    "qi" => "avx512vbmi",
+                    "hi" => "avx512bw",
+                    _ => "avx512f",
+                };
+                expect_avx512_feature(this, link_name, feature)?;
+
+                let [left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                permute(this, left, right, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_permutex2var_{epi8,epi16,epi32,epi64,ps,pd}
+            // functions.
+            // Shuffles the concatenation of `a` and `b`, using the low bits of each
+            // element of `indices` as indices.
+            "vpermi2var.qi.128" | "vpermi2var.qi.256" | "vpermi2var.qi.512"
+            | "vpermi2var.hi.128" | "vpermi2var.hi.256" | "vpermi2var.hi.512"
+            | "vpermi2var.d.128" | "vpermi2var.d.256" | "vpermi2var.d.512" | "vpermi2var.q.128"
+            | "vpermi2var.q.256" | "vpermi2var.q.512" | "vpermi2var.ps.128"
+            | "vpermi2var.ps.256" | "vpermi2var.ps.512" | "vpermi2var.pd.128"
+            | "vpermi2var.pd.256" | "vpermi2var.pd.512" => {
+                let feature = match unprefixed_name.split('.').nth(1).unwrap() {
+                    "qi" => "avx512vbmi",
+                    "hi" => "avx512bw",
+                    _ => "avx512f",
+                };
+                expect_avx512_feature(this, link_name, feature)?;
+
+                let [a, indices, b] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                permute2(this, a, indices, b, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_multishift_epi64_epi8 functions.
+            // For each byte of `control`, extracts the 8 bits of the corresponding
+            // 64-bit element of `data` that start at the bit given by the low 6 bits
+            // of that byte, wrapping around.
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_multishift_epi64_epi8
+            "pmultishift.qb.128" | "pmultishift.qb.256" | "pmultishift.qb.512" => {
+                expect_avx512_feature(this, link_name, "avx512vbmi")?;
+
+                let [control, data] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                multishift(this, control, data, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_dp{bus,wss}{,s}_epi32 functions and
+            // the corresponding _avx_ functions.
+            // Multiplies groups of 4 adjacent unsigned 8-bit integers in `left` with the
+            // corresponding signed 8-bit integers in `right` (or groups of 2 signed
+            // 16-bit integers in both), and adds the sum of the products to the
+            // corresponding 32-bit integer of `src`. The `s` versions saturate the
+            // result instead of wrapping around.
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_dpbusd_epi32
+            "vpdpbusd.128" | "vpdpbusd.256" | "vpdpbusd.512" | "vpdpbusds.128"
+            | "vpdpbusds.256" | "vpdpbusds.512" | "vpdpwssd.128" | "vpdpwssd.256"
+            | "vpdpwssd.512" | "vpdpwssds.128" | "vpdpwssds.256" | "vpdpwssds.512" => {
+                // AVX-VNNI provides the 128- and 256-bit versions without AVX-512.
+                if unprefixed_name.ends_with(".512")
+                    || !this.tcx.sess.unstable_target_features.contains(&Symbol::intern("avxvnni"))
+                {
+                    expect_avx512_feature(this, link_name, "avx512vnni")?;
+                }
+
+                let [src, left, right] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                let op_name = unprefixed_name.split('.').next().unwrap();
+                let words = op_name.starts_with("vpdpw");
+                let saturating = op_name.ends_with('s');
+
+                dot_product_accumulate(this, src, left, right, words, saturating, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_{mask,maskz}_compress_* functions.
+            // Stores the elements of `value` whose bit in `mask` is set contiguously
+            // in the low elements of `dest`, and fills the remaining ones from `src`.
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_mask_compress_epi32
+            name if name.starts_with("mask.compress.")
+                && !name.starts_with("mask.compress.store.") =>
+            {
+                expect_avx512_feature(this, link_name, compress_feature(unprefixed_name))?;
+
+                let [value, src, mask] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                compress(this, value, src, mask, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_mask_compressstoreu_* functions.
+            // Stores the elements of `value` whose bit in `mask` is set contiguously
+            // to `ptr`, which does not need to be aligned.
+            name if name.starts_with("mask.compress.store.") => {
+                expect_avx512_feature(this, link_name, compress_feature(unprefixed_name))?;
+
+                let [ptr, value, mask] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                compress_store(this, ptr, value, mask)?;
+            }
+            // Used to implement the _mm{,256,512}_{mask,maskz}_expand_* functions.
+            // Stores the low elements of `value` in the elements of `dest` whose bit in `mask`
+            // is set, and fills the remaining ones from `src`.
+            // https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_mask_expand_epi32
+            name if name.starts_with("mask.expand.") && !name.starts_with("mask.expand.load.") => {
+                expect_avx512_feature(this, link_name, compress_feature(unprefixed_name))?;
+
+                let [value, src, mask] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                expand(this, value, src, mask, dest)?;
+            }
+            // Used to implement the _mm{,256,512}_{mask,maskz}_cvt{,s,us}epi{16,32,64}_epi{8,16,32}
+            // functions, and the unmasked ones that cannot be expressed with a plain truncation.
+            // Converts the elements of `value` to a smaller integer type, by truncating
+            // (`pmov`) or by saturating them as signed (`pmovs`) or unsigned (`pmovus`)
+            // integers. Elements whose bit in `mask` is not set are taken from `src`,
+            // and any elements of `dest` beyond the length of `value` are set to zero.
+            name if name.starts_with("mask.pmov") && !name.contains(".mem.") => {
+                let feature = if name.contains(".wb.") { "avx512bw" } else { "avx512f" };
+                expect_avx512_feature(this, link_name, feature)?;
+
+                let [value, src, mask] =
+                    this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
+
+                let which = match unprefixed_name.split('.').nth(1).unwrap() {
+                    "pmov" => Narrowing::Truncate,
+                    "pmovs" => Narrowing::SignedSaturate,
+                    "pmovus" => Narrowing::UnsignedSaturate,
+                    _ => return Ok(EmulateItemResult::NotSupported),
+                };
+
+                narrow(this, value, src, mask, which, dest)?;
+            }
+            _ => return Ok(EmulateItemResult::NotSupported),
+        }
+        Ok(EmulateItemResult::NeedsReturn)
+    }
+}
+
+/// Checks that `feature` is enabled, and also `avx512vl` if `link_name` is
+/// the 128- or 256-bit version of an intrinsic.
+fn expect_avx512_feature<'tcx>(
+    this: &crate::MiriInterpCx<'tcx>,
+    link_name: Symbol,
+    feature: &str,
+) -> InterpResult<'tcx, ()> {
+    this.expect_target_feature_for_intrinsic(link_name, feature)?;
+    let name = link_name.as_str();
+    if name.ends_with(".128") || name.ends_with(".256") {
+        this.expect_target_feature_for_intrinsic(link_name, "avx512vl")?;
+    }
+    Ok(())
+}
+
+/// Returns the target feature required by a compress or expand intrinsic:
+/// the 8- and 16-bit versions come with VBMI2.
+fn compress_feature(unprefixed_name: &str) -> &'static str {
+    if unprefixed_name.contains(".b.") || unprefixed_name.contains(".w.") {
+        "avx512vbmi2"
+    } else {
+        "avx512f"
+    }
+}
+
+/// Computes the bitwise function of `a`, `b` and `c` whose truth table is given by `imm8`,
+/// and stores the result in `dest`. Bit `(a << 2) | (b << 1) | c` of `imm8` is the
+/// result for the corresponding bits of `a`, `b` and `c`.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_ternarylogic_epi32>
+fn ternary_logic<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    a: &OpTy<'tcx>,
+    b: &OpTy<'tcx>,
+    c: &OpTy<'tcx>,
+    imm8: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (a, a_len) = this.operand_to_simd(a)?;
+    let (b, b_len) = this.operand_to_simd(b)?;
+    let (c, c_len) = this.operand_to_simd(c)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, a_len);
+    assert_eq!(dest_len, b_len);
+    assert_eq!(dest_len, c_len);
+
+    let imm8 = this.read_scalar(imm8)?.to_u32()? & 0xFF;
+
+    for i in 0..dest_len {
+        let dest = this.project_index(&dest, i)?;
+        let size = dest.layout.size;
+
+        let a = this.read_scalar(&this.project_index(&a, i)?)?.to_uint(size)?;
+        let b = this.read_scalar(&this.project_index(&b, i)?)?.to_uint(size)?;
+        let c = this.read_scalar(&this.project_index(&c, i)?)?.to_uint(size)?;
+
+        // Combine the minterms of the bits of `imm8` that are set.
+        let mut res = 0;
+        for bit in 0..8 {
+            if imm8 & (1 << bit) != 0 {
+                let a = if bit & 0b100 != 0 { a } else { !a };
+                let b = if bit & 0b010 != 0 { b } else { !b };
+                let c = if bit & 0b001 != 0 { c } else { !c };
+                res |= a & b & c;
+            }
+        }
+
+        // `truncate` is needed as the negations set the bits above `size`.
+        this.write_scalar(Scalar::from_uint(size.truncate(res), size), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Shuffles the elements of the concatenation of `a` and `b`, using the low
+/// bits of each element of `indices` as indices. The number of bits used
+/// depends on the number of elements, the remaining ones are ignored.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_permutex2var_epi32>
+fn permute2<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    a: &OpTy<'tcx>,
+    indices: &OpTy<'tcx>,
+    b: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (a, a_len) = this.operand_to_simd(a)?;
+    let (indices, indices_len) = this.operand_to_simd(indices)?;
+    let (b, b_len) = this.operand_to_simd(b)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, a_len);
+    assert_eq!(dest_len, b_len);
+    assert_eq!(dest_len, indices_len);
+    assert!(dest_len.is_power_of_two());
+
+    for i in 0..dest_len {
+        let dest = this.project_index(&dest, i)?;
+        let index = this.project_index(&indices, i)?;
+        let index = this.read_scalar(&index)?.to_uint(index.layout.size)?;
+        // The length is a power of two, so this keeps the low bits.
+        let index = u64::try_from(index % u128::from(dest_len.strict_mul(2))).unwrap();
+
+        let src = if index < dest_len {
+            this.project_index(&a, index)?
+        } else {
+            this.project_index(&b, index.strict_sub(dest_len))?
+        };
+        this.copy_op(&src, &dest)?;
+    }
+
+    Ok(())
+}
+
+/// For each byte of `control`, selects 8 bits of the corresponding 64-bit element
+/// of `data`, starting at the bit given by the low 6 bits of the byte and wrapping
+/// around.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_multishift_epi64_epi8>
+fn multishift<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    control: &OpTy<'tcx>,
+    data: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    assert_eq!(control.layout, data.layout);
+    assert_eq!(control.layout.size, dest.layout.size);
+
+    // Transmute to `[u64; N]`
+
+    let len = dest.layout.size.bits() / 64;
+    let array_layout = this.layout_of(Ty::new_array(this.tcx.tcx, this.tcx.types.u64, len))?;
+    let control = control.transmute(array_layout, this)?;
+    let data = data.transmute(array_layout, this)?;
+    let dest = dest.transmute(array_layout, this)?;
+
+    for i in 0..len {
+        let control = this.read_scalar(&this.project_index(&control, i)?)?.to_u64()?;
+        let data = this.read_scalar(&this.project_index(&data, i)?)?.to_u64()?;
+
+        let res = control
+            .to_le_bytes()
+            .map(|shift| data.rotate_right(u32::from(shift & 0x3F)).to_le_bytes()[0]);
+
+        this.write_scalar(
+            Scalar::from_u64(u64::from_le_bytes(res)),
+            &this.project_index(&dest, i)?,
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Multiplies the 4 unsigned 8-bit integers in each 32-bit element of `left` with the
+/// corresponding signed 8-bit integers of `right` (or the 2 signed 16-bit integers of
+/// both, if `words` is true) and adds the sum of the products to the corresponding
+/// 32-bit integer of `src`, with signed saturation if `saturating` is true.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_dpbusd_epi32>
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_dpwssd_epi32>
+fn dot_product_accumulate<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    src: &OpTy<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    words: bool,
+    saturating: bool,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (src, src_len) = this.operand_to_simd(src)?;
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, src_len);
+    assert_eq!(dest_len, left_len);
+    assert_eq!(dest_len, right_len);
+
+    for i in 0..dest_len {
+        let src = this.read_scalar(&this.project_index(&src, i)?)?.to_i32()?;
+        let left = this.read_scalar(&this.project_index(&left, i)?)?.to_u32()?.to_le_bytes();
+        let right = this.read_scalar(&this.project_index(&right, i)?)?.to_u32()?.to_le_bytes();
+        let dest = this.project_index(&dest, i)?;
+
+        let products: i64 = if words {
+            left.chunks_exact(2)
+                .zip(right.chunks_exact(2))
+                .map(|(l, r)| {
+                    let l = i16::from_le_bytes([l[0], l[1]]);
+                    let r = i16::from_le_bytes([r[0], r[1]]);
+                    i64::from(l).strict_mul(r.into())
+                })
+                .sum()
+        } else {
+            left.iter()
+                .zip(right)
+                .map(|(&l, r)| i64::from(l).strict_mul(i8::from_le_bytes([r]).into()))
+                .sum()
+        };
+        // The sum of the products is small enough for this not to overflow.
+        let res = i64::from(src).strict_add(products);
+        let res = if saturating { res.clamp(i32::MIN.into(), i32::MAX.into()) } else { res };
+        // Without saturation, the result wraps around.
+        #[allow(clippy::cast_possible_truncation)]
+        let res = res as i32;
+
+        this.write_scalar(Scalar::from_i32(res), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Stores the elements of `value` whose bit in `mask` is set contiguously in the
+/// low elements of `dest`, and fills the remaining elements from `src`.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_mask_compress_epi32>
+fn compress<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    value: &OpTy<'tcx>,
+    src: &OpTy<'tcx>,
+    mask: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (value, value_len) = this.operand_to_simd(value)?;
+    let (src, src_len) = this.operand_to_simd(src)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, value_len);
+    assert_eq!(dest_len, src_len);
+
+    let mask = this.read_scalar(mask)?.to_uint(mask.layout.size)?;
+
+    let mut j = 0;
+    for i in 0..value_len {
+        if mask & (1 << i) != 0 {
+            this.copy_op(&this.project_index(&value, i)?, &this.project_index(&dest, j)?)?;
+            j = j.strict_add(1);
+        }
+    }
+    for j in j..dest_len {
+        this.copy_op(&this.project_index(&src, j)?, &this.project_index(&dest, j)?)?;
+    }
+
+    Ok(())
+}
+
+/// Stores the elements of `value` whose bit in `mask` is set contiguously to `ptr`.
+/// `ptr` does not need to be aligned.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_mask_compressstoreu_epi32>
+fn compress_store<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    ptr: &OpTy<'tcx>,
+    value: &OpTy<'tcx>,
+    mask: &OpTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (value, value_len) = this.operand_to_simd(value)?;
+
+    let mask = this.read_scalar(mask)?.to_uint(mask.layout.size)?;
+
+    let ptr = this.read_pointer(ptr)?;
+    let mut j = 0;
+    for i in 0..value_len {
+        if mask & (1 << i) != 0 {
+            let value = this.project_index(&value, i)?;
+            let ptr = ptr.wrapping_offset(value.layout.size * j, &this.tcx);
+            // Unaligned copy, which is what we want.
+            this.mem_copy(value.ptr(), ptr, value.layout.size, /*nonoverlapping*/ true)?;
+            j = j.strict_add(1);
+        }
+    }
+
+    Ok(())
+}
+
+/// Stores the low elements of `value` in the elements of `dest` whose bit in `mask`
+/// is set, and fills the remaining elements from `src`.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_mask_expand_epi32>
+fn expand<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    value: &OpTy<'tcx>,
+    src: &OpTy<'tcx>,
+    mask: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (value, value_len) = this.operand_to_simd(value)?;
+    let (src, src_len) = this.operand_to_simd(src)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, value_len);
+    assert_eq!(dest_len, src_len);
+
+    let mask = this.read_scalar(mask)?.to_uint(mask.layout.size)?;
+
+    let mut j = 0;
+    for i in 0..dest_len {
+        let dest = this.project_index(&dest, i)?;
+        if mask & (1 << i) != 0 {
+            this.copy_op(&this.project_index(&value, j)?, &dest)?;
+            j = j.strict_add(1);
+        } else {
+            this.copy_op(&this.project_index(&src, i)?, &dest)?;
+        }
+    }
+
+    Ok(())
+}
+
+enum Narrowing {
+    /// Keep the low bits.
+    Truncate,
+    /// Saturate as signed integers.
+    SignedSaturate,
+    /// Saturate as unsigned integers.
+    UnsignedSaturate,
+}
+
+/// Converts the elements of `value` to the smaller integer type of `dest` as
+/// specified by `which`. Elements whose bit in `mask` is not set are taken from
+/// `src` instead. If `dest` has more elements than `value`, the remaining ones
+/// are set to zero.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_mask_cvtsepi32_epi8>
+fn narrow<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    value: &OpTy<'tcx>,
+    src: &OpTy<'tcx>,
+    mask: &OpTy<'tcx>,
+    which: Narrowing,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (value, value_len) = this.operand_to_simd(value)?;
+    let (src, src_len) = this.operand_to_simd(src)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, src_len);
+    assert!(dest_len >= value_len);
+
+    let mask = this.read_scalar(mask)?.to_uint(mask.layout.size)?;
+
+    for i in 0..value_len {
+        let dest = this.project_index(&dest, i)?;
+        if mask & (1 << i) == 0 {
+            this.copy_op(&this.project_index(&src, i)?, &dest)?;
+            continue;
+        }
+
+        let value = this.project_index(&value, i)?;
+        let value_size = value.layout.size;
+        let value = this.read_scalar(&value)?;
+        let size = dest.layout.size;
+        let res = match which {
+            Narrowing::Truncate => {
+                let value = value.to_uint(value_size)?;
+                Scalar::from_uint(size.truncate(value), size)
+            }
+            Narrowing::SignedSaturate => {
+                let value = value.to_int(value_size)?;
+                Scalar::from_int(value.clamp(size.signed_int_min(), size.signed_int_max()), size)
+            }
+            Narrowing::UnsignedSaturate => {
+                let value = value.to_uint(value_size)?;
+                Scalar::from_uint(value.min(size.unsigned_int_max()), size)
+            }
+        };
+        this.write_scalar(res, &dest)?;
+    }
+    for i in value_len..dest_len {
+        let dest = this.project_index(&dest, i)?;
+        this.write_scalar(Scalar::from_int(0, dest.layout.size), &dest)?;
+    }
+
+    Ok(())
+}
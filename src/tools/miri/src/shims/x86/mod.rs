@@ -14,6 +14,7 @@
 mod aesni;
 mod avx;
 mod avx2;
+mod avx512;
 mod bmi;
 mod sha;
 mod sse;
@@ -94,7 +95,16 @@ fn emulate_x86_intrinsic(
                 }
             }
 
-            "pclmulqdq" => {
+            // Used to implement the _mm_clmulepi64_si128, _mm256_clmulepi64_epi128
+            // and _mm512_clmulepi64_epi128 functions.
+            // The 256- and 512-bit versions require VPCLMULQDQ and, like the
+            // corresponding instructions, operate on each 128-bit lane separately.
+            "pclmulqdq" | "pclmulqdq.256" | "pclmulqdq.512" => {
+                this.expect_target_feature_for_intrinsic(link_name, "pclmulqdq")?;
+                if unprefixed_name != "pclmulqdq" {
+                    this.expect_target_feature_for_intrinsic(link_name, "vpclmulqdq")?;
+                }
+
                 let [left, right, imm] =
                     this.check_shim(abi, Abi::C { unwind: false }, link_name, args)?;
 
@@ -156,6 +166,11 @@ fn emulate_x86_intrinsic(
                     this, link_name, abi, args, dest,
                 );
             }
+            name if name.starts_with("avx512.") => {
+                return avx512::EvalContextExt::emulate_x86_avx512_intrinsic(
+                    this, link_name, abi, args, dest,
+                );
+            }
 
             _ => return Ok(EmulateItemResult::NotSupported),
         }
@@ -1120,13 +1135,210 @@ fn pmulhrsw<'tcx>(
     Ok(())
 }
 
+/// Multiplies packed signed 16-bit integers in `left` and `right`, producing
+/// intermediate signed 32-bit integers. Horizontally add adjacent pairs of
+/// intermediate 32-bit integers, and pack the results in `dest`.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_madd_epi16>
+fn pmaddwd<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(left_len, right_len);
+    assert_eq!(dest_len.strict_mul(2), left_len);
+
+    for i in 0..dest_len {
+        let j1 = i.strict_mul(2);
+        let left1 = this.read_scalar(&this.project_index(&left, j1)?)?.to_i16()?;
+        let right1 = this.read_scalar(&this.project_index(&right, j1)?)?.to_i16()?;
+
+        let j2 = j1.strict_add(1);
+        let left2 = this.read_scalar(&this.project_index(&left, j2)?)?.to_i16()?;
+        let right2 = this.read_scalar(&this.project_index(&right, j2)?)?.to_i16()?;
+
+        let dest = this.project_index(&dest, i)?;
+
+        // Multiplications are i16*i16->i32, which will not overflow.
+        let mul1 = i32::from(left1).strict_mul(right1.into());
+        let mul2 = i32::from(left2).strict_mul(right2.into());
+        // However, this addition can overflow in the most extreme case
+        // (-0x8000)*(-0x8000)+(-0x8000)*(-0x8000) = 0x80000000
+        let res = mul1.wrapping_add(mul2);
+
+        this.write_scalar(Scalar::from_i32(res), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Multiplies packed 8-bit unsigned integers from `left` and packed
+/// signed 8-bit integers from `right` into 16-bit signed integers. Then,
+/// the saturating sum of the products with indices `2*i` and `2*i+1`
+/// produces the output at index `i`.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_maddubs_epi16>
+fn pmaddubsw<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(left_len, right_len);
+    assert_eq!(dest_len.strict_mul(2), left_len);
+
+    for i in 0..dest_len {
+        let j1 = i.strict_mul(2);
+        let left1 = this.read_scalar(&this.project_index(&left, j1)?)?.to_u8()?;
+        let right1 = this.read_scalar(&this.project_index(&right, j1)?)?.to_i8()?;
+
+        let j2 = j1.strict_add(1);
+        let left2 = this.read_scalar(&this.project_index(&left, j2)?)?.to_u8()?;
+        let right2 = this.read_scalar(&this.project_index(&right, j2)?)?.to_i8()?;
+
+        let dest = this.project_index(&dest, i)?;
+
+        // Multiplication of a u8 and an i8 into an i16 cannot overflow.
+        let mul1 = i16::from(left1).strict_mul(right1.into());
+        let mul2 = i16::from(left2).strict_mul(right2.into());
+        let res = mul1.saturating_add(mul2);
+
+        this.write_scalar(Scalar::from_i16(res), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Compute the absolute differences of packed unsigned 8-bit integers
+/// in `left` and `right`, then horizontally sum each consecutive 8
+/// differences to produce unsigned 16-bit integers, and pack
+/// these unsigned 16-bit integers in the low 16 bits of 64-bit elements
+/// in `dest`.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_sad_epu8>
+fn psadbw<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(left_len, right_len);
+    assert_eq!(left_len, dest_len.strict_mul(8));
+
+    for i in 0..dest_len {
+        let dest = this.project_index(&dest, i)?;
+
+        let mut acc: u16 = 0;
+        for j in 0..8 {
+            let src_index = i.strict_mul(8).strict_add(j);
+
+            let left = this.project_index(&left, src_index)?;
+            let left = this.read_scalar(&left)?.to_u8()?;
+
+            let right = this.project_index(&right, src_index)?;
+            let right = this.read_scalar(&right)?.to_u8()?;
+
+            acc = acc.strict_add(left.abs_diff(right).into());
+        }
+
+        this.write_scalar(Scalar::from_u64(acc.into()), &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Shuffles bytes from `left` using `right` as pattern.
+///
+/// Each 128-bit block is shuffled independently.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_shuffle_epi8>
+fn pshufb<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, left_len);
+    assert_eq!(dest_len, right_len);
+
+    for i in 0..dest_len {
+        let right = this.read_scalar(&this.project_index(&right, i)?)?.to_u8()?;
+        let dest = this.project_index(&dest, i)?;
+
+        let res = if right & 0x80 == 0 {
+            // Shuffle each 128-bit (16-byte) block independently.
+            let j = u64::from(right % 16).strict_add(i & !15);
+            this.read_scalar(&this.project_index(&left, j)?)?
+        } else {
+            // If the highest bit in `right` is 1, write zero.
+            Scalar::from_u8(0)
+        };
+
+        this.write_scalar(res, &dest)?;
+    }
+
+    Ok(())
+}
+
+/// Shuffles the elements of `left` across the whole vector, using the
+/// low bits of each element of `right` as indices. The number of bits
+/// used depends on the number of elements, the remaining ones are ignored.
+///
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm256_permutevar8x32_epi32>
+fn permute<'tcx>(
+    this: &mut crate::MiriInterpCx<'tcx>,
+    left: &OpTy<'tcx>,
+    right: &OpTy<'tcx>,
+    dest: &MPlaceTy<'tcx>,
+) -> InterpResult<'tcx, ()> {
+    let (left, left_len) = this.operand_to_simd(left)?;
+    let (right, right_len) = this.operand_to_simd(right)?;
+    let (dest, dest_len) = this.mplace_to_simd(dest)?;
+
+    assert_eq!(dest_len, left_len);
+    assert_eq!(dest_len, right_len);
+    assert!(dest_len.is_power_of_two());
+
+    for i in 0..dest_len {
+        let dest = this.project_index(&dest, i)?;
+        let right = this.project_index(&right, i)?;
+        let index = this.read_scalar(&right)?.to_uint(right.layout.size)?;
+        // The length is a power of two, so this keeps the low bits.
+        let index = u64::try_from(index % u128::from(dest_len)).unwrap();
+        let left = this.project_index(&left, index)?;
+
+        this.copy_op(&left, &dest)?;
+    }
+
+    Ok(())
+}
+
 /// Perform a carry-less multiplication of two 64-bit integers, selected from `left` and `right` according to `imm8`,
 /// and store the results in `dst`.
 ///
-/// `left` and `right` are both vectors of type 2 x i64. Only bits 0 and 4 of `imm8` matter;
-/// they select the element of `left` and `right`, respectively.
+/// `left` and `right` are both vectors of type N x i64, with N being 2, 4 or 8. Each 128-bit lane
+/// is handled separately. Only bits 0 and 4 of `imm8` matter; they select the element of each lane
+/// of `left` and `right`, respectively.
 ///
 /// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm_clmulepi64_si128>
+/// <https://www.intel.com/content/www/us/en/docs/intrinsics-guide/index.html#text=_mm512_clmulepi64_epi128>
 fn pclmulqdq<'tcx>(
     this: &mut MiriInterpCx<'tcx>,
     left: &OpTy<'tcx>,
@@ -1137,9 +1349,14 @@ fn pclmulqdq<'tcx>(
     assert_eq!(left.layout, right.layout);
     assert_eq!(left.layout.size, dest.layout.size);
 
-    // Transmute to `[u64; 2]`
+    // Transmute to `[[u64; 2]; N]`
 
-    let array_layout = this.layout_of(Ty::new_array(this.tcx.tcx, this.tcx.types.u64, 2))?;
+    let num_lanes = dest.layout.size.bits() / 128;
+    let array_layout = this.layout_of(Ty::new_array(
+        this.tcx.tcx,
+        Ty::new_array(this.tcx.tcx, this.tcx.types.u64, 2),
+        num_lanes,
+    ))?;
     let left = left.transmute(array_layout, this)?;
     let right = right.transmute(array_layout, this)?;
     let dest = dest.transmute(array_layout, this)?;
@@ -1147,37 +1364,43 @@ fn pclmulqdq<'tcx>(
     let imm8 = this.read_scalar(imm8)?.to_u8()?;
 
     // select the 64-bit integer from left that the user specified (low or high)
-    let index = if (imm8 & 0x01) == 0 { 0 } else { 1 };
-    let left = this.read_scalar(&this.project_index(&left, index)?)?.to_u64()?;
-
+    let left_index = if (imm8 & 0x01) == 0 { 0 } else { 1 };
     // select the 64-bit integer from right that the user specified (low or high)
-    let index = if (imm8 & 0x10) == 0 { 0 } else { 1 };
-    let right = this.read_scalar(&this.project_index(&right, index)?)?.to_u64()?;
-
-    // Perform carry-less multiplication
-    //
-    // This operation is like long multiplication, but ignores all carries.
-    // That idea corresponds to the xor operator, which is used in the implementation.
-    //
-    // Wikipedia has an example https://en.wikipedia.org/wiki/Carry-less_product#Example
-    let mut result: u128 = 0;
-
-    for i in 0..64 {
-        // if the i-th bit in right is set
-        if (right & (1 << i)) != 0 {
-            // xor result with `left` shifted to the left by i positions
-            result ^= u128::from(left) << i;
+    let right_index = if (imm8 & 0x10) == 0 { 0 } else { 1 };
+
+    for i in 0..num_lanes {
+        let left = this.project_index(&this.project_index(&left, i)?, left_index)?;
+        let left = this.read_scalar(&left)?.to_u64()?;
+        let right = this.project_index(&this.project_index(&right, i)?, right_index)?;
+        let right = this.read_scalar(&right)?.to_u64()?;
+
+        // Perform carry-less multiplication
+        //
+        // This operation is like long multiplication, but ignores all carries.
+        // That idea corresponds to the xor operator, which is used in the implementation.
+        //
+        // Wikipedia has an example https://en.wikipedia.org/wiki/Carry-less_product#Example
+        let mut result: u128 = 0;
+
+        for j in 0..64 {
+            // if the j-th bit in right is set
+            if (right & (1 << j)) != 0 {
+                // xor result with `left` shifted to the left by j positions
+                result ^= u128::from(left) << j;
+            }
         }
-    }
 
-    let result_low = (result & 0xFFFF_FFFF_FFFF_FFFF) as u64;
-    let result_high = (result >> 64) as u64;
+        let result_low = (result & 0xFFFF_FFFF_FFFF_FFFF) as u64;
+        let result_high = (result >> 64) as u64;
+
+        let dest = this.project_index(&dest, i)?;
 
-    let dest_low = this.project_index(&dest, 0)?;
-    this.write_scalar(Scalar::from_u64(result_low), &dest_low)?;
+        let dest_low = this.project_index(&dest, 0)?;
+        this.write_scalar(Scalar::from_u64(result_low), &dest_low)?;
 
-    let dest_high = this.project_index(&dest, 1)?;
-    this.write_scalar(Scalar::from_u64(result_high), &dest_high)?;
+        let dest_high = this.project_index(&dest, 1)?;
+        this.write_scalar(Scalar::from_u64(result_high), &dest_high)?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,25 @@
+//! Two threads increment a counter with a separate load and store. The update of one thread only
+//! gets lost if it is preempted between the two, which takes exactly one preemption.
+//! `tests/ui.rs` also replays the failing schedule reported here.
+//@compile-flags: -Zmiri-explore-schedules=1 -Zmiri-disable-weak-memory-emulation
+//@normalize-stderr-test: "execution [0-9]+ of" -> "execution N of"
+//@normalize-stderr-test: "(\n +[0-9]+ [0-9]+ [a-z]+ [0-9]+)+" -> "\n      SWITCHES"
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::thread;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn increment() {
+    let value = COUNTER.load(Relaxed);
+    COUNTER.store(value + 1, Relaxed);
+}
+
+fn main() {
+    let handle = thread::spawn(increment);
+    increment();
+    handle.join().unwrap();
+    if COUNTER.load(Relaxed) != 2 {
+        unsafe { std::hint::unreachable_unchecked() } //~ERROR: entering unreachable code
+    }
+}
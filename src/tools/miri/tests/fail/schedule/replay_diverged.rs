@@ -0,0 +1,15 @@
+//! Replays a hand-edited trace that preempts the main thread in favor of a thread that was never
+//! spawned. Without `std`, the first step of the trace is the first statement of `miri_start`.
+//@compile-flags: -Cpanic=abort -Zmiri-replay-schedule=tests/fail/schedule/replay_diverged.trace
+#![no_main]
+#![no_std]
+
+#[no_mangle]
+fn miri_start(_argc: isize, _argv: *const *const u8) -> isize {
+    0 //~ ERROR: diverged from switch #1
+}
+
+#[panic_handler]
+fn panic_handler(_: &core::panic::PanicInfo) -> ! {
+    loop {}
+}
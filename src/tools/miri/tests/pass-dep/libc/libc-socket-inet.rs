@@ -0,0 +1,235 @@
+//@only-target-linux
+// test_epoll_blocking depends on a deterministic schedule.
+//@compile-flags: -Zmiri-preemption-rate=0
+
+use std::mem::{self, MaybeUninit};
+use std::thread;
+
+fn main() {
+    test_tcp_sockets();
+    test_udp_flags();
+    test_sockopt();
+    test_epoll_listener();
+    test_epoll_blocking();
+}
+
+fn loopback(port: u16) -> libc::sockaddr_in {
+    libc::sockaddr_in {
+        sin_family: libc::AF_INET as libc::sa_family_t,
+        sin_port: port.to_be(),
+        sin_addr: libc::in_addr { s_addr: u32::from_be_bytes([127, 0, 0, 1]).to_be() },
+        sin_zero: [0; 8],
+    }
+}
+
+fn socket(ty: i32) -> i32 {
+    let fd = unsafe { libc::socket(libc::AF_INET, ty, 0) };
+    assert!(fd >= 0);
+    fd
+}
+
+fn bind(fd: i32, addr: &libc::sockaddr_in) {
+    let res = unsafe {
+        libc::bind(
+            fd,
+            (addr as *const libc::sockaddr_in).cast(),
+            mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    assert_eq!(res, 0);
+}
+
+fn connect(fd: i32, addr: &libc::sockaddr_in) -> i32 {
+    unsafe {
+        libc::connect(
+            fd,
+            (addr as *const libc::sockaddr_in).cast(),
+            mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    }
+}
+
+fn sockname(fd: i32) -> libc::sockaddr_in {
+    let mut addr = MaybeUninit::<libc::sockaddr_in>::uninit();
+    let mut len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let res = unsafe { libc::getsockname(fd, addr.as_mut_ptr().cast(), &mut len) };
+    assert_eq!(res, 0);
+    assert_eq!(len as usize, mem::size_of::<libc::sockaddr_in>());
+    unsafe { addr.assume_init() }
+}
+
+/// Returns a listening socket and a client connected to it.
+fn listen_and_connect() -> (i32, i32, libc::sockaddr_in) {
+    let listener = socket(libc::SOCK_STREAM | libc::SOCK_NONBLOCK);
+    bind(listener, &loopback(0));
+    assert_eq!(unsafe { libc::listen(listener, 16) }, 0);
+    let addr = sockname(listener);
+    let client = socket(libc::SOCK_STREAM);
+    assert_eq!(connect(client, &addr), 0);
+    (listener, client, addr)
+}
+
+fn test_tcp_sockets() {
+    let (listener, client, addr) = listen_and_connect();
+    assert_ne!(addr.sin_port, 0);
+
+    let server = unsafe {
+        libc::accept4(listener, std::ptr::null_mut(), std::ptr::null_mut(), libc::SOCK_CLOEXEC)
+    };
+    assert!(server >= 0);
+    // Nothing else is waiting on the non-blocking listener.
+    let res = unsafe { libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut()) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EWOULDBLOCK));
+
+    // Non-blocking `read` and `write` work like `recv` and `send`.
+    let mut nonblocking: libc::c_int = 1;
+    assert_eq!(unsafe { libc::ioctl(server, libc::FIONBIO, &mut nonblocking) }, 0);
+    let mut buf = [0u8; 8];
+    let res = unsafe { libc::read(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EWOULDBLOCK));
+    let res = unsafe { libc::send(client, b"abc".as_ptr().cast(), 3, 0) };
+    assert_eq!(res, 3);
+    let res = unsafe { libc::read(server, buf.as_mut_ptr().cast(), buf.len()) };
+    assert_eq!(res, 3);
+    assert_eq!(&buf[..3], b"abc");
+
+    // A connected socket cannot connect again.
+    assert_eq!(connect(client, &addr), -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EISCONN));
+
+    // After shutting down the read half, reads return EOF.
+    assert_eq!(unsafe { libc::shutdown(server, libc::SHUT_RD) }, 0);
+    let res = unsafe { libc::recv(server, buf.as_mut_ptr().cast(), buf.len(), 0) };
+    assert_eq!(res, 0);
+
+    for fd in [server, client, listener] {
+        assert_eq!(unsafe { libc::close(fd) }, 0);
+    }
+}
+
+fn test_udp_flags() {
+    let a = socket(libc::SOCK_DGRAM);
+    let b = socket(libc::SOCK_DGRAM);
+    bind(b, &loopback(0));
+    let b_addr = sockname(b);
+
+    // `b` is blocking, but MSG_DONTWAIT makes a single call non-blocking.
+    let mut buf = [0u8; 4];
+    let res = unsafe { libc::recv(b, buf.as_mut_ptr().cast(), buf.len(), libc::MSG_DONTWAIT) };
+    assert_eq!(res, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EWOULDBLOCK));
+
+    // Sending from an unbound socket binds it to an ephemeral port.
+    let res = unsafe {
+        libc::sendto(
+            a,
+            b"ping".as_ptr().cast(),
+            4,
+            0,
+            (&b_addr as *const libc::sockaddr_in).cast(),
+            mem::size_of::<libc::sockaddr_in>() as libc::socklen_t,
+        )
+    };
+    assert_eq!(res, 4);
+    let a_port = u16::from_be(sockname(a).sin_port);
+    assert_ne!(a_port, 0);
+
+    let mut from = MaybeUninit::<libc::sockaddr_in>::uninit();
+    let mut fromlen = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    let res = unsafe {
+        libc::recvfrom(b, buf.as_mut_ptr().cast(), 4, 0, from.as_mut_ptr().cast(), &mut fromlen)
+    };
+    assert_eq!(res, 4);
+    assert_eq!(&buf, b"ping");
+    assert_eq!(u16::from_be(unsafe { from.assume_init() }.sin_port), a_port);
+
+    // Datagram sockets cannot listen.
+    assert_eq!(unsafe { libc::listen(b, 1) }, -1);
+    assert_eq!(std::io::Error::last_os_error().raw_os_error(), Some(libc::EOPNOTSUPP));
+}
+
+fn test_sockopt() {
+    let fd = socket(libc::SOCK_STREAM);
+    let mut value: libc::c_int = 0;
+    let mut len = mem::size_of::<libc::c_int>() as libc::socklen_t;
+    let res = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::SOL_SOCKET,
+            libc::SO_TYPE,
+            (&mut value as *mut i32).cast(),
+            &mut len,
+        )
+    };
+    assert_eq!(res, 0);
+    assert_eq!(value, libc::SOCK_STREAM);
+
+    let enable: libc::c_int = 1;
+    let res = unsafe {
+        libc::setsockopt(
+            fd,
+            libc::IPPROTO_TCP,
+            libc::TCP_NODELAY,
+            (&enable as *const i32).cast(),
+            mem::size_of::<libc::c_int>() as libc::socklen_t,
+        )
+    };
+    assert_eq!(res, 0);
+    let res = unsafe {
+        libc::getsockopt(
+            fd,
+            libc::IPPROTO_TCP,
+            libc::TCP_NODELAY,
+            (&mut value as *mut i32).cast(),
+            &mut len,
+        )
+    };
+    assert_eq!(res, 0);
+    assert_eq!(value, 1);
+}
+
+fn test_epoll_listener() {
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let listener = socket(libc::SOCK_STREAM);
+    bind(listener, &loopback(0));
+    assert_eq!(unsafe { libc::listen(listener, 16) }, 0);
+    let mut ev = libc::epoll_event { events: libc::EPOLLIN as _, u64: 7 };
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, listener, &mut ev) };
+    assert_eq!(res, 0);
+
+    let mut events = [libc::epoll_event { events: 0, u64: 0 }; 1];
+    assert_eq!(unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 1, 0) }, 0);
+
+    // A pending connection makes the listener readable.
+    let client = socket(libc::SOCK_STREAM);
+    assert_eq!(connect(client, &sockname(listener)), 0);
+    assert_eq!(unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 1, 0) }, 1);
+    assert_eq!({ events[0].events }, libc::EPOLLIN as u32);
+    assert_eq!({ events[0].u64 }, 7);
+}
+
+fn test_epoll_blocking() {
+    let (listener, client, _) = listen_and_connect();
+    let server = unsafe { libc::accept(listener, std::ptr::null_mut(), std::ptr::null_mut()) };
+    assert!(server >= 0);
+
+    let epfd = unsafe { libc::epoll_create1(0) };
+    assert_ne!(epfd, -1);
+    let mut ev = libc::epoll_event { events: (libc::EPOLLIN | libc::EPOLLET) as _, u64: 1 };
+    let res = unsafe { libc::epoll_ctl(epfd, libc::EPOLL_CTL_ADD, server, &mut ev) };
+    assert_eq!(res, 0);
+
+    let writer = thread::spawn(move || {
+        let res = unsafe { libc::send(client, b"wake".as_ptr().cast(), 4, 0) };
+        assert_eq!(res, 4);
+    });
+
+    // Blocks until the other thread sends.
+    let mut events = [libc::epoll_event { events: 0, u64: 0 }; 1];
+    assert_eq!(unsafe { libc::epoll_wait(epfd, events.as_mut_ptr(), 1, -1) }, 1);
+    assert_eq!({ events[0].u64 }, 1);
+    writer.join().unwrap();
+}
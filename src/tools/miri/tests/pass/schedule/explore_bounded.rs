@@ -0,0 +1,18 @@
+//! Atomic increments cannot lose an update, so none of the explored schedules fails and Miri
+//! reports how many there were.
+//@compile-flags: -Zmiri-explore-schedules=1 -Zmiri-disable-weak-memory-emulation
+//@normalize-stderr-test: "explored [0-9]+ schedules" -> "explored N schedules"
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::thread;
+
+static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+fn main() {
+    let handle = thread::spawn(|| {
+        COUNTER.fetch_add(1, Relaxed);
+    });
+    COUNTER.fetch_add(1, Relaxed);
+    handle.join().unwrap();
+    assert_eq!(COUNTER.load(Relaxed), 2);
+}
@@ -0,0 +1,180 @@
+// We're testing aarch64 target specific features
+//@only-target-aarch64
+//@compile-flags: -C target-feature=+neon,+aes,+sha2,+crc
+
+use std::arch::aarch64::*;
+use std::arch::is_aarch64_feature_detected;
+use std::mem::transmute;
+
+fn main() {
+    assert!(is_aarch64_feature_detected!("aes"));
+    assert!(is_aarch64_feature_detected!("sha2"));
+    assert!(is_aarch64_feature_detected!("crc"));
+
+    unsafe {
+        test_crc32();
+        test_aes();
+        test_pmull();
+        test_sha1();
+        test_sha256();
+    }
+}
+
+#[target_feature(enable = "crc")]
+unsafe fn test_crc32() {
+    // The check values of CRC-32/ISO-HDLC and CRC-32C, which invert the checksum
+    // before and after.
+    let data = b"123456789";
+    let crc = data.iter().fold(!0, |crc, &byte| __crc32b(crc, byte));
+    assert_eq!(!crc, 0xCBF43926);
+    let crc = data.iter().fold(!0, |crc, &byte| __crc32cb(crc, byte));
+    assert_eq!(!crc, 0xE3069283);
+
+    // Wider versions process the data in little-endian order.
+    let word = u32::from_le_bytes(data[..4].try_into().unwrap());
+    let by_bytes = data[..4].iter().fold(!0, |crc, &byte| __crc32b(crc, byte));
+    assert_eq!(__crc32w(!0, word), by_bytes);
+    let half = u16::from_le_bytes(data[..2].try_into().unwrap());
+    let by_bytes = data[..2].iter().fold(!0, |crc, &byte| __crc32cb(crc, byte));
+    assert_eq!(__crc32ch(!0, half), by_bytes);
+    let double = u64::from_le_bytes(data[..8].try_into().unwrap());
+    let by_bytes = data[..8].iter().fold(!0, |crc, &byte| __crc32cb(crc, byte));
+    assert_eq!(__crc32cd(!0, double), by_bytes);
+}
+
+/// Returns the first word of the substitution of each byte of `word`.
+#[target_feature(enable = "neon,aes")]
+unsafe fn sub_word(word: u32) -> u32 {
+    // All columns of the state are the same, so the row shifts of `vaeseq_u8` do nothing.
+    let state = vaeseq_u8(vreinterpretq_u8_u32(vdupq_n_u32(word)), vdupq_n_u8(0));
+    vgetq_lane_u32(vreinterpretq_u32_u8(state), 0)
+}
+
+#[target_feature(enable = "neon,aes")]
+unsafe fn test_aes() {
+    // The AES-128 example of FIPS-197, appendix C.1.
+    let key: [u8; 16] = array_from_hex("000102030405060708090a0b0c0d0e0f");
+    let plaintext: [u8; 16] = array_from_hex("00112233445566778899aabbccddeeff");
+    let ciphertext: [u8; 16] = array_from_hex("69c4e0d86a7b0430d8cdb78070b4c55a");
+
+    // Expand the key.
+    const RCON: [u32; 10] = [0x01, 0x02, 0x04, 0x08, 0x10, 0x20, 0x40, 0x80, 0x1b, 0x36];
+    let mut words = [0u32; 44];
+    for (i, chunk) in key.chunks(4).enumerate() {
+        words[i] = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    for i in 4..44 {
+        let mut temp = words[i - 1];
+        if i % 4 == 0 {
+            temp = sub_word(temp.rotate_right(8)) ^ RCON[i / 4 - 1];
+        }
+        words[i] = words[i - 4] ^ temp;
+    }
+    let round_keys: [uint8x16_t; 11] =
+        std::array::from_fn(|i| vreinterpretq_u8_u32(vld1q_u32(words[4 * i..].as_ptr())));
+
+    let mut state = vld1q_u8(plaintext.as_ptr());
+    for round_key in &round_keys[..9] {
+        state = vaesmcq_u8(vaeseq_u8(state, *round_key));
+    }
+    state = veorq_u8(vaeseq_u8(state, round_keys[9]), round_keys[10]);
+    assert_eq!(transmute::<_, [u8; 16]>(state), ciphertext);
+
+    // Decrypt with the equivalent inverse cipher.
+    let mut state = vaesimcq_u8(vaesdq_u8(state, round_keys[10]));
+    for round_key in round_keys[2..10].iter().rev() {
+        state = vaesimcq_u8(vaesdq_u8(state, vaesimcq_u8(*round_key)));
+    }
+    state = veorq_u8(vaesdq_u8(state, vaesimcq_u8(round_keys[1])), round_keys[0]);
+    assert_eq!(transmute::<_, [u8; 16]>(state), plaintext);
+}
+
+#[target_feature(enable = "neon,aes")]
+unsafe fn test_pmull() {
+    assert_eq!(vmull_p64(0b1011, 0b0110), 0b111010);
+    assert_eq!(vmull_p64(1 << 63, 1 << 63), 1 << 126);
+    assert_eq!(vmull_p64(u64::MAX, 3), u128::from(u64::MAX) ^ (u128::from(u64::MAX) << 1));
+}
+
+/// Returns the first block of the SHA-1 and SHA-256 padding of "abc", as big-endian words.
+fn abc_block() -> [u32; 16] {
+    let mut block = [0; 16];
+    block[0] = 0x61626380;
+    block[15] = 24;
+    block
+}
+
+#[target_feature(enable = "neon,sha2")]
+unsafe fn test_sha1() {
+    const K: [u32; 4] = [0x5A827999, 0x6ED9EBA1, 0x8F1BBCDC, 0xCA62C1D6];
+    let initial = [0x67452301u32, 0xEFCDAB89, 0x98BADCFE, 0x10325476];
+    let initial_e = 0xC3D2E1F0u32;
+
+    let block = abc_block();
+    let mut w: Vec<uint32x4_t> = block.chunks(4).map(|chunk| vld1q_u32(chunk.as_ptr())).collect();
+    let mut abcd = vld1q_u32(initial.as_ptr());
+    let mut e = initial_e;
+    for i in 0..20 {
+        if i >= 4 {
+            let next = vsha1su1q_u32(vsha1su0q_u32(w[i - 4], w[i - 3], w[i - 2]), w[i - 1]);
+            w.push(next);
+        }
+        let wk = vaddq_u32(w[i], vdupq_n_u32(K[i / 5]));
+        let next_e = vsha1h_u32(vgetq_lane_u32(abcd, 0));
+        abcd = match i / 5 {
+            0 => vsha1cq_u32(abcd, e, wk),
+            2 => vsha1mq_u32(abcd, e, wk),
+            _ => vsha1pq_u32(abcd, e, wk),
+        };
+        e = next_e;
+    }
+
+    let abcd = vaddq_u32(abcd, vld1q_u32(initial.as_ptr()));
+    let e = e.wrapping_add(initial_e);
+    assert_eq!(transmute::<_, [u32; 4]>(abcd), [0xa9993e36, 0x4706816a, 0xba3e2571, 0x7850c26c]);
+    assert_eq!(e, 0x9cd0d89d);
+}
+
+#[target_feature(enable = "neon,sha2")]
+unsafe fn test_sha256() {
+    const K: [u32; 64] = [
+        0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4,
+        0xab1c5ed5, 0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe,
+        0x9bdc06a7, 0xc19bf174, 0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f,
+        0x4a7484aa, 0x5cb0a9dc, 0x76f988da, 0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7,
+        0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967, 0x27b70a85, 0x2e1b2138, 0x4d2c6dfc,
+        0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85, 0xa2bfe8a1, 0xa81a664b,
+        0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070, 0x19a4c116,
+        0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+        0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7,
+        0xc67178f2,
+    ];
+    let initial: [u32; 8] = [
+        0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+        0x5be0cd19,
+    ];
+
+    let block = abc_block();
+    let mut w: Vec<uint32x4_t> = block.chunks(4).map(|chunk| vld1q_u32(chunk.as_ptr())).collect();
+    let mut abcd = vld1q_u32(initial[..4].as_ptr());
+    let mut efgh = vld1q_u32(initial[4..].as_ptr());
+    for i in 0..16 {
+        if i >= 4 {
+            let next = vsha256su1q_u32(vsha256su0q_u32(w[i - 4], w[i - 3]), w[i - 2], w[i - 1]);
+            w.push(next);
+        }
+        let wk = vaddq_u32(w[i], vld1q_u32(K[4 * i..].as_ptr()));
+        let prev_abcd = abcd;
+        abcd = vsha256hq_u32(abcd, efgh, wk);
+        efgh = vsha256h2q_u32(efgh, prev_abcd, wk);
+    }
+
+    let abcd = vaddq_u32(abcd, vld1q_u32(initial[..4].as_ptr()));
+    let efgh = vaddq_u32(efgh, vld1q_u32(initial[4..].as_ptr()));
+    assert_eq!(transmute::<_, [u32; 4]>(abcd), [0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223]);
+    assert_eq!(transmute::<_, [u32; 4]>(efgh), [0xb00361a3, 0x96177a9c, 0xb410ff61, 0xf20015ad]);
+}
+
+fn array_from_hex<const N: usize>(hex: &str) -> [u8; N] {
+    std::array::from_fn(|i| u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap())
+}
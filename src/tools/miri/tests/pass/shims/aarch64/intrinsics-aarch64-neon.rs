@@ -0,0 +1,175 @@
+// We're testing aarch64 target specific features
+//@only-target-aarch64
+//@compile-flags: -C target-feature=+neon
+
+use std::arch::aarch64::*;
+use std::arch::is_aarch64_feature_detected;
+use std::array;
+use std::mem::transmute;
+
+fn main() {
+    assert!(is_aarch64_feature_detected!("neon"));
+
+    unsafe {
+        test_load_store();
+        test_int_arith();
+        test_widen_narrow();
+        test_pairwise_and_reduce();
+        test_float();
+        test_table_lookup();
+    }
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn test_load_store() {
+    let data: [u8; 64] = array::from_fn(|i| i as u8);
+
+    // The pointer does not need to be aligned.
+    let v = vld1q_u8(data.as_ptr().add(1));
+    assert_eq!(transmute::<_, [u8; 16]>(v), array::from_fn(|i| i as u8 + 1));
+
+    let x2 = vld1q_u8_x2(data.as_ptr());
+    assert_eq!(transmute::<_, [u8; 16]>(x2.0), array::from_fn(|i| i as u8));
+    assert_eq!(transmute::<_, [u8; 16]>(x2.1), array::from_fn(|i| i as u8 + 16));
+
+    // `vld2` and `vld4` de-interleave the elements.
+    let x2 = vld2q_u8(data.as_ptr());
+    assert_eq!(transmute::<_, [u8; 16]>(x2.0), array::from_fn(|i| 2 * i as u8));
+    assert_eq!(transmute::<_, [u8; 16]>(x2.1), array::from_fn(|i| 2 * i as u8 + 1));
+    let x4 = vld4q_u8(data.as_ptr());
+    assert_eq!(transmute::<_, [u8; 16]>(x4.3), array::from_fn(|i| 4 * i as u8 + 3));
+
+    // `vst2` interleaves them again.
+    let mut out = [0u8; 32];
+    vst2q_u8(out.as_mut_ptr(), x2);
+    assert_eq!(out, data[..32]);
+
+    let swapped = vld1q_u8_x2(data.as_ptr());
+    vst1q_u8_x2(out.as_mut_ptr(), uint8x16x2_t(swapped.1, swapped.0));
+    assert_eq!(out[..16], data[16..32]);
+    assert_eq!(out[16..], data[..16]);
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn test_int_arith() {
+    let a = vdupq_n_u8(200);
+    let b = vdupq_n_u8(100);
+    assert_eq!(transmute::<_, [u8; 16]>(vqaddq_u8(a, b)), [255; 16]);
+    assert_eq!(transmute::<_, [u8; 16]>(vqsubq_u8(b, a)), [0; 16]);
+    assert_eq!(transmute::<_, [u8; 16]>(vhaddq_u8(a, b)), [150; 16]);
+    assert_eq!(transmute::<_, [u8; 16]>(vrhaddq_u8(vdupq_n_u8(1), vdupq_n_u8(2))), [2; 16]);
+    assert_eq!(transmute::<_, [u8; 16]>(vabdq_u8(b, a)), [100; 16]);
+
+    let a = vdupq_n_s8(-100);
+    assert_eq!(transmute::<_, [i8; 16]>(vqaddq_s8(a, a)), [i8::MIN; 16]);
+    assert_eq!(transmute::<_, [i8; 16]>(vqsubq_s8(vdupq_n_s8(100), a)), [i8::MAX; 16]);
+    assert_eq!(transmute::<_, [i8; 16]>(vhsubq_s8(vdupq_n_s8(-3), vdupq_n_s8(0))), [-2; 16]);
+
+    // `vabs` wraps around, `vqabs` and `vqneg` saturate.
+    let min = vdupq_n_s8(i8::MIN);
+    assert_eq!(transmute::<_, [i8; 16]>(vabsq_s8(min)), [i8::MIN; 16]);
+    assert_eq!(transmute::<_, [i8; 16]>(vqabsq_s8(min)), [i8::MAX; 16]);
+    assert_eq!(transmute::<_, [i8; 16]>(vqnegq_s8(min)), [i8::MAX; 16]);
+
+    assert_eq!(transmute::<_, [i16; 8]>(vmaxq_s16(vdupq_n_s16(-5), vdupq_n_s16(3))), [3; 8]);
+    assert_eq!(transmute::<_, [u32; 4]>(vminq_u32(vdupq_n_u32(u32::MAX), vdupq_n_u32(7))), [7; 4]);
+
+    // Negative shift amounts shift right; shifting by the lane width or more
+    // shifts out all bits.
+    let shifts = vld1q_s32([1, -2, 40, -40].as_ptr());
+    assert_eq!(transmute::<_, [i32; 4]>(vshlq_s32(vdupq_n_s32(-16), shifts)), [-32, -4, 0, -1]);
+    let shifts = vld1q_s32([1, -31, 0, -32].as_ptr());
+    assert_eq!(
+        transmute::<_, [u32; 4]>(vshlq_u32(vdupq_n_u32(1 << 31), shifts)),
+        [0, 1, 1 << 31, 0]
+    );
+
+    let a = vld1q_s8(array::from_fn::<i8, 16, _>(|i| i as i8 - 8).as_ptr());
+    let expected: [u8; 16] = array::from_fn(|i| if i > 9 { u8::MAX } else { 0 });
+    assert_eq!(transmute::<_, [u8; 16]>(vcgtq_s8(a, vdupq_n_s8(1))), expected);
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn test_widen_narrow() {
+    assert_eq!(transmute::<_, [u16; 8]>(vmull_u8(vdup_n_u8(200), vdup_n_u8(200))), [40000; 8]);
+    assert_eq!(transmute::<_, [i32; 4]>(vmull_s16(vdup_n_s16(-300), vdup_n_s16(300))), [-90000; 4]);
+    assert_eq!(transmute::<_, [u16; 8]>(vmull_p8(vdup_n_p8(0b11), vdup_n_p8(0b11))), [0b101; 8]);
+
+    let a = vld1q_s16([300, -300, 5, -5, 127, -128, 128, -129].as_ptr());
+    assert_eq!(transmute::<_, [i8; 8]>(vqmovn_s16(a)), [127, -128, 5, -5, 127, -128, 127, -128]);
+    assert_eq!(transmute::<_, [u8; 8]>(vqmovun_s16(a)), [255, 0, 5, 0, 127, 0, 128, 0]);
+    assert_eq!(transmute::<_, [u16; 4]>(vqmovn_u32(vdupq_n_u32(70000))), [u16::MAX; 4]);
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn test_pairwise_and_reduce() {
+    let a = vld1q_u8(array::from_fn::<u8, 16, _>(|i| i as u8).as_ptr());
+
+    let sums: [u8; 8] = array::from_fn(|i| 4 * i as u8 + 1);
+    let expected: [u8; 16] = array::from_fn(|i| sums[i % 8]);
+    assert_eq!(transmute::<_, [u8; 16]>(vpaddq_u8(a, a)), expected);
+    let expected: [u8; 16] = array::from_fn(|i| if i < 8 { 2 * i as u8 + 1 } else { u8::MAX });
+    assert_eq!(transmute::<_, [u8; 16]>(vpmaxq_u8(a, vdupq_n_u8(u8::MAX))), expected);
+
+    // `vaddv` wraps around, `vaddlv` returns a wider type.
+    assert_eq!(vaddvq_u8(vdupq_n_u8(20)), 64);
+    assert_eq!(vaddlvq_u8(vdupq_n_u8(20)), 320);
+    assert_eq!(vaddvq_s16(vdupq_n_s16(-1)), -8);
+    assert_eq!(vmaxvq_u8(a), 15);
+    assert_eq!(vminvq_s8(vld1q_s8(array::from_fn::<i8, 16, _>(|i| i as i8 - 8).as_ptr())), -8);
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn test_float() {
+    let a = vld1q_f32([1.0, -2.0, f32::NAN, 4.0].as_ptr());
+    let b = vld1q_f32([3.0, -1.0, 0.0, f32::NAN].as_ptr());
+
+    // `vmax` and `vmin` propagate NaN, `vmaxnm` and `vminnm` ignore it.
+    let res = transmute::<_, [f32; 4]>(vmaxq_f32(a, b));
+    assert_eq!(res[..2], [3.0, -1.0]);
+    assert!(res[2].is_nan() && res[3].is_nan());
+    let res = transmute::<_, [f32; 4]>(vminq_f32(a, b));
+    assert_eq!(res[..2], [1.0, -2.0]);
+    assert!(res[2].is_nan() && res[3].is_nan());
+    assert_eq!(transmute::<_, [f32; 4]>(vmaxnmq_f32(a, b)), [3.0, -1.0, 0.0, 4.0]);
+    assert_eq!(transmute::<_, [f32; 4]>(vminnmq_f32(a, b)), [1.0, -2.0, 0.0, 4.0]);
+
+    let res = transmute::<_, [f32; 4]>(vabdq_f32(a, b));
+    assert_eq!(res[..2], [2.0, 1.0]);
+
+    assert_eq!(transmute::<_, [u32; 4]>(vcageq_f32(a, b)), [0, u32::MAX, 0, 0]);
+    let two = vdupq_n_f32(2.0);
+    let minus_two = vdupq_n_f32(-2.0);
+    assert_eq!(transmute::<_, [u32; 4]>(vcageq_f32(two, minus_two)), [u32::MAX; 4]);
+    assert_eq!(transmute::<_, [u32; 4]>(vcagtq_f32(two, minus_two)), [0; 4]);
+
+    let a = vld1q_f32([1.0, 2.0, 3.0, 4.0].as_ptr());
+    assert_eq!(transmute::<_, [f32; 4]>(vpaddq_f32(a, a)), [3.0, 7.0, 3.0, 7.0]);
+    assert_eq!(vmaxvq_f32(vld1q_f32([1.0, 5.0, -3.0, 2.0].as_ptr())), 5.0);
+    assert_eq!(vminnmvq_f32(vld1q_f32([f32::NAN, 2.0, 1.0, 3.0].as_ptr())), 1.0);
+}
+
+#[target_feature(enable = "neon")]
+unsafe fn test_table_lookup() {
+    let table = vld1q_u8(array::from_fn::<u8, 16, _>(|i| 2 * i as u8).as_ptr());
+    let index = vld1q_u8([0, 1, 15, 16, 31, 32, 255, 3, 5, 5, 5, 5, 5, 5, 5, 5].as_ptr());
+
+    // Out-of-range indices produce 0.
+    assert_eq!(
+        transmute::<_, [u8; 16]>(vqtbl1q_u8(table, index)),
+        [0, 2, 30, 0, 0, 0, 0, 6, 10, 10, 10, 10, 10, 10, 10, 10]
+    );
+    // `vqtbx` keeps the lanes of the first argument instead.
+    assert_eq!(
+        transmute::<_, [u8; 16]>(vqtbx1q_u8(vdupq_n_u8(7), table, index)),
+        [0, 2, 30, 7, 7, 7, 7, 6, 10, 10, 10, 10, 10, 10, 10, 10]
+    );
+    // Larger tables are the concatenation of their vectors.
+    let tables = uint8x16x2_t(table, vdupq_n_u8(100));
+    assert_eq!(
+        transmute::<_, [u8; 16]>(vqtbl2q_u8(tables, index)),
+        [0, 2, 30, 100, 100, 0, 0, 6, 10, 10, 10, 10, 10, 10, 10, 10]
+    );
+    let index = vld1_u8([0, 15, 16, 1, 2, 3, 4, 5].as_ptr());
+    assert_eq!(transmute::<_, [u8; 8]>(vqtbl1_u8(table, index)), [0, 30, 0, 2, 4, 6, 8, 10]);
+}
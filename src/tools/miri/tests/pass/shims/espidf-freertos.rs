@@ -0,0 +1,242 @@
+//@only-target-espidf: this directly tests ESP-IDF and FreeRTOS functions
+//@compile-flags: -Zmiri-preemption-rate=0
+
+use std::ffi::c_void;
+use std::ptr;
+use std::thread;
+
+// FreeRTOS and ESP-IDF API definitions.
+type QueueHandle = *mut c_void;
+type BaseType = i32;
+type UBaseType = u32;
+type TickType = u32;
+
+const PD_TRUE: BaseType = 1;
+const PD_FALSE: BaseType = 0;
+const PORT_MAX_DELAY: TickType = TickType::MAX;
+const QUEUE_SEND_TO_BACK: BaseType = 0;
+const QUEUE_SEND_TO_FRONT: BaseType = 1;
+const QUEUE_OVERWRITE: BaseType = 2;
+const QUEUE_TYPE_BASE: u8 = 0;
+const QUEUE_TYPE_BINARY_SEMAPHORE: u8 = 3;
+const QUEUE_TYPE_RECURSIVE_MUTEX: u8 = 4;
+const MALLOC_CAP_8BIT: u32 = 1 << 2;
+
+extern "C" {
+    fn xQueueGenericCreate(length: UBaseType, item_size: UBaseType, queue_type: u8) -> QueueHandle;
+    fn xQueueCreateMutex(queue_type: u8) -> QueueHandle;
+    fn xQueueCreateCountingSemaphore(max_count: UBaseType, initial_count: UBaseType)
+    -> QueueHandle;
+    fn vQueueDelete(queue: QueueHandle);
+    fn xQueueGenericSend(
+        queue: QueueHandle,
+        item: *const c_void,
+        ticks: TickType,
+        position: BaseType,
+    ) -> BaseType;
+    fn xQueueReceive(queue: QueueHandle, buf: *mut c_void, ticks: TickType) -> BaseType;
+    fn xQueueSemaphoreTake(queue: QueueHandle, ticks: TickType) -> BaseType;
+    fn xQueueTakeMutexRecursive(mutex: QueueHandle, ticks: TickType) -> BaseType;
+    fn xQueueGiveMutexRecursive(mutex: QueueHandle) -> BaseType;
+    fn uxQueueMessagesWaiting(queue: QueueHandle) -> UBaseType;
+    fn uxQueueSpacesAvailable(queue: QueueHandle) -> UBaseType;
+    fn vTaskDelay(ticks: TickType);
+    fn xTaskGetTickCount() -> TickType;
+
+    fn esp_timer_get_time() -> i64;
+    fn esp_fill_random(buf: *mut c_void, len: usize);
+    fn heap_caps_malloc(size: usize, caps: u32) -> *mut c_void;
+    fn heap_caps_calloc(n: usize, size: usize, caps: u32) -> *mut c_void;
+    fn heap_caps_realloc(ptr: *mut c_void, size: usize, caps: u32) -> *mut c_void;
+    fn heap_caps_aligned_alloc(alignment: usize, size: usize, caps: u32) -> *mut c_void;
+    fn heap_caps_free(ptr: *mut c_void);
+}
+
+/// Lets pointers be moved to other threads.
+#[derive(Clone, Copy)]
+struct SendPtr<T>(*mut T);
+unsafe impl<T> Send for SendPtr<T> {}
+
+fn send(queue: QueueHandle, item: u32, ticks: TickType, position: BaseType) -> BaseType {
+    unsafe { xQueueGenericSend(queue, ptr::from_ref(&item).cast(), ticks, position) }
+}
+
+fn receive(queue: QueueHandle, ticks: TickType) -> Option<u32> {
+    let mut item = 0u32;
+    let res = unsafe { xQueueReceive(queue, ptr::from_mut(&mut item).cast(), ticks) };
+    (res == PD_TRUE).then_some(item)
+}
+
+fn test_queue() {
+    unsafe {
+        let queue = xQueueGenericCreate(3, 4, QUEUE_TYPE_BASE);
+        assert!(!queue.is_null());
+
+        assert_eq!(receive(queue, 0), None);
+        assert_eq!(send(queue, 1, 0, QUEUE_SEND_TO_BACK), PD_TRUE);
+        assert_eq!(send(queue, 2, 0, QUEUE_SEND_TO_BACK), PD_TRUE);
+        assert_eq!(send(queue, 0, 0, QUEUE_SEND_TO_FRONT), PD_TRUE);
+        assert_eq!(uxQueueMessagesWaiting(queue), 3);
+        assert_eq!(uxQueueSpacesAvailable(queue), 0);
+        // The queue is full.
+        assert_eq!(send(queue, 3, 0, QUEUE_SEND_TO_BACK), PD_FALSE);
+
+        assert_eq!(receive(queue, 0), Some(0));
+        assert_eq!(receive(queue, 0), Some(1));
+        assert_eq!(receive(queue, 0), Some(2));
+        assert_eq!(receive(queue, 0), None);
+
+        vQueueDelete(queue);
+
+        // Queues of length 1 can be overwritten.
+        let mailbox = xQueueGenericCreate(1, 4, QUEUE_TYPE_BASE);
+        assert_eq!(send(mailbox, 1, 0, QUEUE_OVERWRITE), PD_TRUE);
+        assert_eq!(send(mailbox, 2, 0, QUEUE_OVERWRITE), PD_TRUE);
+        assert_eq!(receive(mailbox, 0), Some(2));
+        vQueueDelete(mailbox);
+    }
+}
+
+fn test_queue_timeout() {
+    unsafe {
+        let queue = xQueueGenericCreate(1, 4, QUEUE_TYPE_BASE);
+        let start = esp_timer_get_time();
+        // Wait for 2 ticks of 10ms.
+        assert_eq!(receive(queue, 2), None);
+        assert!(esp_timer_get_time() - start >= 20_000);
+        vQueueDelete(queue);
+    }
+}
+
+fn test_queue_threads() {
+    unsafe {
+        let queue = SendPtr(xQueueGenericCreate(2, 4, QUEUE_TYPE_BASE));
+        let data = SendPtr(Box::into_raw(Box::new(0)));
+
+        let consumer = thread::spawn(move || {
+            let (queue, data) = (queue, data);
+            let mut sum = 0;
+            for _ in 0..10 {
+                sum += receive(queue.0, PORT_MAX_DELAY).unwrap();
+            }
+            // Receiving synchronizes with the sender, so there is no data race.
+            assert_eq!(*data.0, 42);
+            sum
+        });
+
+        // Let the consumer block on the empty queue.
+        vTaskDelay(1);
+        *data.0 = 42;
+        for i in 0..10 {
+            // Blocks when the queue is full.
+            assert_eq!(send(queue.0, i, PORT_MAX_DELAY, QUEUE_SEND_TO_BACK), PD_TRUE);
+        }
+        assert_eq!(consumer.join().unwrap(), 45);
+        vQueueDelete(queue.0);
+        drop(Box::from_raw(data.0));
+    }
+}
+
+fn test_semaphores() {
+    unsafe {
+        let binary = xQueueGenericCreate(1, 0, QUEUE_TYPE_BINARY_SEMAPHORE);
+        // Binary semaphores start out empty.
+        assert_eq!(xQueueSemaphoreTake(binary, 0), PD_FALSE);
+        assert_eq!(xQueueGenericSend(binary, ptr::null(), 0, QUEUE_SEND_TO_BACK), PD_TRUE);
+        assert_eq!(xQueueGenericSend(binary, ptr::null(), 0, QUEUE_SEND_TO_BACK), PD_FALSE);
+        assert_eq!(xQueueSemaphoreTake(binary, 0), PD_TRUE);
+        vQueueDelete(binary);
+
+        let counting = xQueueCreateCountingSemaphore(3, 2);
+        assert_eq!(uxQueueMessagesWaiting(counting), 2);
+        assert_eq!(xQueueSemaphoreTake(counting, 0), PD_TRUE);
+        assert_eq!(xQueueSemaphoreTake(counting, 0), PD_TRUE);
+        assert_eq!(xQueueSemaphoreTake(counting, 0), PD_FALSE);
+        vQueueDelete(counting);
+
+        assert!(xQueueCreateCountingSemaphore(1, 2).is_null());
+    }
+}
+
+fn test_mutexes() {
+    unsafe {
+        let mutex = SendPtr(xQueueCreateMutex(QUEUE_TYPE_RECURSIVE_MUTEX));
+        assert_eq!(xQueueTakeMutexRecursive(mutex.0, 0), PD_TRUE);
+        assert_eq!(xQueueTakeMutexRecursive(mutex.0, 0), PD_TRUE);
+
+        // Other threads can neither take nor give it.
+        thread::spawn(move || {
+            let mutex = mutex;
+            assert_eq!(xQueueTakeMutexRecursive(mutex.0, 0), PD_FALSE);
+            assert_eq!(xQueueGiveMutexRecursive(mutex.0), PD_FALSE);
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(xQueueGiveMutexRecursive(mutex.0), PD_TRUE);
+        assert_eq!(xQueueGiveMutexRecursive(mutex.0), PD_TRUE);
+        // It is not held anymore.
+        assert_eq!(xQueueGiveMutexRecursive(mutex.0), PD_FALSE);
+
+        // A thread waiting for the mutex gets it when it is given.
+        assert_eq!(xQueueTakeMutexRecursive(mutex.0, 0), PD_TRUE);
+        let waiter = thread::spawn(move || {
+            let mutex = mutex;
+            assert_eq!(xQueueTakeMutexRecursive(mutex.0, PORT_MAX_DELAY), PD_TRUE);
+            assert_eq!(xQueueGiveMutexRecursive(mutex.0), PD_TRUE);
+        });
+        vTaskDelay(1);
+        assert_eq!(xQueueGiveMutexRecursive(mutex.0), PD_TRUE);
+        waiter.join().unwrap();
+        vQueueDelete(mutex.0);
+    }
+}
+
+fn test_time() {
+    unsafe {
+        let ticks = xTaskGetTickCount();
+        let start = esp_timer_get_time();
+        vTaskDelay(5);
+        assert!(esp_timer_get_time() - start >= 50_000);
+        assert!(xTaskGetTickCount().wrapping_sub(ticks) >= 5);
+    }
+}
+
+fn test_random() {
+    let mut buf = [0u8; 32];
+    unsafe { esp_fill_random(buf.as_mut_ptr().cast(), buf.len()) };
+    // The chance of this failing is negligible.
+    assert!(buf.iter().any(|&b| b != 0));
+}
+
+fn test_heap_caps() {
+    unsafe {
+        let p = heap_caps_malloc(16, MALLOC_CAP_8BIT).cast::<u8>();
+        p.write_bytes(1, 16);
+        let p = heap_caps_realloc(p.cast(), 32, MALLOC_CAP_8BIT).cast::<u8>();
+        assert_eq!(*p.add(15), 1);
+        // A size of 0 frees the memory.
+        assert!(heap_caps_realloc(p.cast(), 0, MALLOC_CAP_8BIT).is_null());
+
+        let p = heap_caps_calloc(4, 4, MALLOC_CAP_8BIT).cast::<u32>();
+        assert_eq!(*p.add(3), 0);
+        heap_caps_free(p.cast());
+
+        // The size does not have to be a multiple of the alignment.
+        let p = heap_caps_aligned_alloc(64, 8, MALLOC_CAP_8BIT);
+        assert_eq!(p as usize % 64, 0);
+        heap_caps_free(p);
+        assert!(heap_caps_aligned_alloc(3, 8, MALLOC_CAP_8BIT).is_null());
+    }
+}
+
+fn main() {
+    test_queue();
+    test_queue_timeout();
+    test_queue_threads();
+    test_semaphores();
+    test_mutexes();
+    test_time();
+    test_random();
+    test_heap_caps();
+}
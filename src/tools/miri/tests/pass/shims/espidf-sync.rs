@@ -0,0 +1,103 @@
+//@only-target-espidf: ESP-IDF has its own pthread layouts
+// We use `yield` to test specific interleavings, so disable automatic preemption.
+//@compile-flags: -Zmiri-disable-isolation -Zmiri-preemption-rate=0
+
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn check_mutex() {
+    let data = Arc::new(Mutex::new(0));
+    let threads: Vec<_> = (0..3)
+        .map(|_| {
+            let data = Arc::clone(&data);
+            thread::spawn(move || {
+                let mut data = data.lock().unwrap();
+                thread::yield_now();
+                *data += 1;
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert!(data.try_lock().is_ok());
+    assert_eq!(*data.lock().unwrap(), 3);
+}
+
+fn check_conditional_variables_notify_one() {
+    let pair = Arc::new((Mutex::new(false), Condvar::new()));
+    let pair2 = Arc::clone(&pair);
+
+    let handle = thread::spawn(move || {
+        let (lock, cvar) = &*pair2;
+        *lock.lock().unwrap() = true;
+        cvar.notify_one();
+    });
+
+    let (lock, cvar) = &*pair;
+    let mut started = lock.lock().unwrap();
+    while !*started {
+        started = cvar.wait(started).unwrap();
+    }
+    handle.join().unwrap();
+}
+
+fn check_conditional_variables_notify_all() {
+    let pair = Arc::new((Mutex::new(0), Condvar::new()));
+    let threads: Vec<_> = (0..3)
+        .map(|_| {
+            let pair = Arc::clone(&pair);
+            thread::spawn(move || {
+                let (lock, cvar) = &*pair;
+                let mut count = lock.lock().unwrap();
+                *count += 1;
+                cvar.notify_all();
+                while *count < 3 {
+                    count = cvar.wait(count).unwrap();
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+}
+
+fn check_conditional_variables_timed_wait_timeout() {
+    let lock = Mutex::new(());
+    let cvar = Condvar::new();
+    let guard = lock.lock().unwrap();
+    let now = Instant::now();
+    let (_guard, timeout) = cvar.wait_timeout(guard, Duration::from_millis(100)).unwrap();
+    assert!(timeout.timed_out());
+    assert!(now.elapsed() >= Duration::from_millis(100));
+}
+
+fn check_rwlock() {
+    let data = Arc::new(RwLock::new(0));
+    let writer = {
+        let data = Arc::clone(&data);
+        thread::spawn(move || {
+            let mut data = data.write().unwrap();
+            thread::yield_now();
+            *data += 1;
+        })
+    };
+    writer.join().unwrap();
+    let r1 = data.read().unwrap();
+    let r2 = data.read().unwrap();
+    assert_eq!((*r1, *r2), (1, 1));
+}
+
+fn main() {
+    check_mutex();
+    check_conditional_variables_notify_one();
+    check_conditional_variables_notify_all();
+    check_conditional_variables_timed_wait_timeout();
+    check_rwlock();
+
+    // Standard output and error are locked with a `pthread_mutex_t` as well.
+    println!("Hello from ESP-IDF!");
+    eprintln!("Hello from ESP-IDF's stderr!");
+}
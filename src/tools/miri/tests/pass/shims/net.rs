@@ -0,0 +1,191 @@
+//@ignore-target-windows: Sockets are not implemented yet
+// test_tcp_blocking depends on a deterministic schedule.
+//@compile-flags: -Zmiri-preemption-rate=0
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream, UdpSocket};
+use std::thread;
+use std::time::{Duration, Instant};
+
+fn main() {
+    test_tcp();
+    test_tcp_blocking();
+    test_tcp_large_write();
+    test_tcp_nonblocking();
+    test_tcp_timeout();
+    test_tcp_errors();
+    test_tcp_ipv6();
+    test_udp();
+    test_udp_connected();
+}
+
+fn test_tcp() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(addr.ip(), Ipv4Addr::LOCALHOST);
+    assert_ne!(addr.port(), 0);
+
+    let mut client = TcpStream::connect(addr).unwrap();
+    let (mut server, client_addr) = listener.accept().unwrap();
+    assert_eq!(client.peer_addr().unwrap(), addr);
+    assert_eq!(client.local_addr().unwrap(), client_addr);
+    assert_eq!(server.peer_addr().unwrap(), client_addr);
+
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    assert_eq!(server.peek(&mut buf).unwrap(), 5);
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    server.write_all(b"world").unwrap();
+    // Shutting down writing lets the peer read until EOF.
+    server.shutdown(Shutdown::Write).unwrap();
+    let mut data = Vec::new();
+    client.read_to_end(&mut data).unwrap();
+    assert_eq!(data, b"world");
+
+    // Writing after the peer is gone fails.
+    drop(server);
+    client.write_all(b"lost").unwrap_err();
+}
+
+fn test_tcp_blocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let server = thread::spawn(move || {
+        // Blocks until the client connects.
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut buf = [0; 4];
+        // Blocks until the client writes.
+        stream.read_exact(&mut buf).unwrap();
+        stream.write_all(&buf).unwrap();
+    });
+
+    thread::yield_now();
+    let mut client = TcpStream::connect(addr).unwrap();
+    client.write_all(b"echo").unwrap();
+    let mut buf = [0; 4];
+    client.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"echo");
+    server.join().unwrap();
+
+    // The connection was closed when the server thread dropped the stream.
+    assert_eq!(client.read(&mut buf).unwrap(), 0);
+}
+
+fn test_tcp_large_write() {
+    // More than fits into the socket buffer, so the writer blocks until the reader catches up.
+    const LEN: usize = 300_000;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let mut client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    let writer = thread::spawn(move || {
+        let data: Vec<u8> = (0..LEN).map(|i| i as u8).collect();
+        client.write_all(&data).unwrap();
+    });
+
+    let mut data = vec![0; LEN];
+    server.read_exact(&mut data).unwrap();
+    assert!(data.iter().enumerate().all(|(i, &byte)| byte == i as u8));
+    writer.join().unwrap();
+}
+
+fn test_tcp_nonblocking() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+
+    let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    server.set_nonblocking(true).unwrap();
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    (&client).write_all(b"data").unwrap();
+    assert_eq!(server.read(&mut buf).unwrap(), 4);
+}
+
+fn test_tcp_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let _client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+
+    server.set_read_timeout(Some(Duration::from_millis(10))).unwrap();
+    let start = Instant::now();
+    let mut buf = [0; 4];
+    assert_eq!(server.read(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+    assert!(start.elapsed() >= Duration::from_millis(10));
+}
+
+fn test_tcp_errors() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    assert_eq!(TcpListener::bind(addr).unwrap_err().kind(), ErrorKind::AddrInUse);
+
+    // Nobody listens on this port anymore.
+    drop(listener);
+    assert_eq!(TcpStream::connect(addr).unwrap_err().kind(), ErrorKind::ConnectionRefused);
+
+    // Only the program itself is on the network.
+    assert_eq!(TcpListener::bind("192.0.2.1:0").unwrap_err().kind(), ErrorKind::AddrNotAvailable);
+}
+
+fn test_tcp_ipv6() {
+    let listener = TcpListener::bind("[::]:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let mut client = TcpStream::connect(("::1", port)).unwrap();
+    let (mut server, _) = listener.accept().unwrap();
+    assert!(client.local_addr().unwrap().is_ipv6());
+
+    client.write_all(b"v6").unwrap();
+    let mut buf = [0; 2];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"v6");
+}
+
+fn test_udp() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let a_addr = a.local_addr().unwrap();
+    let b_addr = b.local_addr().unwrap();
+
+    assert_eq!(a.send_to(b"first", b_addr).unwrap(), 5);
+    assert_eq!(a.send_to(b"second", b_addr).unwrap(), 6);
+    let mut buf = [0; 16];
+    assert_eq!(b.peek_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(b.recv_from(&mut buf).unwrap(), (5, a_addr));
+    assert_eq!(&buf[..5], b"first");
+    // The rest of a datagram that does not fit is discarded.
+    let mut small = [0; 3];
+    assert_eq!(b.recv_from(&mut small).unwrap(), (3, a_addr));
+    assert_eq!(&small, b"sec");
+
+    // Datagrams to addresses nobody listens on are lost.
+    let unused: SocketAddr = "127.0.0.1:1".parse().unwrap();
+    assert_eq!(a.send_to(b"lost", unused).unwrap(), 4);
+
+    b.set_nonblocking(true).unwrap();
+    assert_eq!(b.recv_from(&mut buf).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+fn test_udp_connected() {
+    let a = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let b = UdpSocket::bind("127.0.0.1:0").unwrap();
+    let c = UdpSocket::bind("127.0.0.1:0").unwrap();
+    a.connect(b.local_addr().unwrap()).unwrap();
+    b.connect(a.local_addr().unwrap()).unwrap();
+    assert_eq!(a.peer_addr().unwrap(), b.local_addr().unwrap());
+
+    let receiver = thread::spawn(move || {
+        let mut buf = [0; 4];
+        // Blocks until `a` sends. The datagram from `c` is dropped as `b` is connected to `a`.
+        assert_eq!(b.recv(&mut buf).unwrap(), 4);
+        assert_eq!(&buf, b"ping");
+    });
+    thread::yield_now();
+    c.send_to(b"spam", a.peer_addr().unwrap()).unwrap();
+    a.send(b"ping").unwrap();
+    receiver.join().unwrap();
+}
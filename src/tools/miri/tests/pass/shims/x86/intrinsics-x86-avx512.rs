@@ -7,7 +7,7 @@
 //@ignore-target-s390x
 //@ignore-target-thumbv7em
 //@ignore-target-wasm
-//@compile-flags: -C target-feature=+avx512f,+avx512vl,+avx512bitalg,+avx512vpopcntdq
+//@compile-flags: -C target-feature=+avx512f,+avx512vl,+avx512bw,+avx512bitalg,+avx512vpopcntdq,+avx512vbmi,+avx512vbmi2,+avx512vnni
 
 #![feature(avx512_target_feature)]
 #![feature(stdarch_x86_avx512)]
@@ -23,10 +23,18 @@ fn main() {
     assert!(is_x86_feature_detected!("avx512vl"));
     assert!(is_x86_feature_detected!("avx512bitalg"));
     assert!(is_x86_feature_detected!("avx512vpopcntdq"));
+    assert!(is_x86_feature_detected!("avx512bw"));
+    assert!(is_x86_feature_detected!("avx512vbmi"));
+    assert!(is_x86_feature_detected!("avx512vbmi2"));
+    assert!(is_x86_feature_detected!("avx512vnni"));
 
     unsafe {
         test_avx512bitalg();
         test_avx512vpopcntdq();
+        test_avx512f();
+        test_avx512bw();
+        test_avx512vbmi();
+        test_avx512vnni();
     }
 }
 
@@ -201,6 +209,143 @@ unsafe fn test_mm_popcnt_epi64() {
     test_mm_popcnt_epi64();
 }
 
+#[target_feature(enable = "avx512f,avx512vl")]
+unsafe fn test_avx512f() {
+    let a = _mm512_set1_epi32(0b1100);
+    let b = _mm512_set1_epi32(0b1010);
+    let c = _mm512_set1_epi32(0b1001);
+    // Bitwise xor, and the majority function.
+    assert_eq_m512i(_mm512_ternarylogic_epi32::<0x96>(a, b, c), _mm512_set1_epi32(0b1111));
+    assert_eq_m512i(_mm512_ternarylogic_epi32::<0xE8>(a, b, c), _mm512_set1_epi32(0b1000));
+    // Only the bits of the elements are set.
+    let zero = _mm_setzero_si128();
+    assert_eq_m128i(_mm_ternarylogic_epi64::<0x01>(zero, zero, zero), _mm_set1_epi64x(-1));
+
+    let a = transmute::<[i32; 16], __m512i>(std::array::from_fn(|i| i as i32));
+    let odd: [i32; 8] = std::array::from_fn(|i| 2 * i as i32 + 1);
+
+    let res = transmute::<_, [i32; 16]>(_mm512_maskz_compress_epi32(0xAAAA, a));
+    assert_eq!(res[..8], odd);
+    assert_eq!(res[8..], [0; 8]);
+    let res =
+        transmute::<_, [i32; 16]>(_mm512_mask_compress_epi32(_mm512_set1_epi32(-1), 0xAAAA, a));
+    assert_eq!(res[..8], odd);
+    assert_eq!(res[8..], [-1; 8]);
+
+    let mut out = [0i32; 16];
+    _mm512_mask_compressstoreu_epi32(out.as_mut_ptr().cast(), 0xAAAA, a);
+    assert_eq!(out[..8], odd);
+    assert_eq!(out[8..], [0; 8]);
+
+    let res = transmute::<_, [i32; 16]>(_mm512_maskz_expand_epi32(0xAAAA, a));
+    let expected: [i32; 16] = std::array::from_fn(|i| if i % 2 == 1 { i as i32 / 2 } else { 0 });
+    assert_eq!(res, expected);
+
+    // Indices select from the concatenation of `a` and `b`, higher bits are ignored.
+    let b = _mm512_add_epi32(a, _mm512_set1_epi32(100));
+    let idx = transmute::<[i32; 16], __m512i>(std::array::from_fn(|i| 2 * i as i32 + 64));
+    let res = transmute::<_, [i32; 16]>(_mm512_permutex2var_epi32(a, idx, b));
+    let expected: [i32; 16] =
+        std::array::from_fn(|i| if i < 8 { 2 * i as i32 } else { 2 * i as i32 + 84 });
+    assert_eq!(res, expected);
+
+    let a = transmute::<[i32; 16], __m512i>([
+        300, -300, 127, -129, 0, 1, -1, 128, 200, -200, 50, -50, 1000, -1000, 7, -7,
+    ]);
+    assert_eq!(
+        transmute::<_, [i8; 16]>(_mm512_cvtsepi32_epi8(a)),
+        [127, -128, 127, -128, 0, 1, -1, 127, 127, -128, 50, -50, 127, -128, 7, -7],
+    );
+    assert_eq!(
+        transmute::<_, [u8; 16]>(_mm512_cvtusepi32_epi8(a)),
+        [255, 255, 127, 255, 0, 1, 255, 128, 200, 255, 50, 255, 255, 255, 7, 255],
+    );
+    // Elements whose mask bit is not set are taken from `src`.
+    assert_eq!(
+        transmute::<_, [i8; 16]>(_mm512_mask_cvtepi32_epi8(_mm_set1_epi8(9), 0x00FF, a)),
+        [44, -44, 127, 127, 0, 1, -1, -128, 9, 9, 9, 9, 9, 9, 9, 9],
+    );
+    // The elements beyond the converted ones are set to zero.
+    assert_eq!(
+        transmute::<_, [i8; 16]>(_mm_cvtsepi64_epi8(_mm_set_epi64x(-5, 1000))),
+        [127, -5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    );
+}
+
+#[target_feature(enable = "avx512bw,avx512vbmi2")]
+unsafe fn test_avx512bw() {
+    let min = _mm512_set1_epi16(i16::MIN);
+    assert_eq_m512i(_mm512_madd_epi16(min, min), _mm512_set1_epi32(i32::MIN));
+    assert_eq_m512i(
+        _mm512_maddubs_epi16(_mm512_set1_epi8(-1), _mm512_set1_epi8(127)),
+        _mm512_set1_epi16(i16::MAX),
+    );
+    assert_eq_m512i(
+        _mm512_sad_epu8(_mm512_set1_epi8(10), _mm512_set1_epi8(3)),
+        _mm512_set1_epi64(56),
+    );
+
+    let a = transmute::<[u8; 64], __m512i>(std::array::from_fn(|i| i as u8));
+    // Each 128-bit lane is shuffled separately.
+    let expected: [u8; 64] = std::array::from_fn(|i| (i & !15) as u8 + 1);
+    assert_eq!(transmute::<_, [u8; 64]>(_mm512_shuffle_epi8(a, _mm512_set1_epi8(1))), expected);
+    assert_eq!(transmute::<_, [u8; 64]>(_mm512_shuffle_epi8(a, _mm512_set1_epi8(-127))), [0; 64]);
+
+    let res = transmute::<_, [i8; 64]>(_mm512_packs_epi16(
+        _mm512_set1_epi16(300),
+        _mm512_set1_epi16(-300),
+    ));
+    let expected: [i8; 64] = std::array::from_fn(|i| if i % 16 < 8 { 127 } else { -128 });
+    assert_eq!(res, expected);
+
+    let count = transmute::<[u16; 32], __m512i>(std::array::from_fn(|i| i as u16));
+    let res = transmute::<_, [u16; 32]>(_mm512_sllv_epi16(_mm512_set1_epi16(1), count));
+    let expected: [u16; 32] = std::array::from_fn(|i| if i < 16 { 1 << i } else { 0 });
+    assert_eq!(res, expected);
+    assert_eq_m512i(_mm512_sra_epi16(min, _mm_set_epi64x(0, 20)), _mm512_set1_epi16(-1));
+
+    // The 8-bit compress comes with VBMI2.
+    let res = transmute::<_, [u8; 64]>(_mm512_maskz_compress_epi8(0xFFFF_0000_0000_0001, a));
+    let expected: [u8; 64] =
+        std::array::from_fn(|i| if (1..=16).contains(&i) { i as u8 + 47 } else { 0 });
+    assert_eq!(res, expected);
+}
+
+#[target_feature(enable = "avx512vbmi")]
+unsafe fn test_avx512vbmi() {
+    let a = transmute::<[u8; 64], __m512i>(std::array::from_fn(|i| i as u8));
+    // Only the low 6 bits of the indices are used.
+    let idx = transmute::<[u8; 64], __m512i>(std::array::from_fn(|i| 127 - i as u8));
+    let expected: [u8; 64] = std::array::from_fn(|i| 63 - i as u8);
+    assert_eq!(transmute::<_, [u8; 64]>(_mm512_permutexvar_epi8(idx, a)), expected);
+
+    let data = _mm512_set1_epi64(0x0123456789ABCDEF);
+    let bytes = _mm512_set1_epi64(0x3830282018100800);
+    assert_eq_m512i(_mm512_multishift_epi64_epi8(bytes, data), data);
+    // The selected bits wrap around, and only the low 6 bits of the control bytes are used.
+    assert_eq_m512i(
+        _mm512_multishift_epi64_epi8(_mm512_set1_epi8(4), data),
+        _mm512_set1_epi8(0xDEu8 as i8),
+    );
+    assert_eq_m512i(
+        _mm512_multishift_epi64_epi8(_mm512_set1_epi8(-4), data),
+        _mm512_set1_epi8(0xF0u8 as i8),
+    );
+}
+
+#[target_feature(enable = "avx512vnni")]
+unsafe fn test_avx512vnni() {
+    // The bytes of the second operand are unsigned, the ones of the third one are signed.
+    let res =
+        _mm512_dpbusd_epi32(_mm512_set1_epi32(10), _mm512_set1_epi8(-1), _mm512_set1_epi8(i8::MIN));
+    assert_eq_m512i(res, _mm512_set1_epi32(10 - 4 * 255 * 128));
+
+    let max = _mm512_set1_epi32(i32::MAX);
+    let one = _mm512_set1_epi16(1);
+    assert_eq_m512i(_mm512_dpwssd_epi32(max, one, one), _mm512_set1_epi32(i32::MIN + 1));
+    assert_eq_m512i(_mm512_dpwssds_epi32(max, one, one), max);
+}
+
 #[track_caller]
 unsafe fn assert_eq_m512i(a: __m512i, b: __m512i) {
     assert_eq!(transmute::<_, [i32; 16]>(a), transmute::<_, [i32; 16]>(b))
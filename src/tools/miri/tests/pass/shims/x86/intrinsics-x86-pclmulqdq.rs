@@ -7,15 +7,19 @@
 //@ignore-target-s390x
 //@ignore-target-thumbv7em
 //@ignore-target-wasm
-//@compile-flags: -C target-feature=+pclmulqdq
+//@compile-flags: -C target-feature=+pclmulqdq,+vpclmulqdq,+avx512f
+
+#![feature(avx512_target_feature, stdarch_x86_avx512)]
 
 #[cfg(target_arch = "x86")]
 use std::arch::x86::*;
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
+use std::mem::transmute;
 
 fn main() {
     assert!(is_x86_feature_detected!("pclmulqdq"));
+    assert!(is_x86_feature_detected!("vpclmulqdq"));
 
     let a = (0x7fffffffffffffff, 0x4317e40ab4ddcf05);
     let b = (0xdd358416f52ecd34, 0x633d11cc638ca16b);
@@ -25,6 +29,49 @@ fn main() {
         assert_eq!(clmulepi64_si128::<0x01>(a, b), (6707488474444649956, 3901733953304450635));
         assert_eq!(clmulepi64_si128::<0x10>(a, b), (11607166829323378905, 1191897396234301548));
         assert_eq!(clmulepi64_si128::<0x11>(a, b), (7731954893213347271, 1760130762532070957));
+
+        test_vpclmulqdq();
+    }
+}
+
+/// The 256- and 512-bit versions operate on each 128-bit lane separately.
+#[target_feature(enable = "pclmulqdq,vpclmulqdq,avx512f")]
+unsafe fn test_vpclmulqdq() {
+    let a: [u64; 8] = [
+        0x7fffffffffffffff,
+        0x4317e40ab4ddcf05,
+        0xdd358416f52ecd34,
+        0x633d11cc638ca16b,
+        1,
+        u64::MAX,
+        0x8000000000000000,
+        3,
+    ];
+    let b: [u64; 8] = [
+        0xdd358416f52ecd34,
+        0x633d11cc638ca16b,
+        u64::MAX,
+        2,
+        0x8000000000000000,
+        0x7fffffffffffffff,
+        5,
+        0x4317e40ab4ddcf05,
+    ];
+
+    let res =
+        transmute::<_, [u64; 8]>(_mm512_clmulepi64_epi128::<0x10>(transmute(a), transmute(b)));
+    for i in 0..4 {
+        let expected = clmulepi64_si128::<0x10>((a[2 * i], a[2 * i + 1]), (b[2 * i], b[2 * i + 1]));
+        assert_eq!((res[2 * i], res[2 * i + 1]), expected);
+    }
+
+    let a = [a[0], a[1], a[2], a[3]];
+    let b = [b[0], b[1], b[2], b[3]];
+    let res =
+        transmute::<_, [u64; 4]>(_mm256_clmulepi64_epi128::<0x01>(transmute(a), transmute(b)));
+    for i in 0..2 {
+        let expected = clmulepi64_si128::<0x01>((a[2 * i], a[2 * i + 1]), (b[2 * i], b[2 * i + 1]));
+        assert_eq!((res[2 * i], res[2 * i + 1]), expected);
     }
 }
 
@@ -0,0 +1,12 @@
+//! Run by `tests/ui.rs`, which records the schedule of the racy execution below and checks that
+//! replaying the trace reports the same data race.
+use std::thread;
+
+static mut COUNTER: u32 = 0;
+
+fn main() {
+    let handles: Vec<_> = (0..3).map(|_| thread::spawn(|| unsafe { COUNTER += 1 })).collect();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+}
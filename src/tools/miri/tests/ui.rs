@@ -6,7 +6,7 @@
 
 use colored::*;
 use regex::bytes::Regex;
-use ui_test::color_eyre::eyre::{Context, Result};
+use ui_test::color_eyre::eyre::{ensure, Context, Result};
 use ui_test::{
     status_emitter, CommandBuilder, Config, Format, Match, Mode, OutputConflictHandling,
     RustfixMode,
@@ -264,6 +264,74 @@ fn ui(
         .with_context(|| format!("ui tests in {path} for {target} failed"))
 }
 
+/// Checks that replaying a schedule reproduces the failure of the execution it was taken from:
+/// the recorded schedule of a racy program, and the failing schedule reported by
+/// `-Zmiri-explore-schedules`. This needs two Miri runs each, so it does not fit into a ui test
+/// suite.
+fn schedule_replay(target: &str, tmpdir: &Path) -> Result<()> {
+    let msg = format!("## Running schedule replay tests for {target}");
+    eprintln!("{}", msg.green().bold());
+
+    let run = |file: &str, flags: &[String]| -> Result<String> {
+        let mut config = miri_config(
+            target,
+            "",
+            Mode::Yolo { rustfix: RustfixMode::Disabled },
+            /* with dependencies */ false,
+        );
+        config.program.args.clear(); // remove the `--error-format` that ui_test adds by default
+        let mut cmd = config.program.build(&config.out_dir);
+        cmd.arg(format!(
+            "--sysroot={}",
+            env::var("MIRI_SYSROOT").expect("MIRI_SYSROOT must be set to run the ui test suite")
+        ));
+        cmd.args(["--target", target, file]);
+        cmd.args(flags);
+        let output = cmd.output()?;
+        ensure!(!output.status.success(), "`{file}` passed with {flags:?}");
+        Ok(String::from_utf8(output.stderr)?)
+    };
+
+    // Record a racy execution and replay it.
+    let racy = "tests/schedule-replay/data_race.rs";
+    let trace = tmpdir.join("data_race.trace");
+    let recorded = run(racy, &[format!("-Zmiri-record-schedule={}", trace.display())])?;
+    ensure!(recorded.contains("Data race detected"), "no data race was reported:\n{recorded}");
+    let switches = std::fs::read_to_string(&trace)?;
+    ensure!(
+        switches.lines().any(|line| !line.starts_with('#')),
+        "the trace of the racy program contains no switches"
+    );
+    let replayed = run(racy, &[format!("-Zmiri-replay-schedule={}", trace.display())])?;
+    ensure!(
+        replayed == recorded,
+        "replaying the trace did not reproduce the race:\n{recorded}\nreplayed:\n{replayed}"
+    );
+
+    // Replay the failing schedule found by the exploration.
+    let lost_update = "tests/fail/schedule/explore_lost_update.rs";
+    let no_weak_memory = "-Zmiri-disable-weak-memory-emulation".to_string();
+    let explored =
+        run(lost_update, &["-Zmiri-explore-schedules=1".to_string(), no_weak_memory.clone()])?;
+    let switch = regex::Regex::new(r"^[0-9]+ [0-9]+ (yield|blocked|terminated) [0-9]+$").unwrap();
+    let switches: String = explored
+        .lines()
+        .map(str::trim)
+        .filter(|line| switch.is_match(line))
+        .map(|line| format!("{line}\n"))
+        .collect();
+    ensure!(!switches.is_empty(), "the exploration reported no failing schedule:\n{explored}");
+    let trace = tmpdir.join("explore_lost_update.trace");
+    std::fs::write(&trace, switches)?;
+    let replayed =
+        run(lost_update, &[format!("-Zmiri-replay-schedule={}", trace.display()), no_weak_memory])?;
+    ensure!(
+        replayed.contains("entering unreachable code"),
+        "replaying the failing schedule did not reproduce the lost update:\n{replayed}"
+    );
+    Ok(())
+}
+
 fn get_target() -> String {
     env::var("MIRI_TEST_TARGET").ok().unwrap_or_else(get_host)
 }
@@ -310,6 +378,7 @@ fn main() -> Result<()> {
             tmpdir.path(),
         )?;
     }
+    schedule_replay(&target, tmpdir.path())?;
 
     Ok(())
 }
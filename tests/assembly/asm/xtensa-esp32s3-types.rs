@@ -0,0 +1,87 @@
+//@ assembly-output: emit-asm
+//@ compile-flags: --target xtensa-esp32s3-none-elf
+//@ needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items, rustc_attrs, repr_simd, asm_experimental_arch)]
+#![crate_type = "rlib"]
+#![no_core]
+#![allow(non_camel_case_types)]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+#[rustc_builtin_macro]
+macro_rules! concat {
+    () => {};
+}
+#[rustc_builtin_macro]
+macro_rules! stringify {
+    () => {};
+}
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+#[repr(simd)]
+pub struct i8x16(i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8, i8);
+#[repr(simd)]
+pub struct i16x8(i16, i16, i16, i16, i16, i16, i16, i16);
+#[repr(simd)]
+pub struct i32x4(i32, i32, i32, i32);
+
+impl Copy for i32 {}
+impl Copy for i8x16 {}
+impl Copy for i16x8 {}
+impl Copy for i32x4 {}
+
+// Hack to avoid function merging
+extern "Rust" {
+    fn dont_merge(s: &str);
+}
+
+macro_rules! check_qreg {
+    ($func:ident $ty:ident) => {
+        #[no_mangle]
+        pub unsafe fn $func(x: $ty, y: $ty) -> $ty {
+            dont_merge(stringify!($func));
+
+            let z;
+            asm!("ee.andq {}, {}, {}", out(qreg) z, in(qreg) x, in(qreg) y);
+            z
+        }
+    };
+}
+
+// CHECK-LABEL: qreg_i8x16:
+// CHECK: #APP
+// CHECK: ee.andq q{{[0-7]}}, q{{[0-7]}}, q{{[0-7]}}
+// CHECK: #NO_APP
+check_qreg!(qreg_i8x16 i8x16);
+
+// CHECK-LABEL: qreg_i16x8:
+// CHECK: #APP
+// CHECK: ee.andq q{{[0-7]}}, q{{[0-7]}}, q{{[0-7]}}
+// CHECK: #NO_APP
+check_qreg!(qreg_i16x8 i16x8);
+
+// CHECK-LABEL: qreg_i32x4:
+// CHECK: #APP
+// CHECK: ee.andq q{{[0-7]}}, q{{[0-7]}}, q{{[0-7]}}
+// CHECK: #NO_APP
+check_qreg!(qreg_i32x4 i32x4);
+
+// CHECK-LABEL: q3_i16x8:
+// CHECK: #APP
+// CHECK: ee.andq q3, q3, q3
+// CHECK: #NO_APP
+#[no_mangle]
+pub unsafe fn q3_i16x8(x: i16x8) -> i16x8 {
+    dont_merge("q3_i16x8");
+
+    let y;
+    asm!("ee.andq q3, q3, q3", lateout("q3") y, in("q3") x);
+    y
+}
@@ -1,9 +1,15 @@
-// min-llvm-version: 10.0.1
-// assembly-output: emit-asm
-// compile-flags: --target xtensa-esp32-none-elf
-// needs-llvm-components: xtensa
-
-#![feature(no_core, lang_items, rustc_attrs, repr_simd)]
+//@ revisions: esp32 esp32s2 esp32s3 esp8266
+//@ assembly-output: emit-asm
+//@[esp32] compile-flags: --target xtensa-esp32-none-elf
+//@[esp32] needs-llvm-components: xtensa
+//@[esp32s2] compile-flags: --target xtensa-esp32s2-none-elf
+//@[esp32s2] needs-llvm-components: xtensa
+//@[esp32s3] compile-flags: --target xtensa-esp32s3-none-elf
+//@[esp32s3] needs-llvm-components: xtensa
+//@[esp8266] compile-flags: --target xtensa-esp8266-none-elf
+//@[esp8266] needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items, rustc_attrs, repr_simd, asm_experimental_arch)]
 #![crate_type = "rlib"]
 #![no_core]
 #![allow(asm_sub_register, non_camel_case_types)]
@@ -28,11 +34,12 @@ trait Copy {}
 
 type ptr = *const i32;
 
+impl Copy for bool {}
 impl Copy for i8 {}
+impl Copy for u8 {}
 impl Copy for i16 {}
 impl Copy for i32 {}
 impl Copy for f32 {}
-impl Copy for f64 {}
 impl Copy for ptr {}
 
 extern "C" {
@@ -44,13 +51,20 @@ impl Copy for ptr {}
     fn dont_merge(s: &str);
 }
 
+// The windowed CPUs call with `call4`, the ESP8266 only has the call0 ABI.
 // CHECK-LABEL: sym_fn:
 // CHECK: #APP
-// CHECK: call4 extern_func
+// esp32: call4 extern_func
+// esp32s2: call4 extern_func
+// esp32s3: call4 extern_func
+// esp8266: call0 extern_func
 // CHECK: #NO_APP
 #[no_mangle]
 pub unsafe fn sym_fn() {
+    #[cfg(not(esp8266))]
     asm!("call4 {}", sym extern_func);
+    #[cfg(esp8266)]
+    asm!("call0 {}", sym extern_func);
 }
 
 macro_rules! check_general_reg {
@@ -72,6 +86,12 @@ pub unsafe fn $func(x: $ty) -> $ty {
 // CHECK: #NO_APP
 check_general_reg!(reg_i8 i8 reg "mov");
 
+// CHECK-LABEL: reg_u8:
+// CHECK: #APP
+// CHECK: mov a{{[0-9]+}}, a{{[0-9]+}}
+// CHECK: #NO_APP
+check_general_reg!(reg_u8 u8 reg "mov");
+
 // CHECK-LABEL: reg_i16:
 // CHECK: #APP
 // CHECK: mov a{{[0-9]+}}, a{{[0-9]+}}
@@ -90,12 +110,37 @@ pub unsafe fn $func(x: $ty) -> $ty {
 // CHECK: #NO_APP
 check_general_reg!(reg_ptr ptr reg "mov");
 
-// CHECK-LABEL: freg_f32:
-// CHECK: #APP
-// CHECK: mov.s f{{[0-9]+}}, f{{[0-9]+}}
-// CHECK: #NO_APP
+// Only the ESP32 and ESP32-S3 have a floating-point unit.
+// esp32-LABEL: freg_f32:
+// esp32: #APP
+// esp32: mov.s f{{[0-9]+}}, f{{[0-9]+}}
+// esp32: #NO_APP
+// esp32s3-LABEL: freg_f32:
+// esp32s3: #APP
+// esp32s3: mov.s f{{[0-9]+}}, f{{[0-9]+}}
+// esp32s3: #NO_APP
+#[cfg(any(esp32, esp32s3))]
 check_general_reg!(freg_f32 f32 freg "mov.s");
 
+// Only the ESP32 and ESP32-S3 have the boolean registers, which have no move instruction.
+// esp32-LABEL: breg_bool:
+// esp32: #APP
+// esp32: orb b{{[0-9]+}}, b{{[0-9]+}}, b{{[0-9]+}}
+// esp32: #NO_APP
+// esp32s3-LABEL: breg_bool:
+// esp32s3: #APP
+// esp32s3: orb b{{[0-9]+}}, b{{[0-9]+}}, b{{[0-9]+}}
+// esp32s3: #NO_APP
+#[cfg(any(esp32, esp32s3))]
+#[no_mangle]
+pub unsafe fn breg_bool(x: bool) -> bool {
+    dont_merge(stringify!(breg_bool));
+
+    let y;
+    asm!("orb {0}, {1}, {1}", out(breg) y, in(breg) x);
+    y
+}
+
 macro_rules! check_explicit_reg {
     ($func:ident $ty:ident $reg:tt $mov:literal) => {
         #[no_mangle]
@@ -121,7 +166,7 @@ pub unsafe fn $func(x: $ty) -> $ty {
 // CHECK: #NO_APP
 check_explicit_reg!(a5_i16 i16 "a5" "mov");
 
-// CHECK-LABEL: a0_i32:
+// CHECK-LABEL: a5_i32:
 // CHECK: #APP
 // CHECK: mov a5, a5
 // CHECK: #NO_APP
@@ -133,8 +178,65 @@ pub unsafe fn $func(x: $ty) -> $ty {
 // CHECK: #NO_APP
 check_explicit_reg!(a5_ptr ptr "a5" "mov");
 
-// CHECK-LABEL: f0_f32:
-// CHECK: #APP
-// CHECK: mov.s f0, f0
-// CHECK: #NO_APP
+// a7 is the frame pointer of the windowed ABI, and a15 the one of the call0 ABI.
+// esp32-LABEL: a15_i32:
+// esp32: #APP
+// esp32: mov a15, a15
+// esp32: #NO_APP
+// esp32s2-LABEL: a15_i32:
+// esp32s2: #APP
+// esp32s2: mov a15, a15
+// esp32s2: #NO_APP
+// esp32s3-LABEL: a15_i32:
+// esp32s3: #APP
+// esp32s3: mov a15, a15
+// esp32s3: #NO_APP
+#[cfg(not(esp8266))]
+check_explicit_reg!(a15_i32 i32 "a15" "mov");
+
+// esp8266-LABEL: a7_i32:
+// esp8266: #APP
+// esp8266: mov a7, a7
+// esp8266: #NO_APP
+#[cfg(esp8266)]
+check_explicit_reg!(a7_i32 i32 "a7" "mov");
+
+// esp32-LABEL: f0_f32:
+// esp32: #APP
+// esp32: mov.s f0, f0
+// esp32: #NO_APP
+// esp32s3-LABEL: f0_f32:
+// esp32s3: #APP
+// esp32s3: mov.s f0, f0
+// esp32s3: #NO_APP
+#[cfg(any(esp32, esp32s3))]
 check_explicit_reg!(f0_f32 f32 "f0" "mov.s");
+
+// esp32-LABEL: f15_f32:
+// esp32: #APP
+// esp32: mov.s f15, f15
+// esp32: #NO_APP
+// esp32s3-LABEL: f15_f32:
+// esp32s3: #APP
+// esp32s3: mov.s f15, f15
+// esp32s3: #NO_APP
+#[cfg(any(esp32, esp32s3))]
+check_explicit_reg!(f15_f32 f32 "f15" "mov.s");
+
+// esp32-LABEL: b1_bool:
+// esp32: #APP
+// esp32: orb b1, b1, b1
+// esp32: #NO_APP
+// esp32s3-LABEL: b1_bool:
+// esp32s3: #APP
+// esp32s3: orb b1, b1, b1
+// esp32s3: #NO_APP
+#[cfg(any(esp32, esp32s3))]
+#[no_mangle]
+pub unsafe fn b1_bool(x: bool) -> bool {
+    dont_merge(stringify!(b1_bool));
+
+    let y;
+    asm!("orb b1, b1, b1", lateout("b1") y, in("b1") x);
+    y
+}
@@ -0,0 +1,66 @@
+// Xtensa cores only have 32-bit atomic instructions. The ESP targets still provide 64-bit
+// atomics, which must be lowered to calls to the `__atomic_*_8` functions of the runtime.
+
+//@ revisions: esp32 esp32s3
+//@ [esp32] compile-flags: --target=xtensa-esp32-none-elf
+//@ [esp32] needs-llvm-components: xtensa
+//@ [esp32s3] compile-flags: --target=xtensa-esp32s3-none-elf
+//@ [esp32s3] needs-llvm-components: xtensa
+//@ assembly-output: emit-asm
+//@ compile-flags: --crate-type=lib -O -C panic=abort
+
+#![feature(no_core, lang_items, intrinsics)]
+#![no_core]
+
+#[lang = "sized"]
+pub trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for u32 {}
+impl Copy for u64 {}
+
+extern "rust-intrinsic" {
+    fn atomic_load_seqcst<T: Copy>(src: *const T) -> T;
+    fn atomic_store_seqcst<T: Copy>(dst: *mut T, val: T);
+    fn atomic_xadd_seqcst<T: Copy>(dst: *mut T, src: T) -> T;
+    fn atomic_cxchg_seqcst_seqcst<T: Copy>(dst: *mut T, old: T, src: T) -> (T, bool);
+}
+
+// CHECK-LABEL: load_u64:
+// CHECK: __atomic_load_8
+#[no_mangle]
+pub unsafe fn load_u64(p: *const u64) -> u64 {
+    atomic_load_seqcst(p)
+}
+
+// CHECK-LABEL: store_u64:
+// CHECK: __atomic_store_8
+#[no_mangle]
+pub unsafe fn store_u64(p: *mut u64, v: u64) {
+    atomic_store_seqcst(p, v)
+}
+
+// CHECK-LABEL: fetch_add_u64:
+// CHECK: __atomic_fetch_add_8
+#[no_mangle]
+pub unsafe fn fetch_add_u64(p: *mut u64, v: u64) -> u64 {
+    atomic_xadd_seqcst(p, v)
+}
+
+// CHECK-LABEL: compare_exchange_u64:
+// CHECK: __atomic_compare_exchange_8
+#[no_mangle]
+pub unsafe fn compare_exchange_u64(p: *mut u64, old: u64, new: u64) -> bool {
+    atomic_cxchg_seqcst_seqcst(p, old, new).1
+}
+
+// 32-bit atomics are native and must not go through the runtime.
+// CHECK-LABEL: fetch_add_u32:
+// CHECK-NOT: __atomic_fetch_add_4
+// CHECK: s32c1i
+#[no_mangle]
+pub unsafe fn fetch_add_u32(p: *mut u32, v: u32) -> u32 {
+    atomic_xadd_seqcst(p, v)
+}
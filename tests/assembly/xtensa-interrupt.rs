@@ -0,0 +1,137 @@
+// Xtensa interrupt handlers are entered through a sequence that saves the interrupted registers,
+// calls the handler's body and returns with `RFE` for level 1 and `RFI <level>` above that.
+
+//@ revisions: esp32 esp8266
+//@ [esp32] compile-flags: --target=xtensa-esp32-none-elf
+//@ [esp32] needs-llvm-components: xtensa
+//@ [esp8266] compile-flags: --target=xtensa-esp8266-none-elf
+//@ [esp8266] needs-llvm-components: xtensa
+//@ assembly-output: emit-asm
+//@ compile-flags: --crate-type=lib -O -C panic=abort
+
+#![feature(no_core, lang_items, abi_xtensa_interrupt)]
+#![no_core]
+
+#[lang = "sized"]
+pub trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+extern "C" {
+    fn handle(level: u32);
+}
+
+// CHECK-LABEL: level1:
+// CHECK: addi a1, a1, -112
+// CHECK-NEXT: s32i a0, a1, 16
+// CHECK-NEXT: s32i a2, a1, 20
+// CHECK: s32i a15, a1, 72
+// CHECK-NEXT: rsr a0, sar
+// CHECK-NEXT: s32i a0, a1, 76
+// CHECK-NEXT: rsr a0, epc1
+// CHECK-NEXT: s32i a0, a1, 80
+// CHECK-NEXT: rsr a0, ps
+// CHECK-NEXT: s32i a0, a1, 84
+// esp32: call4 level1.body
+// esp8266: call0 level1.body
+// CHECK: l32i a0, a1, 84
+// CHECK-NEXT: wsr a0, ps
+// CHECK: l32i a15, a1, 72
+// CHECK-NEXT: l32i a0, a1, 16
+// CHECK-NEXT: addi a1, a1, 112
+// CHECK-NEXT: rfe
+#[no_mangle]
+pub extern "xtensa-interrupt" fn level1() {
+    unsafe { handle(1) }
+}
+
+// esp32-LABEL: level2:
+// esp32: addi a1, a1, -112
+// esp32-NEXT: s32i a0, a1, 16
+// esp32: s32i a15, a1, 72
+// esp32-NEXT: rsr a0, sar
+// esp32-NEXT: s32i a0, a1, 76
+// esp32-NEXT: rsr a0, epc2
+// esp32-NEXT: s32i a0, a1, 80
+// esp32-NEXT: rsr a0, eps2
+// esp32-NEXT: s32i a0, a1, 84
+// esp32-NEXT: rsr a0, epc1
+// esp32-NEXT: s32i a0, a1, 88
+// esp32-NEXT: rsr a0, excsave1
+// esp32-NEXT: s32i a0, a1, 92
+// esp32: call4 level2.body
+// esp32: l32i a15, a1, 72
+// esp32-NEXT: l32i a0, a1, 16
+// esp32-NEXT: addi a1, a1, 112
+// esp32-NEXT: rfi 2
+#[cfg(esp32)]
+#[no_mangle]
+pub extern "xtensa-interrupt-2" fn level2() {
+    unsafe { handle(2) }
+}
+
+// esp32-LABEL: level3:
+// esp32: s32i a15, a1, 72
+// esp32: rsr a0, epc3
+// esp32: rsr a0, eps3
+// esp32: call4 level3.body
+// esp32: l32i a15, a1, 72
+// esp32: rfi 3
+#[cfg(esp32)]
+#[no_mangle]
+pub extern "xtensa-interrupt-3" fn level3() {
+    unsafe { handle(3) }
+}
+
+// esp32-LABEL: level4:
+// esp32: s32i a15, a1, 72
+// esp32: rsr a0, epc4
+// esp32: rsr a0, eps4
+// esp32: call4 level4.body
+// esp32: l32i a15, a1, 72
+// esp32: rfi 4
+#[cfg(esp32)]
+#[no_mangle]
+pub extern "xtensa-interrupt-4" fn level4() {
+    unsafe { handle(4) }
+}
+
+// esp32-LABEL: level5:
+// esp32: s32i a15, a1, 72
+// esp32: rsr a0, epc5
+// esp32: rsr a0, eps5
+// esp32: call4 level5.body
+// esp32: l32i a15, a1, 72
+// esp32: rfi 5
+#[cfg(esp32)]
+#[no_mangle]
+pub extern "xtensa-interrupt-5" fn level5() {
+    unsafe { handle(5) }
+}
+
+// esp32-LABEL: level6:
+// esp32: s32i a15, a1, 72
+// esp32: rsr a0, epc6
+// esp32: rsr a0, eps6
+// esp32: call4 level6.body
+// esp32: l32i a15, a1, 72
+// esp32: rfi 6
+#[cfg(esp32)]
+#[no_mangle]
+pub extern "xtensa-interrupt-6" fn level6() {
+    unsafe { handle(6) }
+}
+
+// esp32-LABEL: level7:
+// esp32: s32i a15, a1, 72
+// esp32: rsr a0, epc7
+// esp32: rsr a0, eps7
+// esp32: call4 level7.body
+// esp32: l32i a15, a1, 72
+// esp32: rfi 7
+#[cfg(esp32)]
+#[no_mangle]
+pub extern "xtensa-interrupt-7" fn level7() {
+    unsafe { handle(7) }
+}
@@ -0,0 +1,30 @@
+// Checks that a minimal program for the Espressif low-power cores builds and links with the
+// defaults of their targets.
+
+//@ revisions: ulp lp
+//@ [ulp] compile-flags: --target riscv32imc-espulp-none-elf
+//@ [ulp] needs-llvm-components: riscv
+//@ [lp] compile-flags: --target riscv32imac-esplp-none-elf
+//@ [lp] needs-llvm-components: riscv
+
+#![feature(no_core, lang_items)]
+#![no_core]
+#![no_main]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for u32 {}
+
+// Read by the main core once the program has run.
+#[no_mangle]
+static mut SHARED_COUNTER: u32 = 0;
+
+#[no_mangle]
+pub unsafe extern "C" fn _start() -> ! {
+    SHARED_COUNTER = 1;
+    loop {}
+}
@@ -0,0 +1,80 @@
+//@ revisions: esp32 esp32s2 esp32s3 esp8266
+//@[esp32] compile-flags: --target xtensa-esp32-none-elf
+//@[esp32] needs-llvm-components: xtensa
+//@[esp32s2] compile-flags: --target xtensa-esp32s2-none-elf
+//@[esp32s2] needs-llvm-components: xtensa
+//@[esp32s3] compile-flags: --target xtensa-esp32s3-none-elf
+//@[esp32s3] needs-llvm-components: xtensa
+//@[esp8266] compile-flags: --target xtensa-esp8266-none-elf
+//@[esp8266] needs-llvm-components: xtensa
+
+// The registers of the FPU, the boolean registers and the ESP32-S3 vector registers can be
+// clobbered on every CPU, but are only reserved as outputs on the CPUs that have them.
+
+#![crate_type = "rlib"]
+#![feature(no_core, rustc_attrs, lang_items, asm_experimental_arch)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+
+// CHECK-LABEL: @a8_clobber
+// CHECK: call i32 asm sideeffect "", "={a8}"()
+#[no_mangle]
+pub unsafe fn a8_clobber() {
+    asm!("", out("a8") _, options(nostack, nomem));
+}
+
+// CHECK-LABEL: @f0_clobber
+// esp32: call float asm sideeffect "", "={f0}"()
+// esp32s3: call float asm sideeffect "", "={f0}"()
+// esp32s2: call void asm sideeffect "", "~{f0}"()
+// esp8266: call void asm sideeffect "", "~{f0}"()
+#[no_mangle]
+pub unsafe fn f0_clobber() {
+    asm!("", out("f0") _, options(nostack, nomem));
+}
+
+// CHECK-LABEL: @b0_clobber
+// esp32: call i1 asm sideeffect "", "={b0}"()
+// esp32s3: call i1 asm sideeffect "", "={b0}"()
+// esp32s2: call void asm sideeffect "", "~{b0}"()
+// esp8266: call void asm sideeffect "", "~{b0}"()
+#[no_mangle]
+pub unsafe fn b0_clobber() {
+    asm!("", out("b0") _, options(nostack, nomem));
+}
+
+// CHECK-LABEL: @q0_clobber
+// esp32s3: call <16 x i8> asm sideeffect "", "={q0}"()
+// esp32: call void asm sideeffect "", "~{q0}"()
+// esp32s2: call void asm sideeffect "", "~{q0}"()
+// esp8266: call void asm sideeffect "", "~{q0}"()
+#[no_mangle]
+pub unsafe fn q0_clobber() {
+    asm!("", out("q0") _, options(nostack, nomem));
+}
+
+// ACCX can only be clobbered, which is allowed even on the CPUs that do not have it.
+// CHECK-LABEL: @accx_clobber
+// CHECK: call void asm sideeffect "", "~{accx_0},~{accx_1}"()
+#[no_mangle]
+pub unsafe fn accx_clobber() {
+    asm!("", out("accx_0") _, out("accx_1") _, options(nostack, nomem));
+}
+
+// A `CALL8` of the windowed ABI only clobbers a8-a15, while the call0 ABI clobbers a2-a11.
+// CHECK-LABEL: @clobber_abi
+// esp32: asm sideeffect "", "={a8},={a9},={a10},={a11},={a12},={a13},={a14},={a15},
+// esp32s2: asm sideeffect "", "={a8},={a9},={a10},={a11},={a12},={a13},={a14},={a15},
+// esp32s3: asm sideeffect "", "={a8},={a9},={a10},={a11},={a12},={a13},={a14},={a15},
+// esp8266: asm sideeffect "", "={a2},={a3},={a4},={a5},={a6},={a7},={a8},={a9},={a10},={a11},={sar},
+#[no_mangle]
+pub unsafe fn clobber_abi() {
+    asm!("", clobber_abi("C"), options(nostack, nomem));
+}
@@ -0,0 +1,56 @@
+// Checks that `#[link_region]` places every item in its own section of the region, named after
+// the item's symbol, on both the Xtensa and the RISC-V targets of Espressif chips.
+
+//@ revisions: xtensa riscv
+//@ [xtensa] compile-flags: --target xtensa-esp32-none-elf
+//@ [xtensa] needs-llvm-components: xtensa
+//@ [riscv] compile-flags: --target riscv32imc-unknown-none-elf
+//@ [riscv] needs-llvm-components: riscv
+//@ compile-flags: -Copt-level=0
+
+#![feature(no_core, lang_items, link_region)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+// CHECK: @COUNTER = {{.*}} section ".dram1.COUNTER"
+#[no_mangle]
+#[link_region(dram)]
+pub static mut COUNTER: u8 = 0;
+
+// CHECK: @WAKE_COUNT = {{.*}} section ".rtc.force_slow.WAKE_COUNT"
+#[no_mangle]
+#[link_region(rtc_slow)]
+pub static mut WAKE_COUNT: u8 = 0;
+
+// CHECK: define {{.*}}void @in_iram() {{.*}}section ".iram1.in_iram"
+#[no_mangle]
+#[link_region(iram)]
+pub fn in_iram() {}
+
+pub mod isr {
+    // CHECK: define {{.*}}void @handler() {{.*}}section ".iram1.handler"
+    #[no_mangle]
+    #[link_region(iram)]
+    pub fn handler() {}
+}
+
+// CHECK: define {{.*}}void @wake_stub() {{.*}}section ".rtc.text.wake_stub"
+#[no_mangle]
+#[link_region(rtc_fast)]
+pub fn wake_stub() {}
+
+// Every instance of a generic function gets a section of its own.
+// CHECK: define {{.*}}void @[[U8:_ZN[0-9A-Za-z_]+generic[0-9A-Za-z_]+E]]() {{.*}}section ".iram1.[[U8]]"
+// CHECK: define {{.*}}void @[[U16:_ZN[0-9A-Za-z_]+generic[0-9A-Za-z_]+E]]() {{.*}}section ".iram1.[[U16]]"
+#[link_region(iram)]
+fn generic<T>() {}
+
+#[no_mangle]
+#[link_region(iram)]
+pub fn instantiate() {
+    generic::<u8>();
+    generic::<u16>();
+}
@@ -0,0 +1,204 @@
+//@ revisions: esp32 esp32s2 esp32s3 esp8266
+//@ compile-flags: -O -C no-prepopulate-passes
+//@[esp32] compile-flags: --target xtensa-esp32-none-elf
+//@[esp32] needs-llvm-components: xtensa
+//@[esp32s2] compile-flags: --target xtensa-esp32s2-none-elf
+//@[esp32s2] needs-llvm-components: xtensa
+//@[esp32s3] compile-flags: --target xtensa-esp32s3-none-elf
+//@[esp32s3] needs-llvm-components: xtensa
+//@[esp8266] compile-flags: --target xtensa-esp8266-none-elf
+//@[esp8266] needs-llvm-components: xtensa
+
+// The windowed and call0 ABIs pass arguments the same way, so all the CPUs are checked against the
+// same expectations: six argument registers a2-a7, and four return registers a2-a5.
+
+#![feature(no_core, lang_items)]
+#![crate_type = "lib"]
+#![no_std]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "freeze"]
+trait Freeze {}
+#[lang = "copy"]
+trait Copy {}
+
+// Integers smaller than a register are extended to 32 bits.
+
+// CHECK: define void @f_scalar_i8(i8 noundef signext %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_i8(a: i8) {}
+
+// CHECK: define void @f_scalar_u8(i8 noundef zeroext %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_u8(a: u8) {}
+
+// CHECK: define void @f_scalar_i16(i16 noundef signext %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_i16(a: i16) {}
+
+// CHECK: define void @f_scalar_u16(i16 noundef zeroext %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_u16(a: u16) {}
+
+// CHECK: define void @f_scalar_bool(i1 noundef zeroext %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_bool(a: bool) {}
+
+// CHECK: define void @f_scalar_i32(i32 noundef %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_i32(a: i32) {}
+
+// CHECK: define void @f_scalar_i64(i64 noundef %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_i64(a: i64) {}
+
+// CHECK: define void @f_scalar_f64(double noundef %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_f64(a: f64) {}
+
+// Returning up to four registers doesn't need an `sret` pointer.
+
+// CHECK: define noundef signext i8 @f_ret_i8()
+#[no_mangle]
+pub extern "C" fn f_ret_i8() -> i8 {
+    1
+}
+
+// CHECK: define noundef i64 @f_ret_i64()
+#[no_mangle]
+pub extern "C" fn f_ret_i64() -> i64 {
+    1
+}
+
+// CHECK: define noundef double @f_ret_f64()
+#[no_mangle]
+pub extern "C" fn f_ret_f64() -> f64 {
+    1.
+}
+
+// CHECK: define noundef i128 @f_ret_i128()
+#[no_mangle]
+pub extern "C" fn f_ret_i128() -> i128 {
+    1
+}
+
+// CHECK: define noundef i128 @f_ret_u128()
+#[no_mangle]
+pub extern "C" fn f_ret_u128() -> u128 {
+    1
+}
+
+// A 128-bit integer is passed in registers only when all of them are left, as it has to start
+// at a2.
+
+// CHECK: define void @f_scalar_i128(i128 noundef %a)
+#[no_mangle]
+pub extern "C" fn f_scalar_i128(a: i128) {}
+
+// CHECK: define void @f_scalar_i128_after_i32(i32 noundef %a, ptr {{.*}}byval([16 x i8]) align 16{{.*}} %b)
+#[no_mangle]
+pub extern "C" fn f_scalar_i128_after_i32(a: i32, b: i128) {}
+
+// Aggregates are coerced to integers.
+
+#[repr(C)]
+pub struct Tiny {
+    a: u8,
+    b: u8,
+    c: u16,
+}
+
+#[repr(C)]
+pub struct Pair {
+    a: i32,
+    b: i32,
+}
+
+#[repr(C)]
+pub struct DoubleInt {
+    a: f64,
+    b: i32,
+}
+
+#[repr(C)]
+pub struct Quad {
+    a: i32,
+    b: i32,
+    c: i32,
+    d: i32,
+}
+
+#[repr(C)]
+pub struct Five {
+    a: i32,
+    b: i32,
+    c: i32,
+    d: i32,
+    e: i32,
+}
+
+#[repr(C)]
+pub struct Seven {
+    a: [i32; 7],
+}
+
+#[repr(C)]
+pub struct Empty {}
+
+// CHECK: define void @f_agg_tiny(i32 %0)
+#[no_mangle]
+pub extern "C" fn f_agg_tiny(a: Tiny) {}
+
+// CHECK: define void @f_agg_pair([2 x i32] %0)
+#[no_mangle]
+pub extern "C" fn f_agg_pair(a: Pair) {}
+
+// An aggregate aligned to 8 bytes is coerced to 64-bit integers.
+// CHECK: define void @f_agg_double_int([2 x i64] %0)
+#[no_mangle]
+pub extern "C" fn f_agg_double_int(a: DoubleInt) {}
+
+// CHECK: define void @f_agg_five([5 x i32] %0)
+#[no_mangle]
+pub extern "C" fn f_agg_five(a: Five) {}
+
+// An aggregate that doesn't fit in six registers is passed on the stack.
+// CHECK: define void @f_agg_seven(ptr {{.*}}byval([28 x i8]) align 4{{.*}} %a)
+#[no_mangle]
+pub extern "C" fn f_agg_seven(a: Seven) {}
+
+// An aggregate is never split between registers and the stack.
+// CHECK: define void @f_agg_pair_spilled(i32 noundef %a, i32 noundef %b, i32 noundef %c, i32 noundef %d, i32 noundef %e, ptr {{.*}}byval([8 x i8]) align 4{{.*}} %f)
+#[no_mangle]
+pub extern "C" fn f_agg_pair_spilled(a: i32, b: i32, c: i32, d: i32, e: i32, f: Pair) {}
+
+// CHECK: define void @f_agg_empty(i32 noundef %a, i32 noundef %b)
+#[no_mangle]
+pub extern "C" fn f_agg_empty(a: i32, e: Empty, b: i32) {}
+
+// CHECK: define i32 @f_ret_agg_tiny()
+#[no_mangle]
+pub extern "C" fn f_ret_agg_tiny() -> Tiny {
+    Tiny { a: 1, b: 2, c: 3 }
+}
+
+// CHECK: define [4 x i32] @f_ret_agg_quad()
+#[no_mangle]
+pub extern "C" fn f_ret_agg_quad() -> Quad {
+    Quad { a: 1, b: 2, c: 3, d: 4 }
+}
+
+// CHECK: define [2 x i64] @f_ret_agg_double_int()
+#[no_mangle]
+pub extern "C" fn f_ret_agg_double_int() -> DoubleInt {
+    DoubleInt { a: 1., b: 2 }
+}
+
+// Larger aggregates are returned through a pointer passed in a2.
+// CHECK: define void @f_ret_agg_five(ptr {{.*}}sret([20 x i8]) align 4{{.*}} %_0)
+#[no_mangle]
+pub extern "C" fn f_ret_agg_five() -> Five {
+    Five { a: 1, b: 2, c: 3, d: 4, e: 5 }
+}
@@ -0,0 +1,118 @@
+//@ revisions: esp32 esp32s2 esp32s3 esp8266
+//@ compile-flags: -C no-prepopulate-passes -C opt-level=0
+//@[esp32] compile-flags: --target xtensa-esp32-none-elf
+//@[esp32] needs-llvm-components: xtensa
+//@[esp32s2] compile-flags: --target xtensa-esp32s2-none-elf
+//@[esp32s2] needs-llvm-components: xtensa
+//@[esp32s3] compile-flags: --target xtensa-esp32s3-none-elf
+//@[esp32s3] needs-llvm-components: xtensa
+//@[esp8266] compile-flags: --target xtensa-esp8266-none-elf
+//@[esp8266] needs-llvm-components: xtensa
+
+// Checks the lowering of `va_arg`: the first 24 bytes of arguments are read from the registers
+// saved by the prologue, the others from the stack, and an argument is never split between them.
+
+#![feature(no_core, lang_items, intrinsics, c_variadic)]
+#![crate_type = "lib"]
+#![no_std]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+#[lang = "phantom_data"]
+pub struct PhantomData<T: ?Sized>;
+
+#[lang = "va_list"]
+#[repr(C)]
+pub struct VaListImpl<'f> {
+    stk: *mut i32,
+    reg: *mut i32,
+    ndx: i32,
+    _marker: PhantomData<&'f mut &'f i32>,
+}
+
+extern "rust-intrinsic" {
+    fn va_arg<T>(ap: &mut VaListImpl<'_>) -> T;
+}
+
+// CHECK-LABEL: @va_arg_i32
+// CHECK: [[NDX_PTR:%.*]] = getelementptr inbounds i8, ptr [[AP:%.*]], i32 8
+// CHECK-NEXT: [[NDX:%.*]] = load i32, ptr [[NDX_PTR]], align 4
+// CHECK-NEXT: [[NEXT:%.*]] = add i32 [[NDX]], 4
+// CHECK-NEXT: store i32 [[NEXT]], ptr [[NDX_PTR]], align 4
+// CHECK-NEXT: [[IN_REGS:%.*]] = icmp ule i32 [[NEXT]], 24
+// CHECK-NEXT: br i1 [[IN_REGS]], label %va_arg.from_regsave, label %va_arg.from_stack
+//
+// The first argument on the stack is at an offset of 32 from `va_stk`.
+// CHECK: va_arg.from_stack:
+// CHECK: [[FIRST:%.*]] = icmp ule i32 [[NDX]], 24
+// CHECK-NEXT: [[OFFSET:%.*]] = select i1 [[FIRST]], i32 32, i32 [[NDX]]
+// CHECK-NEXT: [[OFFSET_NEXT:%.*]] = select i1 [[FIRST]], i32 36, i32 [[NEXT]]
+// CHECK-NEXT: store i32 [[OFFSET_NEXT]], ptr [[NDX_PTR]], align 4
+//
+// CHECK: va_arg.from_regsave:
+// CHECK: [[REG_AREA_PTR:%.*]] = getelementptr inbounds i8, ptr [[AP]], i32 4
+// CHECK-NEXT: [[REG_AREA:%.*]] = load ptr, ptr [[REG_AREA_PTR]], align 4
+// CHECK-NEXT: getelementptr inbounds i8, ptr [[REG_AREA]], i32 [[NDX]]
+//
+// CHECK: va_arg.end:
+// CHECK-NEXT: [[VALUE_PTR:%.*]] = phi ptr
+// CHECK-NEXT: load i32, ptr [[VALUE_PTR]], align 4
+#[no_mangle]
+pub unsafe extern "C" fn va_arg_i32(n: i32, mut args: ...) -> i32 {
+    va_arg(&mut args)
+}
+
+// Arguments aligned to 8 bytes start at an even register.
+// CHECK-LABEL: @va_arg_i64
+// CHECK: [[NDX:%.*]] = load i32, ptr [[NDX_PTR:%.*]], align 4
+// CHECK-NEXT: [[ROUNDED:%.*]] = add i32 [[NDX]], 7
+// CHECK-NEXT: [[ALIGNED:%.*]] = and i32 [[ROUNDED]], -8
+// CHECK-NEXT: [[NEXT:%.*]] = add i32 [[ALIGNED]], 8
+// CHECK-NEXT: store i32 [[NEXT]], ptr [[NDX_PTR]], align 4
+// CHECK-NEXT: icmp ule i32 [[NEXT]], 24
+// CHECK: va_arg.end:
+// CHECK-NEXT: [[VALUE_PTR:%.*]] = phi ptr
+// CHECK-NEXT: load i64, ptr [[VALUE_PTR]], align 8
+#[no_mangle]
+pub unsafe extern "C" fn va_arg_i64(n: i32, mut args: ...) -> i64 {
+    va_arg(&mut args)
+}
+
+// CHECK-LABEL: @va_arg_f64
+// CHECK: [[NDX:%.*]] = load i32, ptr [[NDX_PTR:%.*]], align 4
+// CHECK-NEXT: [[ROUNDED:%.*]] = add i32 [[NDX]], 7
+// CHECK-NEXT: [[ALIGNED:%.*]] = and i32 [[ROUNDED]], -8
+// CHECK-NEXT: [[NEXT:%.*]] = add i32 [[ALIGNED]], 8
+// CHECK: va_arg.end:
+// CHECK-NEXT: [[VALUE_PTR:%.*]] = phi ptr
+// CHECK-NEXT: load double, ptr [[VALUE_PTR]], align 8
+#[no_mangle]
+pub unsafe extern "C" fn va_arg_f64(n: i32, mut args: ...) -> f64 {
+    va_arg(&mut args)
+}
+
+// Integers smaller than a register are read as a whole `i32` and truncated.
+// CHECK-LABEL: @va_arg_u8
+// CHECK: add i32 {{%.*}}, 4
+// CHECK: va_arg.end:
+// CHECK-NEXT: [[VALUE_PTR:%.*]] = phi ptr
+// CHECK-NEXT: [[VALUE:%.*]] = load i32, ptr [[VALUE_PTR]], align 4
+// CHECK-NEXT: trunc i32 [[VALUE]] to i8
+#[no_mangle]
+pub unsafe extern "C" fn va_arg_u8(n: i32, mut args: ...) -> u8 {
+    va_arg(&mut args)
+}
+
+// CHECK-LABEL: @va_arg_ptr
+// CHECK: add i32 {{%.*}}, 4
+// CHECK: va_arg.end:
+// CHECK-NEXT: [[VALUE_PTR:%.*]] = phi ptr
+// CHECK-NEXT: load ptr, ptr [[VALUE_PTR]], align 4
+#[no_mangle]
+pub unsafe extern "C" fn va_arg_ptr(n: i32, mut args: ...) -> *const u8 {
+    va_arg(&mut args)
+}
@@ -0,0 +1,7 @@
+fn main() {
+    let answer = std::hint::black_box(42);
+    // Only panic and log messages are deferred, the strings the program uses itself are not.
+    let formatted = format!("a string formatted by the program itself: {answer:#x}");
+    println!("{formatted}");
+    panic!("a panic message that is long enough to be deferred: {answer:>4}");
+}
@@ -0,0 +1,32 @@
+// With `-Z deferred-strings`, the format strings of panic messages are replaced by references into
+// a table in a non-loaded section of the ELF file, and their arguments are written as raw bytes.
+// Check that the strings formatted by the program itself are unchanged, that the panic message
+// doesn't contain its format string or its formatted argument, and that the in-tree decoder
+// restores the message from the executable.
+
+//@ ignore-cross-compile
+//@ ignore-windows
+//@ ignore-apple
+// Reason: the table of deferred strings is only emitted in ELF files.
+
+use run_make_support::{cmd, python_command, rustc, source_root};
+
+fn main() {
+    rustc().input("foo.rs").edition("2021").arg("-Zdeferred-strings").run();
+    let output = cmd("./foo").env("RUST_BACKTRACE", "0").run_fail();
+    output.assert_stdout_equals("a string formatted by the program itself: 0x2a\n");
+
+    let stderr = output.stderr_utf8();
+    assert!(stderr.contains('\x1c'), "{stderr:?}");
+    assert!(!stderr.contains("long enough to be deferred"), "{stderr:?}");
+    // The argument is written as the raw bytes of the `i32`, and padded on the host.
+    assert!(stderr.contains("\x1ei2a000000\x1d"), "{stderr:?}");
+
+    let decoded = python_command()
+        .arg(source_root().join("src/etc/decode-deferred-strings.py"))
+        .arg("foo")
+        .stdin(stderr)
+        .run();
+    decoded.assert_stdout_contains("foo.rs:6:5:\n");
+    decoded.assert_stdout_contains("a panic message that is long enough to be deferred:   42");
+}
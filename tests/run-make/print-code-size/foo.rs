@@ -0,0 +1,23 @@
+#![crate_type = "staticlib"]
+
+use std::hint::black_box;
+
+#[inline(never)]
+fn generic<T: Copy>(value: T) -> T {
+    black_box(black_box(value))
+}
+
+#[no_mangle]
+pub extern "C" fn bytes(value: u8) -> u8 {
+    generic(value)
+}
+
+#[no_mangle]
+pub extern "C" fn words(value: u32) -> u32 {
+    generic(value)
+}
+
+#[no_mangle]
+pub extern "C" fn more_words(value: u32) -> u32 {
+    generic(value) + 1
+}
@@ -0,0 +1,47 @@
+// `--print code-size` reports the sizes of the symbols in the object files, attributed to crates,
+// generic definitions and the functions instantiating them. Check that the instances of a generic
+// function are grouped under their definition, that their callers are listed, and that the report
+// can be written to a file.
+
+//@ ignore-windows
+//@ ignore-apple
+// Reason: only ELF object files record the size of symbols.
+
+use run_make_support::serde_json::{self, Value};
+use run_make_support::{rfs, rustc};
+
+fn main() {
+    let output = rustc()
+        .input("foo.rs")
+        .opt_level("2")
+        .arg("-Zunstable-options")
+        .arg("-Zdump-mono-stats-format=json")
+        .print("code-size")
+        .run();
+    let report: Value = serde_json::from_str(&output.stdout_utf8()).unwrap();
+
+    let crates = report["crates"].as_array().unwrap();
+    let foo = crates.iter().find(|krate| krate["name"] == "foo").unwrap();
+    assert!(foo["size"].as_u64().unwrap() > 0);
+
+    let definitions = report["definitions"].as_array().unwrap();
+    let generic = definitions.iter().find(|definition| definition["name"] == "generic").unwrap();
+    assert_eq!(generic["crate"], "foo");
+    assert_eq!(generic["instantiations"], 2);
+
+    let instantiations = report["instantiations"].as_array().unwrap();
+    let words = instantiations.iter().find(|item| item["name"] == "generic::<u32>").unwrap();
+    assert_eq!(words["definition"], "generic");
+    assert_eq!(words["callers"], serde_json::json!(["more_words", "words"]));
+
+    // With a path, the report goes to the file and nothing is printed.
+    let output = rustc()
+        .input("foo.rs")
+        .opt_level("2")
+        .arg("-Zunstable-options")
+        .print("code-size=sizes.md")
+        .run();
+    output.assert_stdout_equals("");
+    let report = rfs::read_to_string("sizes.md");
+    assert!(report.contains("generic::<u32>"), "{report}");
+}
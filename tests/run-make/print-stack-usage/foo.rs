@@ -0,0 +1,40 @@
+#![crate_type = "staticlib"]
+
+use std::hint::black_box;
+
+#[inline(never)]
+fn leaf(buf: &mut [u8; 256]) {
+    black_box(buf);
+}
+
+#[inline(never)]
+fn middle() {
+    let mut buf = [0; 256];
+    leaf(&mut buf);
+}
+
+#[no_mangle]
+pub extern "C" fn bounded() {
+    middle();
+}
+
+#[no_mangle]
+pub extern "C" fn recursive(n: u32) -> u32 {
+    if n == 0 { 0 } else { recursive(black_box(n - 1)) + 1 }
+}
+
+// Neither function of the cycle is called from outside of it, so it is reported through `ping`.
+#[no_mangle]
+pub extern "C" fn ping(n: u32) -> u32 {
+    if n == 0 { 0 } else { pong(black_box(n - 1)) }
+}
+
+#[no_mangle]
+pub extern "C" fn pong(n: u32) -> u32 {
+    if n == 0 { 1 } else { ping(black_box(n - 1)) }
+}
+
+#[no_mangle]
+pub extern "C" fn indirect(f: extern "C" fn() -> u8) -> u8 {
+    f()
+}
@@ -0,0 +1,33 @@
+// `--print stack-usage` combines the frame sizes from the `.stack_sizes` sections with the call
+// graph of the crate. Check that bounded roots get a size along with their deepest path, and that
+// recursion and indirect calls are reported as unbounded, including for self-recursive roots and
+// cycles that no other function calls.
+
+//@ ignore-windows
+//@ ignore-apple
+// Reason: the frame sizes are only emitted for ELF object files.
+
+use run_make_support::{regex, rustc};
+
+fn main() {
+    let output = rustc()
+        .input("foo.rs")
+        .opt_level("2")
+        .arg("-Zunstable-options")
+        .print("stack-usage")
+        .run();
+    let stdout = output.stdout_utf8();
+
+    let bounded = regex::Regex::new(r"(?m)^bounded: (\d+) bytes$").unwrap();
+    let bytes: u64 = bounded.captures(&stdout).unwrap()[1].parse().unwrap();
+    assert!(bytes >= 256, "`middle` has a 256 byte buffer on its stack:\n{stdout}");
+    // The deepest path goes through `middle`, whose frame holds the buffer.
+    output.assert_stdout_contains_regex(r"(?m)^ +\d+  bounded\n +\d+  middle\n +\d+  leaf$");
+
+    output.assert_stdout_contains("recursive: unbounded, `recursive` is recursive");
+    output.assert_stdout_contains("ping: unbounded, `ping` is recursive");
+    output.assert_stdout_not_contains("pong: unbounded");
+    output.assert_stdout_contains(
+        "indirect: unbounded, `indirect` calls through a function pointer or vtable",
+    );
+}
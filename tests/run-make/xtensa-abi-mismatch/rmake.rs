@@ -0,0 +1,24 @@
+// Crates compiled for the windowed and the call0 Xtensa ABI pass arguments in different
+// registers, so they must not be linked together. Check that the crate graph is rejected when a
+// dependency was built for the other ABI.
+
+//@ needs-llvm-components: xtensa
+
+use run_make_support::rustc;
+
+fn main() {
+    rustc()
+        .input("call0.rs")
+        .target("xtensa-esp32-none-elf")
+        .arg("-Ctarget-feature=-windowed")
+        .run();
+    rustc()
+        .input("windowed.rs")
+        .target("xtensa-esp32-none-elf")
+        .library_search_path(".")
+        .run_fail()
+        .assert_stderr_contains(
+            "the crate `call0` is compiled for the `call0` Xtensa ABI which is incompatible with \
+             this crate's `windowed` ABI",
+        );
+}
@@ -0,0 +1,9 @@
+#![feature(no_core)]
+#![no_core]
+#![crate_type = "staticlib"]
+
+extern crate call0;
+
+pub fn windowed() {
+    call0::call0()
+}
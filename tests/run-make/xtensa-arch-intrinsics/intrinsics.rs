@@ -0,0 +1,44 @@
+#![feature(stdarch_xtensa)]
+#![no_std]
+#![crate_type = "rlib"]
+
+use core::arch::xtensa::{self, esp32s3, mac16, sr};
+
+#[no_mangle]
+pub unsafe fn read_intenable() -> u32 {
+    xtensa::rsr::<{ sr::INTENABLE }>()
+}
+
+#[no_mangle]
+pub unsafe fn swap_ps(value: u32) -> u32 {
+    xtensa::xsr::<{ sr::PS }>(value)
+}
+
+#[no_mangle]
+pub fn cycles() -> u32 {
+    xtensa::ccount()
+}
+
+#[no_mangle]
+pub fn leading_zeros(value: u32) -> u32 {
+    xtensa::nsau(value)
+}
+
+#[no_mangle]
+pub fn mac(a: u32, b: u32) -> i64 {
+    mac16::mul_aa_ll(a, b);
+    mac16::mula_aa_hh(a, b);
+    mac16::read_acc()
+}
+
+#[no_mangle]
+pub unsafe fn mul(a: *const i16, b: *const i16, dst: *mut i16, shift: u32) {
+    esp32s3::vmul_s16(a, b, dst, shift)
+}
+
+#[no_mangle]
+pub unsafe fn dot(a: *const i16, b: *const i16) -> i64 {
+    esp32s3::zero_accx();
+    esp32s3::vmulas_s16_accx(a, b);
+    esp32s3::read_accx()
+}
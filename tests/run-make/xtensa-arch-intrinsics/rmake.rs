@@ -0,0 +1,49 @@
+// Builds `core` for the ESP32-S3 and checks that the `core::arch::xtensa` intrinsics compile to
+// the expected instructions: special registers are addressed by number, MAC16 and PIE
+// instructions are emitted as written, and the PIE multiplies take their shift from `SAR`.
+
+//@ needs-llvm-components: xtensa
+
+use run_make_support::{regex, rfs, rustc, source_root};
+
+const TARGET: &str = "xtensa-esp32s3-none-elf";
+
+fn main() {
+    rustc()
+        .edition("2021")
+        .crate_type("rlib")
+        .target(TARGET)
+        .input(source_root().join("library/core/src/lib.rs"))
+        .run();
+    rustc()
+        .input("intrinsics.rs")
+        .target(TARGET)
+        .opt_level("2")
+        .library_search_path(".")
+        .emit("asm")
+        .run();
+
+    let asm = rfs::read_to_string("intrinsics.s");
+    for expected in [
+        // `INTENABLE`, `PS` and `CCOUNT`.
+        r"rsr\s+a\d+, 228",
+        r"xsr\s+a\d+, 230",
+        r"rsr\s+a\d+, 234",
+        r"nsau\s+a\d+, a\d+",
+        r"mul\.aa\.ll\s+a\d+, a\d+",
+        r"mula\.aa\.hh\s+a\d+, a\d+",
+        // `ACCLO` and `ACCHI`.
+        r"rsr\s+a\d+, 16\b",
+        r"rsr\s+a\d+, 17\b",
+        r"wsr\s+a\d+, sar\s+ee\.vld\.128\.ip\s+q0, a\d+, 0",
+        r"ee\.vmul\.s16\s+q2, q0, q1",
+        r"ee\.zero\.accx",
+        r"ee\.vmulas\.s16\.accx\s+q0, q1",
+        r"rur\.accx_1\s+a\d+",
+    ] {
+        assert!(
+            regex::Regex::new(expected).unwrap().is_match(&asm),
+            "`{expected}` not found in the assembly:\n{asm}"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//@ needs-llvm-components: xtensa
+//@ compile-flags: --target=xtensa-esp32-none-elf --crate-type=rlib
+#![no_core]
+#![feature(no_core, lang_items, abi_xtensa_interrupt)]
+#[lang = "sized"]
+trait Sized {}
+
+extern "xtensa-interrupt" fn ok() {}
+
+extern "xtensa-interrupt-5" fn ok_high() {}
+
+extern "xtensa-interrupt" fn with_arg(_level: u32) {}
+//~^ ERROR functions with the "xtensa-interrupt" ABI cannot have arguments
+
+extern "xtensa-interrupt-2" fn with_ret() -> u32 {
+    //~^ ERROR functions with the "xtensa-interrupt-2" ABI cannot have a return type
+    0
+}
@@ -0,0 +1,27 @@
+//@ compile-flags: --target xtensa-esp32s3-none-elf
+//@ needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items, rustc_attrs, asm_experimental_arch)]
+#![crate_type = "rlib"]
+#![no_core]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+impl Copy for i32 {}
+
+// The vector accumulator cannot be an operand, only a clobber.
+pub unsafe fn f() {
+    asm!("", in("accx_0") 0i32);
+    //~^ ERROR register class `accx` can only be used as a clobber, not as an input or output
+    //~| ERROR type `i32` cannot be used with this register class
+    asm!("{}", out(accx) _);
+    //~^ ERROR register class `accx` can only be used as a clobber, not as an input or output
+    asm!("", out("accx_1") _, out("accx_0") _);
+}
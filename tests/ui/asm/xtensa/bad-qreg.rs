@@ -0,0 +1,34 @@
+//@ compile-flags: --target xtensa-esp32-none-elf
+//@ needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items, rustc_attrs, repr_simd, asm_experimental_arch)]
+#![crate_type = "rlib"]
+#![no_core]
+#![allow(non_camel_case_types)]
+
+#[rustc_builtin_macro]
+macro_rules! asm {
+    () => {};
+}
+#[lang = "sized"]
+trait Sized {}
+#[lang = "copy"]
+trait Copy {}
+
+#[repr(simd)]
+pub struct i16x8(i16, i16, i16, i16, i16, i16, i16, i16);
+
+impl Copy for i16x8 {}
+
+// The ESP32 has no PIE unit, so neither the class nor its registers are usable.
+pub unsafe fn f(x: i16x8) {
+    asm!("", in(qreg) x);
+    //~^ ERROR register class `qreg` requires the `esp32s3` target feature
+    asm!("", in("q0") x);
+    //~^ ERROR invalid register `q0`: target does not support esp32s3 registers
+    asm!("", in("accx_0") 0i32);
+    //~^ ERROR invalid register `accx_0`: target does not support esp32s3 registers
+
+    // Clobbering them is allowed, so that `clobber_abi` can list the Q registers on every CPU.
+    asm!("", out("q0") _, out("accx_0") _);
+}
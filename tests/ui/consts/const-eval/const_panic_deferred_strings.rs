@@ -0,0 +1,12 @@
+// With `-Z deferred-strings`, the message of a panic is replaced by a reference into the table of
+// deferred strings. Const-eval errors still show the message itself.
+
+//@ edition:2021
+//@ compile-flags: -Zdeferred-strings
+//@ only-linux
+// Reason: deferred strings are only supported on targets producing ELF objects.
+
+#![crate_type = "lib"]
+
+const A: () = std::panic!("a panic message that is long enough to be deferred");
+//~^ ERROR evaluation of constant value failed
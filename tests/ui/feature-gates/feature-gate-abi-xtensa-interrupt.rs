@@ -0,0 +1,17 @@
+//@ needs-llvm-components: xtensa
+//@ compile-flags: --target=xtensa-esp32-none-elf --crate-type=rlib
+#![no_core]
+#![feature(no_core, lang_items)]
+#[lang = "sized"]
+trait Sized {}
+
+// Test that the xtensa interrupt ABIs cannot be used when abi_xtensa_interrupt
+// feature gate is not used.
+
+extern "xtensa-interrupt" fn f() {}
+//~^ ERROR xtensa-interrupt ABIs are experimental
+extern "xtensa-interrupt-3" fn f3() {}
+//~^ ERROR xtensa-interrupt ABIs are experimental
+
+type TA = extern "xtensa-interrupt" fn();
+//~^ ERROR xtensa-interrupt ABIs are experimental
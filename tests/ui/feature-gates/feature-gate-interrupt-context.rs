@@ -0,0 +1,7 @@
+#![crate_type = "lib"]
+
+#[interrupt_context] //~ ERROR the `#[interrupt_context]` attribute is an experimental feature
+pub fn handler() {}
+
+#[may_block] //~ ERROR the `#[may_block]` attribute is an experimental feature
+pub fn wait() {}
@@ -0,0 +1,12 @@
+//@ compile-flags: --target xtensa-esp32-none-elf
+//@ needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[link_region(iram)] //~ ERROR the `#[link_region]` attribute is an experimental feature
+pub fn f() {}
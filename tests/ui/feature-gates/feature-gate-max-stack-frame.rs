@@ -0,0 +1,4 @@
+#![crate_type = "lib"]
+
+#[max_stack_frame = "1024"] //~ ERROR the `#[max_stack_frame]` attribute is an experimental feature
+pub fn f() {}
@@ -0,0 +1,4 @@
+#![crate_type = "lib"]
+
+#[no_panic] //~ ERROR the `#[no_panic]` attribute is an experimental feature
+pub fn f() {}
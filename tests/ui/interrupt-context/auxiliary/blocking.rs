@@ -0,0 +1,16 @@
+//@ compile-flags: -Zinterrupt-context-summaries
+
+#![feature(interrupt_context)]
+#![crate_type = "lib"]
+
+#[may_block]
+pub fn wait_for_event() {}
+
+pub fn log(message: &str) {
+    let _ = message;
+    wait_for_event();
+}
+
+pub fn add(a: u32, b: u32) -> u32 {
+    a.wrapping_add(b)
+}
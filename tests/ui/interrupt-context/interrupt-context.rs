@@ -0,0 +1,50 @@
+// Functions running in interrupt context must not reach a `#[may_block]` function. The functions
+// of other crates are checked with the summaries encoded in their metadata.
+
+//@ build-fail
+//@ aux-build:blocking.rs
+//@ compile-flags: --crate-type=lib
+
+#![feature(interrupt_context)]
+
+extern crate blocking;
+
+use std::sync::Mutex;
+
+static COUNT: Mutex<u32> = Mutex::new(0);
+
+#[interrupt_context]
+pub fn add(a: u32, b: u32) -> u32 {
+    blocking::add(a, b)
+}
+
+#[interrupt_context]
+pub fn locks() -> u32 {
+    //~^ ERROR `locks` may block or allocate, but runs in interrupt context
+    *COUNT.lock().unwrap()
+}
+
+#[may_block]
+fn wait() {}
+
+fn handle() {
+    wait();
+}
+
+#[interrupt_context]
+pub fn nested() {
+    //~^ ERROR `nested` may block or allocate, but runs in interrupt context
+    handle();
+}
+
+#[interrupt_context]
+pub fn logs() {
+    //~^ ERROR `logs` may block or allocate, but runs in interrupt context
+    blocking::log("interrupt");
+}
+
+// Calls through function pointers are not followed.
+#[interrupt_context]
+pub fn call_through(f: fn()) {
+    f()
+}
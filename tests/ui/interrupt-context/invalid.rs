@@ -0,0 +1,15 @@
+#![feature(interrupt_context)]
+#![crate_type = "lib"]
+
+#[interrupt_context] //~ ERROR attribute should be applied to a function definition
+pub struct S;
+
+pub trait T {
+    #[may_block] //~ ERROR attribute should be applied to a function definition
+    fn required(&self);
+}
+
+extern "C" {
+    #[may_block]
+    fn sem_wait(sem: *mut u8) -> i32;
+}
@@ -0,0 +1,41 @@
+//@ compile-flags: --target xtensa-esp32-none-elf
+//@ needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items, link_region)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[lang = "sync"]
+trait Sync {}
+impl Sync for u8 {}
+
+#[link_region(iram)] //~ ERROR attribute should be applied to a function or static
+pub struct S;
+
+#[link_region(flash)] //~ ERROR expected one of `iram`, `dram`, `rtc_fast` or `rtc_slow`
+pub fn unknown_region() {}
+
+#[link_region(iram, rtc_fast)] //~ ERROR expected one of `iram`, `dram`, `rtc_fast` or `rtc_slow`
+pub fn two_regions() {}
+
+#[link_region(iram)] //~ ERROR `iram` cannot hold statics
+pub static IRAM_STATIC: u8 = 0;
+
+#[link_region(rtc_slow)] //~ ERROR `rtc_slow` cannot hold functions
+pub fn rtc_slow_fn() {}
+
+#[link_region(iram)] //~ ERROR `#[link_region]` cannot be combined with `#[link_section]`
+#[link_section = ".iram1.custom"]
+pub fn with_link_section() {}
+
+#[link_region(dram)]
+pub static DRAM_STATIC: u8 = 0;
+
+#[link_region(rtc_fast)]
+pub static mut RTC_FAST_STATIC: u8 = 0;
+
+#[link_region(rtc_fast)]
+pub fn rtc_fast_fn() {}
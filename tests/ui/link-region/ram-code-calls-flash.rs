@@ -0,0 +1,53 @@
+//@ build-pass
+//@ compile-flags: --target xtensa-esp32-none-elf
+//@ needs-llvm-components: xtensa
+
+#![feature(no_core, lang_items, link_region)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+fn in_flash() {}
+
+#[inline(always)]
+fn always_inlined() {}
+
+#[link_region(iram)]
+fn in_iram() {}
+
+#[link_section = ".iram1.manual"]
+fn manually_in_iram() {}
+
+fn generic<T>() {}
+
+extern "C" {
+    fn rom_function();
+}
+
+#[no_mangle]
+#[link_region(iram)]
+pub unsafe extern "C" fn isr() {
+    in_flash();
+    //~^ WARN function in `iram` calls `in_flash`, which is not in RAM
+    generic::<u8>();
+    //~^ WARN function in `iram` calls `generic`, which is not in RAM
+    always_inlined();
+    in_iram();
+    manually_in_iram();
+    rom_function();
+}
+
+#[no_mangle]
+#[link_region(rtc_fast)]
+pub unsafe extern "C" fn wake_stub() {
+    in_flash();
+    //~^ WARN function in `rtc_fast` calls `in_flash`, which is not in RAM
+    in_iram();
+}
+
+#[no_mangle]
+pub fn not_placed() {
+    in_flash();
+}
@@ -0,0 +1,12 @@
+//@ compile-flags: --target x86_64-unknown-linux-gnu
+//@ needs-llvm-components: x86
+
+#![feature(no_core, lang_items, link_region)]
+#![no_core]
+#![crate_type = "rlib"]
+
+#[lang = "sized"]
+trait Sized {}
+
+#[link_region(iram)] //~ ERROR `#[link_region]` is not supported on this target
+pub fn f() {}
@@ -0,0 +1,37 @@
+// Functions whose stack frame, as computed by the backend, is larger than `-Z max-stack-frame` are
+// reported. `#[max_stack_frame]` overrides the limit for one function.
+
+//@ build-fail
+//@ compile-flags: -Z max-stack-frame=256 --crate-type=lib -C opt-level=0
+//@ ignore-apple
+//@ ignore-windows
+//@ ignore-wasm
+//@ normalize-stderr-test: "is \d+ bytes" -> "is $$SIZE bytes"
+
+#![feature(max_stack_frame)]
+
+use std::hint::black_box;
+
+pub fn small() -> u8 {
+    let buffer = [0u8; 16];
+    black_box(&buffer)[0]
+}
+
+pub fn large() -> u8 {
+    //~^ ERROR the stack frame of `large` is
+    let buffer = [0u8; 1024];
+    black_box(&buffer)[0]
+}
+
+#[max_stack_frame = "4096"]
+pub fn raised() -> u8 {
+    let buffer = [0u8; 1024];
+    black_box(&buffer)[0]
+}
+
+#[max_stack_frame = "16"]
+pub fn lowered() -> u8 {
+    //~^ ERROR the stack frame of `lowered` is
+    let buffer = [0u8; 64];
+    black_box(&buffer)[0]
+}
@@ -0,0 +1,44 @@
+// A generic `#[no_panic]` function is checked for each type it is instantiated with, so a panic in
+// the trait impl of one of the types is reported for that instance only.
+
+//@ build-fail
+//@ compile-flags: --crate-type=lib
+
+#![feature(no_panic)]
+
+pub trait Value {
+    fn get(&self) -> u32;
+}
+
+pub struct Fine(u32);
+
+impl Value for Fine {
+    fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+pub struct Panics {
+    values: [u32; 4],
+    index: usize,
+}
+
+impl Value for Panics {
+    fn get(&self) -> u32 {
+        self.values[self.index]
+    }
+}
+
+#[no_panic]
+fn read<T: Value>(value: &T) -> u32 {
+    //~^ ERROR `read::<Panics>` may panic
+    value.get()
+}
+
+pub fn read_fine(value: &Fine) -> u32 {
+    read(value)
+}
+
+pub fn read_panics(value: &Panics) -> u32 {
+    read(value)
+}
@@ -0,0 +1,10 @@
+#![feature(no_panic)]
+#![crate_type = "lib"]
+
+#[no_panic] //~ ERROR attribute should be applied to a function definition
+pub struct S;
+
+pub trait T {
+    #[no_panic] //~ ERROR attribute should be applied to a function definition
+    fn required(&self);
+}
@@ -0,0 +1,43 @@
+// Functions marked `#[no_panic]` must not reach a panic. This is checked after
+// monomorphization, and the shortest call chain to a panic is reported.
+
+//@ build-fail
+//@ compile-flags: --crate-type=lib
+
+#![feature(no_panic)]
+
+#[no_panic]
+pub fn wrapping(a: u8, b: u8) -> u8 {
+    a.wrapping_add(b)
+}
+
+#[no_panic]
+pub fn index(values: &[u32], i: usize) -> u32 {
+    //~^ ERROR `index` may panic
+    values[i]
+}
+
+fn first(values: &[u32]) -> u32 {
+    values[0]
+}
+
+#[no_panic]
+pub fn nested(values: &[u32]) -> u32 {
+    //~^ ERROR `nested` may panic
+    first(values)
+}
+
+#[no_panic]
+fn generic<T: Copy>(value: T) -> T {
+    value
+}
+
+pub fn instantiate() -> u32 {
+    generic(1)
+}
+
+#[no_panic]
+pub fn call_through(f: fn() -> u32) -> u32 {
+    //~^ ERROR `call_through` may panic
+    f()
+}
@@ -23,6 +23,7 @@
 // gate-test-lahfsahf_target_feature
 // gate-test-prfchw_target_feature
 // gate-test-s390x_target_feature
+// gate-test-xtensa_target_feature
 
 #[target_feature(enable = "avx512bw")]
 //~^ ERROR: currently unstable
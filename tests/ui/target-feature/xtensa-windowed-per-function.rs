@@ -0,0 +1,16 @@
+//@ build-fail
+//@ compile-flags: --crate-type=rlib --target=xtensa-esp32-none-elf
+//@ needs-llvm-components: xtensa
+#![feature(no_core, lang_items, xtensa_target_feature)]
+#![no_core]
+
+#[lang = "sized"]
+trait Sized {}
+
+// Register windows select the calling convention, so they cannot be toggled per function.
+#[target_feature(enable = "windowed")]
+//~^ ERROR the target feature `windowed` changes the calling convention
+pub unsafe fn windowed() {}
+
+#[target_feature(enable = "mac16")]
+pub unsafe fn mac16() {}